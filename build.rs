@@ -0,0 +1,152 @@
+//! Compiles character maps from `data/charmaps` into static tables.
+//!
+//! Each file is a table of parallel character maps. The first row names
+//! the columns: `case` followed by names of the maps, which become
+//! uppercase constants. Every other row holds letter case and a pattern
+//! for each map. Patterns may contain `\u{...}` escapes (ie. for
+//! combining marks), and lines starting with `#` are comments.
+//!
+//! Files are named after their language (ie. `kazakh-latin.csv`). Tables of
+//! languages other than Serbian are compiled only with the language's
+//! cargo feature enabled.
+//!
+//! For every map, an index of patterns by their first character is
+//! generated too, so matching tries only patterns which can match.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const DATA: &str = "data/charmaps";
+
+fn unescape(cell: &str, file: &str, line: usize) -> Vec<char> {
+    let mut chars = Vec::new();
+    let mut rest = cell;
+    while let Some(c) = rest.chars().next() {
+        if let Some(escape) = rest.strip_prefix("\\u{") {
+            let end = escape
+                .find('}')
+                .unwrap_or_else(|| panic!("{}:{}: unterminated escape", file, line));
+            let c = u32::from_str_radix(&escape[..end], 16)
+                .ok()
+                .and_then(char::from_u32)
+                .unwrap_or_else(|| panic!("{}:{}: invalid escape", file, line));
+            chars.push(c);
+            rest = &escape[end + 1..];
+        } else {
+            chars.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    chars
+}
+
+/// Index of a map, as pattern indices by their first character. Indices
+/// are in descending order, as patterns are matched from the bottom.
+fn index(patterns: &[Vec<char>]) -> String {
+    let mut index: BTreeMap<char, Vec<usize>> = BTreeMap::new();
+    for (i, pattern) in patterns.iter().enumerate().rev() {
+        if let Some(first) = pattern.first() {
+            index.entry(*first).or_default().push(i);
+        }
+    }
+    let mut output = String::new();
+    for (first, indices) in index {
+        let indices = indices
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        let _ = writeln!(output, "    ({:?}, &[{}]),", first, indices);
+    }
+    output
+}
+
+/// Compile a table into maps, and return their names
+fn compile(file: &str, source: &str, output: &mut String) -> Vec<String> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.starts_with('#'));
+    let header = match lines.next() {
+        Some((_, header)) => header.split(',').collect::<Vec<&str>>(),
+        None => panic!("{}: missing header", file),
+    };
+    if header.first() != Some(&"case") || header.len() < 2 {
+        panic!("{}: header must be case followed by map names", file);
+    }
+    let mut maps = vec![String::new(); header.len() - 1];
+    let mut patterns = vec![Vec::new(); header.len() - 1];
+    for (n, line) in lines {
+        let cells = line.split(',').collect::<Vec<&str>>();
+        if cells.len() != header.len() {
+            panic!("{}:{}: expected {} cells", file, n + 1, header.len());
+        }
+        let case = match cells[0] {
+            "upper" => "Upper",
+            "mixed" => "Mixed",
+            "lower" => "Lower",
+            other => panic!("{}:{}: unknown case {}", file, n + 1, other),
+        };
+        for ((map, patterns), cell) in maps.iter_mut().zip(&mut patterns).zip(&cells[1..]) {
+            let pattern = unescape(cell, file, n + 1);
+            let value = pattern
+                .iter()
+                .map(|c| format!("{:?}", c))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let _ = writeln!(map, "    Character {{ value: &[{}], case: Case::{} }},", value, case);
+            patterns.push(pattern);
+        }
+    }
+    let mut names = Vec::new();
+    for ((name, map), patterns) in header[1..].iter().zip(maps).zip(patterns) {
+        let name = name.to_uppercase();
+        let _ = writeln!(output, "// Generated from {}", file);
+        let _ = writeln!(output, "pub const {}: &[Character] = &[\n{}];\n", name, map);
+        let _ = writeln!(output, "pub const {}_INDEX: Index = &[\n{}];\n", name, index(&patterns));
+        names.push(name);
+    }
+    names
+}
+
+/// Returns whether tables of the language in file `path` are needed
+fn enabled(path: &Path) -> bool {
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_default();
+    match name.split('-').next() {
+        Some("serbian") => true,
+        Some(language) => {
+            env::var_os(format!("CARGO_FEATURE_{}", language.to_uppercase())).is_some()
+        }
+        None => false,
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", DATA);
+    let mut files = fs::read_dir(DATA)
+        .expect("character map directory is missing")
+        .map(|entry| entry.expect("unreadable character map").path())
+        .filter(|path| path.extension().map_or(false, |e| e == "csv") && enabled(path))
+        .collect::<Vec<_>>();
+    files.sort();
+    let mut output = String::new();
+    let mut names = Vec::new();
+    for path in files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let source = fs::read_to_string(&path).expect("unreadable character map");
+        names.extend(compile(&path.display().to_string(), &source, &mut output));
+    }
+    let _ = writeln!(output, "const INDICES: &[(&[Character], Index)] = &[");
+    for name in names {
+        let _ = writeln!(output, "    ({}, {}_INDEX),", name, name);
+    }
+    let _ = writeln!(output, "];");
+    let out = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out).join("charmaps.rs"), output).expect("cannot write character maps");
+}
@@ -0,0 +1,73 @@
+use pgrx::prelude::*;
+use translitrs::{Charset, Direction, Transliterator};
+
+::pgrx::pg_module_magic!();
+
+/// Transliterate `text` in a direction, raising a PostgreSQL error if it
+/// can't be transliterated
+fn process(text: &str, direction: Direction) -> String {
+    Transliterator::for_direction(direction)
+        .process(text)
+        .unwrap_or_else(|e| error!("{}", e.to_string().trim_end()))
+}
+
+/// Transliterate Latin text into Cyrillic, ie. `translit_cyr('Beograd')`
+#[pg_extern(immutable, strict, parallel_safe)]
+fn translit_cyr(text: &str) -> String {
+    let direction = Direction {
+        from: Charset::Latin,
+        into: Charset::Cyrillic,
+    };
+    process(text, direction)
+}
+
+/// Transliterate Cyrillic text into Latin, ie. `translit_lat('Београд')`
+#[pg_extern(immutable, strict, parallel_safe)]
+fn translit_lat(text: &str) -> String {
+    let direction = Direction {
+        from: Charset::Cyrillic,
+        into: Charset::Latin,
+    };
+    process(text, direction)
+}
+
+/// Transliterate text in a direction, ie. `translit('Београд', 'cyr2lat')`
+#[pg_extern(immutable, strict, parallel_safe)]
+fn translit(text: &str, direction: &str) -> String {
+    let direction = direction
+        .parse::<Direction>()
+        .unwrap_or_else(|e| error!("{}", e.to_string().trim_end()));
+    process(text, direction)
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    #[pg_test]
+    fn test_translit() {
+        assert_eq!(crate::translit_cyr("Dobar dan"), "Добар дан");
+        assert_eq!(crate::translit_lat("Добар дан"), "Dobar dan");
+        assert_eq!(crate::translit("Њива", "cyr2lat"), "Njiva");
+        let lat = Spi::get_one::<String>("SELECT translit_lat(translit_cyr('Novi Sad'))");
+        assert_eq!(lat, Ok(Some("Novi Sad".to_owned())));
+        let null = Spi::get_one::<String>("SELECT translit_cyr(NULL)");
+        assert_eq!(null, Ok(None));
+    }
+
+    #[pg_test(error = "Unknown direction - up")]
+    fn test_direction() {
+        crate::translit("Dan", "up");
+    }
+}
+
+/// Required by `cargo pgrx test`
+#[cfg(test)]
+pub mod pg_test {
+    pub fn setup(_options: Vec<&str>) {}
+
+    pub fn postgresql_conf_options() -> Vec<&'static str> {
+        vec![]
+    }
+}
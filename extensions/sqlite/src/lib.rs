@@ -0,0 +1,83 @@
+use std::os::raw::{c_char, c_int};
+
+use rusqlite::functions::{Context, FunctionFlags};
+use rusqlite::{ffi, Connection, Error, Result};
+use translitrs::{Charset, Direction, Transliterator};
+
+fn user_error(error: impl ToString) -> Error {
+    Error::UserFunctionError(error.to_string().trim_end().into())
+}
+
+/// Transliterate `text` in a direction. Options are fixed, so results
+/// depend only on arguments, as deterministic functions require.
+fn process(text: &str, direction: Direction) -> Result<String> {
+    Transliterator::for_direction(direction)
+        .process(text)
+        .map_err(user_error)
+}
+
+/// Transliterate the first argument, which keeps `NULL` values
+fn convert(context: &Context, direction: Direction) -> Result<Option<String>> {
+    context
+        .get::<Option<String>>(0)?
+        .map(|text| process(&text, direction))
+        .transpose()
+}
+
+fn init(db: Connection) -> Result<bool> {
+    let flags = FunctionFlags::SQLITE_UTF8
+        | FunctionFlags::SQLITE_DETERMINISTIC
+        | FunctionFlags::SQLITE_INNOCUOUS;
+    db.create_scalar_function("translit", 2, flags, |context| {
+        let direction = context
+            .get::<String>(1)?
+            .parse::<Direction>()
+            .map_err(user_error)?;
+        convert(context, direction)
+    })?;
+    db.create_scalar_function("translit_cyr", 1, flags, |context| {
+        let direction = Direction {
+            from: Charset::Latin,
+            into: Charset::Cyrillic,
+        };
+        convert(context, direction)
+    })?;
+    db.create_scalar_function("translit_lat", 1, flags, |context| {
+        let direction = Direction {
+            from: Charset::Cyrillic,
+            into: Charset::Latin,
+        };
+        convert(context, direction)
+    })?;
+    Ok(false)
+}
+
+/// Entry point of the extension, which registers functions
+/// `translit(text, direction)`, `translit_cyr(text)` and
+/// `translit_lat(text)` on a connection
+///
+/// # Safety
+///
+/// Called only by SQLite when the extension is loaded
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_extension_init(
+    db: *mut ffi::sqlite3,
+    pz_err_msg: *mut *mut c_char,
+    p_api: *mut ffi::sqlite3_api_routines,
+) -> c_int {
+    Connection::extension_init2(db, pz_err_msg, p_api, init)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() {
+        let cyr2lat = "cyr2lat".parse().unwrap();
+        assert_eq!(process("Добар дан", cyr2lat).unwrap(), "Dobar dan");
+        assert_eq!(process("Dobar dan", cyr2lat).unwrap(), "Dobar dan");
+        let lat2cyr = "lat2cyr".parse().unwrap();
+        assert_eq!(process("Njiva", lat2cyr).unwrap(), "Њива");
+    }
+}
@@ -0,0 +1,138 @@
+use std::io;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::{ArrayRef, GenericStringArray, OffsetSizeTrait, RecordBatch, StringViewArray};
+
+use crate::process::Error;
+use crate::transliterate::{self, Transliterator};
+
+fn invalid_input(message: String) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
+
+/// Transliterate strings of an Arrow string array. Null values are kept.
+///
+/// # Example
+///
+/// ```
+/// use arrow_array::StringArray;
+/// use translitrs::{transliterate_array, Charset, Transliterator};
+/// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+/// let array = StringArray::from(vec![Some("Beograd"), None]);
+/// let converted = transliterate_array(&t, &array).unwrap();
+/// assert_eq!(converted, StringArray::from(vec![Some("Београд"), None]));
+/// ```
+pub fn transliterate_array<O: OffsetSizeTrait>(
+    processor: &Transliterator,
+    array: &GenericStringArray<O>,
+) -> Result<GenericStringArray<O>, transliterate::Error> {
+    array
+        .iter()
+        .map(|value| value.map(|v| processor.process(v)).transpose())
+        .collect()
+}
+
+/// Transliterate an Arrow column of strings, string views or dictionary
+/// encoded strings. Returns `None` for columns of other types.
+pub fn transliterate_column(
+    processor: &Transliterator,
+    column: &ArrayRef,
+) -> Result<Option<ArrayRef>, transliterate::Error> {
+    if let Some(array) = column.as_string_opt::<i32>() {
+        return Ok(Some(Arc::new(transliterate_array(processor, array)?)));
+    }
+    if let Some(array) = column.as_string_opt::<i64>() {
+        return Ok(Some(Arc::new(transliterate_array(processor, array)?)));
+    }
+    if let Some(array) = column.as_string_view_opt() {
+        let converted = array
+            .iter()
+            .map(|value| value.map(|v| processor.process(v)).transpose())
+            .collect::<Result<StringViewArray, _>>()?;
+        return Ok(Some(Arc::new(converted)));
+    }
+    // Only distinct values of dictionaries are converted
+    if let Some(dictionary) = column.as_any_dictionary_opt() {
+        return Ok(transliterate_column(processor, dictionary.values())?
+            .map(|values| dictionary.with_values(values)));
+    }
+    Ok(None)
+}
+
+/// Transliterate string columns of an Arrow record batch
+///
+/// # Arguments
+///
+/// * `processor` - Transliterator to use
+/// * `batch` - Record batch to transliterate
+/// * `columns` - Names of columns to transliterate, which must be string
+///   columns. All string columns are transliterated if empty.
+pub fn transliterate_batch(
+    processor: &Transliterator,
+    batch: &RecordBatch,
+    columns: &[String],
+) -> Result<RecordBatch, Error> {
+    let schema = batch.schema();
+    if let Some(missing) = columns.iter().find(|c| schema.index_of(c).is_err()) {
+        return Err(invalid_input(format!("no column {}", missing)));
+    }
+    let converted = schema
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, column)| {
+            if !columns.is_empty() && !columns.contains(field.name()) {
+                return Ok(Arc::clone(column));
+            }
+            match transliterate_column(processor, column)? {
+                Some(converted) => Ok(converted),
+                None if columns.is_empty() => Ok(Arc::clone(column)),
+                None => {
+                    Err(invalid_input(format!("column {} is not a string column", field.name())))
+                }
+            }
+        })
+        .collect::<Result<Vec<ArrayRef>, Error>>()?;
+    RecordBatch::try_new(schema, converted).map_err(|e| invalid_input(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+    use arrow_array::types::Int8Type;
+    use arrow_array::{DictionaryArray, Int64Array, LargeStringArray, StringArray};
+
+    #[test]
+    fn test_batch() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+            ("grad", Arc::new(StringArray::from(vec![Some("Niš"), None]))),
+            ("opis", Arc::new(LargeStringArray::from(vec!["Jug", "Sever"]))),
+            ("region", Arc::new(DictionaryArray::<Int8Type>::from_iter(["Jug", "Jug"]))),
+        ])
+        .unwrap();
+
+        let converted = transliterate_batch(&t, &batch, &[]).unwrap();
+        assert_eq!(converted.column(0), batch.column(0));
+        assert_eq!(
+            converted.column(1).as_string::<i32>(),
+            &StringArray::from(vec![Some("Ниш"), None])
+        );
+        assert_eq!(
+            converted.column(2).as_string::<i64>(),
+            &LargeStringArray::from(vec!["Југ", "Север"])
+        );
+        let region = converted.column(3).as_dictionary::<Int8Type>();
+        assert_eq!(region.keys(), batch.column(3).as_dictionary::<Int8Type>().keys());
+        assert_eq!(region.values().as_string::<i32>(), &StringArray::from(vec!["Југ"]));
+
+        let converted = transliterate_batch(&t, &batch, &["opis".to_owned()]).unwrap();
+        assert_eq!(converted.column(1), batch.column(1));
+        assert_eq!(converted.column(2).as_string::<i64>().value(1), "Север");
+        assert!(transliterate_batch(&t, &batch, &["id".to_owned()]).is_err());
+        assert!(transliterate_batch(&t, &batch, &["naziv".to_owned()]).is_err());
+    }
+}
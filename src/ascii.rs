@@ -0,0 +1,306 @@
+use std::borrow::Cow;
+use std::str;
+
+use crate::transliterate::Error;
+
+/// Latin letters with diacritics and their ASCII replacements
+const LETTERS: &[(char, &str)] = &[
+    // Serbian, Croatian and Bosnian
+    ('č', "c"),
+    ('Č', "C"),
+    ('ć', "c"),
+    ('Ć', "C"),
+    ('đ', "dj"),
+    ('Đ', "Dj"),
+    ('š', "s"),
+    ('Š', "S"),
+    ('ž', "z"),
+    ('Ž', "Z"),
+    ('ǆ', "dz"),
+    ('ǅ', "Dz"),
+    ('Ǆ', "DZ"),
+    ('ǉ', "lj"),
+    ('ǈ', "Lj"),
+    ('Ǉ', "LJ"),
+    ('ǌ', "nj"),
+    ('ǋ', "Nj"),
+    ('Ǌ', "NJ"),
+    // Kazakh and Uzbek
+    ('ä', "a"),
+    ('Ä', "A"),
+    ('ğ', "g"),
+    ('Ğ', "G"),
+    ('ı', "i"),
+    ('İ', "I"),
+    ('ñ', "n"),
+    ('Ñ', "N"),
+    ('ö', "o"),
+    ('Ö', "O"),
+    ('ş', "s"),
+    ('Ş', "S"),
+    ('ū', "u"),
+    ('Ū', "U"),
+    ('ü', "u"),
+    ('Ü', "U"),
+    ('ʻ', "'"),
+    ('ʼ', "'"),
+    // Other common letters
+    ('á', "a"),
+    ('Á', "A"),
+    ('à', "a"),
+    ('À', "A"),
+    ('â', "a"),
+    ('Â', "A"),
+    ('ã', "a"),
+    ('Ã', "A"),
+    ('å', "a"),
+    ('Å', "A"),
+    ('æ', "ae"),
+    ('Æ', "Ae"),
+    ('ç', "c"),
+    ('Ç', "C"),
+    ('é', "e"),
+    ('É', "E"),
+    ('è', "e"),
+    ('È', "E"),
+    ('ê', "e"),
+    ('Ê', "E"),
+    ('ë', "e"),
+    ('Ë', "E"),
+    ('ě', "e"),
+    ('Ě', "E"),
+    ('í', "i"),
+    ('Í', "I"),
+    ('ì', "i"),
+    ('Ì', "I"),
+    ('î', "i"),
+    ('Î', "I"),
+    ('ï', "i"),
+    ('Ï', "I"),
+    ('ł', "l"),
+    ('Ł', "L"),
+    ('ń', "n"),
+    ('Ń', "N"),
+    ('ň', "n"),
+    ('Ň', "N"),
+    ('ó', "o"),
+    ('Ó', "O"),
+    ('ò', "o"),
+    ('Ò', "O"),
+    ('ô', "o"),
+    ('Ô', "O"),
+    ('õ', "o"),
+    ('Õ', "O"),
+    ('ő', "o"),
+    ('Ő', "O"),
+    ('ø', "o"),
+    ('Ø', "O"),
+    ('œ', "oe"),
+    ('Œ', "Oe"),
+    ('ř', "r"),
+    ('Ř', "R"),
+    ('ß', "ss"),
+    ('ť', "t"),
+    ('Ť', "T"),
+    ('ú', "u"),
+    ('Ú', "U"),
+    ('ù', "u"),
+    ('Ù', "U"),
+    ('û', "u"),
+    ('Û', "U"),
+    ('ů', "u"),
+    ('Ů', "U"),
+    ('ű', "u"),
+    ('Ű', "U"),
+    ('ý', "y"),
+    ('Ý', "Y"),
+    ('ÿ', "y"),
+    ('ź', "z"),
+    ('Ź', "Z"),
+    ('ż', "z"),
+    ('Ż', "Z"),
+    // Punctuation
+    ('‘', "'"),
+    ('’', "'"),
+    ('“', "\""),
+    ('”', "\""),
+    ('„', "\""),
+    ('«', "\""),
+    ('»', "\""),
+    ('–', "-"),
+    ('—', "-"),
+    ('…', "..."),
+];
+
+/// Character which replaces non-ASCII characters without a replacement
+const UNKNOWN: char = '_';
+
+/// Replacements of letters which override the built-in ones when folding
+/// text into ASCII, so established romanizations of publishers can be
+/// followed (ie. `đ=d` instead of `đ=dj`). Uppercase letters follow
+/// replacements of their lowercase letters, unless they are replaced too.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::AsciiMap;
+///
+/// let map: AsciiMap = "đ=d".parse().unwrap();
+/// assert_eq!(map.fold("Đorđe Balašević"), "Dorde Balasevic");
+/// assert_eq!(AsciiMap::default().fold("Đorđe"), "Djordje");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AsciiMap(Vec<(char, String)>);
+
+impl str::FromStr for AsciiMap {
+    type Err = Error;
+
+    /// Parse comma separated replacements (ie. `đ=d,ђ=dj`). Replacements
+    /// must be ASCII, but can be empty.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut map = AsciiMap::default();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (letter, replacement) = entry
+                .split_once('=')
+                .ok_or_else(|| Error::InvalidAsciiMap(entry.to_owned()))?;
+            let mut letters = letter.trim().chars();
+            match (letters.next(), letters.next()) {
+                (Some(letter), None) if replacement.is_ascii() => {
+                    map = map.with(letter, replacement.trim())
+                }
+                _ => return Err(Error::InvalidAsciiMap(entry.to_owned())),
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl AsciiMap {
+    /// Replace `letter` with `replacement`, which should be ASCII
+    pub fn with(mut self, letter: char, replacement: &str) -> Self {
+        self.0.retain(|(l, _)| *l != letter);
+        self.0.push((letter, replacement.to_owned()));
+        self
+    }
+
+    /// Returns replacement of `c`, including capitalized replacement of
+    /// its lowercase letter
+    fn replacement(&self, c: char) -> Option<Cow<'_, str>> {
+        let find = |c: char| self.0.iter().find(|(l, _)| *l == c).map(|(_, r)| r);
+        if let Some(replacement) = find(c) {
+            return Some(Cow::Borrowed(replacement));
+        }
+        let mut lower = c.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(l), None) if l != c => find(l).map(|replacement| {
+                let mut chars = replacement.chars();
+                Cow::Owned(match chars.next() {
+                    Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                })
+            }),
+            _ => None,
+        }
+    }
+
+    /// Replace Latin letters with diacritics by their closest ASCII
+    /// letters, or replacements of this map, like [`ascii_fold`]
+    pub fn fold(&self, text: &str) -> String {
+        let chars = text.chars().collect::<Vec<char>>();
+        let mut output = String::with_capacity(text.len());
+        for (i, &c) in chars.iter().enumerate() {
+            let replacement = self.replacement(c);
+            if replacement.is_none() && c.is_ascii() {
+                output.push(c);
+                continue;
+            }
+            let replacement = replacement.or_else(|| {
+                LETTERS
+                    .iter()
+                    .find(|(letter, _)| *letter == c)
+                    .map(|(_, replacement)| Cow::Borrowed(*replacement))
+            });
+            match replacement {
+                // Uppercase digraph is followed or preceded by uppercase letter
+                Some(replacement)
+                    if replacement.len() > 1
+                        && c.is_uppercase()
+                        && (matches!(chars.get(i + 1), Some(n) if n.is_uppercase())
+                            || matches!(i.checked_sub(1).map(|p| chars[p]), Some(p) if p.is_uppercase())) =>
+                {
+                    output.push_str(&replacement.to_uppercase())
+                }
+                Some(replacement) => output.push_str(&replacement),
+                None => output.push(UNKNOWN),
+            }
+        }
+        output
+    }
+}
+
+/// Replace Latin letters with diacritics by their closest ASCII letters
+/// (ie. `č` → `c`, `đ` → `dj`). Other non-ASCII characters are replaced
+/// with `_`, so the result is safe for file systems such as FAT.
+/// Transliterate Cyrillic text into Latin before folding it.
+///
+/// # Arguments
+///
+/// * `text` - Text to fold
+///
+/// # Example
+///
+/// ```
+/// use translitrs::ascii_fold;
+///
+/// assert_eq!(ascii_fold("Đorđe Balašević"), "Djordje Balasevic");
+/// assert_eq!(ascii_fold("ĐURĐEVDAN"), "DJURDJEVDAN");
+/// assert_eq!(ascii_fold("Пјесма"), "______");
+/// ```
+pub fn ascii_fold(text: &str) -> String {
+    AsciiMap::default().fold(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_fold() {
+        for (text, expected) in [
+            ("", ""),
+            ("Ljubičica 1.mp3", "Ljubicica 1.mp3"),
+            ("ǈubičica", "Ljubicica"),
+            ("ǇUBIČICA", "LJUBICICA"),
+            ("Đ", "Dj"),
+            ("ĐA", "DJA"),
+            ("O‘zbekiston", "O'zbekiston"),
+            ("„Naslov” – opis…", "\"Naslov\" - opis..."),
+            ("Oʻzbekiston", "O'zbekiston"),
+            ("Ærøskøbing", "Aeroskobing"),
+            ("日本", "__"),
+        ] {
+            assert_eq!(ascii_fold(text), expected);
+        }
+    }
+
+    #[test]
+    fn test_ascii_map() {
+        let map: AsciiMap = "đ=d, ђ=dj, ž=zh, Ž=Z, ´=".parse().unwrap();
+        for (text, expected) in [
+            ("Đorđe", "Dorde"),
+            ("ĐURĐEVDAN", "DURDEVDAN"),
+            ("Ђорђе", "Dj__dj_"),
+            ("ЂОРЂЕ", "DJ__DJ_"),
+            ("Žiža", "Zizha"),
+            ("ŽAR", "ZAR"),
+            ("ka´", "ka"),
+            ("čaša", "casa"),
+        ] {
+            assert_eq!(map.fold(text), expected, "{}", text);
+        }
+        for map in ["đ", "đđ=d", "đ=ђ"] {
+            assert!(map.parse::<AsciiMap>().is_err(), "{}", map);
+        }
+        assert_eq!("".parse::<AsciiMap>().unwrap(), AsciiMap::default());
+    }
+}
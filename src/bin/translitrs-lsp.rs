@@ -0,0 +1,299 @@
+//! Language server which offers transliteration code actions and warns
+//! about words which mix Latin and Cyrillic letters. It communicates with
+//! the editor over standard input and output.
+
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+
+use serde_json::{json, Value};
+use translitrs::{Charset, Direction, Options};
+
+/// Read a message framed with `Content-Length` header
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+/// Returns byte offset of an LSP position (line and UTF-16 column)
+fn offset(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+    let start = text
+        .split_inclusive('\n')
+        .take(line)
+        .map(str::len)
+        .sum::<usize>();
+    let mut units = 0;
+    for (i, c) in text[start..].char_indices() {
+        if units >= character || c == '\n' {
+            return start + i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+/// Returns LSP position of a byte offset
+fn position(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character = before[start..].encode_utf16().count();
+    json!({ "line": line, "character": character })
+}
+
+/// Returns byte ranges of words with both Latin and Cyrillic letters
+fn mixed_words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, c) in text.char_indices().chain(Some((text.len(), ' '))) {
+        match (c.is_alphabetic(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                let word = &text[s..i];
+                let latin = word.chars().any(|c| Charset::of(c) == Some(Charset::Latin));
+                let cyrillic = word
+                    .chars()
+                    .any(|c| Charset::of(c) == Some(Charset::Cyrillic));
+                if latin && cyrillic {
+                    words.push(s..i);
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+struct Server {
+    options: Options,
+    documents: HashMap<String, String>,
+}
+
+impl Server {
+    fn diagnostics(&self, uri: &str) -> Value {
+        let text = self
+            .documents
+            .get(uri)
+            .map(String::as_str)
+            .unwrap_or_default();
+        let diagnostics = mixed_words(text)
+            .into_iter()
+            .map(|word| {
+                json!({
+                    "range": {
+                        "start": position(text, word.start),
+                        "end": position(text, word.end),
+                    },
+                    "severity": 2,
+                    "source": env!("CARGO_PKG_NAME"),
+                    "message": "Word mixes Latin and Cyrillic letters",
+                })
+            })
+            .collect::<Vec<Value>>();
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    /// Returns `text` converted into `into` script
+    fn convert(&self, text: &str, into: Charset) -> Option<String> {
+        let from = match into {
+            Charset::Cyrillic => Charset::Latin,
+            _ => Charset::Cyrillic,
+        };
+        let options = Options {
+            direction: Some(Direction { from, into }),
+            force_foreign: Some(true),
+            ..self.options.clone()
+        };
+        options.transliterator().ok()?.process(text).ok()
+    }
+
+    fn code_actions(&self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let text = match self.documents.get(uri) {
+            Some(text) => text,
+            None => return json!([]),
+        };
+        let mut actions = Vec::new();
+        let mut action =
+            |title: String, range: &Value, into: Charset, diagnostic: Option<&Value>| {
+                let (start, end) = (offset(text, &range["start"]), offset(text, &range["end"]));
+                if start >= end {
+                    return;
+                }
+                let selection = &text[start..end];
+                if let Some(converted) = self.convert(selection, into) {
+                    if converted != selection {
+                        actions.push(json!({
+                        "title": title,
+                        "kind": if diagnostic.is_some() { "quickfix" } else { "refactor.rewrite" },
+                        "diagnostics": diagnostic.into_iter().collect::<Vec<&Value>>(),
+                        "edit": { "changes": { uri: [{ "range": range, "newText": converted }] } },
+                    }));
+                    }
+                }
+            };
+        let empty = Vec::new();
+        for diagnostic in params["context"]["diagnostics"]
+            .as_array()
+            .unwrap_or(&empty)
+            .iter()
+            .filter(|d| d["source"] == env!("CARGO_PKG_NAME"))
+        {
+            // Mixed word is converted into script of most of its letters
+            let range = &diagnostic["range"];
+            let (start, end) = (offset(text, &range["start"]), offset(text, &range["end"]));
+            let word = text.get(start..end).unwrap_or_default();
+            let (into, name) = match Charset::detect(word) {
+                Some(Charset::Cyrillic) => (Charset::Cyrillic, "Cyrillic"),
+                _ => (Charset::Latin, "Latin"),
+            };
+            action(format!("Convert word to {}", name), range, into, Some(diagnostic));
+        }
+        for (into, name) in [(Charset::Cyrillic, "Cyrillic"), (Charset::Latin, "Latin")] {
+            action(format!("Convert selection to {}", name), &params["range"], into, None);
+        }
+        Value::Array(actions)
+    }
+
+    /// Handle a message. Returns messages to send to the editor.
+    fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned();
+        let result = match message["method"].as_str().unwrap_or_default() {
+            "initialize" => json!({
+                "capabilities": { "textDocumentSync": 1, "codeActionProvider": true },
+                "serverInfo": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+            "shutdown" => Value::Null,
+            "textDocument/codeAction" => self.code_actions(params),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_owned());
+                return vec![self.diagnostics(&uri)];
+            }
+            "textDocument/didChange" => {
+                // Text is always synchronized in full
+                if let Some(change) = params["contentChanges"].as_array().and_then(|c| c.last()) {
+                    let text = change["text"].as_str().unwrap_or_default();
+                    self.documents.insert(uri.clone(), text.to_owned());
+                }
+                return vec![self.diagnostics(&uri)];
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![self.diagnostics(&uri)];
+            }
+            _ if message.get("id").is_some() => {
+                return vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": message["id"],
+                    "error": { "code": -32601, "message": "Method not found" },
+                })]
+            }
+            _ => return vec![],
+        };
+        vec![json!({ "jsonrpc": "2.0", "id": message["id"], "result": result })]
+    }
+}
+
+/// Read options from `translit.toml` in current or the closest parent directory
+fn load_options() -> Options {
+    let options = env::current_dir()
+        .map_err(translitrs::Error::from)
+        .and_then(Options::discover);
+    match options {
+        Ok(options) => options.map(|(_, options)| options).unwrap_or_default(),
+        Err(e) => {
+            eprint!("{}", e);
+            Options::default()
+        }
+    }
+}
+
+fn main() {
+    let mut server = Server {
+        options: load_options(),
+        documents: HashMap::new(),
+    };
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut output = io::stdout();
+    let mut shutdown = false;
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+        match message["method"].as_str() {
+            Some("exit") => std::process::exit(if shutdown { 0 } else { 1 }),
+            Some("shutdown") => shutdown = true,
+            _ => {}
+        }
+        for response in server.handle(&message) {
+            if let Err(e) = write_message(&mut output, &response) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions() {
+        let text = "Prva linija\nДругa 𝄞 linija\n";
+        assert_eq!(mixed_words(text), vec![12..21]);
+        assert_eq!(position(text, 12), json!({ "line": 1, "character": 0 }));
+        let end = text.rfind(" linija\n").unwrap();
+        assert_eq!(position(text, end), json!({ "line": 1, "character": 8 }));
+        assert_eq!(offset(text, &json!({ "line": 1, "character": 8 })), end);
+        assert_eq!(offset(text, &json!({ "line": 0, "character": 99 })), 11);
+    }
+}
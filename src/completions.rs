@@ -0,0 +1,465 @@
+use std::str::FromStr;
+
+/// Shell for which completions are generated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Values accepted by an option
+enum Value {
+    None,
+    Path,
+    #[cfg_attr(not(feature = "serve"), allow(dead_code))]
+    Text,
+    Choices(&'static [&'static str]),
+}
+
+/// Command line option as (short, long, value, description)
+type Flag = (Option<char>, &'static str, Value, &'static str);
+
+const CHARSETS: &[&str] = &["latin", "latin8", "cyrillic"];
+const LANGUAGES: &[&str] = &[
+    "serbian",
+    "croatian",
+    "bosnian",
+    #[cfg(feature = "kazakh")]
+    "kazakh",
+    #[cfg(feature = "uzbek")]
+    "uzbek",
+];
+#[cfg(feature = "dialect")]
+const DIALECTS: &[&str] = &["ekavian", "ijekavian"];
+const ARCHAIC: &[&str] = &["ignore", "modernize", "preserve", "annotate"];
+const COMPOUNDS: &[&str] = &["whole", "hyphens", "apostrophes", "all"];
+const ALPHANUMERIC: &[&str] = &["transliterate", "suffixes", "keep"];
+
+const LETTERS: &[&str] = &["convert", "keep", "serbian"];
+const DIRECTIONS: &[&str] = &[
+    #[cfg(feature = "clipboard")]
+    "auto",
+    "lat2cyr",
+    "cyr2lat",
+    "lat2lat8",
+    "lat82lat",
+    "lat82cyr",
+    "cyr2lat8",
+];
+const SOURCE_LANGUAGES: &[&str] = &["rust", "c", "python", "javascript"];
+const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+const SUBCOMMANDS: &[(&str, &str)] = &[
+    ("detect", "report scripts of text"),
+    ("learn", "learn digraph exceptions from a parallel corpus"),
+    ("coverage", "report use of character maps and exceptions by a corpus"),
+    ("grep", "print lines matching a pattern in either script"),
+    ("rename", "rename files and directories"),
+    ("filter", "run as git clean or smudge filter"),
+    #[cfg(feature = "clipboard")]
+    ("clip", "convert clipboard contents"),
+    #[cfg(feature = "serve")]
+    ("serve", "run HTTP conversion server"),
+    #[cfg(feature = "watch")]
+    ("watch", "convert changed files continuously"),
+    #[cfg(feature = "site")]
+    ("site", "generate a static site in the other script"),
+    #[cfg(feature = "pdf")]
+    ("pdf", "extract and convert text of a PDF document"),
+    #[cfg(feature = "tui")]
+    ("preview", "preview conversion while changing options"),
+    ("completions", "print shell completions"),
+];
+
+const FLAGS: &[Flag] = &[
+    #[cfg(feature = "config")]
+    (Some('C'), "config", Value::Path, "read options from configuration file"),
+    (Some('i'), "input", Value::Path, "read input from file"),
+    (Some('o'), "output", Value::Path, "write output to file"),
+    (Some('I'), "in-place", Value::None, "write output to input file"),
+    (Some('b'), "backup", Value::None, "keep original output file as .bak file"),
+    (Some('f'), "from", Value::Choices(CHARSETS), "convert from character set"),
+    (Some('t'), "into", Value::Choices(CHARSETS), "convert to character set"),
+    (None, "direction", Value::Choices(DIRECTIONS), "convert between character sets"),
+    (
+        Some('L'),
+        "language",
+        Value::Choices(LANGUAGES),
+        "follow orthography conventions of a language",
+    ),
+    (
+        Some('a'),
+        "archaic",
+        Value::Choices(ARCHAIC),
+        "treatment of pre-reform Cyrillic letters",
+    ),
+    (
+        None,
+        "compounds",
+        Value::Choices(COMPOUNDS),
+        "split compound words into components",
+    ),
+    (
+        None,
+        "alphanumeric",
+        Value::Choices(ALPHANUMERIC),
+        "treatment of words mixing digits and letters",
+    ),
+    (None, "letters", Value::Choices(LETTERS), "treatment of standalone letters"),
+    #[cfg(feature = "dialect")]
+    (
+        Some('D'),
+        "dialect",
+        Value::Choices(DIALECTS),
+        "convert words into ekavian or ijekavian",
+    ),
+    (Some('d'), "skip-digraph", Value::None, "do not check for digraph exceptions"),
+    (
+        Some('u'),
+        "force-foreign",
+        Value::None,
+        "process words with foreign and mixed characters",
+    ),
+    (
+        Some('l'),
+        "force-links",
+        Value::None,
+        "process hyperlinks, email addresses and units",
+    ),
+    (Some('r'), "force-roman", Value::None, "process Roman numerals"),
+    (None, "fix-ocr", Value::None, "fix common OCR errors before transliterating"),
+    (
+        None,
+        "fold",
+        Value::Text,
+        "classes of compatibility characters folded before transliterating",
+    ),
+    (
+        None,
+        "titlecase-digraphs",
+        Value::None,
+        "write titlecase Latin digraphs as ǅ, ǈ and ǋ",
+    ),
+    #[cfg(feature = "dates")]
+    (None, "dates", Value::None, "transliterate dates written without spaces"),
+    (
+        None,
+        "skip-converted",
+        Value::None,
+        "leave text already in the target script intact",
+    ),
+    (
+        None,
+        "max-input",
+        Value::Text,
+        "fail on longer input instead of transliterating it",
+    ),
+    (
+        None,
+        "max-word",
+        Value::Text,
+        "fail on longer words instead of transliterating them",
+    ),
+    #[cfg(feature = "ngram")]
+    (
+        None,
+        "ngram",
+        Value::None,
+        "resolve ambiguous digraphs with a statistical model",
+    ),
+    #[cfg(feature = "gazetteer")]
+    (
+        None,
+        "gazetteer",
+        Value::None,
+        "keep international brand and personal names in Latin",
+    ),
+    (None, "ansi", Value::None, "pass ANSI escape sequences (ie. colors) through"),
+    #[cfg(feature = "detect-language")]
+    (Some('g'), "detect-language", Value::None, "skip sentences in foreign languages"),
+    #[cfg(feature = "pandoc")]
+    (Some('p'), "pandoc-filter", Value::None, "run in Pandoc JSON pipe filter mode"),
+    #[cfg(feature = "html")]
+    (None, "html", Value::None, "convert text of an HTML document"),
+    #[cfg(feature = "feed")]
+    (None, "feed", Value::None, "convert titles and content of an RSS or Atom feed"),
+    #[cfg(feature = "geo")]
+    (None, "gpx", Value::None, "convert names and descriptions of a GPX file"),
+    #[cfg(feature = "geo")]
+    (None, "kml", Value::None, "convert names and descriptions of a KML document"),
+    #[cfg(feature = "mail")]
+    (None, "mail", Value::None, "convert subject and text of an email message"),
+    (
+        None,
+        "source",
+        Value::Choices(SOURCE_LANGUAGES),
+        "convert comments and strings of source code",
+    ),
+    (None, "yaml", Value::None, "convert string values of a YAML document"),
+    (None, "front-matter", Value::None, "convert a document with YAML front matter"),
+    (None, "ndjson", Value::None, "convert string values of JSON Lines, line by line"),
+    (None, "geojson", Value::None, "add transliterated names to GeoJSON features"),
+    (
+        None,
+        "keys",
+        Value::Text,
+        "convert only YAML or JSON values with these key paths",
+    ),
+    (
+        None,
+        "add-properties",
+        Value::Text,
+        "properties to add transliterated to GeoJSON features",
+    ),
+    (None, "sql", Value::None, "convert string literals of a SQL dump"),
+    (None, "ass", Value::None, "convert dialogue of ASS or SSA subtitles"),
+    (None, "log", Value::None, "convert messages of log lines"),
+    (None, "chat", Value::None, "convert chat messages with Markdown formatting"),
+    (None, "wikitext", Value::None, "convert prose of MediaWiki wikitext"),
+    (None, "vcard", Value::None, "convert display text of vCard contacts"),
+    (None, "ics", Value::None, "convert display text of iCalendar events"),
+    (None, "anki", Value::None, "convert fields of Anki notes exported as plain text"),
+    (None, "speech", Value::None, "normalize transcripts for speech datasets"),
+    (None, "conllu", Value::None, "convert word forms and lemmas of a CoNLL-U file"),
+    (None, "vertical", Value::None, "convert words of a vertical corpus"),
+    (
+        None,
+        "pseudo",
+        Value::None,
+        "pseudolocalize strings of a user interface, one per line",
+    ),
+    (
+        None,
+        "padding",
+        Value::Text,
+        "pad pseudolocalized strings by this percentage of their length",
+    ),
+    (
+        None,
+        "phonetic",
+        Value::None,
+        "convert text typed phonetically (ie. zh, ch, sh)",
+    ),
+    (
+        None,
+        "phonetic-map",
+        Value::Text,
+        "override triggers of phonetic typing with --phonetic",
+    ),
+    (
+        None,
+        "expand",
+        Value::Text,
+        "expand numbers or abbreviations into words with --speech",
+    ),
+    #[cfg(feature = "xlsx")]
+    (None, "xlsx", Value::None, "convert text of cells of an Excel workbook"),
+    #[cfg(feature = "arrow")]
+    (None, "parquet", Value::None, "convert string columns of a Parquet file"),
+    (Some('c'), "check", Value::None, "report lines which would change"),
+    (
+        None,
+        "unmapped",
+        Value::None,
+        "report characters which are neither Latin nor Cyrillic",
+    ),
+    (None, "stats", Value::None, "report counts of transliterated and skipped words"),
+    (None, "strict", Value::None, "fail on characters outside of passthrough classes"),
+    (
+        None,
+        "passthrough",
+        Value::Text,
+        "character classes which are not reported as unmapped",
+    ),
+    #[cfg(feature = "hunspell")]
+    (None, "validate", Value::None, "report converted words not in the word list"),
+    #[cfg(feature = "hunspell")]
+    (
+        None,
+        "dictionary",
+        Value::Path,
+        "word list (Hunspell .dic and .aff) used by --validate",
+    ),
+    (
+        None,
+        "json",
+        Value::None,
+        "print check, stats, validate, unmapped, detect or coverage results as JSON",
+    ),
+    (
+        None,
+        "interactive",
+        Value::None,
+        "confirm or override conversions of ambiguous words",
+    ),
+    (None, "session", Value::Path, "file remembering decisions of --interactive"),
+    (
+        None,
+        "highlight",
+        Value::None,
+        "print converted text with changed words highlighted",
+    ),
+    (
+        None,
+        "show-original",
+        Value::None,
+        "show original words before changed ones with --highlight",
+    ),
+    (Some('n'), "dry-run", Value::None, "print new names without renaming"),
+    (Some('R'), "recursive", Value::None, "rename directory contents too"),
+    (Some('A'), "ascii", Value::None, "replace non-ASCII characters in new names"),
+    (None, "ascii-map", Value::Text, "override ASCII replacements of letters"),
+    #[cfg(feature = "pdf")]
+    (None, "font", Value::Path, "write a PDF document with TrueType font"),
+    #[cfg(feature = "serve")]
+    (None, "listen", Value::Text, "listen on address"),
+    #[cfg(feature = "serve")]
+    (None, "workers", Value::Text, "number of requests handled at the same time"),
+    #[cfg(feature = "serve")]
+    (None, "max-body", Value::Text, "largest accepted request body"),
+    #[cfg(feature = "site")]
+    (None, "base", Value::Text, "URL path of the output tree"),
+    (Some('v'), "version", Value::None, "show version and quit"),
+    (Some('h'), "help", Value::None, "show usage help and quit"),
+];
+
+fn bash(name: &str) -> String {
+    let mut script = String::new();
+    script.push_str(&format!("_{}() {{\n", name));
+    script.push_str("    local cur prev\n");
+    script.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    script.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    script.push_str("    case \"$prev\" in\n");
+    for (short, long, value, _) in FLAGS {
+        let pattern = match short {
+            Some(s) => format!("-{}|--{}", s, long),
+            None => format!("--{}", long),
+        };
+        match value {
+            Value::None => {}
+            Value::Text => script.push_str(&format!("        {}) return ;;\n", pattern)),
+            Value::Path => script.push_str(&format!(
+                "        {}) COMPREPLY=($(compgen -f -- \"$cur\")); return ;;\n",
+                pattern
+            )),
+            Value::Choices(choices) => script.push_str(&format!(
+                "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return ;;\n",
+                pattern,
+                choices.join(" ")
+            )),
+        }
+    }
+    script.push_str("        completions) COMPREPLY=($(compgen -W \"");
+    script.push_str(&SHELLS.join(" "));
+    script.push_str("\" -- \"$cur\")); return ;;\n");
+    script.push_str("    esac\n");
+    let flags = FLAGS
+        .iter()
+        .flat_map(|(short, long, _, _)| {
+            short
+                .map(|s| format!("-{}", s))
+                .into_iter()
+                .chain(Some(format!("--{}", long)))
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    let subcommands = SUBCOMMANDS
+        .iter()
+        .map(|(s, _)| *s)
+        .collect::<Vec<&str>>()
+        .join(" ");
+    script.push_str("    if [[ $COMP_CWORD -eq 1 && \"$cur\" != -* ]]; then\n");
+    script
+        .push_str(&format!("        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n", subcommands));
+    script.push_str("    elif [[ \"$cur\" == -* ]]; then\n");
+    script.push_str(&format!("        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n", flags));
+    script.push_str("    else\n");
+    script.push_str("        COMPREPLY=($(compgen -f -- \"$cur\"))\n");
+    script.push_str("    fi\n");
+    script.push_str("}\n");
+    script.push_str(&format!("complete -F _{} {}\n", name, name));
+    script
+}
+
+fn zsh(name: &str) -> String {
+    let mut script = String::new();
+    script.push_str(&format!("#compdef {}\n\n", name));
+    script.push_str(&format!("_{}() {{\n", name));
+    script.push_str("    _arguments -s \\\n");
+    for (short, long, value, description) in FLAGS {
+        let action = match value {
+            Value::None => String::new(),
+            Value::Text => ":value:".to_owned(),
+            Value::Path => ":path:_files".to_owned(),
+            Value::Choices(choices) => format!(":value:({})", choices.join(" ")),
+        };
+        let spec = match short {
+            Some(s) => format!("'(-{s} --{l})'{{-{s},--{l}}}'", s = s, l = long),
+            None => format!("'--{}", long),
+        };
+        script.push_str(&format!("        {}[{}]{}' \\\n", spec, description, action));
+    }
+    let subcommands = SUBCOMMANDS
+        .iter()
+        .map(|(s, d)| format!("{}\\:\"{}\"", s, d))
+        .collect::<Vec<String>>()
+        .join(" ");
+    script.push_str(&format!("        '1::command:(({}))' \\\n", subcommands));
+    script.push_str("        '*:path:_files'\n");
+    script.push_str("}\n\n");
+    script.push_str(&format!("_{} \"$@\"\n", name));
+    script
+}
+
+fn fish(name: &str) -> String {
+    let mut script = String::new();
+    for (subcommand, description) in SUBCOMMANDS {
+        script.push_str(&format!(
+            "complete -c {} -n '__fish_use_subcommand' -f -a {} -d '{}'\n",
+            name, subcommand, description
+        ));
+    }
+    script.push_str(&format!(
+        "complete -c {} -n '__fish_seen_subcommand_from completions' -f -a '{}'\n",
+        name,
+        SHELLS.join(" ")
+    ));
+    for (short, long, value, description) in FLAGS {
+        let mut line = format!("complete -c {}", name);
+        if let Some(s) = short {
+            line.push_str(&format!(" -s {}", s));
+        }
+        line.push_str(&format!(" -l {}", long));
+        match value {
+            Value::None => {}
+            Value::Text => line.push_str(" -x"),
+            Value::Path => line.push_str(" -r -F"),
+            Value::Choices(choices) => line.push_str(&format!(" -x -a '{}'", choices.join(" "))),
+        }
+        line.push_str(&format!(" -d '{}'\n", description));
+        script.push_str(&line);
+    }
+    script
+}
+
+/// Returns completion script for a shell
+pub fn generate(shell: Shell, name: &str) -> String {
+    match shell {
+        Shell::Bash => bash(name),
+        Shell::Zsh => zsh(name),
+        Shell::Fish => fish(name),
+    }
+}
@@ -0,0 +1,244 @@
+//! Reference corpus for checking that other implementations (ie. ports
+//! and bindings for other languages) transliterate like this crate.
+
+use crate::transliterate::{Charset, Direction};
+
+/// Text in Serbian Latin and its Cyrillic transliteration, with default
+/// transliterator options
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Case {
+    pub latin: &'static str,
+    pub cyrillic: &'static str,
+    /// Cyrillic text is transliterated back into the same Latin text,
+    /// as reported by [`is_roundtrip_safe`](crate::is_roundtrip_safe)
+    pub reversible: bool,
+}
+
+/// Output of an implementation which differs from the reference
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    pub direction: Direction,
+    pub input: &'static str,
+    pub expected: &'static str,
+    pub actual: String,
+}
+
+/// Alphabet, digraphs and common sentences
+pub const EXAMPLES: &[Case] = &[
+    Case {
+        latin: "",
+        cyrillic: "",
+        reversible: true,
+    },
+    Case {
+        latin: "1234567890",
+        cyrillic: "1234567890",
+        reversible: true,
+    },
+    Case {
+        latin: "A B V G D Đ E Ž Z I J K L Lj M N Nj O P R S T Ć U F H C Č Dž Š a b v g d đ e ž z i j k l lj m n nj o p r s t ć u f h c č dž š",
+        cyrillic: "А Б В Г Д Ђ Е Ж З И Ј К Л Љ М Н Њ О П Р С Т Ћ У Ф Х Ц Ч Џ Ш а б в г д ђ е ж з и ј к л љ м н њ о п р с т ћ у ф х ц ч џ ш",
+        reversible: true,
+    },
+    Case {
+        latin: "Stala mala Mara na kraj stara hana sama.",
+        cyrillic: "Стала мала Мара на крај стара хана сама.",
+        reversible: true,
+    },
+    Case {
+        latin: "Nevesele snene žene plele teške mreže",
+        cyrillic: "Невеселе снене жене плеле тешке мреже",
+        reversible: true,
+    },
+    Case {
+        latin: "Javorov jaram, javorova ralica, ralo drvo javorovo.",
+        cyrillic: "Јаворов јарам, јаворова ралица, рало дрво јаворово.",
+        reversible: true,
+    },
+    Case {
+        latin: "Jesi li to ti to tu? Jesi li to tu ti? Jesi li to ti tu? Jesi li tu ti to?",
+        cyrillic: "Јеси ли то ти то ту? Јеси ли то ту ти? Јеси ли то ти ту? Јеси ли ту ти то?",
+        reversible: true,
+    },
+    Case {
+        latin: "Adjektivisati|ZABLUDJE|odžvać|PredŽivot|kenjon|konjug|TANJug",
+        cyrillic: "Адјективисати|ЗАБЛУДЈЕ|оджваћ|ПредЖивот|кенјон|конјуг|ТАНЈуг",
+        reversible: true,
+    },
+    Case {
+        latin: "A B V G D Đ Ð DJ Dj E Z Ž Ž I J K L LJ Ǉ Lj ǈ M N NJ Ǌ Nj ǋ O P R S T Ć Ć U F H C Č Č DŽ Ǆ DŽ Dž ǅ Dž Š Š",
+        cyrillic: "А Б В Г Д Ђ Ђ Ђ Ђ Е З Ж Ж И Ј К Л Љ Љ Љ Љ М Н Њ Њ Њ Њ О П Р С Т Ћ Ћ У Ф Х Ц Ч Ч Џ Џ Џ Џ Џ Џ Ш Ш",
+        reversible: false,
+    },
+    Case {
+        latin: "a æ b v g d đ dj e z ž ž i ĳ j k l lj ǉ m n nj ǌ o œ p r s ﬆ t ć ć u f ﬁ ﬂ h c č č dž ǆ dž š š",
+        cyrillic: "а ае б в г д ђ ђ е з ж ж и иј ј к л љ љ м н њ њ о ое п р с ст т ћ ћ у ф фи фл х ц ч ч џ џ џ ш ш",
+        reversible: false,
+    },
+    Case {
+        latin: "A B V G D Đ Ð DJ Dj e z ž ž i ĳ j k l lj ǉ M N NJ Ǌ Nj ǋ O P R s ﬆ t ć ć u f ﬁ ﬂ h c č č dž Ǆ DŽ Dž ǅ Dž Š Š",
+        cyrillic: "А Б В Г Д Ђ Ђ Ђ Ђ е з ж ж и иј ј к л љ љ М Н Њ Њ Њ Њ О П Р с ст т ћ ћ у ф фи фл х ц ч ч џ Џ Џ Џ Џ Џ Ш Ш",
+        reversible: false,
+    },
+];
+
+/// Digraph capitalization and exceptions, links, numerals and foreign words
+pub const CORPUS: &[Case] = &[
+    Case {
+        latin: "Ljubiša, ljubiša, LJUBIŠA, Naljutiti, NALJUTITI",
+        cyrillic: "Љубиша, љубиша, ЉУБИША, Наљутити, НАЉУТИТИ",
+        reversible: true,
+    },
+    Case {
+        latin: "Njiva, njiva, NJIVA, Anja, ANJA",
+        cyrillic: "Њива, њива, ЊИВА, Ања, АЊА",
+        reversible: true,
+    },
+    Case {
+        latin: "Džonatan, džonatan, DŽONATAN, Midžor, MIDŽOR",
+        cyrillic: "Џонатан, џонатан, ЏОНАТАН, Миџор, МИЏОР",
+        reversible: true,
+    },
+    Case {
+        latin: "Đorđe, ĐORĐE, Džon i DŽON",
+        cyrillic: "Ђорђе, ЂОРЂЕ, Џон и ЏОН",
+        reversible: true,
+    },
+    Case {
+        latin: "Konjugacija i injekcija",
+        cyrillic: "Конјугација и инјекција",
+        reversible: true,
+    },
+    Case {
+        latin: "Nadživeti, podjarmiti i nadžnjeti",
+        cyrillic: "Надживети, подјармити и наджњети",
+        reversible: true,
+    },
+    Case {
+        latin: "Vanjezički, tanjug, Tanjug, tANJUG",
+        cyrillic: "Ванјезички, танјуг, Танјуг, тАНЈУГ",
+        reversible: true,
+    },
+    Case {
+        latin: "Posetite example.com ili pišite na info@example.com.",
+        cyrillic: "Посетите example.com или пишите на info@example.com.",
+        reversible: true,
+    },
+    Case {
+        latin: "https://www.google.com/?q=Beograd",
+        cyrillic: "https://www.google.com/?q=Beograd",
+        reversible: true,
+    },
+    Case {
+        latin: "Brzina od 100 km/h",
+        cyrillic: "Брзина од 100 km/h",
+        reversible: true,
+    },
+    Case {
+        latin: "Petar II i Luj XIV",
+        cyrillic: "Петар II и Луј XIV",
+        reversible: true,
+    },
+    Case {
+        latin: "Krk 2:1, MI, LI, DIV",
+        cyrillic: "Крк 2:1, МИ, ЛИ, ДИВ",
+        reversible: true,
+    },
+    Case {
+        latin: "Ovo je João.",
+        cyrillic: "Ово је João.",
+        reversible: true,
+    },
+    Case {
+        latin: "Ljudi (LJudi)",
+        cyrillic: "Људи (Људи)",
+        reversible: false,
+    },
+];
+
+/// Returns all cases of the corpus
+pub fn cases() -> impl Iterator<Item = &'static Case> {
+    EXAMPLES.iter().chain(CORPUS)
+}
+
+/// Run an implementation against the corpus. Every case is converted from
+/// Latin into Cyrillic, and reversible cases from Cyrillic into Latin.
+/// Returns outputs which differ from the reference.
+///
+/// # Arguments
+///
+/// * `convert` - Transliterate text in given direction with default options
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{conformance, Transliterator};
+///
+/// let mismatches = conformance::run(|direction, text| {
+///     Transliterator::new(direction.from, direction.into, false, false, false)
+///         .process(text)
+///         .unwrap()
+/// });
+/// assert!(mismatches.is_empty());
+/// ```
+pub fn run<F>(mut convert: F) -> Vec<Mismatch>
+where
+    F: FnMut(Direction, &str) -> String,
+{
+    let lat2cyr = Direction {
+        from: Charset::Latin,
+        into: Charset::Cyrillic,
+    };
+    let cyr2lat = Direction {
+        from: Charset::Cyrillic,
+        into: Charset::Latin,
+    };
+    let mut mismatches = Vec::new();
+    for case in cases() {
+        let mut check = |direction: Direction, input: &'static str, expected: &'static str| {
+            let actual = convert(direction, input);
+            if actual != expected {
+                mismatches.push(Mismatch {
+                    direction,
+                    input,
+                    expected,
+                    actual,
+                });
+            }
+        };
+        check(lat2cyr, case.latin, case.cyrillic);
+        if case.reversible {
+            check(cyr2lat, case.cyrillic, case.latin);
+        }
+    }
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::{is_roundtrip_safe, Transliterator};
+
+    #[test]
+    fn test_run() {
+        let reference = |direction: Direction, text: &str| {
+            Transliterator::new(direction.from, direction.into, false, false, false)
+                .process(text)
+                .unwrap()
+        };
+        assert_eq!(run(reference), vec![]);
+
+        // Reversible cases are exactly the ones which round-trip
+        let lat2cyr = Direction {
+            from: Charset::Latin,
+            into: Charset::Cyrillic,
+        };
+        for case in cases() {
+            assert_eq!(is_roundtrip_safe(case.latin, lat2cyr), case.reversible, "{}", case.latin);
+        }
+
+        let identity = run(|_, text: &str| text.to_owned());
+        assert!(identity.iter().any(|m| m.input == "Ljudi (LJudi)"));
+        assert!(!identity.iter().any(|m| m.input == "1234567890"));
+    }
+}
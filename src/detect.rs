@@ -0,0 +1,147 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::transliterate::Charset;
+
+/// Number of letters from which confidence depends only on their scripts
+const CONFIDENT_LETTERS: usize = 20;
+
+/// Script of a word
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Script {
+    Latin,
+    Cyrillic,
+    /// Both Latin and Cyrillic letters (ie. `Кyћa` with Latin `y` and `a`)
+    Mixed,
+    /// Letters of other scripts only
+    Other,
+}
+
+/// Word and the script of its letters
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word {
+    pub text: String,
+    pub script: Script,
+}
+
+/// Scripts used in a text, as returned by [`detect`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptReport {
+    /// Number of letters
+    pub letters: usize,
+    /// Percentages of letters in each script
+    pub latin: f64,
+    pub cyrillic: f64,
+    pub other: f64,
+    /// Script of most Latin and Cyrillic letters, if there are any
+    pub script: Option<Charset>,
+    /// Confidence in the detected script, from 0 to 1
+    pub confidence: f64,
+    /// Words with letters, in order of the text
+    pub words: Vec<Word>,
+}
+
+impl ScriptReport {
+    /// Returns number of words in `script`
+    pub fn count(&self, script: Script) -> usize {
+        self.words.iter().filter(|w| w.script == script).count()
+    }
+}
+
+/// Detect scripts of a text and its words. Confidence is the share of
+/// Latin and Cyrillic letters in the detected script, reduced for texts
+/// shorter than 20 letters, so documents can be routed by script.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{detect, Charset, Script};
+///
+/// let report = detect("Ћирилица и latinica, Кyћa");
+/// assert_eq!(report.script, Some(Charset::Cyrillic));
+/// assert_eq!(report.letters, 21);
+/// assert_eq!(report.words[2].script, Script::Latin);
+/// assert_eq!(report.count(Script::Mixed), 1);
+/// assert!(report.confidence > 0.5 && report.confidence < 0.6);
+/// ```
+pub fn detect(text: &str) -> ScriptReport {
+    let (mut latin, mut cyrillic, mut other) = (0, 0, 0);
+    let mut words = Vec::new();
+    for word in text.unicode_words() {
+        let (mut l, mut c, mut o) = (0, 0, 0);
+        for letter in word.chars().filter(|c| c.is_alphabetic()) {
+            match Charset::of(letter) {
+                Some(Charset::Cyrillic) => c += 1,
+                Some(_) => l += 1,
+                None => o += 1,
+            }
+        }
+        let script = match (l, c, o) {
+            (0, 0, 0) => continue,
+            (0, 0, _) => Script::Other,
+            (_, 0, _) => Script::Latin,
+            (0, _, _) => Script::Cyrillic,
+            _ => Script::Mixed,
+        };
+        words.push(Word {
+            text: word.to_owned(),
+            script,
+        });
+        latin += l;
+        cyrillic += c;
+        other += o;
+    }
+    let letters = latin + cyrillic + other;
+    let percentage = |n: usize| match letters {
+        0 => 0.0,
+        _ => n as f64 * 100.0 / letters as f64,
+    };
+    let (script, confidence) = match (latin, cyrillic) {
+        (0, 0) => (None, 0.0),
+        (l, c) => {
+            let script = if c > l {
+                Charset::Cyrillic
+            } else {
+                Charset::Latin
+            };
+            let share = l.max(c) as f64 / (l + c) as f64;
+            let length = (l + c).min(CONFIDENT_LETTERS) as f64 / CONFIDENT_LETTERS as f64;
+            (Some(script), share * length)
+        }
+    };
+    ScriptReport {
+        letters,
+        latin: percentage(latin),
+        cyrillic: percentage(cyrillic),
+        other: percentage(other),
+        script,
+        confidence,
+        words,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let report = detect("");
+        assert_eq!(report.script, None);
+        assert_eq!(report.confidence, 0.0);
+        assert!(report.words.is_empty());
+
+        let report = detect("12, 34 — Ελλάδα");
+        assert_eq!(report.script, None);
+        assert_eq!(report.other, 100.0);
+        assert_eq!(report.words.len(), 1);
+        assert_eq!(report.words[0].script, Script::Other);
+
+        let report = detect("Ovo je dugačak tekst na latinici, sa dve ćirilične reči: Београд");
+        assert_eq!(report.script, Some(Charset::Latin));
+        assert_eq!(report.count(Script::Latin), 10);
+        assert_eq!(report.count(Script::Cyrillic), 1);
+        assert_eq!(report.letters, 52);
+        assert!((report.latin - 45.0 * 100.0 / 52.0).abs() < 1e-9);
+        assert!((report.confidence - 45.0 / 52.0).abs() < 1e-9);
+    }
+}
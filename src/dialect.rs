@@ -0,0 +1,413 @@
+use lazy_static::lazy_static;
+use std::borrow::Cow;
+use std::{fmt, str};
+
+use crate::transliterate::{Charset, Error, Language, Transliterator};
+
+/// Reflex of the Old Slavic yat (`ѣ`) used by a text
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dialect {
+    /// Yat is written as `e` (ie. `mleko`, `reč`)
+    Ekavian,
+    /// Yat is written as `ije` or `je` (ie. `mlijeko`, `riječ`)
+    Ijekavian,
+}
+
+/// Converter between ekavian and ijekavian reflexes of words
+#[derive(Clone)]
+pub struct DialectConverter {
+    into: Dialect,
+    exceptions: Vec<String>,
+}
+
+/// Stems written differently in ijekavian and ekavian, as (ijekavian, ekavian)
+const STEMS: &[(&str, &str)] = &[
+    ("bijel", "bel"),
+    ("bjelin", "belin"),
+    ("bijed", "bed"),
+    ("bjež", "bež"),
+    ("cijel", "cel"),
+    ("cijen", "cen"),
+    ("cjelin", "celin"),
+    ("cjelovit", "celovit"),
+    ("cvijet", "cvet"),
+    ("cvjetov", "cvetov"),
+    ("cvjetn", "cvetn"),
+    ("dijel", "del"),
+    ("djelo", "delo"),
+    ("djec", "dec"),
+    ("djetet", "detet"),
+    ("djetinj", "detinj"),
+    ("djevoj", "devoj"),
+    ("djed", "ded"),
+    ("gnijezd", "gnezd"),
+    ("griješ", "greš"),
+    ("htje", "hte"),
+    ("lijek", "lek"),
+    ("ljekar", "lekar"),
+    ("ljekov", "lekov"),
+    ("lijep", "lep"),
+    ("ljepot", "lepot"),
+    ("lijev", "lev"),
+    ("mijenj", "menj"),
+    ("mjer", "mer"),
+    ("mjesec", "mesec"),
+    ("mjest", "mest"),
+    ("mlijek", "mlek"),
+    ("mliječ", "mleč"),
+    ("nedjelj", "nedelj"),
+    ("ocjen", "ocen"),
+    ("pjesm", "pesm"),
+    ("pjesnik", "pesnik"),
+    ("pjev", "pev"),
+    ("pijesak", "pesak"),
+    ("pijesk", "pesk"),
+    ("plijen", "plen"),
+    ("riječ", "reč"),
+    ("rječnik", "rečnik"),
+    ("rijetk", "retk"),
+    ("sjed", "sed"),
+    ("sjenk", "senk"),
+    ("sjever", "sever"),
+    ("slijep", "slep"),
+    ("sljedeć", "sledeć"),
+    ("snijeg", "sneg"),
+    ("snjež", "snež"),
+    ("svijet", "svet"),
+    ("svjetl", "svetl"),
+    ("svjetsk", "svetsk"),
+    ("tijel", "tel"),
+    ("vijek", "vek"),
+    ("vjekov", "vekov"),
+    ("vijest", "vest"),
+    ("vjenčanj", "venčanj"),
+    ("vjer", "ver"),
+    ("vjetar", "vetar"),
+    ("vjetr", "vetr"),
+    ("vrijed", "vred"),
+    ("vrijem", "vrem"),
+    ("zvijer", "zver"),
+    ("zvijezd", "zvezd"),
+];
+
+/// Whole words written differently in ijekavian and ekavian,
+/// as (ijekavian, ekavian)
+const WORDS: &[(&str, &str)] = &[
+    ("cio", "ceo"),
+    ("dio", "deo"),
+    ("dijete", "dete"),
+    ("dvije", "dve"),
+    ("dvjesta", "dvesta"),
+    ("gdje", "gde"),
+    ("htio", "hteo"),
+    ("negdje", "negde"),
+    ("nigdje", "nigde"),
+    ("ondje", "onde"),
+    ("ovdje", "ovde"),
+    ("poslije", "posle"),
+    ("prije", "pre"),
+    ("rijeka", "reka"),
+    ("rijeke", "reke"),
+    ("rijeku", "reku"),
+    ("rijekom", "rekom"),
+    ("rijekama", "rekama"),
+    ("smio", "smeo"),
+    ("srijeda", "sreda"),
+    ("srijedu", "sredu"),
+    ("svugdje", "svugde"),
+    ("umio", "umeo"),
+    ("uvijek", "uvek"),
+    ("volio", "voleo"),
+    ("želio", "želeo"),
+];
+
+/// Prefixes which keep the reflex of a stem (ie. `nevjera`, `nedjelja`)
+const PREFIXES: &[&str] = &["ne"];
+
+/// Words starting with these are never converted from ekavian, because
+/// they only look like ekavian stems (ie. `sedam`, `decembar`)
+const EXCEPTIONS_EKAVIAN: &[&str] = &[
+    "bedem", "bedr", "belgij", "beletr", "celer", "cels", "celul", "cent", "cenzur", "decemb",
+    "decenij", "decimal", "dedukc", "delegac", "delegat", "delfin", "delikat", "delir", "delt",
+    "greška", "grešk", "lekcij", "leksi", "lekt", "merid", "merk", "plenum", "retor", "retro",
+    "retuš", "sedam", "sedl", "sedm", "svetac", "svetc", "sveti", "svetinj", "svetkov", "tele",
+    "verand", "verbal", "verzij", "vertikal", "vremen",
+];
+
+/// Words starting with these are never converted from ijekavian
+const EXCEPTIONS_IJEKAVIAN: &[&str] = &["vremen"];
+
+/// Conversion tables for a single script
+struct Tables {
+    stems: Vec<(String, String)>,
+    words: Vec<(String, String)>,
+    prefixes: Vec<String>,
+    exceptions_ekavian: Vec<String>,
+    exceptions_ijekavian: Vec<String>,
+}
+
+lazy_static! {
+    static ref LATIN: Tables = Tables::new(|s| s.to_owned());
+    static ref CYRILLIC: Tables = Tables::new(to_cyrillic);
+}
+
+fn to_cyrillic(word: &str) -> String {
+    // Written out `dj` in ijekavian is never a digraph
+    Transliterator::new(Charset::Latin, Charset::Cyrillic, false, true, false)
+        .with_language(Language::Bosnian)
+        .process_word(word)
+        .unwrap_or_else(|_| word.to_owned())
+}
+
+impl Tables {
+    fn new(convert: fn(&str) -> String) -> Self {
+        let pairs = |table: &[(&str, &str)]| {
+            table
+                .iter()
+                .map(|(i, e)| (convert(i), convert(e)))
+                .collect()
+        };
+        let list = |table: &[&str]| table.iter().map(|s| convert(s)).collect();
+        Self {
+            stems: pairs(STEMS),
+            words: pairs(WORDS),
+            prefixes: list(PREFIXES),
+            exceptions_ekavian: list(EXCEPTIONS_EKAVIAN),
+            exceptions_ijekavian: list(EXCEPTIONS_IJEKAVIAN),
+        }
+    }
+}
+
+/// Change case of `replacement` to match `original`
+fn match_case(original: &str, replacement: &str) -> String {
+    let mut chars = original.chars();
+    let first_upper = matches!(chars.next(), Some(c) if c.is_uppercase());
+    if first_upper && chars.clone().next().is_some() && chars.all(char::is_uppercase) {
+        return replacement.to_uppercase();
+    }
+    let mut output = String::with_capacity(replacement.len());
+    let mut replacement = replacement.chars();
+    if let Some(c) = replacement.next() {
+        match first_upper {
+            true => output.extend(c.to_uppercase()),
+            false => output.push(c),
+        }
+    }
+    output.extend(replacement);
+    output
+}
+
+impl str::FromStr for Dialect {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ekavian" | "ek" | "e" => Ok(Dialect::Ekavian),
+            "ijekavian" | "ijek" | "ije" => Ok(Dialect::Ijekavian),
+            _ => Err(Error::UnknownDialect(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Dialect::Ekavian => "ekavian",
+            Dialect::Ijekavian => "ijekavian",
+        })
+    }
+}
+
+impl DialectConverter {
+    /// Returns converter of words into a dialect. Works with both Latin
+    /// and Cyrillic words.
+    ///
+    /// # Arguments
+    ///
+    /// * `into` - Convert words into this dialect
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Dialect, DialectConverter};
+    /// let d = DialectConverter::new(Dialect::Ekavian);
+    /// assert_eq!(d.process("Mlijeko je uvijek bijelo."), "Mleko je uvek belo.");
+    /// let d = DialectConverter::new(Dialect::Ijekavian);
+    /// assert_eq!(d.process("Лепа реч"), "Лијепа ријеч");
+    /// ```
+    pub fn new(into: Dialect) -> Self {
+        Self {
+            into,
+            exceptions: Vec::new(),
+        }
+    }
+
+    /// Never convert words starting with any of `exceptions`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Dialect, DialectConverter};
+    /// let d = DialectConverter::new(Dialect::Ijekavian);
+    /// assert_eq!(d.process("Vera i Vesna"), "Vjera i Vesna");
+    /// let d = d.with_exceptions(["vera"]);
+    /// assert_eq!(d.process("Vera i Vesna"), "Vera i Vesna");
+    /// ```
+    pub fn with_exceptions<I, S>(mut self, exceptions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.exceptions
+            .extend(exceptions.into_iter().map(|e| e.as_ref().to_lowercase()));
+        self
+    }
+
+    /// Convert a single word (without spaces) into the dialect
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - Word to convert
+    pub fn process_word<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let start = word.len() - word.trim_start_matches(|c: char| !c.is_alphabetic()).len();
+        let end = word.trim_end_matches(|c: char| !c.is_alphabetic()).len();
+        if start >= end {
+            return Cow::Borrowed(word);
+        }
+        let letters = &word[start..end];
+        let lowercase = letters.to_lowercase();
+        // Byte lengths of lowercase and original letters may differ
+        if lowercase.len() != letters.len() {
+            return Cow::Borrowed(word);
+        }
+        let tables: &'static Tables =
+            match letters.chars().any(|c| matches!(c, '\u{400}'..='\u{4ff}')) {
+                true => &CYRILLIC,
+                false => &LATIN,
+            };
+        let exceptions = match self.into {
+            Dialect::Ekavian => &tables.exceptions_ijekavian,
+            Dialect::Ijekavian => &tables.exceptions_ekavian,
+        };
+        if exceptions
+            .iter()
+            .chain(self.exceptions.iter())
+            .any(|e| lowercase.starts_with(e.as_str()))
+        {
+            return Cow::Borrowed(word);
+        }
+        // Returns (source, target) pair for the dialect being converted into
+        let into = self.into;
+        let direction = move |pair: &'static (String, String)| match into {
+            Dialect::Ekavian => (pair.0.as_str(), pair.1.as_str()),
+            Dialect::Ijekavian => (pair.1.as_str(), pair.0.as_str()),
+        };
+        let replace = |position: usize, source: &str, target: &str| {
+            let mut output = String::with_capacity(word.len() + 2);
+            output.push_str(&word[..start + position]);
+            output.push_str(&match_case(&letters[position..position + source.len()], target));
+            output.push_str(&word[start + position + source.len()..]);
+            output
+        };
+        for (source, target) in tables.words.iter().map(direction) {
+            if lowercase == source {
+                return Cow::Owned(replace(0, source, target));
+            }
+        }
+        let positions = tables
+            .prefixes
+            .iter()
+            .filter(|p| lowercase.starts_with(p.as_str()))
+            .map(String::len);
+        for position in std::iter::once(0).chain(positions) {
+            // Prefer longest matching stem
+            let found = tables
+                .stems
+                .iter()
+                .map(direction)
+                .filter(|(source, _)| lowercase[position..].starts_with(source))
+                .max_by_key(|(source, _)| source.len());
+            if let Some((source, target)) = found {
+                return Cow::Owned(replace(position, source, target));
+            }
+        }
+        Cow::Borrowed(word)
+    }
+
+    /// Convert arbitrary text into the dialect
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to convert
+    pub fn process<S: AsRef<str>>(&self, input: S) -> String {
+        let input = input.as_ref();
+        let mut output = String::with_capacity(input.len());
+        let mut word_start: Option<usize> = None;
+        for (i, c) in input.char_indices() {
+            match (c.is_whitespace(), word_start) {
+                (true, Some(start)) => {
+                    output.push_str(&self.process_word(&input[start..i]));
+                    output.push(c);
+                    word_start = None;
+                }
+                (true, None) => output.push(c),
+                (false, None) => word_start = Some(i),
+                (false, Some(_)) => {}
+            }
+        }
+        if let Some(start) = word_start {
+            output.push_str(&self.process_word(&input[start..]));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ekavian() {
+        let d = DialectConverter::new(Dialect::Ekavian);
+        for (word, expected) in [
+            ("mlijeko", "mleko"),
+            ("Mlijeko,", "Mleko,"),
+            ("MLIJEKO", "MLEKO"),
+            ("djevojka", "devojka"),
+            ("nedjelja", "nedelja"),
+            ("gdje?", "gde?"),
+            ("vrijeme", "vreme"),
+            ("vremena", "vremena"),
+            ("riječ", "reč"),
+            ("ријеч", "реч"),
+            ("Ђевојка", "Ђевојка"),
+            ("Дјевојка", "Девојка"),
+            ("kuća", "kuća"),
+            ("...", "..."),
+        ] {
+            assert_eq!(d.process_word(word), expected);
+        }
+    }
+
+    #[test]
+    fn test_ijekavian() {
+        let d = DialectConverter::new(Dialect::Ijekavian);
+        for (word, expected) in [
+            ("mleko", "mlijeko"),
+            ("devojka", "djevojka"),
+            ("Deca", "Djeca"),
+            ("nevera", "nevjera"),
+            ("uvek", "uvijek"),
+            ("sedam", "sedam"),
+            ("decembar", "decembar"),
+            ("telefon", "telefon"),
+            ("lepota", "ljepota"),
+            ("лепота", "љепота"),
+            ("ВЕРА", "ВЈЕРА"),
+        ] {
+            assert_eq!(d.process_word(word), expected);
+        }
+        let d = d.with_exceptions(["Lep"]);
+        assert_eq!(d.process(" Lepa devojka \n"), " Lepa djevojka \n");
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::HashSet;
+
+use crate::ascii::ascii_fold;
+use crate::detect::Script;
+use crate::transliterate::{Charset, Transliterator};
+
+/// Letters of the Serbian Cyrillic alphabet
+const CYRILLIC: &str = "абвгдђежзијклљмнњопрстћуфхцчџшАБВГДЂЕЖЗИЈКЛЉМНЊОПРСТЋУФХЦЧЏШ";
+
+/// Latin letters which are not in the Serbian alphabet, and Cyrillic
+/// letters they are written with
+const FOREIGN: &[(char, &str)] = &[
+    ('q', "к"),
+    ('w', "в"),
+    ('x', "кс"),
+    ('y', "и"),
+    ('Q', "К"),
+    ('W', "В"),
+    ('X', "Кс"),
+    ('Y', "И"),
+];
+
+/// Reserved words of Rust, Python and JavaScript, which get a `_` suffix
+const RESERVED: &[&str] = &[
+    "False",
+    "None",
+    "Self",
+    "True",
+    "abstract",
+    "and",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "become",
+    "box",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "crate",
+    "debugger",
+    "def",
+    "default",
+    "del",
+    "delete",
+    "do",
+    "dyn",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "extern",
+    "false",
+    "final",
+    "finally",
+    "fn",
+    "for",
+    "from",
+    "function",
+    "global",
+    "if",
+    "impl",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "is",
+    "lambda",
+    "let",
+    "loop",
+    "macro",
+    "match",
+    "mod",
+    "move",
+    "mut",
+    "new",
+    "nonlocal",
+    "not",
+    "null",
+    "or",
+    "override",
+    "package",
+    "pass",
+    "priv",
+    "private",
+    "protected",
+    "pub",
+    "public",
+    "raise",
+    "ref",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "unsafe",
+    "unsized",
+    "use",
+    "var",
+    "virtual",
+    "void",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Case of generated identifiers
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IdentifierCase {
+    /// Lowercase words joined with underscores (ie. `broj_racuna`)
+    #[default]
+    Snake,
+    /// Uppercase words joined with underscores (ie. `BROJ_RACUNA`)
+    Constant,
+    /// Capitalized words after the first one (ie. `brojRacuna`)
+    Camel,
+    /// Capitalized words (ie. `BrojRacuna`)
+    Pascal,
+}
+
+/// Returns a word with its first letter in uppercase, and other letters
+/// in lowercase
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Generates identifiers for programming languages from names written in
+/// either script (ie. of domain models or spreadsheet headers), which are
+/// unique among the ones it has generated. Identifiers are written only
+/// with letters of the target script, ASCII digits and underscores, so
+/// they never mix letters which look the same (ie. Latin `a` and Cyrillic
+/// `а`). Latin identifiers are folded into ASCII, while Cyrillic ones are
+/// valid in languages which allow Unicode identifiers (ie. Rust, Python,
+/// JavaScript). Reserved words of Rust, Python and JavaScript (ie. `type`)
+/// get a `_` suffix, and identifiers which were already generated get a
+/// number.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{IdentifierCase, Identifiers, Script};
+///
+/// let mut ids = Identifiers::new(Script::Latin).with_case(IdentifierCase::Camel);
+/// assert_eq!(ids.generate("Broj računa"), "brojRacuna");
+/// assert_eq!(ids.generate("Број рачуна"), "brojRacuna2");
+/// assert_eq!(ids.generate("Iznos (RSD)"), "iznosRsd");
+/// assert_eq!(ids.generate("Tip"), "tip");
+/// assert_eq!(ids.generate("Type"), "type_");
+/// ```
+pub struct Identifiers {
+    target: Script,
+    case: IdentifierCase,
+    transliterator: Transliterator,
+    reserved: HashSet<String>,
+    used: HashSet<String>,
+}
+
+impl Identifiers {
+    /// Returns a generator of identifiers in Cyrillic if `target` is
+    /// Cyrillic, or in ASCII Latin otherwise
+    pub fn new(target: Script) -> Self {
+        let (from, into) = match target {
+            Script::Cyrillic => (Charset::Latin, Charset::Cyrillic),
+            _ => (Charset::Cyrillic, Charset::Latin),
+        };
+        Self {
+            target,
+            case: IdentifierCase::default(),
+            // Letters of the other script inside words are fixed first
+            transliterator: Transliterator::new(from, into, false, true, true).with_fix_ocr(true),
+            reserved: RESERVED.iter().map(|&w| w.to_owned()).collect(),
+            used: HashSet::new(),
+        }
+    }
+
+    /// Join words of identifiers in this case (snake case by default)
+    pub fn with_case(mut self, case: IdentifierCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Also add a `_` suffix to these words (ie. keywords of other
+    /// languages)
+    pub fn with_reserved<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.reserved
+            .extend(words.into_iter().map(|w| w.as_ref().to_owned()));
+        self
+    }
+
+    /// Returns words of `text` in the target script, which are separated
+    /// by other characters and where a lowercase letter is followed by an
+    /// uppercase letter (ie. `brojRačuna`)
+    fn words(&self, text: &str) -> Vec<String> {
+        let text = self
+            .transliterator
+            .process(text)
+            .unwrap_or_else(|_| text.to_owned());
+        let text = match self.target {
+            Script::Cyrillic => {
+                let letter = |c: char| match FOREIGN.iter().find(|(l, _)| *l == c) {
+                    Some((_, k)) => Some((*k).to_owned()),
+                    None if c.is_ascii_digit() || CYRILLIC.contains(c) => Some(c.to_string()),
+                    None => None,
+                };
+                text.chars()
+                    .map(|c| match letter(c) {
+                        Some(letter) => letter,
+                        // Letters of other alphabets (ie. `ß` or `é`) are
+                        // folded into ASCII and transliterated again
+                        None if c.is_alphabetic() => self
+                            .transliterator
+                            .process(ascii_fold(&c.to_string()))
+                            .unwrap_or_default()
+                            .chars()
+                            .map(|c| letter(c).unwrap_or_else(|| " ".to_owned()))
+                            .collect(),
+                        None => " ".to_owned(),
+                    })
+                    .collect()
+            }
+            _ => ascii_fold(&text)
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { ' ' })
+                .collect::<String>(),
+        };
+        let mut words = Vec::new();
+        for word in text.split_whitespace() {
+            let mut current = String::new();
+            let mut lowercase = false;
+            for c in word.chars() {
+                if lowercase && c.is_uppercase() {
+                    words.push(current);
+                    current = String::new();
+                }
+                lowercase = c.is_lowercase();
+                current.push(c);
+            }
+            words.push(current);
+        }
+        words
+    }
+
+    /// Returns an identifier of `text`, which is different from all
+    /// identifiers generated before. Identifiers of texts without letters
+    /// or digits are `_`, identifiers starting with a digit start with
+    /// `_`, and reserved words end with `_`.
+    pub fn generate(&mut self, text: &str) -> String {
+        let words = self.words(text);
+        let mut identifier = match self.case {
+            IdentifierCase::Snake => words
+                .iter()
+                .map(|w| w.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            IdentifierCase::Constant => words
+                .iter()
+                .map(|w| w.to_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            IdentifierCase::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(n, w)| match n {
+                    0 => w.to_lowercase(),
+                    _ => capitalize(w),
+                })
+                .collect(),
+            IdentifierCase::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+        };
+        if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+            identifier.insert(0, '_');
+        }
+        if self.reserved.contains(&identifier) {
+            identifier.push('_');
+        }
+        let separator = match self.case {
+            IdentifierCase::Snake | IdentifierCase::Constant => "_",
+            IdentifierCase::Camel | IdentifierCase::Pascal => "",
+        };
+        let mut unique = identifier.clone();
+        let mut number = 1;
+        while self.used.contains(&unique) {
+            number += 1;
+            unique = format!("{}{}{}", identifier, separator, number);
+        }
+        self.used.insert(unique.clone());
+        unique
+    }
+}
+
+/// Returns an identifier in snake case of `text` written in either
+/// script, with only letters of the `target` script, ASCII digits and
+/// underscores. Identifiers are in Cyrillic if `target` is Cyrillic, or
+/// in ASCII Latin otherwise. Use [`Identifiers`] for other cases and for
+/// unique identifiers.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{to_identifier, Script};
+///
+/// assert_eq!(to_identifier("Datum rođenja", Script::Latin), "datum_rodjenja");
+/// assert_eq!(to_identifier("Datum rođenja", Script::Cyrillic), "датум_рођења");
+/// assert_eq!(to_identifier("2. kvartal", Script::Latin), "_2_kvartal");
+/// ```
+pub fn to_identifier(text: &str, target: Script) -> String {
+    Identifiers::new(target).generate(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers() {
+        for (text, latin, cyrillic) in [
+            ("", "_", "_"),
+            ("  !? ", "_", "_"),
+            ("Кyћa", "kuca", "кућа"),
+            ("ЉУБАВ", "ljubav", "љубав"),
+            ("brojRačuna", "broj_racuna", "број_рачуна"),
+            ("Wi-Fi lozinka", "wi_fi_lozinka", "ви_фи_лозинка"),
+            ("Cena (€) sa PDV-om", "cena_sa_pdv_om", "цена_са_пдв_ом"),
+            ("Excel 2024", "excel_2024", "ексцел_2024"),
+            ("Straße", "strasse", "страссе"),
+            ("Café ß", "cafe_ss", "цафе_сс"),
+            ("Ελλάδα", "_", "_"),
+        ] {
+            assert_eq!(to_identifier(text, Script::Latin), latin);
+            assert_eq!(to_identifier(text, Script::Cyrillic), cyrillic);
+        }
+
+        let mut ids = Identifiers::new(Script::Cyrillic).with_case(IdentifierCase::Pascal);
+        assert_eq!(ids.generate("ime kupca"), "ИмеКупца");
+        assert_eq!(ids.generate("Име купца"), "ИмеКупца2");
+        assert_eq!(ids.generate("ИМЕ КУПЦА"), "ИмеКупца3");
+        let mut ids = Identifiers::new(Script::Latin).with_case(IdentifierCase::Constant);
+        assert_eq!(ids.generate("ljubav"), "LJUBAV");
+        assert_eq!(ids.generate("Ljubav"), "LJUBAV_2");
+    }
+
+    #[test]
+    fn test_reserved() {
+        for (text, latin, cyrillic) in [
+            ("type", "type_", "типе"),
+            ("Tip", "tip", "тип"),
+            ("Klasa", "klasa", "класа"),
+            ("class", "class_", "цласс"),
+            ("Self", "self_", "селф"),
+        ] {
+            assert_eq!(to_identifier(text, Script::Latin), latin);
+            assert_eq!(to_identifier(text, Script::Cyrillic), cyrillic);
+        }
+        let mut ids = Identifiers::new(Script::Latin).with_case(IdentifierCase::Pascal);
+        assert_eq!(ids.generate("self"), "Self_");
+        assert_eq!(ids.generate("none"), "None_");
+        let mut ids = Identifiers::new(Script::Latin).with_reserved(["record"]);
+        assert_eq!(ids.generate("Record"), "record_");
+        assert_eq!(ids.generate("type"), "type_");
+    }
+}
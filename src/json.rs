@@ -0,0 +1,110 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    pub static ref NUMBER: Regex =
+        Regex::new(r"^-?(?:0|[1-9][0-9]*)(?:\.[0-9]+)?(?:[eE][+-]?[0-9]+)?").unwrap();
+}
+
+/// Returns `text` as a quoted JSON string
+pub fn string(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + 2);
+    output.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// Parses four hexadecimal digits at `position` of `text`
+fn hex(text: &str, position: &mut usize) -> Option<u32> {
+    let digits = text
+        .get(*position..*position + 4)
+        .filter(|digits| digits.bytes().all(|c| c.is_ascii_hexdigit()))?;
+    *position += 4;
+    Some(u32::from_str_radix(digits, 16).unwrap_or_default())
+}
+
+/// Parses a JSON string at `position` of `text`, moves `position` past it
+/// and returns its unescaped contents. Invalid strings return `None`, with
+/// `position` at the unexpected character.
+pub fn parse_string(text: &str, position: &mut usize) -> Option<String> {
+    if !text[*position..].starts_with('"') {
+        return None;
+    }
+    *position += 1;
+    let mut contents = String::new();
+    loop {
+        let c = text[*position..].chars().next().filter(|&c| c >= ' ')?;
+        *position += c.len_utf8();
+        match c {
+            '"' => return Some(contents),
+            '\\' => {
+                let escape = text.as_bytes().get(*position).copied()?;
+                *position += 1;
+                contents.push(match escape {
+                    b'"' => '"',
+                    b'\\' => '\\',
+                    b'/' => '/',
+                    b'b' => '\u{8}',
+                    b'f' => '\u{c}',
+                    b'n' => '\n',
+                    b'r' => '\r',
+                    b't' => '\t',
+                    b'u' => {
+                        let unit = hex(text, position)?;
+                        // Characters outside of the basic plane are
+                        // escaped as surrogate pairs
+                        let c = match (0xd800..0xdc00).contains(&unit)
+                            && text[*position..].starts_with("\\u")
+                        {
+                            true => {
+                                *position += 2;
+                                match hex(text, position)? {
+                                    low @ 0xdc00..=0xdfff => char::from_u32(
+                                        0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00),
+                                    ),
+                                    _ => None,
+                                }
+                            }
+                            false => char::from_u32(unit),
+                        };
+                        c.unwrap_or(char::REPLACEMENT_CHARACTER)
+                    }
+                    _ => {
+                        *position -= 1;
+                        return None;
+                    }
+                });
+            }
+            c => contents.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string() {
+        for (text, expected) in [
+            ("", r#""""#),
+            ("Ћирилица", r#""Ћирилица""#),
+            ("\"a\\b\"", r#""\"a\\b\"""#),
+            ("a\tb\r\n", r#""a\tb\r\n""#),
+            ("\u{1}", r#""\u0001""#),
+        ] {
+            assert_eq!(string(text), expected);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+/// Version of [`normalize_for_key`] output, which is incremented whenever
+/// the output of any input changes. It only changes with a new major
+/// version of the crate.
+pub const KEY_VERSION: u32 = 1;
+
+/// Serbian Cyrillic letters and their keys. Frozen, unlike charmaps of
+/// the transliterator, which change with transliteration rules.
+const CYRILLIC: &[(char, &str)] = &[
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('д', "d"),
+    ('ђ', "dj"),
+    ('е', "e"),
+    ('ж', "z"),
+    ('з', "z"),
+    ('и', "i"),
+    ('ј', "j"),
+    ('к', "k"),
+    ('л', "l"),
+    ('љ', "lj"),
+    ('м', "m"),
+    ('н', "n"),
+    ('њ', "nj"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('ћ', "c"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "h"),
+    ('ц', "c"),
+    ('ч', "c"),
+    ('џ', "dz"),
+    ('ш', "s"),
+];
+
+/// Serbian Latin letters with diacritics and digraph ligatures and their keys
+const LATIN: &[(char, &str)] = &[
+    ('č', "c"),
+    ('ć', "c"),
+    ('đ', "dj"),
+    ('š', "s"),
+    ('ž', "z"),
+    ('ǆ', "dz"),
+    ('ǉ', "lj"),
+    ('ǌ', "nj"),
+];
+
+/// Precomposed lowercase letters and their base letters, one per line
+const DECOMPOSITIONS: &str = include_str!("../data/decompositions.txt");
+
+lazy_static! {
+    static ref BASE_LETTERS: HashMap<char, char> = DECOMPOSITIONS
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut chars = line.chars();
+            match (chars.next(), chars.next(), chars.next(), chars.next()) {
+                (Some(letter), Some(' '), Some(base), None) => Some((letter, base)),
+                _ => None,
+            }
+        })
+        .collect();
+}
+
+/// Returns a key of `text` for caches and deduplication, which is the
+/// same for Cyrillic, Latin and ASCII Latin spellings of a text, and
+/// regardless of case and punctuation (ie. `Ђурђевдан`, `Đurđevdan` and
+/// `DJURDJEVDAN!` have the same key `djurdjevdan`).
+///
+/// Unlike [`Transliterator`](crate::Transliterator), keys don't depend on
+/// transliteration rules, and key of a text never changes within a major
+/// version of the crate. Keys which were created with a different
+/// [`KEY_VERSION`] need to be created again. Letters of other scripts are
+/// lowercased with Unicode rules of the Rust standard library, which may
+/// recognize new letters in later versions of Rust.
+///
+/// Letters are lowercased and Serbian letters are folded into ASCII,
+/// combining marks are removed from both decomposed and precomposed
+/// letters (with a frozen table, so `café` and `cafe` followed by U+0301
+/// have the same key), and each run of other characters (ie. spaces and
+/// punctuation) is replaced with a single space.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::normalize_for_key;
+/// assert_eq!(normalize_for_key("Ђурђевдан"), "djurdjevdan");
+/// assert_eq!(normalize_for_key(" DJURDJEVDAN! "), "djurdjevdan");
+/// assert_eq!(normalize_for_key("Novi   Sad, 2024."), "novi sad 2024");
+/// ```
+pub fn normalize_for_key(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut separator = false;
+    for c in text.chars().flat_map(char::to_lowercase) {
+        // Combining diacritical marks (ie. `c` followed by U+030C)
+        if ('\u{300}'..='\u{36f}').contains(&c) {
+            continue;
+        }
+        if !c.is_alphanumeric() {
+            separator = true;
+            continue;
+        }
+        if separator && !output.is_empty() {
+            output.push(' ');
+        }
+        separator = false;
+        let c = BASE_LETTERS.get(&c).copied().unwrap_or(c);
+        match CYRILLIC
+            .iter()
+            .chain(LATIN)
+            .find(|(letter, _)| *letter == c)
+        {
+            Some((_, key)) => output.push_str(key),
+            None => output.push(c),
+        }
+    }
+    output
+}
+
+/// Returns groups of strings which are the same text written in different
+/// scripts, case or with different diacritics, ie. names of customers
+/// entered in both alphabets. Strings are grouped by their keys of
+/// [`normalize_for_key`], and only groups of at least two strings are
+/// returned, in order of their first strings. Strings without letters or
+/// digits are never duplicates.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::find_cross_script_duplicates;
+/// let names = ["Đorđe Marković", "Petar Petrović", "ЂОРЂЕ МАРКОВИЋ", "Djordje Markovic"];
+/// assert_eq!(
+///     find_cross_script_duplicates(names),
+///     vec![vec!["Đorđe Marković", "ЂОРЂЕ МАРКОВИЋ", "Djordje Markovic"]]
+/// );
+/// ```
+pub fn find_cross_script_duplicates<I, S>(strings: I) -> Vec<Vec<S>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut groups: Vec<Vec<S>> = Vec::new();
+    let mut keys: HashMap<String, usize> = HashMap::new();
+    for string in strings {
+        let key = normalize_for_key(string.as_ref());
+        if key.is_empty() {
+            continue;
+        }
+        match keys.get(&key) {
+            Some(&group) => groups[group].push(string),
+            None => {
+                keys.insert(key, groups.len());
+                groups.push(vec![string]);
+            }
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_for_key() {
+        // Keys must never change without incrementing KEY_VERSION
+        for (text, expected) in [
+            ("", ""),
+            ("  ", ""),
+            ("Љубав и Џем", "ljubav i dzem"),
+            ("Ljubav i Džem", "ljubav i dzem"),
+            ("ǈubav", "ljubav"),
+            ("Ćevapčići", "cevapcici"),
+            ("Ћевапчићи", "cevapcici"),
+            ("C\u{30c}ac\u{30c}ak", "cacak"),
+            ("„Na Drini ćuprija” – Andrić", "na drini cuprija andric"),
+            ("Бр. 12/3", "br 12 3"),
+            ("Αθήνα", "αθηνα"),
+            ("Café Crème", "cafe creme"),
+            ("Cafe\u{301} Cre\u{300}me", "cafe creme"),
+            ("Ελλάδα", "ελλαδα"),
+            ("Ελλα\u{301}δα", "ελλαδα"),
+            ("Чайка Ёлка", "caika elka"),
+            ("Чаи\u{306}ка Е\u{308}лка", "caika elka"),
+            ("ѝ", "i"),
+            ("Москва", "moskva"),
+            ("Ыы", "ыы"),
+        ] {
+            assert_eq!(normalize_for_key(text), expected);
+        }
+    }
+
+    #[test]
+    fn test_find_cross_script_duplicates() {
+        let names = vec![
+            "Ана Ђурић".to_owned(),
+            "Marko Jovanović".to_owned(),
+            "ana đurić".to_owned(),
+            "!!".to_owned(),
+            "Marko Jovanovic".to_owned(),
+            "—".to_owned(),
+            "Ana  Djuric.".to_owned(),
+            "Ана Ђурић".to_owned(),
+            "Јована".to_owned(),
+        ];
+        assert_eq!(
+            find_cross_script_duplicates(&names),
+            vec![
+                vec!["Ана Ђурић", "ana đurić", "Ana  Djuric.", "Ана Ђурић"],
+                vec!["Marko Jovanović", "Marko Jovanovic"],
+            ]
+        );
+        assert!(find_cross_script_duplicates(Vec::<String>::new()).is_empty());
+        // Precomposed and decomposed spellings are duplicates
+        assert_eq!(
+            find_cross_script_duplicates(["Crème brûlée", "Cre\u{300}me bru\u{302}le\u{301}e"]),
+            vec![vec!["Crème brûlée", "Cre\u{300}me bru\u{302}le\u{301}e"]]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::transliterate::{Charset, Language, Transliterator};
+
+/// Lowercase digraphs which can be split by digraph exceptions
+const DIGRAPHS: [[char; 2]; 3] = [['d', 'j'], ['d', 'ž'], ['n', 'j']];
+
+/// Minimum number of letters of a learned exception
+const MIN_LETTERS: usize = 5;
+
+/// Words with more digraphs are skipped
+const MAX_DIGRAPHS: usize = 4;
+
+/// Digraph of a word which is split in its Cyrillic form
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Split {
+    digraph: usize,
+    word: Vec<char>,
+    position: usize,
+}
+
+/// Learns digraph exceptions from a parallel corpus of Latin texts and
+/// their Cyrillic counterparts. Exceptions are learned from words whose
+/// digraph `dj`, `dž` or `nj` is written as two Cyrillic letters but is
+/// transliterated as a single one, and are as short as possible while
+/// matching no word where the digraph is a single letter. Learned
+/// exceptions can be passed to [`Transliterator::with_exceptions`].
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{Charset, Language, Learner, Transliterator};
+///
+/// let mut learner = Learner::new(Language::Serbian);
+/// learner.add("Injunkcija je izdata.\nKonj i njiva", "Инјункција је издата.\nКоњ и њива");
+/// let exceptions = learner.exceptions();
+/// assert_eq!(exceptions, ["injun"]);
+///
+/// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+///     .with_exceptions(&exceptions);
+/// assert_eq!(t.process("Injunkcije").unwrap(), "Инјункције");
+/// ```
+#[derive(Clone)]
+pub struct Learner {
+    language: Language,
+    /// Transliterator with known exceptions
+    known: Transliterator,
+    /// Transliterator without exceptions, used to find split digraphs
+    plain: Transliterator,
+    splits: BTreeSet<Split>,
+    /// Words where each digraph is a single letter
+    joined: [HashSet<String>; 3],
+}
+
+impl Learner {
+    /// Learn exceptions for `language` in addition to its built-in ones
+    pub fn new(language: Language) -> Self {
+        let transliterator = |skip_digraph| {
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, skip_digraph, false, false)
+                .with_language(language)
+        };
+        Self {
+            language,
+            known: transliterator(false),
+            plain: transliterator(true),
+            splits: BTreeSet::new(),
+            joined: Default::default(),
+        }
+    }
+
+    /// Learn only exceptions which are not covered by `exceptions`
+    /// (ie. exceptions from the configuration file)
+    pub fn with_exceptions<I, S>(mut self, exceptions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.known = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_language(self.language)
+            .with_exceptions(exceptions);
+        self
+    }
+
+    /// Collect digraphs from a Latin text and its Cyrillic counterpart.
+    /// Texts are aligned by lines and words, so lines with different
+    /// numbers of words are skipped, as are words which are not
+    /// transliterations of each other.
+    ///
+    /// # Arguments
+    ///
+    /// * `latin` - Text written in Latin
+    /// * `cyrillic` - The same text written in Cyrillic
+    pub fn add(&mut self, latin: &str, cyrillic: &str) {
+        for (latin, cyrillic) in latin.lines().zip(cyrillic.lines()) {
+            let latin = latin.split_whitespace().collect::<Vec<&str>>();
+            let cyrillic = cyrillic.split_whitespace().collect::<Vec<&str>>();
+            if latin.len() != cyrillic.len() {
+                continue;
+            }
+            for (latin, cyrillic) in latin.into_iter().zip(cyrillic) {
+                let trim = |word: &str| {
+                    word.trim_matches(|c: char| !c.is_alphabetic())
+                        .to_lowercase()
+                };
+                self.add_word(&trim(latin), &trim(cyrillic));
+            }
+        }
+    }
+
+    fn add_word(&mut self, latin: &str, cyrillic: &str) {
+        let word = latin.chars().collect::<Vec<char>>();
+        let digraphs = (0..word.len().saturating_sub(1))
+            .filter_map(|i| {
+                DIGRAPHS
+                    .iter()
+                    .position(|d| word[i..i + 2] == d[..])
+                    .map(|n| (n, i))
+            })
+            .collect::<Vec<(usize, usize)>>();
+        if digraphs.is_empty() || digraphs.len() > MAX_DIGRAPHS {
+            return;
+        }
+        // Find which digraphs are split by trying every combination
+        let split = (0..1 << digraphs.len()).find(|combination| {
+            let mut output = String::new();
+            let mut start = 0;
+            for (k, (_, i)) in digraphs.iter().enumerate() {
+                if combination & 1 << k != 0 {
+                    let piece = word[start..i + 1].iter().collect::<String>();
+                    output.push_str(&self.plain.process_word(&piece).unwrap_or(piece));
+                    start = i + 1;
+                }
+            }
+            let piece = word[start..].iter().collect::<String>();
+            output.push_str(&self.plain.process_word(&piece).unwrap_or(piece));
+            output == cyrillic
+        });
+        let combination = match split {
+            Some(combination) => combination,
+            None => return,
+        };
+        let known = self
+            .known
+            .process_word(latin)
+            .map_or(false, |c| c == cyrillic);
+        for (k, (n, i)) in digraphs.into_iter().enumerate() {
+            if combination & 1 << k == 0 {
+                self.joined[n].insert(latin.to_owned());
+            } else if !known {
+                self.splits.insert(Split {
+                    digraph: n,
+                    word: word.clone(),
+                    position: i,
+                });
+            }
+        }
+    }
+
+    /// Returns learned exceptions in lowercase Latin, sorted
+    pub fn exceptions(&self) -> Vec<String> {
+        let mut learned: Vec<(usize, String)> = Vec::new();
+        for split in &self.splits {
+            let word = &split.word;
+            let shortest = (MIN_LETTERS.min(word.len())..=word.len())
+                .flat_map(|length| {
+                    let first = (split.position + 2).saturating_sub(length);
+                    let last = split.position.min(word.len() - length);
+                    (first..=last).map(move |start| start..start + length)
+                })
+                .filter(|range| word[range.clone()].iter().all(|c| c.is_alphabetic()))
+                .map(|range| word[range].iter().collect::<String>())
+                .find(|candidate| {
+                    !self.joined[split.digraph]
+                        .iter()
+                        .any(|joined| joined.contains(candidate.as_str()))
+                });
+            if let Some(exception) = shortest {
+                learned.push((split.digraph, exception));
+            }
+        }
+        // Exceptions containing shorter ones are redundant
+        learned.sort_by_key(|(_, exception)| exception.chars().count());
+        let mut exceptions: Vec<(usize, String)> = Vec::new();
+        for (digraph, exception) in learned {
+            if !exceptions
+                .iter()
+                .any(|(d, e)| *d == digraph && exception.contains(e.as_str()))
+            {
+                exceptions.push((digraph, exception));
+            }
+        }
+        let mut exceptions = exceptions
+            .into_iter()
+            .map(|(_, exception)| exception)
+            .collect::<Vec<String>>();
+        exceptions.sort();
+        exceptions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_learner() {
+        let mut learner = Learner::new(Language::Serbian);
+        learner.add(
+            concat!(
+                "Tanjug, injunkcija i nadživeti\n",
+                "Konj, injekcija, džem i Njegoš\n",
+                "Ova linija ima više reči nego druga\n",
+                "Džudo-Injunkcije\n",
+            ),
+            concat!(
+                "Танјуг, инјункција и наджившти\n",
+                "Коњ, ињекција, џем и Његош\n",
+                "Ова линија има\n",
+                "Џудо-Инјункције\n",
+            ),
+        );
+        // Tanjug is a built-in exception, and nadživeti is misspelled
+        assert_eq!(learner.exceptions(), ["injun"]);
+
+        // Exceptions which are already known are not learned again
+        let mut learner = Learner::new(Language::Serbian).with_exceptions(["injunkc"]);
+        learner.add("Injunkcija, konjunkcija", "Инјункција, конјункција");
+        assert!(learner.exceptions().is_empty());
+    }
+}
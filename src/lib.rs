@@ -1,11 +1,95 @@
+#[cfg(feature = "arrow")]
+mod arrow;
+mod ascii;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+#[cfg(all(test, not(feature = "conformance")))]
+mod conformance;
+mod detect;
+#[cfg(feature = "dialect")]
+mod dialect;
+mod identifier;
+mod json;
+mod key;
+mod learn;
+mod matching;
+mod options;
+mod parallel;
+mod pattern;
+mod phonetic;
 mod process;
+#[cfg(feature = "provenance")]
+mod provenance;
+mod record;
+mod segment;
+#[cfg(feature = "hunspell")]
+mod spell;
 mod transliterate;
+mod unmapped;
 
+#[cfg(feature = "arrow")]
+pub use arrow::{transliterate_array, transliterate_batch, transliterate_column};
+pub use ascii::{ascii_fold, AsciiMap};
+pub use detect::{detect, Script, ScriptReport, Word};
+#[cfg(feature = "dialect")]
+pub use dialect::{Dialect, DialectConverter};
+pub use identifier::{to_identifier, IdentifierCase, Identifiers};
+pub use key::{find_cross_script_duplicates, normalize_for_key, KEY_VERSION};
+pub use learn::Learner;
+pub use matching::{similarity, Matcher};
+pub use options::Options;
+pub use pattern::regex_for_both_scripts;
+pub use phonetic::PhoneticMap;
 pub use process::FileProcessor;
+#[cfg(feature = "provenance")]
+pub use provenance::Provenance;
+pub use record::Record;
 
+#[cfg(feature = "clipboard")]
+pub use process::ClipProcessor;
+#[cfg(feature = "feed")]
+pub use process::FeedProcessor;
+#[cfg(feature = "geo")]
+pub use process::GeoProcessor;
+#[cfg(feature = "mail")]
+pub use process::MailProcessor;
 #[cfg(feature = "pandoc")]
 pub use process::PandocProcessor;
+#[cfg(feature = "arrow")]
+pub use process::ParquetProcessor;
+#[cfg(feature = "pdf")]
+pub use process::PdfProcessor;
+#[cfg(feature = "tui")]
+pub use process::PreviewProcessor;
+#[cfg(feature = "serve")]
+pub use process::ServeProcessor;
+#[cfg(feature = "site")]
+pub use process::SiteProcessor;
+#[cfg(feature = "hunspell")]
+pub use process::ValidateProcessor;
+#[cfg(feature = "watch")]
+pub use process::WatchProcessor;
+#[cfg(feature = "xlsx")]
+pub use process::XlsxProcessor;
+#[cfg(feature = "html")]
+pub use process::{HtmlProcessor, HtmlStream};
+#[cfg(feature = "hunspell")]
+pub use spell::Dictionary;
 
-pub use process::{Error, PlaintextProcessor};
+pub use process::{
+    process_file, AnkiProcessor, AssProcessor, ChatProcessor, CheckProcessor, ConlluProcessor,
+    CoverageProcessor, DetectProcessor, Error, FilterProcessor, GeojsonProcessor, GrepProcessor,
+    HighlightProcessor, LearnProcessor, LogProcessor, NdjsonProcessor, PhoneticProcessor,
+    PlaintextProcessor, PseudoProcessor, RenameProcessor, ReviewProcessor, SourceProcessor,
+    SpeechProcessor, SqlProcessor, StatsProcessor, Syntax, UnmappedProcessor, VcardProcessor,
+    WikiProcessor, YamlProcessor,
+};
 
-pub use transliterate::{Charset, Transliterator};
+pub use segment::{paragraphs, sentences, Paragraphs, Sentences};
+pub use transliterate::{
+    is_roundtrip_safe, negotiate_script, rules_checksum, AlignedToken, Alphanumeric, Archaic,
+    Charset, Composer, Compounds, CoverageReport, Decision, Direction, EntryUsage, ExceptionUsage,
+    Expand, Fold, Language, Letters, LineError, LineErrorKind, Lines, Profile, Stats, Trace,
+    Transliterator, Update, Warning, WarningKind, WordClass, CONVERTED_TOLERANCE, MAX_LINE_LENGTH,
+};
+pub use unmapped::{Sample, UnmappedChar, UnmappedReport};
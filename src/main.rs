@@ -1,13 +1,49 @@
 use std::str::FromStr;
 use std::{env, error, fmt, path};
 
-mod process;
-mod transliterate;
+mod completions;
 
+use completions::Shell;
+
+#[cfg(feature = "clipboard")]
+use translitrs::ClipProcessor;
+#[cfg(feature = "dialect")]
+use translitrs::Dialect;
+#[cfg(feature = "feed")]
+use translitrs::FeedProcessor;
+#[cfg(feature = "geo")]
+use translitrs::GeoProcessor;
+#[cfg(feature = "html")]
+use translitrs::HtmlProcessor;
+#[cfg(feature = "mail")]
+use translitrs::MailProcessor;
 #[cfg(feature = "pandoc")]
-use process::PandocProcessor;
-use process::{FileProcessor, PlaintextProcessor};
-use transliterate::{Charset, Transliterator};
+use translitrs::PandocProcessor;
+#[cfg(feature = "arrow")]
+use translitrs::ParquetProcessor;
+#[cfg(feature = "pdf")]
+use translitrs::PdfProcessor;
+#[cfg(feature = "tui")]
+use translitrs::PreviewProcessor;
+#[cfg(feature = "serve")]
+use translitrs::ServeProcessor;
+#[cfg(feature = "site")]
+use translitrs::SiteProcessor;
+#[cfg(feature = "watch")]
+use translitrs::WatchProcessor;
+#[cfg(feature = "xlsx")]
+use translitrs::XlsxProcessor;
+use translitrs::{
+    Alphanumeric, AnkiProcessor, Archaic, AsciiMap, AssProcessor, Charset, ChatProcessor,
+    CheckProcessor, Compounds, ConlluProcessor, CoverageProcessor, DetectProcessor, Direction,
+    Expand, FileProcessor, FilterProcessor, Fold, GeojsonProcessor, GrepProcessor,
+    HighlightProcessor, Language, LearnProcessor, Learner, Letters, LogProcessor, NdjsonProcessor,
+    Options, PhoneticMap, PhoneticProcessor, PlaintextProcessor, PseudoProcessor, RenameProcessor,
+    ReviewProcessor, SourceProcessor, SpeechProcessor, SqlProcessor, StatsProcessor, Syntax,
+    Transliterator, UnmappedProcessor, VcardProcessor, WikiProcessor, YamlProcessor,
+};
+#[cfg(feature = "hunspell")]
+use translitrs::{Dictionary, ValidateProcessor};
 
 fn version() {
     println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"),);
@@ -18,36 +54,283 @@ fn help() {
     println!();
     println!("USAGE:");
     println!("  {} [OPTIONS]", env!("CARGO_PKG_NAME"));
+    println!("  {} --check [OPTIONS] [<path>...]", env!("CARGO_PKG_NAME"));
+    println!("  {} --unmapped [--json] [<path>...]", env!("CARGO_PKG_NAME"));
+    println!("  {} --stats [--json] [OPTIONS] [<path>...]", env!("CARGO_PKG_NAME"));
+    println!("  {} --interactive [OPTIONS] -i <path>", env!("CARGO_PKG_NAME"));
+    println!("  {} --highlight [--show-original] [OPTIONS]", env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "hunspell")]
+    println!("  {} --validate [OPTIONS] [<path>...]", env!("CARGO_PKG_NAME"));
+    println!("  {} detect [--json] [<path>...]", env!("CARGO_PKG_NAME"));
+    println!("  {} learn [OPTIONS] <latin> <cyrillic>", env!("CARGO_PKG_NAME"));
+    println!("  {} coverage [--json] [OPTIONS] [<path>...]", env!("CARGO_PKG_NAME"));
+    println!("  {} grep [-L <lang>] <pattern> [<path>...]", env!("CARGO_PKG_NAME"));
+    println!("  {} rename [OPTIONS] <path>...", env!("CARGO_PKG_NAME"));
+    println!("  {} filter [OPTIONS]", env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "clipboard")]
+    println!("  {} clip [OPTIONS]", env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "serve")]
+    println!("  {} serve [OPTIONS]", env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "watch")]
+    println!("  {} watch [OPTIONS] <path> --output <path>", env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "site")]
+    println!("  {} site [OPTIONS] <path> --output <path>", env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "pdf")]
+    println!("  {} pdf [OPTIONS] <path>", env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "tui")]
+    println!("  {} preview [OPTIONS] <path>", env!("CARGO_PKG_NAME"));
+    println!("  {} completions <shell>", env!("CARGO_PKG_NAME"));
     println!("  pandoc --filter {} [...]", env!("CARGO_PKG_NAME"));
     println!();
     println!("OPTIONS:");
+    #[cfg(feature = "config")]
+    println!("  -C, --config <path>     read options from configuration file");
+    #[cfg(feature = "config")]
+    println!("                          default: translit.toml in closest directory");
     println!("  -i, --input <path>      read input from file");
     println!("                          default: stdin");
     println!("  -o, --output <path>     write output to file");
     println!("                          default: stdout");
+    println!("  -I, --in-place          write output to input file");
+    println!("  -b, --backup            keep original output file as .bak file");
     println!("  -f, --from <charset>    convert from character set");
     println!("                          default: latin");
     println!("  -t, --into <charset>    convert to character set");
     println!("                          default: cyrillic");
+    println!("      --direction <dir>   convert between character sets (ie. cyr2lat)");
+    #[cfg(feature = "clipboard")]
+    println!("                          clip default: auto (from script of the text)");
+    println!("  -L, --language <lang>   follow orthography conventions of a language");
+    println!("                          default: serbian");
+    println!("  -a, --archaic <mode>    treatment of pre-reform Cyrillic letters");
+    println!("                          default: ignore");
+    println!("      --compounds <mode>  split compound words into components");
+    println!("                          default: whole");
+    println!("      --alphanumeric <mode>");
+    println!("                          treatment of words mixing digits and letters");
+    println!("                          default: suffixes");
+    println!("      --letters <mode>    treatment of standalone letters (ie. a), vitamin D)");
+    println!("                          default: convert");
+    #[cfg(feature = "dialect")]
+    println!("  -D, --dialect <dialect> convert words into ekavian or ijekavian");
     println!("  -d, --skip-digraph      do not check for digraph exceptions");
     println!("  -u, --force-foreign     process words with foreign and mixed characters");
     println!("  -l, --force-links       process hyperlinks, email addresses and units");
+    println!("  -r, --force-roman       process Roman numerals");
+    println!("      --fix-ocr           fix common OCR errors before transliterating");
+    println!("      --fold <classes>    fold compatibility characters before transliterating");
+    println!("                          comma separated (ie. fullwidth,circled) or all");
+    println!("      --titlecase-digraphs");
+    println!("                          write titlecase Latin digraphs as ǅ, ǈ and ǋ");
+    #[cfg(feature = "dates")]
+    println!("      --dates             transliterate dates written without spaces (ie. 1.jan.)");
+    println!("      --skip-converted    leave text already in the target script intact");
+    println!("      --max-input <bytes> fail on longer input instead of transliterating it");
+    println!("      --max-word <bytes>  fail on longer words instead of transliterating them");
+    #[cfg(feature = "ngram")]
+    println!("      --ngram             resolve ambiguous digraphs with a statistical model");
+    #[cfg(feature = "gazetteer")]
+    println!("      --gazetteer         keep international brand and personal names in Latin");
+    println!("      --ansi              pass ANSI escape sequences (ie. colors) through");
+    #[cfg(feature = "detect-language")]
+    println!("  -g, --detect-language   skip sentences in foreign languages");
     #[cfg(feature = "pandoc")]
     println!("  -p, --pandoc-filter     run in Pandoc JSON pipe filter mode");
+    #[cfg(feature = "html")]
+    println!("      --html              convert text of an HTML document");
+    #[cfg(feature = "feed")]
+    println!("      --feed              convert titles and content of an RSS or Atom feed");
+    #[cfg(feature = "geo")]
+    println!("      --gpx               convert names and descriptions of a GPX file");
+    #[cfg(feature = "geo")]
+    println!("      --kml               convert names and descriptions of a KML document");
+    #[cfg(feature = "mail")]
+    println!("      --mail              convert subject and text of an email message");
+    println!("      --source <lang>     convert comments and strings of source code");
+    println!("                          rust, c, python or javascript");
+    println!("      --yaml              convert string values of a YAML document");
+    println!("      --front-matter      convert a document with YAML front matter");
+    println!("      --ndjson            convert string values of JSON Lines, line by line");
+    println!("      --geojson           add transliterated names to GeoJSON features");
+    println!("      --add-properties <properties>");
+    println!("                          properties to add transliterated with --geojson");
+    println!("                          comma separated (ie. name:sr,opis=opis:cyr)");
+    println!("      --keys <paths>      convert only YAML or JSON values with these key paths");
+    println!("                          comma separated (ie. title,seo.*)");
+    #[cfg(feature = "site")]
+    println!("                          with site, front matter keys (default: title,description)");
+    println!("      --sql               convert string literals of a SQL dump");
+    println!("      --ass               convert dialogue of ASS or SSA subtitles");
+    println!("      --log               convert messages of log lines");
+    println!("      --chat              convert chat messages with Markdown formatting");
+    println!("      --wikitext          convert prose of MediaWiki wikitext");
+    println!("      --vcard             convert display text of vCard contacts");
+    println!("      --ics               convert display text of iCalendar events");
+    println!("      --anki              convert fields of Anki notes exported as plain text");
+    println!("                          with --keys, only these fields (or source=new field)");
+    println!("      --speech            normalize transcripts for speech datasets");
+    println!("      --expand <steps>    expand numbers or abbreviations into words with --speech");
+    println!("                          comma separated (ie. numbers,abbreviations) or all");
+    println!("      --conllu            convert word forms and lemmas of a CoNLL-U file");
+    println!("      --vertical          convert words of a vertical corpus");
+    println!("                          with --keys, only these columns (ie. 1,3 or form)");
+    println!("      --pseudo            pseudolocalize strings of a user interface, one per line");
+    println!("      --padding <percent> pad strings by a percentage of their length with --pseudo");
+    println!("                          default: 30");
+    println!("      --phonetic          convert text typed phonetically (ie. zh, ch, sh)");
+    println!("      --phonetic-map <map>");
+    println!("                          override triggers of phonetic typing");
+    println!("                          example: x=ж,cc=ћ,cj=");
+    #[cfg(feature = "xlsx")]
+    println!("      --xlsx              convert text of cells of an Excel workbook");
+    #[cfg(feature = "arrow")]
+    println!("      --parquet           convert string columns of a Parquet file");
+    #[cfg(feature = "arrow")]
+    println!("                          with --keys, only columns with these names");
+    println!("  -c, --check             report lines which would change and exit with 1");
+    println!("      --unmapped          report characters which are neither Latin nor Cyrillic");
+    println!("      --stats             report counts of transliterated and skipped words");
+    #[cfg(feature = "hunspell")]
+    println!(
+        "      --validate          report converted words not in the word list and exit with 1"
+    );
+    #[cfg(feature = "hunspell")]
+    println!("                          (built-in subset of Hunspell affixes, not Hunspell)");
+    #[cfg(feature = "hunspell")]
+    println!("      --dictionary <path> word list (Hunspell .dic and .aff) used by --validate");
+    println!("      --strict            fail on characters outside of passthrough classes");
+    println!("      --passthrough <classes>");
+    println!("                          character classes which are not reported as unmapped");
+    println!("                          comma separated (ie. digits,emoji,Sc,[№°])");
+    println!(
+        "      --json              print check, stats, validate, unmapped, detect or coverage"
+    );
+    println!("                          results as JSON");
+    println!("      --interactive       confirm or override conversions of ambiguous words");
+    println!("      --session <path>    remember decisions of --interactive in this file");
+    println!("                          default: .translit-session");
+    println!("      --highlight         print converted text with changed words highlighted");
+    println!("      --show-original     show original words before changed ones with --highlight");
     println!("  -v, --version           show version and quit");
     println!("  -h, --help              show usage help and quit");
     println!();
+    println!("RENAME OPTIONS:");
+    println!("  -n, --dry-run           print new names without renaming");
+    println!("  -R, --recursive         rename directory contents too");
+    println!("  -A, --ascii             replace non-ASCII characters in new names");
+    println!("      --ascii-map <map>   override ASCII replacements of letters (implies --ascii)");
+    println!("                          example: đ=d,ђ=dj");
+    #[cfg(feature = "serve")]
+    {
+        println!();
+        println!("SERVE OPTIONS:");
+        println!("      --listen <address>  listen on address");
+        println!("                          default: 127.0.0.1:8080");
+        println!("      --workers <n>       number of requests handled at the same time");
+        println!("                          default: 4");
+        println!("      --max-body <bytes>  largest accepted request body");
+        println!("                          default: 1048576");
+    }
+    #[cfg(feature = "site")]
+    {
+        println!();
+        println!("SITE OPTIONS:");
+        println!("      --base <url>        URL path of the output tree, or source=output paths");
+        println!("                          default: /<output directory name>/");
+    }
+    #[cfg(feature = "pdf")]
+    {
+        println!();
+        println!("PDF OPTIONS:");
+        println!("      --font <path>       write a PDF document with TrueType font");
+        println!("                          default: write plain text");
+    }
+    println!();
+    println!("Exit codes:");
+    println!("  0                       success");
+    println!("  1                       check found lines which would change");
+    #[cfg(feature = "hunspell")]
+    println!("                          or validate found words not in the word list");
+    println!("  2                       error");
+    println!();
     println!("Character sets:");
     println!("  latin,    lat,  l       Serbian Latin");
     println!("  latin8,   lat8, l8      Serbian Latin (Unicode)");
     println!("  cyrillic, cyr,  c       Serbian Cyrillic");
     println!();
+    println!("Languages:");
+    println!("  serbian,  srp,  sr      Serbian");
+    println!("  croatian, hrv,  hr      Croatian");
+    println!("  bosnian,  bos,  bs      Bosnian");
+    #[cfg(feature = "kazakh")]
+    println!("  kazakh,   kaz,  kk      Kazakh");
+    #[cfg(feature = "uzbek")]
+    println!("  uzbek,    uzb,  uz      Uzbek");
+    println!();
+    println!("Shells:");
+    println!("  bash, zsh, fish");
+    println!();
+    #[cfg(feature = "dialect")]
+    {
+        println!("Dialects:");
+        println!("  ekavian,   ek,   e      Ekavian (ie. mleko)");
+        println!("  ijekavian, ijek, ije    Ijekavian (ie. mlijeko)");
+        println!();
+    }
+    println!("Archaic letter modes:");
+    println!("  ignore                  words with archaic letters are foreign");
+    println!("  modernize               replace with modern letters");
+    println!("  preserve                keep archaic letters intact");
+    println!("  annotate                replace and append original in brackets");
+    println!();
+    println!("Compound word modes:");
+    println!("  whole                   transliterate whole words");
+    println!("  hyphens                 split words on internal hyphens");
+    println!("  apostrophes             split words on internal apostrophes");
+    println!("  all                     split words on hyphens and apostrophes");
+    println!();
+    println!("Alphanumeric word modes:");
+    println!("  transliterate           transliterate all words");
+    println!("  suffixes                transliterate suffixes of numbers only (ie. 19-og)");
+    println!("  keep                    keep all words mixing digits and letters intact");
+    println!();
+    println!("Standalone letter modes:");
+    println!("  convert                 transliterate standalone letters");
+    println!("  keep                    keep standalone letters intact");
+    println!("  serbian                 transliterate letters of the Serbian alphabet only");
+    println!();
+    println!("Compatibility character classes:");
+    println!("  fullwidth               fullwidth letters, digits and punctuation (ie. Ａ)");
+    println!("  superscripts            superscript letters and digits (ie. ᵃ, ²)");
+    println!("  subscripts              subscript letters and digits (ie. ₐ, ₂)");
+    println!("  circled                 circled letters and numbers (ie. Ⓐ, ⑫)");
+    println!();
     println!("Pandoc filter environment variables:");
     println!("  CHARS_FROM=<charset>");
     println!("  CHARS_INTO=<charset>");
+    println!("  CHARS_LANGUAGE=<lang>");
+    println!("  ARCHAIC_LETTERS=<mode>");
+    println!("  COMPOUNDS=<mode>");
+    println!("  ALPHANUMERIC=<mode>");
+    println!("  LETTERS=<mode>");
+    #[cfg(feature = "dialect")]
+    println!("  DIALECT=<dialect>");
     println!("  SKIP_DIGRAPH");
     println!("  FORCE_FOREIGN");
     println!("  FORCE_LINKS");
+    println!("  FORCE_ROMAN");
+    println!("  FIX_OCR");
+    println!("  FOLD=<classes>");
+    println!("  TITLECASE_DIGRAPHS");
+    #[cfg(feature = "dates")]
+    println!("  DATES");
+    println!("  SKIP_CONVERTED");
+    #[cfg(feature = "ngram")]
+    println!("  NGRAM");
+    #[cfg(feature = "gazetteer")]
+    println!("  GAZETTEER");
+    #[cfg(feature = "detect-language")]
+    println!("  DETECT_LANGUAGE");
 }
 
 #[derive(Debug)]
@@ -55,11 +338,11 @@ pub enum Error {
     ArgumentMissing,
     ArgumentUnknown,
     ArgumentInvalid,
-    Runtime(process::Error),
+    Runtime(translitrs::Error),
 }
 
-impl From<process::Error> for Error {
-    fn from(error: process::Error) -> Self {
+impl From<translitrs::Error> for Error {
+    fn from(error: translitrs::Error) -> Self {
         Self::Runtime(error)
     }
 }
@@ -77,41 +360,241 @@ impl fmt::Display for Error {
 
 impl error::Error for Error {}
 
-impl std::str::FromStr for Charset {
-    type Err = Error;
+fn parse_charset(value: &str) -> Result<Charset, Error> {
+    Charset::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "latin" | "lat" | "l" => Ok(Charset::Latin),
-            "latin8" | "lat8" | "l8" => Ok(Charset::LatinUnicode),
-            "cyrillic" | "cyr" | "c" => Ok(Charset::Cyrillic),
-            _ => Err(Error::ArgumentInvalid),
-        }
-    }
+fn parse_direction(value: &str) -> Result<Direction, Error> {
+    Direction::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_language(value: &str) -> Result<Language, Error> {
+    Language::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_archaic(value: &str) -> Result<Archaic, Error> {
+    Archaic::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_compounds(value: &str) -> Result<Compounds, Error> {
+    Compounds::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_alphanumeric(value: &str) -> Result<Alphanumeric, Error> {
+    Alphanumeric::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_letters(value: &str) -> Result<Letters, Error> {
+    Letters::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_fold(value: &str) -> Result<Fold, Error> {
+    Fold::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_expand(value: &str) -> Result<Expand, Error> {
+    Expand::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_phonetic_map(value: &str) -> Result<PhoneticMap, Error> {
+    PhoneticMap::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+fn parse_ascii_map(value: &str) -> Result<AsciiMap, Error> {
+    AsciiMap::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+#[cfg(feature = "dialect")]
+fn parse_dialect(value: &str) -> Result<Dialect, Error> {
+    Dialect::from_str(value).map_err(|_| Error::ArgumentInvalid)
+}
+
+/// Read options from configuration file at `path`, or from `translit.toml`
+/// in current or the closest parent directory
+#[cfg(feature = "config")]
+fn load_config(path: Option<path::PathBuf>) -> Result<Options, Error> {
+    Ok(match path {
+        Some(path) => Options::from_path(path)?,
+        None => Options::discover(env::current_dir().map_err(translitrs::Error::from)?)?
+            .map(|(_, options)| options)
+            .unwrap_or_default(),
+    })
+}
+
+#[derive(PartialEq)]
+enum Command {
+    Convert,
+    Check,
+    Review,
+    Highlight,
+    Unmapped,
+    Stats,
+    #[cfg(feature = "hunspell")]
+    Validate,
+    Detect,
+    Learn,
+    Coverage,
+    Grep,
+    Rename,
+    Filter,
+    #[cfg(feature = "watch")]
+    Watch,
+    #[cfg(feature = "site")]
+    Site,
+    #[cfg(feature = "clipboard")]
+    Clip,
+    #[cfg(feature = "serve")]
+    Serve,
+    #[cfg(feature = "pdf")]
+    Pdf,
+    #[cfg(feature = "tui")]
+    Preview,
 }
 
 struct Arguments {
+    command: Command,
     transliterator: Transliterator,
+    paths: Vec<path::PathBuf>,
+    ascii: bool,
+    ascii_map: AsciiMap,
+    recursive: bool,
+    dry_run: bool,
+    json: bool,
+    #[cfg(feature = "clipboard")]
+    clip_options: Options,
+    #[cfg(feature = "serve")]
+    server: ServeProcessor,
+    #[cfg(feature = "site")]
+    base: Option<String>,
     input: Option<path::PathBuf>,
     output: Option<path::PathBuf>,
+    backup: bool,
     #[cfg(feature = "pandoc")]
     pandoc_mode: bool,
+    #[cfg(feature = "html")]
+    html_mode: bool,
+    #[cfg(feature = "feed")]
+    feed_mode: bool,
+    #[cfg(feature = "geo")]
+    geo_mode: bool,
+    #[cfg(feature = "mail")]
+    mail_mode: bool,
+    source: Option<Syntax>,
+    yaml_mode: bool,
+    front_matter: bool,
+    keys: Vec<String>,
+    add_properties: Vec<String>,
+    passthrough: Vec<String>,
+    learner: Learner,
+    grep: Option<GrepProcessor>,
+    forced: Option<Transliterator>,
+    session: Option<path::PathBuf>,
+    show_original: bool,
+    ndjson_mode: bool,
+    geojson_mode: bool,
+    sql_mode: bool,
+    ass_mode: bool,
+    log_mode: bool,
+    chat_mode: bool,
+    wiki_mode: bool,
+    vcard_mode: bool,
+    anki_mode: bool,
+    speech_mode: bool,
+    conllu_mode: bool,
+    vertical: bool,
+    pseudo_mode: bool,
+    padding: usize,
+    phonetic_mode: bool,
+    phonetic_map: PhoneticMap,
+    expand: Expand,
+    #[cfg(feature = "xlsx")]
+    xlsx_mode: bool,
+    #[cfg(feature = "arrow")]
+    parquet_mode: bool,
+    #[cfg(feature = "pdf")]
+    font: Option<path::PathBuf>,
+    #[cfg(feature = "hunspell")]
+    dictionary: Option<(Dictionary, Charset)>,
+    #[cfg(feature = "tui")]
+    preview_options: Options,
 }
 
 fn parse_args() -> Result<Arguments, Error> {
     let mut input: Option<path::PathBuf> = None;
     let mut output: Option<path::PathBuf> = None;
 
-    let mut charset_from = Charset::Latin;
-    let mut charset_into = Charset::Cyrillic;
-    let mut skip_digraph = false;
-    let mut force_foreign = false;
-    let mut force_links = false;
-    #[cfg(feature = "pandoc")]
-    let mut pandoc_mode = false;
+    // Options which are not set are read from the configuration file
+    let mut options = Options::default();
+    let mut charset_from: Option<Charset> = None;
+    let mut charset_into: Option<Charset> = None;
+    #[cfg(feature = "config")]
+    let mut config: Option<path::PathBuf> = None;
+
+    let mut command = Command::Convert;
+    let mut paths: Vec<path::PathBuf> = Vec::new();
+    let mut ascii = false;
+    let mut ascii_map = AsciiMap::default();
+    let mut recursive = false;
+    let mut dry_run = false;
+    let mut json = false;
+    #[cfg(feature = "clipboard")]
+    let mut auto_direction = false;
+    #[cfg(feature = "serve")]
+    let mut listen = "127.0.0.1:8080".to_owned();
+    #[cfg(feature = "serve")]
+    let mut workers: Option<usize> = None;
+    #[cfg(feature = "serve")]
+    let mut max_body: Option<usize> = None;
+    #[cfg(feature = "site")]
+    let mut base: Option<String> = None;
+    let mut in_place = false;
+    let mut backup = false;
+    #[cfg(feature = "pdf")]
+    let mut font: Option<path::PathBuf> = None;
+    #[cfg(feature = "hunspell")]
+    let mut dictionary: Option<path::PathBuf> = None;
+    let mut session: Option<path::PathBuf> = None;
+    let mut show_original = false;
+    let mut expand = Expand::default();
+    let mut padding = 30;
+    let mut phonetic_map = PhoneticMap::default();
+    let mut pattern: Option<String> = None;
 
-    let mut arguments = env::args();
-    let _ = arguments.next();
+    let mut arguments = env::args().skip(1).peekable();
+    match arguments.peek().map(String::as_str) {
+        Some("detect") => command = Command::Detect,
+        Some("learn") => command = Command::Learn,
+        Some("coverage") => command = Command::Coverage,
+        Some("grep") => command = Command::Grep,
+        Some("rename") => command = Command::Rename,
+        Some("filter") => command = Command::Filter,
+        #[cfg(feature = "watch")]
+        Some("watch") => command = Command::Watch,
+        #[cfg(feature = "site")]
+        Some("site") => command = Command::Site,
+        #[cfg(feature = "clipboard")]
+        Some("clip") => command = Command::Clip,
+        #[cfg(feature = "serve")]
+        Some("serve") => command = Command::Serve,
+        #[cfg(feature = "pdf")]
+        Some("pdf") => command = Command::Pdf,
+        #[cfg(feature = "tui")]
+        Some("preview") => command = Command::Preview,
+        Some("completions") => {
+            let _ = arguments.next();
+            let shell = arguments.next().ok_or(Error::ArgumentMissing)?;
+            let shell = Shell::from_str(&shell).map_err(|_| Error::ArgumentInvalid)?;
+            if arguments.next().is_some() {
+                return Err(Error::ArgumentUnknown);
+            }
+            print!("{}", completions::generate(shell, env!("CARGO_PKG_NAME")));
+            std::process::exit(0);
+        }
+        _ => {}
+    }
+    if command != Command::Convert {
+        let _ = arguments.next();
+    }
 
     while let Some(arg) = arguments.next() {
         match &*arg {
@@ -125,14 +608,222 @@ fn parse_args() -> Result<Arguments, Error> {
             }
             "-t" | "--into" => {
                 if let Some(value) = arguments.next() {
-                    charset_into = Charset::from_str(&value)?
+                    charset_into = Some(parse_charset(&value)?)
                 } else {
                     return Err(Error::ArgumentMissing);
                 }
             }
             "-f" | "--from" => {
                 if let Some(value) = arguments.next() {
-                    charset_from = Charset::from_str(&value)?
+                    charset_from = Some(parse_charset(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--direction" => {
+                if let Some(value) = arguments.next() {
+                    #[cfg(feature = "clipboard")]
+                    if value == "auto" && command == Command::Clip {
+                        auto_direction = true;
+                        continue;
+                    }
+                    let direction = parse_direction(&value)?;
+                    charset_from = Some(direction.from);
+                    charset_into = Some(direction.into);
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--source" => {
+                if let Some(value) = arguments.next() {
+                    Syntax::from_str(&value).map_err(|_| Error::ArgumentInvalid)?;
+                    options.format = Some(value);
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--yaml" => {
+                options.format = Some("yaml".to_owned());
+            }
+            "--front-matter" => {
+                options.format = Some("front-matter".to_owned());
+            }
+            "--ndjson" => {
+                options.format = Some("ndjson".to_owned());
+            }
+            "--geojson" => {
+                options.format = Some("geojson".to_owned());
+            }
+            "--ass" => {
+                options.format = Some("ass".to_owned());
+            }
+            "--log" => {
+                options.format = Some("log".to_owned());
+            }
+            "--chat" => {
+                options.format = Some("chat".to_owned());
+            }
+            "--wikitext" => {
+                options.format = Some("wikitext".to_owned());
+            }
+            "--vcard" => {
+                options.format = Some("vcard".to_owned());
+            }
+            "--ics" => {
+                options.format = Some("ics".to_owned());
+            }
+            "--anki" => {
+                options.format = Some("anki".to_owned());
+            }
+            "--speech" => {
+                options.format = Some("speech".to_owned());
+            }
+            "--conllu" => {
+                options.format = Some("conllu".to_owned());
+            }
+            "--vertical" => {
+                options.format = Some("vertical".to_owned());
+            }
+            "--pseudo" => {
+                options.format = Some("pseudo".to_owned());
+            }
+            "--phonetic" => {
+                options.format = Some("phonetic".to_owned());
+            }
+            "--phonetic-map" => {
+                let value = arguments.next().ok_or(Error::ArgumentMissing)?;
+                phonetic_map = parse_phonetic_map(&value)?;
+            }
+            "--padding" => {
+                let value = arguments.next().ok_or(Error::ArgumentMissing)?;
+                padding = value.parse().map_err(|_| Error::ArgumentInvalid)?;
+            }
+            "--expand" => {
+                if let Some(value) = arguments.next() {
+                    expand = parse_expand(&value)?
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--sql" => {
+                options.format = Some("sql".to_owned());
+            }
+            #[cfg(feature = "xlsx")]
+            "--xlsx" => {
+                options.format = Some("xlsx".to_owned());
+            }
+            #[cfg(feature = "arrow")]
+            "--parquet" => {
+                options.format = Some("parquet".to_owned());
+            }
+            "--keys" => {
+                if let Some(value) = arguments.next() {
+                    options
+                        .keys
+                        .extend(value.split(',').map(|k| k.trim().to_owned()));
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--add-properties" => {
+                if let Some(value) = arguments.next() {
+                    options
+                        .add_properties
+                        .extend(value.split(',').map(|p| p.trim().to_owned()));
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--passthrough" => {
+                if let Some(value) = arguments.next() {
+                    options
+                        .passthrough
+                        .extend(value.split(',').map(|c| c.trim().to_owned()));
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "-c" | "--check" if command == Command::Convert => {
+                command = Command::Check;
+            }
+            "--unmapped" if command == Command::Convert => {
+                command = Command::Unmapped;
+            }
+            "--stats" if command == Command::Convert => {
+                command = Command::Stats;
+            }
+            #[cfg(feature = "hunspell")]
+            "--validate" if command == Command::Convert => {
+                command = Command::Validate;
+            }
+            #[cfg(feature = "hunspell")]
+            "--dictionary" => {
+                let path = arguments.next().ok_or(Error::ArgumentMissing)?;
+                dictionary = Some(path::PathBuf::from(path));
+            }
+            "--json" => {
+                json = true;
+            }
+            "--interactive" if command == Command::Convert => {
+                command = Command::Review;
+            }
+            "--session" => {
+                let path = arguments.next().ok_or(Error::ArgumentMissing)?;
+                session = Some(path::PathBuf::from(path));
+            }
+            "--highlight" if command == Command::Convert => {
+                command = Command::Highlight;
+            }
+            "--show-original" => {
+                show_original = true;
+            }
+            "-L" | "--language" => {
+                if let Some(value) = arguments.next() {
+                    options.language = Some(parse_language(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "-a" | "--archaic" => {
+                if let Some(value) = arguments.next() {
+                    options.archaic = Some(parse_archaic(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--compounds" => {
+                if let Some(value) = arguments.next() {
+                    options.compounds = Some(parse_compounds(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--alphanumeric" => {
+                if let Some(value) = arguments.next() {
+                    options.alphanumeric = Some(parse_alphanumeric(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--letters" => {
+                if let Some(value) = arguments.next() {
+                    options.letters = Some(parse_letters(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            #[cfg(feature = "dialect")]
+            "-D" | "--dialect" => {
+                if let Some(value) = arguments.next() {
+                    options.dialect = Some(parse_dialect(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            #[cfg(feature = "config")]
+            "-C" | "--config" => {
+                if let Some(path) = arguments.next() {
+                    config = Some(path::PathBuf::from(path));
                 } else {
                     return Err(Error::ArgumentMissing);
                 }
@@ -144,82 +835,759 @@ fn parse_args() -> Result<Arguments, Error> {
                     return Err(Error::ArgumentMissing);
                 }
             }
+            "-I" | "--in-place" => {
+                in_place = true;
+            }
+            "-b" | "--backup" => {
+                backup = true;
+            }
             "-d" | "--skip-digraph" => {
-                skip_digraph = true;
+                options.skip_digraph = Some(true);
             }
             "-u" | "--force-foreign" => {
-                force_foreign = true;
+                options.force_foreign = Some(true);
             }
             "-l" | "--force-links" => {
-                force_links = true;
+                options.force_links = Some(true);
+            }
+            "-r" | "--force-roman" => {
+                options.force_roman = Some(true);
+            }
+            "--fix-ocr" => {
+                options.fix_ocr = Some(true);
+            }
+            "--fold" => {
+                if let Some(value) = arguments.next() {
+                    options.fold = Some(parse_fold(&value)?)
+                } else {
+                    return Err(Error::ArgumentMissing);
+                }
+            }
+            "--titlecase-digraphs" => {
+                options.titlecase_digraphs = Some(true);
+            }
+            #[cfg(feature = "dates")]
+            "--dates" => {
+                options.dates = Some(true);
+            }
+            "--skip-converted" => {
+                options.skip_converted = Some(true);
+            }
+            "--max-input" => {
+                let value = arguments.next().ok_or(Error::ArgumentMissing)?;
+                options.max_input = Some(value.parse().map_err(|_| Error::ArgumentInvalid)?);
+            }
+            "--max-word" => {
+                let value = arguments.next().ok_or(Error::ArgumentMissing)?;
+                options.max_word = Some(value.parse().map_err(|_| Error::ArgumentInvalid)?);
+            }
+            #[cfg(feature = "ngram")]
+            "--ngram" => {
+                options.ngram = Some(true);
+            }
+            #[cfg(feature = "gazetteer")]
+            "--gazetteer" => {
+                options.gazetteer = Some(true);
+            }
+            "--ansi" => {
+                options.ansi = Some(true);
+            }
+            "--strict" => {
+                options.strict = Some(true);
+            }
+            #[cfg(feature = "detect-language")]
+            "-g" | "--detect-language" => {
+                options.detect_language = Some(true);
             }
             #[cfg(feature = "pandoc")]
             "-p" | "--pandoc-filter" => {
-                pandoc_mode = true;
+                options.format = Some("pandoc".to_owned());
+            }
+            #[cfg(feature = "html")]
+            "--html" => {
+                options.format = Some("html".to_owned());
+            }
+            #[cfg(feature = "feed")]
+            "--feed" => {
+                options.format = Some("feed".to_owned());
+            }
+            #[cfg(feature = "geo")]
+            "--gpx" => {
+                options.format = Some("gpx".to_owned());
             }
-            "-o" | "--output" => {
+            #[cfg(feature = "geo")]
+            "--kml" => {
+                options.format = Some("kml".to_owned());
+            }
+            #[cfg(feature = "mail")]
+            "--mail" => {
+                options.format = Some("mail".to_owned());
+            }
+            "-o" | "--output" | "--out" => {
                 if let Some(path) = arguments.next() {
                     output = Some(path::PathBuf::from(path));
                 } else {
                     return Err(Error::ArgumentMissing);
                 }
             }
+            #[cfg(feature = "pdf")]
+            "--font" if command == Command::Pdf => {
+                let path = arguments.next().ok_or(Error::ArgumentMissing)?;
+                font = Some(path::PathBuf::from(path));
+            }
+            #[cfg(feature = "serve")]
+            "--listen" if command == Command::Serve => {
+                listen = arguments.next().ok_or(Error::ArgumentMissing)?;
+            }
+            #[cfg(feature = "serve")]
+            "--workers" if command == Command::Serve => {
+                let value = arguments.next().ok_or(Error::ArgumentMissing)?;
+                workers = Some(value.parse().map_err(|_| Error::ArgumentInvalid)?);
+            }
+            #[cfg(feature = "serve")]
+            "--max-body" if command == Command::Serve => {
+                let value = arguments.next().ok_or(Error::ArgumentMissing)?;
+                max_body = Some(value.parse().map_err(|_| Error::ArgumentInvalid)?);
+            }
+            #[cfg(feature = "site")]
+            "--base" if command == Command::Site => {
+                base = Some(arguments.next().ok_or(Error::ArgumentMissing)?);
+            }
+            "-n" | "--dry-run" if command == Command::Rename => {
+                dry_run = true;
+            }
+            "-R" | "--recursive" if command == Command::Rename => {
+                recursive = true;
+            }
+            "-A" | "--ascii" if command == Command::Rename => {
+                ascii = true;
+            }
+            "--ascii-map" if command == Command::Rename => {
+                let value = arguments.next().ok_or(Error::ArgumentMissing)?;
+                ascii_map = parse_ascii_map(&value)?;
+                ascii = true;
+            }
+            value if command == Command::Grep && pattern.is_none() && !value.starts_with('-') => {
+                pattern = Some(value.to_owned());
+            }
+            path if !path.starts_with('-') => {
+                paths.push(path::PathBuf::from(path));
+            }
             _ => return Err(Error::ArgumentUnknown),
         }
     }
-    Ok(Arguments {
-        transliterator: Transliterator::new(
-            charset_from,
-            charset_into,
-            skip_digraph,
-            force_foreign,
-            force_links,
+    if matches!(
+        command,
+        Command::Convert | Command::Filter | Command::Review | Command::Highlight
+    ) && !paths.is_empty()
+    {
+        return Err(Error::ArgumentUnknown);
+    }
+    // Highlighted text is always printed
+    if command == Command::Highlight && output.is_some()
+        || show_original && command != Command::Highlight
+    {
+        return Err(Error::ArgumentUnknown);
+    }
+    let json_commands = [
+        Command::Check,
+        Command::Unmapped,
+        Command::Stats,
+        #[cfg(feature = "hunspell")]
+        Command::Validate,
+        Command::Detect,
+        Command::Coverage,
+    ];
+    if json && !json_commands.contains(&command) {
+        return Err(Error::ArgumentUnknown);
+    }
+    if command == Command::Grep && pattern.is_none() {
+        return Err(Error::ArgumentMissing);
+    }
+    if command == Command::Rename && paths.is_empty() {
+        return Err(Error::ArgumentMissing);
+    }
+    if command == Command::Learn {
+        if paths.len() > 2 {
+            return Err(Error::ArgumentUnknown);
+        }
+        if paths.len() < 2 {
+            return Err(Error::ArgumentMissing);
+        }
+    }
+    #[cfg(feature = "pdf")]
+    if command == Command::Pdf {
+        if paths.len() > 1 {
+            return Err(Error::ArgumentUnknown);
+        }
+        if paths.is_empty() {
+            return Err(Error::ArgumentMissing);
+        }
+    }
+    #[cfg(feature = "tui")]
+    if command == Command::Preview {
+        if paths.len() > 1 {
+            return Err(Error::ArgumentUnknown);
+        }
+        if paths.is_empty() {
+            return Err(Error::ArgumentMissing);
+        }
+    }
+    #[cfg(feature = "watch")]
+    if command == Command::Watch {
+        if paths.len() > 1 {
+            return Err(Error::ArgumentUnknown);
+        }
+        if paths.is_empty() || output.is_none() {
+            return Err(Error::ArgumentMissing);
+        }
+    }
+    #[cfg(feature = "site")]
+    if command == Command::Site {
+        if paths.len() > 1 {
+            return Err(Error::ArgumentUnknown);
+        }
+        if paths.is_empty() || output.is_none() {
+            return Err(Error::ArgumentMissing);
+        }
+    }
+    if in_place {
+        if input.is_none() {
+            return Err(Error::ArgumentMissing);
+        }
+        output = input.clone();
+    }
+    // Answers are read from stdin, so input must be a file
+    if command == Command::Review && input.is_none() {
+        return Err(Error::ArgumentMissing);
+    }
+    #[cfg(feature = "config")]
+    {
+        options = options.merge(load_config(config)?);
+    }
+    // Clipboard direction is detected unless it is set
+    #[cfg(feature = "clipboard")]
+    let auto_direction = auto_direction
+        || (charset_from.is_none() && charset_into.is_none() && options.direction.is_none());
+    let direction = options.direction.unwrap_or(Direction {
+        from: Charset::Latin,
+        into: Charset::Cyrillic,
+    });
+    options.direction = Some(Direction {
+        from: charset_from.unwrap_or(direction.from),
+        into: charset_into.unwrap_or(direction.into),
+    });
+    #[cfg(feature = "hunspell")]
+    let dictionary = match (command == Command::Validate, options.direction) {
+        (true, Some(direction)) => {
+            let path = dictionary
+                .or_else(|| Dictionary::find(direction.into))
+                .ok_or_else(|| translitrs::Error::Config("dictionary not found".to_owned()))?;
+            Some((Dictionary::from_path(path)?, direction.into))
+        }
+        _ => None,
+    };
+    #[cfg(feature = "clipboard")]
+    let clip_options = Options {
+        direction: options.direction.filter(|_| !auto_direction),
+        ..options.clone()
+    };
+    #[cfg(feature = "serve")]
+    let mut server = ServeProcessor::new(listen, options.clone());
+    #[cfg(feature = "serve")]
+    {
+        if let Some(workers) = workers {
+            server = server.with_workers(workers);
+        }
+        if let Some(max_body) = max_body {
+            server = server.with_max_body(max_body);
+        }
+    }
+    #[cfg(feature = "pandoc")]
+    let pandoc_mode = options.format.as_deref() == Some("pandoc");
+    #[cfg(feature = "html")]
+    let html_mode = options.format.as_deref() == Some("html");
+    #[cfg(feature = "feed")]
+    let feed_mode = options.format.as_deref() == Some("feed");
+    #[cfg(feature = "geo")]
+    let geo_mode = matches!(options.format.as_deref(), Some("gpx" | "kml"));
+    #[cfg(feature = "mail")]
+    let mail_mode = options.format.as_deref() == Some("mail");
+    let source = options
+        .format
+        .as_deref()
+        .and_then(|format| Syntax::from_str(format).ok());
+    let front_matter = options.format.as_deref() == Some("front-matter");
+    let yaml_mode = front_matter || options.format.as_deref() == Some("yaml");
+    let keys = options.keys.clone();
+    let add_properties = options.add_properties.clone();
+    let passthrough = options.passthrough.clone();
+    let learner = Learner::new(options.language.unwrap_or(Language::Serbian))
+        .with_exceptions(&options.exceptions);
+    let grep = match pattern {
+        Some(pattern) => Some(GrepProcessor::new(
+            &pattern,
+            paths.clone(),
+            options.language.unwrap_or(Language::Serbian),
+        )?),
+        None => None,
+    };
+    // Words skipped by the transliterator are converted anyway if overridden
+    let forced = match command {
+        Command::Review => Some(
+            Options {
+                force_foreign: Some(true),
+                force_links: Some(true),
+                force_roman: Some(true),
+                alphanumeric: Some(Alphanumeric::Transliterate),
+                letters: Some(Letters::Convert),
+                protected: Vec::new(),
+                #[cfg(feature = "gazetteer")]
+                gazetteer: Some(false),
+                skip_patterns: Vec::new(),
+                ..options.clone()
+            }
+            .transliterator()?,
         ),
+        _ => None,
+    };
+    let ndjson_mode = options.format.as_deref() == Some("ndjson");
+    let geojson_mode = options.format.as_deref() == Some("geojson");
+    let sql_mode = options.format.as_deref() == Some("sql");
+    let ass_mode = options.format.as_deref() == Some("ass");
+    let log_mode = options.format.as_deref() == Some("log");
+    let chat_mode = options.format.as_deref() == Some("chat");
+    let wiki_mode = options.format.as_deref() == Some("wikitext");
+    let vcard_mode = matches!(options.format.as_deref(), Some("vcard" | "ics"));
+    let anki_mode = options.format.as_deref() == Some("anki");
+    let speech_mode = options.format.as_deref() == Some("speech");
+    let vertical = options.format.as_deref() == Some("vertical");
+    let conllu_mode = vertical || options.format.as_deref() == Some("conllu");
+    let pseudo_mode = options.format.as_deref() == Some("pseudo");
+    let phonetic_mode = options.format.as_deref() == Some("phonetic");
+    #[cfg(feature = "xlsx")]
+    let xlsx_mode = options.format.as_deref() == Some("xlsx");
+    #[cfg(feature = "arrow")]
+    let parquet_mode = options.format.as_deref() == Some("parquet");
+    #[cfg(feature = "tui")]
+    let preview_options = options.clone();
+    let transliterator = options.transliterator()?;
+    Ok(Arguments {
+        command,
+        transliterator,
+        paths,
+        ascii,
+        ascii_map,
+        recursive,
+        dry_run,
+        json,
+        #[cfg(feature = "clipboard")]
+        clip_options,
+        #[cfg(feature = "serve")]
+        server,
+        #[cfg(feature = "site")]
+        base,
         input,
         output,
+        backup,
         #[cfg(feature = "pandoc")]
         pandoc_mode,
+        #[cfg(feature = "html")]
+        html_mode,
+        #[cfg(feature = "feed")]
+        feed_mode,
+        #[cfg(feature = "geo")]
+        geo_mode,
+        #[cfg(feature = "mail")]
+        mail_mode,
+        source,
+        yaml_mode,
+        front_matter,
+        keys,
+        add_properties,
+        passthrough,
+        learner,
+        grep,
+        forced,
+        session,
+        show_original,
+        ndjson_mode,
+        geojson_mode,
+        sql_mode,
+        ass_mode,
+        log_mode,
+        chat_mode,
+        wiki_mode,
+        vcard_mode,
+        anki_mode,
+        speech_mode,
+        conllu_mode,
+        vertical,
+        pseudo_mode,
+        padding,
+        phonetic_mode,
+        phonetic_map,
+        expand,
+        #[cfg(feature = "xlsx")]
+        xlsx_mode,
+        #[cfg(feature = "arrow")]
+        parquet_mode,
+        #[cfg(feature = "pdf")]
+        font,
+        #[cfg(feature = "hunspell")]
+        dictionary,
+        #[cfg(feature = "tui")]
+        preview_options,
     })
 }
 
 fn regular_mode() -> Result<Box<dyn FileProcessor>, Error> {
     let args = parse_args()?;
+    if args.command == Command::Filter {
+        return Ok(Box::new(FilterProcessor::new(args.transliterator)));
+    }
+    if args.command == Command::Unmapped {
+        return Ok(Box::new(
+            UnmappedProcessor::new(args.paths)
+                .with_json(args.json)
+                .with_passthrough(&args.passthrough)?,
+        ));
+    }
+    if args.command == Command::Detect {
+        return Ok(Box::new(DetectProcessor::new(args.paths).with_json(args.json)));
+    }
+    if args.command == Command::Coverage {
+        return Ok(Box::new(
+            CoverageProcessor::new(args.paths, args.transliterator).with_json(args.json),
+        ));
+    }
+    if let Some(grep) = args.grep {
+        return Ok(Box::new(grep));
+    }
+    if args.command == Command::Learn {
+        let mut paths = args.paths.into_iter();
+        return Ok(Box::new(LearnProcessor::new(
+            paths.next().unwrap_or_default(),
+            paths.next().unwrap_or_default(),
+            args.learner,
+        )));
+    }
+    #[cfg(feature = "hunspell")]
+    if let Some((dictionary, charset)) = args.dictionary {
+        return Ok(Box::new(
+            ValidateProcessor::new(args.paths, args.transliterator, dictionary, charset)
+                .with_json(args.json),
+        ));
+    }
+    if let (Some(input), Some(forced)) = (args.input.clone(), args.forced) {
+        let review = ReviewProcessor::new(input, args.output, args.transliterator, forced)
+            .with_backup(args.backup);
+        return Ok(Box::new(match args.session {
+            Some(session) => review.with_session(session),
+            None => review,
+        }));
+    }
+    if args.command == Command::Highlight {
+        return Ok(Box::new(
+            HighlightProcessor::new(args.input, args.transliterator)?
+                .with_original(args.show_original)
+                .with_color(env::var_os("NO_COLOR").map_or(true, |v| v.is_empty())),
+        ));
+    }
+    if args.command == Command::Stats {
+        return Ok(Box::new(
+            StatsProcessor::new(args.paths, args.transliterator).with_json(args.json),
+        ));
+    }
+    if args.command == Command::Check {
+        return Ok(Box::new(
+            CheckProcessor::new(args.paths, args.transliterator).with_json(args.json),
+        ));
+    }
+    #[cfg(feature = "serve")]
+    if args.command == Command::Serve {
+        return Ok(Box::new(args.server));
+    }
+    #[cfg(feature = "clipboard")]
+    if args.command == Command::Clip {
+        return Ok(Box::new(ClipProcessor::new(args.clip_options)));
+    }
+    #[cfg(feature = "pdf")]
+    if args.command == Command::Pdf {
+        let source = args
+            .paths
+            .into_iter()
+            .next()
+            .ok_or(Error::ArgumentMissing)?;
+        return Ok(Box::new(
+            PdfProcessor::new(source, args.output, args.transliterator)?
+                .with_font(args.font)
+                .with_backup(args.backup),
+        ));
+    }
+    #[cfg(feature = "tui")]
+    if args.command == Command::Preview {
+        let source = args
+            .paths
+            .into_iter()
+            .next()
+            .ok_or(Error::ArgumentMissing)?;
+        return Ok(Box::new(PreviewProcessor::new(source, args.preview_options)));
+    }
+    #[cfg(feature = "watch")]
+    if args.command == Command::Watch {
+        let source = args
+            .paths
+            .into_iter()
+            .next()
+            .ok_or(Error::ArgumentMissing)?;
+        let output = args.output.ok_or(Error::ArgumentMissing)?;
+        return Ok(Box::new(WatchProcessor::new(source, output, args.transliterator)?));
+    }
+    #[cfg(feature = "site")]
+    if args.command == Command::Site {
+        let source = args
+            .paths
+            .into_iter()
+            .next()
+            .ok_or(Error::ArgumentMissing)?;
+        let output = args.output.ok_or(Error::ArgumentMissing)?;
+        let site = SiteProcessor::new(source, output, args.transliterator)?.with_keys(&args.keys);
+        return Ok(Box::new(match args.base {
+            Some(base) => site.with_base(&base),
+            None => site,
+        }));
+    }
+    if args.command == Command::Rename {
+        return Ok(Box::new(
+            RenameProcessor::new(args.paths, args.transliterator)
+                .with_ascii(args.ascii)
+                .with_ascii_map(args.ascii_map)
+                .with_recursive(args.recursive)
+                .with_dry_run(args.dry_run),
+        ));
+    }
+    #[cfg(feature = "arrow")]
+    if args.parquet_mode {
+        let input = args.input.ok_or(Error::ArgumentMissing)?;
+        return Ok(Box::new(
+            ParquetProcessor::new(input, args.output, args.transliterator)?
+                .with_columns(&args.keys)
+                .with_backup(args.backup),
+        ));
+    }
+    #[cfg(feature = "xlsx")]
+    if args.xlsx_mode {
+        return Ok(Box::new(
+            XlsxProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    if args.ass_mode {
+        return Ok(Box::new(
+            AssProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    if args.log_mode {
+        return Ok(Box::new(
+            LogProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    if args.chat_mode {
+        return Ok(Box::new(
+            ChatProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    if args.wiki_mode {
+        return Ok(Box::new(
+            WikiProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    if args.vcard_mode {
+        return Ok(Box::new(
+            VcardProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    if args.anki_mode {
+        return Ok(Box::new(
+            AnkiProcessor::new(args.input, args.output, args.transliterator)?
+                .with_fields(&args.keys)
+                .with_backup(args.backup),
+        ));
+    }
+    if args.conllu_mode {
+        return Ok(Box::new(
+            ConlluProcessor::new(args.input, args.output, args.transliterator)?
+                .with_vertical(args.vertical)
+                .with_columns(&args.keys)
+                .with_backup(args.backup),
+        ));
+    }
+    if args.pseudo_mode {
+        return Ok(Box::new(
+            PseudoProcessor::new(args.input, args.output, args.transliterator)?
+                .with_padding(args.padding)
+                .with_backup(args.backup),
+        ));
+    }
+    if args.phonetic_mode {
+        return Ok(Box::new(
+            PhoneticProcessor::new(args.input, args.output, args.transliterator)?
+                .with_map(args.phonetic_map)
+                .with_backup(args.backup),
+        ));
+    }
+    if args.speech_mode {
+        return Ok(Box::new(
+            SpeechProcessor::new(args.input, args.output, args.transliterator)?
+                .with_expand(args.expand)
+                .with_backup(args.backup),
+        ));
+    }
+    if args.sql_mode {
+        return Ok(Box::new(
+            SqlProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    if args.geojson_mode {
+        return Ok(Box::new(
+            GeojsonProcessor::new(args.input, args.output, args.transliterator)?
+                .with_properties(&args.add_properties)
+                .with_backup(args.backup),
+        ));
+    }
+    if args.ndjson_mode {
+        return Ok(Box::new(
+            NdjsonProcessor::new(args.input, args.output, args.transliterator)?
+                .with_keys(&args.keys)
+                .with_backup(args.backup),
+        ));
+    }
+    if args.yaml_mode {
+        return Ok(Box::new(
+            YamlProcessor::new(args.input, args.output, args.transliterator)?
+                .with_keys(&args.keys)
+                .with_front_matter(args.front_matter)
+                .with_backup(args.backup),
+        ));
+    }
+    if let Some(syntax) = args.source {
+        return Ok(Box::new(
+            SourceProcessor::new(args.input, args.output, args.transliterator, syntax)?
+                .with_backup(args.backup),
+        ));
+    }
+    #[cfg(feature = "mail")]
+    if args.mail_mode {
+        return Ok(Box::new(
+            MailProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    #[cfg(feature = "feed")]
+    if args.feed_mode {
+        return Ok(Box::new(
+            FeedProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    #[cfg(feature = "geo")]
+    if args.geo_mode {
+        return Ok(Box::new(
+            GeoProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
+    #[cfg(feature = "html")]
+    if args.html_mode {
+        return Ok(Box::new(
+            HtmlProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ));
+    }
     #[cfg(not(feature = "pandoc"))]
-    return Ok(Box::new(PlaintextProcessor::new(args.input, args.output, args.transliterator)?));
+    return Ok(Box::new(
+        PlaintextProcessor::new(args.input, args.output, args.transliterator)?
+            .with_backup(args.backup),
+    ));
     #[cfg(feature = "pandoc")]
     return Ok(match args.pandoc_mode {
         true => Box::new(PandocProcessor::new(args.transliterator)),
-        false => Box::new(PlaintextProcessor::new(args.input, args.output, args.transliterator)?),
+        false => Box::new(
+            PlaintextProcessor::new(args.input, args.output, args.transliterator)?
+                .with_backup(args.backup),
+        ),
     });
 }
 
 #[cfg(feature = "pandoc")]
 fn pandoc_mode() -> Result<Box<dyn FileProcessor>, Error> {
-    fn parse_env_charset(key: &str, default: Charset) -> Result<Charset, Error> {
+    fn parse_env<T>(key: &str, parse: fn(&str) -> Result<T, Error>) -> Result<Option<T>, Error> {
         if let Ok(value) = env::var(key) {
             if !value.is_empty() {
-                return Charset::from_str(&value);
+                return parse(&value).map(Some);
             }
         }
-        Ok(default)
+        Ok(None)
     }
-    fn parse_env_bool(key: &str, default: bool) -> Result<bool, Error> {
-        if let Ok(value) = env::var(key) {
-            return Ok(!matches!(value.as_str(), "0" | "false" | "no"));
-        }
-        Ok(default)
+    fn parse_env_bool(key: &str) -> Option<bool> {
+        env::var(key)
+            .ok()
+            .map(|value| !matches!(value.as_str(), "0" | "false" | "no"))
     }
-    let transliterator = Transliterator::new(
-        parse_env_charset("CHARS_FROM", Charset::Latin)?,
-        parse_env_charset("CHARS_INTO", Charset::Cyrillic)?,
-        parse_env_bool("SKIP_DIGRAPH", false)?,
-        parse_env_bool("FORCE_FOREIGN", false)?,
-        parse_env_bool("FORCE_LINKS", false)?,
-    );
+    let mut options = Options {
+        language: parse_env("CHARS_LANGUAGE", parse_language)?,
+        archaic: parse_env("ARCHAIC_LETTERS", parse_archaic)?,
+        compounds: parse_env("COMPOUNDS", parse_compounds)?,
+        alphanumeric: parse_env("ALPHANUMERIC", parse_alphanumeric)?,
+        letters: parse_env("LETTERS", parse_letters)?,
+        #[cfg(feature = "dialect")]
+        dialect: parse_env("DIALECT", parse_dialect)?,
+        skip_digraph: parse_env_bool("SKIP_DIGRAPH"),
+        force_foreign: parse_env_bool("FORCE_FOREIGN"),
+        force_links: parse_env_bool("FORCE_LINKS"),
+        force_roman: parse_env_bool("FORCE_ROMAN"),
+        fix_ocr: parse_env_bool("FIX_OCR"),
+        fold: parse_env("FOLD", parse_fold)?,
+        titlecase_digraphs: parse_env_bool("TITLECASE_DIGRAPHS"),
+        #[cfg(feature = "dates")]
+        dates: parse_env_bool("DATES"),
+        skip_converted: parse_env_bool("SKIP_CONVERTED"),
+        #[cfg(feature = "ngram")]
+        ngram: parse_env_bool("NGRAM"),
+        #[cfg(feature = "gazetteer")]
+        gazetteer: parse_env_bool("GAZETTEER"),
+        ansi: parse_env_bool("ANSI"),
+        strict: parse_env_bool("STRICT"),
+        #[cfg(feature = "detect-language")]
+        detect_language: parse_env_bool("DETECT_LANGUAGE"),
+        ..Default::default()
+    };
+    let charset_from = parse_env("CHARS_FROM", parse_charset)?;
+    let charset_into = parse_env("CHARS_INTO", parse_charset)?;
+    #[cfg(feature = "config")]
+    {
+        options = options.merge(load_config(None)?);
+    }
+    let direction = options.direction.unwrap_or(Direction {
+        from: Charset::Latin,
+        into: Charset::Cyrillic,
+    });
+    options.direction = Some(Direction {
+        from: charset_from.unwrap_or(direction.from),
+        into: charset_into.unwrap_or(direction.into),
+    });
+    let transliterator = options.transliterator()?;
     Ok(Box::new(PandocProcessor::new(transliterator)))
 }
 
-fn main() -> Result<(), Error> {
+fn run() -> Result<(), Error> {
     #[cfg(not(feature = "pandoc"))]
     regular_mode()?.run()?;
     #[cfg(feature = "pandoc")]
@@ -238,3 +1606,18 @@ fn main() -> Result<(), Error> {
     }
     Ok(())
 }
+
+fn main() {
+    match run() {
+        Ok(()) => {}
+        Err(Error::Runtime(
+            translitrs::Error::Unconverted(_)
+            | translitrs::Error::Misspelled(_)
+            | translitrs::Error::Unmatched,
+        )) => std::process::exit(1),
+        Err(e) => {
+            eprint!("{}", e);
+            std::process::exit(2);
+        }
+    }
+}
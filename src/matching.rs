@@ -0,0 +1,138 @@
+use std::cmp::Ordering;
+
+use crate::key::normalize_for_key;
+
+/// Returns number of insertions, deletions and substitutions of
+/// characters which turn `a` into `b`
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Returns similarity of normalized keys, from `0.0` for completely
+/// different to `1.0` for equal keys
+fn key_similarity(a: &[char], b: &[char]) -> f64 {
+    let length = a.len().max(b.len());
+    if length == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / length as f64
+}
+
+fn key(text: &str) -> Vec<char> {
+    normalize_for_key(text).chars().collect()
+}
+
+/// Returns similarity of two texts regardless of their scripts, case and
+/// diacritics, from `0.0` for completely different to `1.0` for the same
+/// text. Both texts are normalized with [`normalize_for_key`], so the
+/// similarity is one minus edit distance of normalized texts divided by
+/// length of the longer one, which tolerates typos (ie. in names).
+///
+/// # Example
+///
+/// ```
+/// use translitrs::similarity;
+/// assert_eq!(similarity("Ђорђе", "DJORDJE"), 1.0);
+/// assert!(similarity("Ђорђе", "Djorde") > 0.8);
+/// assert!(similarity("Ђорђе", "Petar") < 0.3);
+/// ```
+pub fn similarity(a: &str, b: &str) -> f64 {
+    key_similarity(&key(a), &key(b))
+}
+
+/// Candidates which are scored against queries, with normalized keys
+/// created only once, ie. to match names of a whole database
+///
+/// # Example
+///
+/// ```
+/// use translitrs::Matcher;
+/// let matcher = Matcher::new(["Đorđe Marković", "Петар Петровић", "Djordje Markovc"]);
+/// assert_eq!(matcher.best("ЂОРЂЕ МАРКОВИЋ", 0.8), vec![(0, 1.0), (2, 0.9375)]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Matcher {
+    candidates: Vec<Vec<char>>,
+}
+
+impl Matcher {
+    /// Returns matcher of `candidates`, which are referred to by their
+    /// positions
+    pub fn new<I, S>(candidates: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            candidates: candidates.into_iter().map(|c| key(c.as_ref())).collect(),
+        }
+    }
+
+    /// Returns similarity of `query` to each candidate, in order of
+    /// candidates
+    pub fn scores(&self, query: &str) -> Vec<f64> {
+        let query = key(query);
+        self.candidates
+            .iter()
+            .map(|candidate| key_similarity(&query, candidate))
+            .collect()
+    }
+
+    /// Returns positions and similarities of candidates which are at
+    /// least `threshold` similar to `query`, from the most similar one
+    pub fn best(&self, query: &str, threshold: f64) -> Vec<(usize, f64)> {
+        let mut matches = self
+            .scores(query)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, score)| *score >= threshold)
+            .collect::<Vec<(usize, f64)>>();
+        // Sorting is stable, so equal candidates keep their order
+        matches.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity() {
+        for (a, b, expected) in [
+            ("", "", 1.0),
+            ("Đorđe", "Ђорђе", 1.0),
+            ("Đorđe", "Djordje", 1.0),
+            ("Ђорђе", "Djorđe!", 1.0),
+            ("Đorđe", "Dorde", 0.7142857142857143),
+            ("Джордже", "Đorđe", 0.7142857142857143),
+            ("Marko", "", 0.0),
+            ("abc", "xyz", 0.0),
+        ] {
+            assert_eq!(similarity(a, b), expected, "{} {}", a, b);
+            assert_eq!(similarity(b, a), expected, "{} {}", b, a);
+        }
+    }
+
+    #[test]
+    fn test_matcher() {
+        let matcher = Matcher::new(vec!["Jovana", "Јована", "Jovan", "Ivana"]);
+        assert_eq!(
+            matcher.scores("JOVANA"),
+            vec![1.0, 1.0, 0.8333333333333334, 0.6666666666666667]
+        );
+        assert_eq!(matcher.best("Јована", 0.8), vec![(0, 1.0), (1, 1.0), (2, 0.8333333333333334)]);
+        assert!(matcher.best("Petar", 0.5).is_empty());
+        assert!(Matcher::default().best("Jovana", 0.0).is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+#[cfg(feature = "config")]
+use std::fs;
+#[cfg(feature = "config")]
+use std::path::{Path, PathBuf};
+
+use std::convert::TryFrom;
+
+#[cfg(feature = "config")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "dialect")]
+use crate::dialect::{Dialect, DialectConverter};
+use crate::process::Error;
+use crate::transliterate::{
+    Alphanumeric, Archaic, Charset, Compounds, Direction, Fold, Language, Letters, Transliterator,
+    CONVERTED_TOLERANCE,
+};
+
+/// Name of the configuration file
+#[cfg(feature = "config")]
+pub const CONFIG_FILE: &str = "translit.toml";
+
+/// Format handlers which can be selected in the configuration file
+#[cfg(feature = "config")]
+pub const FORMATS: &[&str] = &[
+    "plain",
+    "pandoc",
+    #[cfg(feature = "html")]
+    "html",
+    #[cfg(feature = "feed")]
+    "feed",
+    #[cfg(feature = "geo")]
+    "gpx",
+    #[cfg(feature = "geo")]
+    "kml",
+    #[cfg(feature = "mail")]
+    "mail",
+    "rust",
+    "c",
+    "python",
+    "javascript",
+    "yaml",
+    "front-matter",
+    "ndjson",
+    "geojson",
+    "sql",
+    "ass",
+    "log",
+    "chat",
+    "wikitext",
+    "vcard",
+    "ics",
+    "anki",
+    "speech",
+    "conllu",
+    "vertical",
+    "pseudo",
+    "phonetic",
+    #[cfg(feature = "xlsx")]
+    "xlsx",
+    #[cfg(feature = "arrow")]
+    "parquet",
+];
+
+/// Transliteration settings, usually read from `translit.toml`.
+/// Unset options keep their default values. With the `config` feature,
+/// options can be serialized too, so conversion profiles can be stored
+/// (ie. in databases) and used again with the same results.
+///
+/// # Example
+///
+/// ```toml
+/// direction = "lat2cyr"
+/// language = "sr"
+/// exceptions = ["injunkc"]
+/// protected = ["Nikon", "GitHub"]
+/// skip-patterns = ['^\{\{\w+\}\}$']
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "config", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "config",
+    serde(default, rename_all = "kebab-case", deny_unknown_fields)
+)]
+pub struct Options {
+    /// Character sets to transliterate between (ie. `lat2cyr`)
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub direction: Option<Direction>,
+    /// Language whose orthography conventions are followed
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub language: Option<Language>,
+    /// Convert words into this dialect
+    #[cfg(feature = "dialect")]
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub dialect: Option<Dialect>,
+    /// Treatment of pre-reform Cyrillic letters
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub archaic: Option<Archaic>,
+    /// Splitting of compound words into components
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub compounds: Option<Compounds>,
+    /// Treatment of words which mix digits and letters
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub alphanumeric: Option<Alphanumeric>,
+    /// Treatment of standalone letters
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub letters: Option<Letters>,
+    pub skip_digraph: Option<bool>,
+    pub force_foreign: Option<bool>,
+    pub force_links: Option<bool>,
+    pub force_roman: Option<bool>,
+    /// Fix common OCR errors before transliterating
+    pub fix_ocr: Option<bool>,
+    /// Classes of compatibility characters folded before transliterating
+    #[cfg_attr(
+        feature = "config",
+        serde(deserialize_with = "parse", serialize_with = "display")
+    )]
+    pub fold: Option<Fold>,
+    /// Write titlecase Latin digraphs as single letters (ie. `ǈ`)
+    pub titlecase_digraphs: Option<bool>,
+    /// Transliterate dates and ordinal numbers written without spaces
+    #[cfg(feature = "dates")]
+    pub dates: Option<bool>,
+    /// Leave text which is already in the target script intact
+    pub skip_converted: Option<bool>,
+    /// Longest text in bytes which is transliterated
+    pub max_input: Option<usize>,
+    /// Longest word in bytes which is transliterated
+    pub max_word: Option<usize>,
+    /// Resolve ambiguous digraphs with the n-gram model
+    #[cfg(feature = "ngram")]
+    pub ngram: Option<bool>,
+    /// Keep sentences of detected foreign languages intact
+    #[cfg(feature = "detect-language")]
+    pub detect_language: Option<bool>,
+    /// Additional digraph exceptions
+    pub exceptions: Vec<String>,
+    /// Words which are never transliterated
+    pub protected: Vec<String>,
+    /// Never transliterate names in the bundled gazetteer
+    #[cfg(feature = "gazetteer")]
+    pub gazetteer: Option<bool>,
+    /// Pass ANSI escape sequences (ie. colors of terminals) through
+    pub ansi: Option<bool>,
+    /// Regular expressions matching words which are never transliterated
+    pub skip_patterns: Vec<String>,
+    /// Format handler used for input files (ie. `plain`, `pandoc`, `html`)
+    pub format: Option<String>,
+    /// Key paths of YAML values which are converted (ie. `title`, `seo.*`)
+    pub keys: Vec<String>,
+    /// Properties of GeoJSON features which are added transliterated
+    /// (ie. `name:sr`, `opis=opis:cyr`)
+    pub add_properties: Vec<String>,
+    /// Fail on characters outside of passthrough classes
+    pub strict: Option<bool>,
+    /// Character classes which are not reported as unmapped (ie. `digits`, `Sc`)
+    pub passthrough: Vec<String>,
+}
+
+#[cfg(feature = "config")]
+fn parse<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| {
+            s.parse()
+                .map_err(|e: T::Err| de::Error::custom(e.to_string().trim_end()))
+        })
+        .transpose()
+}
+
+#[cfg(feature = "config")]
+fn display<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: std::fmt::Display,
+{
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl Options {
+    /// Read options from a TOML file.
+    /// Fails if the file can't be read or contains unknown options.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the configuration file
+    #[cfg(feature = "config")]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let options: Options =
+            toml::from_str(&fs::read_to_string(path)?).map_err(|e| Error::Config(e.to_string()))?;
+        if let Some(format) = &options.format {
+            if !FORMATS.contains(&format.as_str()) {
+                return Err(Error::Config(format!("unknown format {}", format)));
+            }
+        }
+        Ok(options)
+    }
+
+    /// Returns these options in the format of `translit.toml`, where
+    /// unset options are left out
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Language, Options};
+    /// let options = Options {
+    ///     direction: Some("cyr2lat".parse().unwrap()),
+    ///     language: Some(Language::Croatian),
+    ///     ..Default::default()
+    /// };
+    /// let profile = options.to_toml().unwrap();
+    /// assert!(profile.starts_with("direction = \"cyr2lat\"\nlanguage = \"hr\"\n"));
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn to_toml(&self) -> Result<String, Error> {
+        toml::to_string(self).map_err(|e| Error::Config(e.to_string()))
+    }
+
+    /// Find `translit.toml` in `directory` or the closest of its parent
+    /// directories, and read options from it. Returns path of the file
+    /// and its options, or `None` if there is no configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - Directory to start searching from
+    #[cfg(feature = "config")]
+    pub fn discover<P: AsRef<Path>>(directory: P) -> Result<Option<(PathBuf, Self)>, Error> {
+        for directory in directory.as_ref().ancestors() {
+            let path = directory.join(CONFIG_FILE);
+            if path.is_file() {
+                let options = Self::from_path(&path)?;
+                return Ok(Some((path, options)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns these options with unset options taken from `defaults`.
+    /// Word lists and patterns of both are combined.
+    ///
+    /// # Arguments
+    ///
+    /// * `defaults` - Options used where these options are unset
+    pub fn merge(self, defaults: Options) -> Self {
+        Self {
+            direction: self.direction.or(defaults.direction),
+            language: self.language.or(defaults.language),
+            #[cfg(feature = "dialect")]
+            dialect: self.dialect.or(defaults.dialect),
+            archaic: self.archaic.or(defaults.archaic),
+            compounds: self.compounds.or(defaults.compounds),
+            alphanumeric: self.alphanumeric.or(defaults.alphanumeric),
+            letters: self.letters.or(defaults.letters),
+            skip_digraph: self.skip_digraph.or(defaults.skip_digraph),
+            force_foreign: self.force_foreign.or(defaults.force_foreign),
+            force_links: self.force_links.or(defaults.force_links),
+            force_roman: self.force_roman.or(defaults.force_roman),
+            fix_ocr: self.fix_ocr.or(defaults.fix_ocr),
+            fold: self.fold.or(defaults.fold),
+            titlecase_digraphs: self.titlecase_digraphs.or(defaults.titlecase_digraphs),
+            #[cfg(feature = "dates")]
+            dates: self.dates.or(defaults.dates),
+            skip_converted: self.skip_converted.or(defaults.skip_converted),
+            max_input: self.max_input.or(defaults.max_input),
+            max_word: self.max_word.or(defaults.max_word),
+            #[cfg(feature = "ngram")]
+            ngram: self.ngram.or(defaults.ngram),
+            #[cfg(feature = "detect-language")]
+            detect_language: self.detect_language.or(defaults.detect_language),
+            exceptions: [defaults.exceptions, self.exceptions].concat(),
+            protected: [defaults.protected, self.protected].concat(),
+            #[cfg(feature = "gazetteer")]
+            gazetteer: self.gazetteer.or(defaults.gazetteer),
+            ansi: self.ansi.or(defaults.ansi),
+            strict: self.strict.or(defaults.strict),
+            skip_patterns: [defaults.skip_patterns, self.skip_patterns].concat(),
+            format: self.format.or(defaults.format),
+            keys: [defaults.keys, self.keys].concat(),
+            add_properties: [defaults.add_properties, self.add_properties].concat(),
+            passthrough: [defaults.passthrough, self.passthrough].concat(),
+        }
+    }
+
+    /// Returns transliterator with these options.
+    /// Fails if a skip pattern is not a valid regular expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Direction, Charset, Options};
+    /// let options = Options {
+    ///     direction: Some(Direction { from: Charset::Cyrillic, into: Charset::Latin }),
+    ///     protected: vec!["Ћирилица".to_owned()],
+    ///     ..Default::default()
+    /// };
+    /// let t = options.transliterator().unwrap();
+    /// assert_eq!(t.process("Ћирилица и латиница").unwrap(), "Ћирилица i latinica");
+    /// ```
+    pub fn transliterator(&self) -> Result<Transliterator, Error> {
+        let direction = self.direction.unwrap_or(Direction {
+            from: Charset::Latin,
+            into: Charset::Cyrillic,
+        });
+        let transliterator = Transliterator::new(
+            direction.from,
+            direction.into,
+            self.skip_digraph.unwrap_or(false),
+            self.force_foreign.unwrap_or(false),
+            self.force_links.unwrap_or(false),
+        )
+        .with_force_roman(self.force_roman.unwrap_or(false))
+        .with_fix_ocr(self.fix_ocr.unwrap_or(false))
+        .with_fold(self.fold.unwrap_or_default())
+        .with_titlecase_digraphs(self.titlecase_digraphs.unwrap_or(false))
+        .with_skip_converted(
+            self.skip_converted
+                .unwrap_or(false)
+                .then_some(CONVERTED_TOLERANCE),
+        )
+        .with_max_input(self.max_input)
+        .with_max_word(self.max_word)
+        .with_language(self.language.unwrap_or(Language::Serbian))
+        .with_archaic(self.archaic.unwrap_or(Archaic::Ignore))
+        .with_compounds(self.compounds.unwrap_or(Compounds::Whole))
+        .with_alphanumeric(self.alphanumeric.unwrap_or(Alphanumeric::Suffixes))
+        .with_letters(self.letters.unwrap_or(Letters::Convert))
+        .with_exceptions(&self.exceptions)
+        .with_protected(&self.protected)
+        .with_ansi(self.ansi.unwrap_or(false))
+        .with_skip_patterns(&self.skip_patterns)?
+        .with_strict(self.strict.unwrap_or(false))
+        .with_passthrough(&self.passthrough)?;
+        #[cfg(feature = "dates")]
+        let transliterator = transliterator.with_dates(self.dates.unwrap_or(false));
+        #[cfg(feature = "dialect")]
+        let transliterator = transliterator.with_dialect(self.dialect.map(DialectConverter::new));
+        #[cfg(feature = "gazetteer")]
+        let transliterator = transliterator.with_gazetteer(self.gazetteer.unwrap_or(false));
+        #[cfg(feature = "ngram")]
+        let transliterator = transliterator.with_ngram(self.ngram.unwrap_or(false));
+        #[cfg(feature = "detect-language")]
+        let transliterator =
+            transliterator.with_detect_language(self.detect_language.unwrap_or(false));
+        Ok(transliterator)
+    }
+}
+
+impl TryFrom<&Options> for Transliterator {
+    type Error = Error;
+
+    fn try_from(options: &Options) -> Result<Self, Self::Error> {
+        options.transliterator()
+    }
+}
+
+#[cfg(all(test, feature = "config"))]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_from_path() {
+        let root = env::temp_dir().join(format!("translitrs-options-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("docs/chapter")).unwrap();
+        fs::write(
+            root.join(CONFIG_FILE),
+            "direction = \"cyr2lat\"\nlanguage = \"bs\"\nskip-patterns = ['^\\d+$']\n",
+        )
+        .unwrap();
+
+        let (path, options) = Options::discover(root.join("docs/chapter"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(path, root.join(CONFIG_FILE));
+        assert_eq!(
+            options,
+            Options {
+                direction: Some(Direction {
+                    from: Charset::Cyrillic,
+                    into: Charset::Latin
+                }),
+                language: Some(Language::Bosnian),
+                skip_patterns: vec![r"^\d+$".to_owned()],
+                ..Default::default()
+            }
+        );
+        assert!(options.transliterator().is_ok());
+
+        for text in ["direction = \"up\"", "unknown = true", "format = \"docx\""] {
+            fs::write(root.join(CONFIG_FILE), text).unwrap();
+            assert!(matches!(Options::from_path(root.join(CONFIG_FILE)), Err(Error::Config(_))));
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_to_toml() {
+        let options = Options {
+            direction: Some(Direction {
+                from: Charset::Latin,
+                into: Charset::Cyrillic,
+            }),
+            #[cfg(feature = "dialect")]
+            dialect: Some(Dialect::Ijekavian),
+            archaic: Some(Archaic::Annotate),
+            compounds: Some(Compounds::Hyphens),
+            alphanumeric: Some(Alphanumeric::Keep),
+            letters: Some(Letters::Serbian),
+            fold: Some(Fold {
+                fullwidth: true,
+                circled: true,
+                ..Default::default()
+            }),
+            force_roman: Some(false),
+            max_word: Some(256),
+            exceptions: vec!["injunkc".to_owned()],
+            skip_patterns: vec![r"^\d+$".to_owned()],
+            ..Default::default()
+        };
+        let profile = options.to_toml().unwrap();
+        assert!(profile.contains("fold = \"fullwidth,circled\"\n"));
+        assert!(profile.contains("max-word = 256\n"));
+        assert!(!profile.contains("language"));
+        assert_eq!(toml::from_str::<Options>(&profile).unwrap(), options);
+        assert_eq!(
+            toml::from_str::<Options>(&Options::default().to_toml().unwrap()).unwrap(),
+            Options::default()
+        );
+        assert!(Transliterator::try_from(&options).is_ok());
+    }
+}
@@ -0,0 +1,127 @@
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::transliterate::{Error, Transliterator};
+
+/// Parallel transliteration of many texts. Neither method uses a global
+/// thread pool, so they can be used where spawning one is forbidden
+/// (ie. in serverless functions and plugins).
+impl Transliterator {
+    /// Transliterate many texts on `threads` scoped threads, which borrow
+    /// texts and the transliterator instead of copying them and are joined
+    /// before returning. Texts are returned in the same order.
+    /// Fails with the error of the first text which can't be transliterated.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Texts to transliterate
+    /// * `threads` - Number of threads to spawn, at most one per text
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    /// use translitrs::{Charset, Transliterator};
+    ///
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+    /// let threads = NonZeroUsize::new(2).unwrap();
+    /// assert_eq!(
+    ///     t.process_parallel(&["Београд", "Нови Сад", "Ниш"], threads).unwrap(),
+    ///     ["Beograd", "Novi Sad", "Niš"]
+    /// );
+    /// ```
+    pub fn process_parallel<S: AsRef<str> + Sync>(
+        &self,
+        inputs: &[S],
+        threads: NonZeroUsize,
+    ) -> Result<Vec<String>, Error> {
+        // Each thread gets a contiguous chunk, so outputs stay in order
+        let size = ((inputs.len() + threads.get() - 1) / threads.get()).max(1);
+        thread::scope(|scope| {
+            let chunks = inputs
+                .chunks(size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|input| self.process(input))
+                            .collect::<Result<Vec<String>, Error>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut outputs = Vec::with_capacity(inputs.len());
+            let mut error = None;
+            // Join every thread before returning, even after an error
+            for chunk in chunks {
+                match chunk.join() {
+                    Ok(Ok(chunk)) => outputs.extend(chunk),
+                    Ok(Err(e)) => error = error.or(Some(e)),
+                    Err(panic) => std::panic::resume_unwind(panic),
+                }
+            }
+            match error {
+                Some(e) => Err(e),
+                None => Ok(outputs),
+            }
+        })
+    }
+
+    /// Transliterate many texts on a rayon thread pool owned by the caller,
+    /// instead of the global pool. Texts are returned in the same order.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Texts to transliterate
+    /// * `pool` - Thread pool to transliterate on
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Charset, Transliterator};
+    ///
+    /// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(
+    ///     t.process_in_pool(&["Beograd", "Niš"], &pool).unwrap(),
+    ///     ["Београд", "Ниш"]
+    /// );
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn process_in_pool<S: AsRef<str> + Sync>(
+        &self,
+        inputs: &[S],
+        pool: &rayon::ThreadPool,
+    ) -> Result<Vec<String>, Error> {
+        use rayon::prelude::*;
+        pool.install(|| inputs.par_iter().map(|input| self.process(input)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_process_parallel() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let inputs = (0..100).map(|i| format!("Njiva {}", i)).collect::<Vec<_>>();
+        let expected = (0..100).map(|i| format!("Њива {}", i)).collect::<Vec<_>>();
+        for threads in [1, 3, 8, 200] {
+            let threads = NonZeroUsize::new(threads).unwrap();
+            assert_eq!(t.process_parallel(&inputs, threads).unwrap(), expected);
+        }
+        let empty: [&str; 0] = [];
+        let threads = NonZeroUsize::new(4).unwrap();
+        assert!(t.process_parallel(&empty, threads).unwrap().is_empty());
+
+        #[cfg(feature = "rayon")]
+        {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .build()
+                .unwrap();
+            assert_eq!(t.process_in_pool(&inputs, &pool).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::transliterate::{Charset, Transliterator};
+
+/// Returns a non-capturing group matching any of `alternatives`, which
+/// are escaped, or the only one of them on its own
+fn group(alternatives: &[String]) -> String {
+    let mut unique: Vec<&String> = Vec::new();
+    for alternative in alternatives {
+        if !unique.contains(&alternative) {
+            unique.push(alternative);
+        }
+    }
+    match unique.as_slice() {
+        [alternative] => regex::escape(alternative),
+        _ => format!(
+            "(?:{})",
+            unique
+                .iter()
+                .map(|a| regex::escape(a))
+                .collect::<Vec<String>>()
+                .join("|")
+        ),
+    }
+}
+
+/// Returns a regular expression matching Serbian Latin `text` written in
+/// either Latin or Cyrillic, for searching texts in both scripts in a
+/// single pass (ie. `Beograd` and `Београд`). Text is matched literally,
+/// so characters with a special meaning in regular expressions are
+/// escaped, and case is kept, so `(?i)` can be prepended to ignore it.
+///
+/// Digraphs match both their single letter and two separate letters in
+/// Cyrillic (ie. `nj` matches `њ` and `нј` of `конјугација`), and their
+/// ligature in Latin (ie. `ǌ`, or `đ` for `dj`). Letters which have no
+/// Cyrillic counterpart (ie. `q`) are kept in Latin, so words with them
+/// are matched as they are in both scripts.
+///
+/// # Example
+///
+/// ```
+/// use regex::Regex;
+/// use translitrs::regex_for_both_scripts;
+///
+/// let re = Regex::new(&regex_for_both_scripts("Beograd")).unwrap();
+/// assert!(re.is_match("Beograd"));
+/// assert!(re.is_match("Београд"));
+///
+/// let re = Regex::new(&regex_for_both_scripts("konjug")).unwrap();
+/// assert!(re.is_match("konjugacija") && re.is_match("коњугација"));
+/// assert!(re.is_match("конјугација"));
+/// ```
+pub fn regex_for_both_scripts(text: &str) -> String {
+    let cyrillic = Transliterator::new(Charset::Latin, Charset::Cyrillic, true, false, false);
+    let unicode = Transliterator::new(Charset::Latin, Charset::LatinUnicode, true, false, false);
+    let convert = |t: &Transliterator, text: &str| {
+        t.transliterate_word(text, false)
+            .unwrap_or_else(|_| text.to_owned())
+    };
+    let (mut latin_branch, mut cyrillic_branch) = (String::new(), String::new());
+    for word in text.split_inclusive(char::is_whitespace) {
+        // Words with foreign letters are kept in Latin in Cyrillic texts
+        let foreign = convert(&cyrillic, word)
+            .chars()
+            .any(|c| Charset::of(c) == Some(Charset::Latin));
+        let chars = word.chars().collect::<Vec<char>>();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i].to_string();
+            if foreign || !chars[i].is_alphabetic() {
+                latin_branch.push_str(&regex::escape(&c));
+                cyrillic_branch.push_str(&regex::escape(&c));
+                i += 1;
+                continue;
+            }
+            // Digraphs are written with a single Cyrillic letter
+            if let Some(next) = chars.get(i + 1).filter(|c| c.is_alphabetic()) {
+                let pair = format!("{}{}", chars[i], next);
+                let single = convert(&cyrillic, &pair);
+                if single.chars().count() == 1 {
+                    latin_branch.push_str(&group(&[pair.clone(), convert(&unicode, &pair)]));
+                    let split = convert(&cyrillic, &c) + &convert(&cyrillic, &next.to_string());
+                    cyrillic_branch.push_str(&group(&[single, split]));
+                    i += 2;
+                    continue;
+                }
+            }
+            latin_branch.push_str(&regex::escape(&c));
+            cyrillic_branch.push_str(&regex::escape(&convert(&cyrillic, &c)));
+            i += 1;
+        }
+    }
+    if latin_branch == cyrillic_branch {
+        return latin_branch;
+    }
+    format!("(?:{}|{})", latin_branch, cyrillic_branch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_regex_for_both_scripts() {
+        assert_eq!(regex_for_both_scripts("Beograd"), "(?:Beograd|Београд)");
+        assert_eq!(regex_for_both_scripts("a.b"), r"(?:a\.b|а\.б)");
+        assert_eq!(regex_for_both_scripts("12 (3)"), r"12 \(3\)");
+        assert_eq!(regex_for_both_scripts("Njegoš"), "(?:(?:Nj|ǋ)egoš|(?:Њ|Нј)егош)");
+        for (query, matches, other) in [
+            ("Đorđe", &["Đorđe", "Ђорђе"][..], &["Djordje", "Đorde"][..]),
+            ("Djordje", &["Djordje", "Đorđe", "Ђорђе", "Дјордје"], &["Đorde"]),
+            ("džep", &["džep", "ǆep", "џеп", "джеп"], &["dzep"]),
+            ("LJUBAV", &["LJUBAV", "ǇUBAV", "ЉУБАВ", "ЛЈУБАВ"], &["Љубав"]),
+            ("quiz", &["quiz"], &["qуиз"]),
+            ("quiz i", &["quiz i", "quiz и"], &["qуиз и"]),
+        ] {
+            let re = Regex::new(&format!("^{}$", regex_for_both_scripts(query))).unwrap();
+            for text in matches {
+                assert!(re.is_match(text), "{} should match {}", query, text);
+            }
+            for text in other {
+                assert!(!re.is_match(text), "{} should not match {}", query, text);
+            }
+        }
+        let re = Regex::new(&format!("(?i){}", regex_for_both_scripts("Novi Sad"))).unwrap();
+        assert!(re.is_match("u NOVOM SADU, ne; ali NOVI SAD i нови сад"));
+    }
+}
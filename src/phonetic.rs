@@ -0,0 +1,162 @@
+use std::cmp::Reverse;
+use std::str;
+
+use crate::transliterate::{Charset, Error, Transliterator};
+
+/// Letters typed with two or three ASCII letters on keyboards without
+/// Serbian letters. `dj` is not replaced, since it is already converted
+/// into `đ` by the transliterator, which knows words like `odjednom`.
+const TRIGGERS: &[(&str, &str)] = &[
+    ("dzh", "dž"),
+    ("zh", "ž"),
+    ("ch", "č"),
+    ("sh", "š"),
+    ("cj", "ć"),
+];
+
+/// Beginnings of words where letters of a trigger are separate letters
+/// (ie. `shvatiti`, `razhladiti`)
+const EXCEPTIONS: &[&str] = &["shva", "shod", "izh", "razh"];
+
+/// Triggers of phonetic typing (ie. `zh` for `ž`), which are replaced
+/// with letters of Serbian Latin before transliterating, so text typed on
+/// US keyboards can be converted into proper Serbian Cyrillic. Triggers
+/// are matched regardless of case, and letters are capitalized after
+/// capitalized triggers.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::PhoneticMap;
+///
+/// let map = PhoneticMap::default();
+/// assert_eq!(map.to_latin("Zhivot je lep, chovece. SHTA?"), "Život je lep, čovece. ŠTA?");
+/// let map: PhoneticMap = "cj=,cc=ć,x=ž".parse().unwrap();
+/// assert_eq!(map.to_latin("Cjao, kucca xivi"), "Cjao, kuća živi");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhoneticMap(Vec<(String, String)>);
+
+impl Default for PhoneticMap {
+    fn default() -> Self {
+        Self(
+            TRIGGERS
+                .iter()
+                .map(|&(trigger, letter)| (trigger.to_owned(), letter.to_owned()))
+                .collect(),
+        )
+    }
+}
+
+impl str::FromStr for PhoneticMap {
+    type Err = Error;
+
+    /// Parse comma separated triggers and their letters in either script
+    /// (ie. `x=ж,cc=ć`), which override default triggers. Triggers must be
+    /// ASCII, and triggers without a letter are removed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let latin = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let mut map = PhoneticMap::default();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (trigger, letter) = entry
+                .split_once('=')
+                .ok_or_else(|| Error::InvalidPhoneticMap(entry.to_owned()))?;
+            let trigger = trigger.trim();
+            if trigger.is_empty() || !trigger.is_ascii() {
+                return Err(Error::InvalidPhoneticMap(entry.to_owned()));
+            }
+            map = map.with(trigger, &latin.process_word(letter.trim())?);
+        }
+        Ok(map)
+    }
+}
+
+impl PhoneticMap {
+    /// Replace `trigger`, which should be ASCII, with `letter` of Serbian
+    /// Latin, or don't replace it if `letter` is empty
+    pub fn with(mut self, trigger: &str, letter: &str) -> Self {
+        let trigger = trigger.to_ascii_lowercase();
+        self.0.retain(|(t, _)| *t != trigger);
+        if !letter.is_empty() {
+            self.0.push((trigger, letter.to_lowercase()));
+        }
+        // Longer triggers are matched first (ie. `dzh` before `zh`)
+        self.0.sort_by_key(|(trigger, _)| Reverse(trigger.len()));
+        self
+    }
+
+    /// Replace triggers in `text` with letters of Serbian Latin, which can
+    /// be transliterated into Cyrillic
+    pub fn to_latin(&self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut position = 0;
+        while let Some(c) = text[position..].chars().next() {
+            let rest = &text[position..];
+            let start = !text[..position]
+                .chars()
+                .next_back()
+                .map_or(false, char::is_alphanumeric);
+            // Letters of exceptions are copied as they are
+            if let Some(exception) = EXCEPTIONS.iter().find(|e| {
+                start
+                    && rest
+                        .get(..e.len())
+                        .map_or(false, |p| p.eq_ignore_ascii_case(e))
+            }) {
+                output.push_str(&rest[..exception.len()]);
+                position += exception.len();
+                continue;
+            }
+            let found = self.0.iter().find(|(trigger, _)| {
+                rest.get(..trigger.len())
+                    .map_or(false, |p| p.eq_ignore_ascii_case(trigger))
+            });
+            match found {
+                Some((trigger, letter)) => {
+                    let typed = &rest[..trigger.len()];
+                    let mut letters = letter.chars();
+                    match (c.is_uppercase(), typed.chars().skip(1).all(|c| !c.is_lowercase())) {
+                        // Uppercase trigger (ie. `SH`) is an uppercase letter
+                        (true, true) => output.push_str(&letter.to_uppercase()),
+                        (true, false) => {
+                            output.extend(letters.next().into_iter().flat_map(char::to_uppercase));
+                            output.push_str(letters.as_str());
+                        }
+                        _ => output.push_str(letter),
+                    }
+                    position += trigger.len();
+                }
+                None => {
+                    output.push(c);
+                    position += c.len_utf8();
+                }
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phonetic_map() {
+        let map = PhoneticMap::default();
+        for (text, expected) in [
+            ("", ""),
+            ("zhaba", "žaba"),
+            ("Dzhungla i DZHEP", "Džungla i DŽEP"),
+            ("Shvatio sam da je kucja Cjirina", "Shvatio sam da je kuća Ćirina"),
+            ("razhladjen, izhabati", "razhladjen, izhabati"),
+            ("ushao u shkolu", "ušao u školu"),
+            ("Šta čovek hoće", "Šta čovek hoće"),
+        ] {
+            assert_eq!(map.to_latin(text), expected);
+        }
+        let map: PhoneticMap = "ch=ћ, c'=ć, sh=".parse().unwrap();
+        assert_eq!(map.to_latin("chao c'ao shala"), "ćao ćao shala");
+        assert!("ж=zh".parse::<PhoneticMap>().is_err());
+        assert!("zh".parse::<PhoneticMap>().is_err());
+    }
+}
@@ -1,19 +1,146 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::{error, fmt, io};
 
-use crate::transliterate;
+use crate::transliterate::{self, Transliterator};
 
+/// Implement `with_backup` for processors which write into an [`Output`]
+/// field named `output`
+macro_rules! with_backup {
+    ($processor:ty) => {
+        impl $processor {
+            /// Keep the original output file as a `.bak` file
+            pub fn with_backup(mut self, backup: bool) -> Self {
+                self.output.backup = backup;
+                self
+            }
+        }
+    };
+}
+
+mod anki;
+mod ass;
+mod atomic;
+mod chat;
+mod check;
+#[cfg(feature = "clipboard")]
+mod clip;
+mod conllu;
+mod coverage;
+mod detect;
+#[cfg(feature = "feed")]
+mod feed;
+mod filter;
+#[cfg(feature = "geo")]
+mod geo;
+mod geojson;
+mod grep;
+mod highlight;
+#[cfg(feature = "html")]
+mod html;
+mod learn;
+mod log;
+#[cfg(feature = "mail")]
+mod mail;
+mod ndjson;
 #[cfg(feature = "pandoc")]
 mod pandoc;
+#[cfg(feature = "arrow")]
+mod parquet;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod phonetic;
 mod plaintext;
+#[cfg(feature = "tui")]
+mod preview;
+mod pseudo;
+mod rename;
+mod review;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(feature = "site")]
+mod site;
+mod source;
+mod speech;
+mod sql;
+mod stats;
+mod unmapped;
+#[cfg(feature = "hunspell")]
+mod validate;
+mod vcard;
+#[cfg(feature = "watch")]
+mod watch;
+mod wiki;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+mod yaml;
 
 #[cfg(feature = "pandoc")]
 pub use self::pandoc::PandocProcessor;
+pub use anki::AnkiProcessor;
+pub use ass::AssProcessor;
+pub use chat::ChatProcessor;
+pub use check::CheckProcessor;
+#[cfg(feature = "clipboard")]
+pub use clip::ClipProcessor;
+pub use conllu::ConlluProcessor;
+pub use coverage::CoverageProcessor;
+pub use detect::DetectProcessor;
+#[cfg(feature = "feed")]
+pub use feed::FeedProcessor;
+pub use filter::FilterProcessor;
+#[cfg(feature = "geo")]
+pub use geo::GeoProcessor;
+pub use geojson::GeojsonProcessor;
+pub use grep::GrepProcessor;
+pub use highlight::HighlightProcessor;
+#[cfg(feature = "html")]
+pub use html::{HtmlProcessor, HtmlStream};
+pub use learn::LearnProcessor;
+pub use log::LogProcessor;
+#[cfg(feature = "mail")]
+pub use mail::MailProcessor;
+pub use ndjson::NdjsonProcessor;
+#[cfg(feature = "arrow")]
+pub use parquet::ParquetProcessor;
+#[cfg(feature = "pdf")]
+pub use pdf::PdfProcessor;
+pub use phonetic::PhoneticProcessor;
 pub use plaintext::PlaintextProcessor;
+#[cfg(feature = "tui")]
+pub use preview::PreviewProcessor;
+pub use pseudo::PseudoProcessor;
+pub use rename::RenameProcessor;
+pub use review::ReviewProcessor;
+#[cfg(feature = "serve")]
+pub use serve::ServeProcessor;
+#[cfg(feature = "site")]
+pub use site::SiteProcessor;
+pub use source::{SourceProcessor, Syntax};
+pub use speech::SpeechProcessor;
+pub use sql::SqlProcessor;
+pub use stats::StatsProcessor;
+pub use unmapped::UnmappedProcessor;
+#[cfg(feature = "hunspell")]
+pub use validate::ValidateProcessor;
+pub use vcard::VcardProcessor;
+#[cfg(feature = "watch")]
+pub use watch::WatchProcessor;
+pub use wiki::WikiProcessor;
+#[cfg(feature = "xlsx")]
+pub use xlsx::XlsxProcessor;
+pub use yaml::YamlProcessor;
 
 #[derive(Debug)]
 pub enum Error {
     Io(io::Error),
     Processing(transliterate::Error),
+    Collision(PathBuf),
+    Unconverted(usize),
+    Misspelled(usize),
+    Unmatched,
+    Config(String),
 }
 
 impl fmt::Display for Error {
@@ -21,6 +148,11 @@ impl fmt::Display for Error {
         match self {
             Self::Io(e) => writeln!(f, "IO error - {}", e),
             Self::Processing(e) => writeln!(f, "Processing error - {}", e),
+            Self::Collision(p) => writeln!(f, "Path already exists - {}", p.display()),
+            Self::Unconverted(n) => writeln!(f, "Lines not transliterated - {}", n),
+            Self::Misspelled(n) => writeln!(f, "Words not in dictionary - {}", n),
+            Self::Unmatched => writeln!(f, "No lines matched"),
+            Self::Config(e) => writeln!(f, "Configuration error - {}", e),
         }
     }
 }
@@ -42,3 +174,80 @@ impl From<transliterate::Error> for Error {
 pub trait FileProcessor {
     fn run(&mut self) -> Result<(), Error>;
 }
+
+/// Open input file of a processor, or standard input if there is none
+fn open(path: Option<PathBuf>) -> Result<Box<dyn Read>, Error> {
+    Ok(match path {
+        Some(path) => Box::from(File::open(path)?),
+        None => Box::from(io::stdin()),
+    })
+}
+
+/// Output of a processor: a file or standard output. Output file is
+/// replaced only after input is processed, so it can be the same as
+/// input file.
+struct Output {
+    path: Option<PathBuf>,
+    backup: bool,
+}
+
+impl Output {
+    fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            backup: false,
+        }
+    }
+
+    /// Write whole `contents` at once
+    fn write(&self, contents: &[u8]) -> Result<(), Error> {
+        match &self.path {
+            Some(path) => atomic::write(path, contents, self.backup)?,
+            None => io::stdout().write_all(contents)?,
+        }
+        Ok(())
+    }
+
+    /// Write contents as they are produced by `contents`
+    fn write_with(
+        &self,
+        contents: impl FnOnce(&mut dyn Write) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        match &self.path {
+            Some(path) => {
+                atomic::write_with(path, self.backup, |file| contents(&mut BufWriter::new(file)))
+            }
+            // Standard output is line buffered, so lines are not delayed
+            None => contents(&mut io::stdout().lock()),
+        }
+    }
+}
+
+/// Transliterate a file in place. The file is replaced atomically, so it
+/// is never left truncated if the process is interrupted.
+/// Fails if the file is not valid UTF-8.
+///
+/// # Arguments
+///
+/// * `path` - File to transliterate
+/// * `processor` - Transliterator to use
+/// * `backup` - Keep the original file as a `.bak` file
+///
+/// # Example
+///
+/// ```no_run
+/// use translitrs::{process_file, Transliterator, Charset};
+/// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+/// process_file("notes.txt", &t, true).unwrap();
+/// ```
+pub fn process_file<P: AsRef<Path>>(
+    path: P,
+    processor: &Transliterator,
+    backup: bool,
+) -> Result<(), Error> {
+    let path = path.as_ref();
+    let input = fs::read_to_string(path)?;
+    let output = processor.process(input)?;
+    atomic::write(path, output.as_bytes(), backup)?;
+    Ok(())
+}
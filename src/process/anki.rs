@@ -0,0 +1,287 @@
+use std::io::Read;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    /// Media reference (ie. `[sound:pas.mp3]`), LaTeX or MathJax formula,
+    /// HTML tag (ie. `<img src="pas.jpg">`) or character reference, or
+    /// markup of a cloze deletion (ie. `{{c1::pas::hint}}`)
+    static ref TOKEN: Regex = Regex::new(concat!(
+        r"\[sound:[^\]]*\]",
+        r"|\[latex\][\s\S]*?\[/latex\]|\[\$\$?\][\s\S]*?\[/\$\$?\]",
+        r"|\\\([\s\S]*?\\\)|\\\[[\s\S]*?\\\]",
+        r"|<[^<>]*>|&(?:#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);",
+        r"|\{\{c\d+::|::|\}\}"
+    ))
+    .unwrap();
+}
+
+/// Headers of columns which are not fields of notes
+const COLUMN_HEADERS: &[&str] = &[
+    "tags column",
+    "notetype column",
+    "deck column",
+    "guid column",
+];
+
+/// Returns separator named by the `#separator` header
+fn separator(name: &str) -> Option<char> {
+    match name.to_lowercase().as_str() {
+        "tab" => Some('\t'),
+        "comma" => Some(','),
+        "semicolon" => Some(';'),
+        "colon" => Some(':'),
+        "pipe" => Some('|'),
+        "space" => Some(' '),
+        _ => {
+            let mut chars = name.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        }
+    }
+}
+
+/// Returns fields of the record at the start of `text`, and the length
+/// of the record with its line break. Quoted fields (ie. `"a""b"`) may
+/// contain separators and line breaks.
+fn record(text: &str, separator: char) -> (Vec<Range<usize>>, usize) {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut quoted = text.starts_with('"');
+    let mut chars = text.char_indices().skip(quoted as usize).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek().map(|&(_, c)| c) == Some('"') => {
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            _ if quoted => {}
+            '\n' => {
+                fields.push(start..text[..i].trim_end_matches('\r').len().max(start));
+                return (fields, i + 1);
+            }
+            c if c == separator => {
+                fields.push(start..i);
+                start = i + c.len_utf8();
+                quoted = text[start..].starts_with('"');
+                if quoted {
+                    chars.next();
+                }
+            }
+            _ => {}
+        }
+    }
+    fields.push(start..text.len());
+    (fields, text.len())
+}
+
+/// Transliterates notes of Anki decks exported as plain text, so decks
+/// for language learning can show both alphabets. Selected fields are
+/// converted, or copied into new fields in the other script. Media
+/// references (ie. `[sound:pas.mp3]`, `<img src="pas.jpg">`), formulas,
+/// HTML markup and markup of cloze deletions are left unchanged, as well
+/// as tags, note types, decks and ids of notes.
+pub struct AnkiProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    /// Names or numbers of source fields, and names of new fields
+    fields: Vec<(String, Option<String>)>,
+}
+
+impl AnkiProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            fields: Vec::new(),
+        })
+    }
+
+    /// Convert only these fields, given by name or number (ie. `Back` or
+    /// `2`). Fields given as `source=target` are left unchanged, and
+    /// converted into a new field `target` instead. All fields are
+    /// converted if empty.
+    pub fn with_fields(mut self, fields: &[String]) -> Self {
+        self.fields = fields
+            .iter()
+            .map(|f| match f.split_once('=') {
+                Some((source, target)) => (source.to_owned(), Some(target.to_owned())),
+                None => (f.clone(), None),
+            })
+            .collect();
+        self
+    }
+
+    /// Transliterate a field, leaving media references and markup
+    /// unchanged
+    fn convert_field(&self, field: &str) -> Result<String, transliterate::Error> {
+        let mut output = String::with_capacity(field.len());
+        let mut last = 0;
+        for token in TOKEN.find_iter(field) {
+            output.push_str(&self.processor.process(&field[last..token.start()])?);
+            output.push_str(token.as_str());
+            last = token.end();
+        }
+        output.push_str(&self.processor.process(&field[last..])?);
+        Ok(output)
+    }
+
+    /// Transliterate a field, which may be quoted
+    fn convert_quoted(&self, field: &str) -> Result<String, transliterate::Error> {
+        match field.len() > 1 && field.starts_with('"') && field.ends_with('"') {
+            true => Ok(format!("\"{}\"", self.convert_field(&field[1..field.len() - 1])?)),
+            false => self.convert_field(field),
+        }
+    }
+
+    /// Transliterate a deck exported as plain text
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(text.len());
+        let mut separator_char = '\t';
+        let mut columns: Vec<String> = Vec::new();
+        // Numbers of columns which are not fields
+        let mut skipped: Vec<usize> = Vec::new();
+        let mut position = 0;
+        // Headers are lines at the start of the file
+        while text[position..].starts_with('#') {
+            let end = text[position..]
+                .find('\n')
+                .map_or(text.len(), |n| position + n + 1);
+            let line = text[position..end].trim_end_matches(['\n', '\r']);
+            let (name, value) = line[1..].split_once(':').unwrap_or((&line[1..], ""));
+            match name {
+                "separator" => separator_char = separator(value).unwrap_or(separator_char),
+                "columns" => {
+                    let (fields, _) = record(value, separator_char);
+                    columns = fields.into_iter().map(|f| value[f].to_owned()).collect();
+                    // New fields are named in the header
+                    output.push_str(line);
+                    for (_, target) in &self.fields {
+                        if let Some(target) = target {
+                            output.push(separator_char);
+                            output.push_str(target);
+                        }
+                    }
+                    output.push_str(&text[position + line.len()..end]);
+                    position = end;
+                    continue;
+                }
+                name if COLUMN_HEADERS.contains(&name) => {
+                    skipped.extend(value.trim().parse::<usize>())
+                }
+                _ => {}
+            }
+            output.push_str(&text[position..end]);
+            position = end;
+        }
+        // Selects a field by its number (starting with one) and name
+        let selected = |number: usize, source: &str| {
+            source.parse::<usize>() == Ok(number)
+                || columns.get(number - 1).map_or(false, |name| name == source)
+        };
+        while position < text.len() {
+            let (fields, length) = record(&text[position..], separator_char);
+            let record = &text[position..position + length];
+            let end = fields.last().map_or(0, |f| f.end);
+            let mut added = String::new();
+            let mut last = 0;
+            for (n, field) in fields.iter().enumerate() {
+                let number = n + 1;
+                output.push_str(&record[last..field.start]);
+                let value = &record[field.clone()];
+                let convert = match self.fields.is_empty() {
+                    true => !skipped.contains(&number),
+                    false => self
+                        .fields
+                        .iter()
+                        .any(|(source, target)| target.is_none() && selected(number, source)),
+                };
+                match convert {
+                    true => output.push_str(&self.convert_quoted(value)?),
+                    false => output.push_str(value),
+                }
+                last = field.end;
+            }
+            // New fields are added in the order they are given
+            for (source, target) in &self.fields {
+                if target.is_none() {
+                    continue;
+                }
+                let value = fields
+                    .iter()
+                    .enumerate()
+                    .find(|(n, _)| selected(n + 1, source))
+                    .map_or("", |(_, field)| &record[field.clone()]);
+                added.push(separator_char);
+                added.push_str(&self.convert_quoted(value)?);
+            }
+            output.push_str(&added);
+            output.push_str(&record[end..]);
+            position += length;
+        }
+        Ok(output)
+    }
+}
+
+with_backup!(AnkiProcessor);
+
+impl FileProcessor for AnkiProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_anki() {
+        let p = AnkiProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        let deck = concat!(
+            "#separator:tab\n#html:true\n#tags column:3\n",
+            "pas [sound:pas.mp3]\t<img src=\"pas.jpg\"> Pas laje &nbsp;\tzivotinje\n",
+            "\"Reč\tsa \"\"navodnicima\"\"\nu dva reda\"\t{{c1::Beograd::glavni grad}}\tgradovi\r\n",
+            "Prazno\t\t"
+        );
+        let expected = concat!(
+            "#separator:tab\n#html:true\n#tags column:3\n",
+            "пас [sound:pas.mp3]\t<img src=\"pas.jpg\"> Пас лаје &nbsp;\tzivotinje\n",
+            "\"Реч\tса \"\"наводницима\"\"\nу два реда\"\t{{c1::Београд::главни град}}\tgradovi\r\n",
+            "Празно\t\t"
+        );
+        assert_eq!(p.convert(deck).unwrap(), expected);
+
+        let p = p.with_fields(&["Front=Ćirilica".to_owned(), "2".to_owned()]);
+        let deck = concat!(
+            "#separator:Semicolon\n#columns:Front;Back;Tags\n#tags column:3\n",
+            "Ljubav;\"Voleti; ljubiti\";reci\n"
+        );
+        let expected = concat!(
+            "#separator:Semicolon\n#columns:Front;Back;Tags;Ćirilica\n#tags column:3\n",
+            "Ljubav;\"Волети; љубити\";reci;Љубав\n"
+        );
+        assert_eq!(p.convert(deck).unwrap(), expected);
+    }
+}
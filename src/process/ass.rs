@@ -0,0 +1,158 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    /// Override tags (ie. `{\i1}`) and line breaks and hard spaces
+    static ref TAG: Regex = Regex::new(r"\{[^}]*\}|\\[Nnh]").unwrap();
+    /// Drawing mode tag, whose text is a drawing instead of dialogue
+    static ref DRAWING: Regex = Regex::new(r"\\p([0-9]+)").unwrap();
+}
+
+/// Fields of an event line in both ASS and SSA scripts, before `Text`
+const FIELDS: usize = 9;
+
+/// Transliterates dialogue of Advanced SubStation Alpha (`.ass`) and
+/// SubStation Alpha (`.ssa`) subtitles. Override tags (ie. `{\i1}`),
+/// drawings, styles, comments and other sections are left unchanged.
+pub struct AssProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl AssProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate text of a dialogue event
+    fn convert_text(&self, text: &str) -> Result<String, transliterate::Error> {
+        let mut output = String::with_capacity(text.len());
+        let mut drawing = false;
+        let mut last = 0;
+        for tag in TAG.find_iter(text) {
+            let segment = &text[last..tag.start()];
+            match drawing {
+                true => output.push_str(segment),
+                false => output.push_str(&self.processor.process(segment)?),
+            }
+            output.push_str(tag.as_str());
+            if let Some(scale) = DRAWING.captures_iter(tag.as_str()).last() {
+                drawing = &scale[1] != "0";
+            }
+            last = tag.end();
+        }
+        match drawing {
+            true => output.push_str(&text[last..]),
+            false => output.push_str(&self.processor.process(&text[last..])?),
+        }
+        Ok(output)
+    }
+
+    /// Transliterate a subtitle script
+    pub fn convert(&self, script: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(script.len());
+        let mut events = false;
+        // Position of the text field, from format of the events section
+        let mut fields = FIELDS;
+        for line in script.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let trimmed = content.trim_start_matches('\u{feff}').trim_start();
+            if trimmed.starts_with('[') {
+                events = trimmed.eq_ignore_ascii_case("[events]");
+            } else if let (true, Some(format)) = (events, trimmed.strip_prefix("Format:")) {
+                let names = format.split(',').map(str::trim).collect::<Vec<&str>>();
+                fields = names
+                    .iter()
+                    .position(|name| name.eq_ignore_ascii_case("text"))
+                    .unwrap_or(FIELDS);
+            } else if let (true, Some(event)) = (events, trimmed.strip_prefix("Dialogue:")) {
+                // Text is the last field, and may contain commas
+                let mut columns = event.splitn(fields + 1, ',');
+                let prefix = (&mut columns).take(fields).collect::<Vec<&str>>();
+                if let (true, Some(text)) = (prefix.len() == fields, columns.next()) {
+                    let start = content.len() - text.len();
+                    output.push_str(&content[..start]);
+                    output.push_str(&self.convert_text(text)?);
+                    output.push_str(&line[content.len()..]);
+                    continue;
+                }
+            }
+            output.push_str(line);
+        }
+        Ok(output)
+    }
+}
+
+with_backup!(AssProcessor);
+
+impl FileProcessor for AssProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_ass() {
+        let p = AssProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        let script = concat!(
+            "[Script Info]\r\n",
+            "Title: Prva epizoda\r\n",
+            "ScriptType: v4.00+\r\n",
+            "\r\n",
+            "[V4+ Styles]\r\n",
+            "Format: Name, Fontname, Fontsize, PrimaryColour, Bold, Italic\r\n",
+            "Style: Default,Arial,20,&H00FFFFFF,0,0\r\n",
+            "\r\n",
+            "[Events]\r\n",
+            "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\r\n",
+            "Dialogue: 0,0:00:01.00,0:00:03.00,Default,Ana,0,0,0,,{\\i1}Zdravo{\\i0}, svete!\\Nnjiva\r\n",
+            "Comment: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Prevod\r\n",
+            "Dialogue: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,{\\p1}m 0 0 l 10 0{\\p0}Kraj\r\n"
+        );
+        let expected = concat!(
+            "[Script Info]\r\n",
+            "Title: Prva epizoda\r\n",
+            "ScriptType: v4.00+\r\n",
+            "\r\n",
+            "[V4+ Styles]\r\n",
+            "Format: Name, Fontname, Fontsize, PrimaryColour, Bold, Italic\r\n",
+            "Style: Default,Arial,20,&H00FFFFFF,0,0\r\n",
+            "\r\n",
+            "[Events]\r\n",
+            "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\r\n",
+            "Dialogue: 0,0:00:01.00,0:00:03.00,Default,Ana,0,0,0,,{\\i1}Здраво{\\i0}, свете!\\Nњива\r\n",
+            "Comment: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Prevod\r\n",
+            "Dialogue: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,{\\p1}m 0 0 l 10 0{\\p0}Крај\r\n"
+        );
+        assert_eq!(p.convert(script).unwrap(), expected);
+    }
+}
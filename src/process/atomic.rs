@@ -0,0 +1,142 @@
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Returns path of a file with `suffix` appended to its name
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name: OsString = path.file_name().unwrap_or_default().to_owned();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Returns path of the backup file (ie. `notes.txt.bak`)
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// Replace contents of a file so it is never left truncated: write to a
+/// temporary file in the same directory, flush it to disk and rename it
+/// over the original file. Original file is kept as a `.bak` file if
+/// `backup` is set.
+pub fn write(path: &Path, contents: &[u8], backup: bool) -> io::Result<()> {
+    write_with(path, backup, |file| file.write_all(contents))
+}
+
+/// Replace contents of a file like [`write`], with contents written by
+/// `contents` into the temporary file, so they can be streamed. Symbolic
+/// links are followed, so the file they point to is replaced, and files
+/// which are not regular files (ie. `/dev/stdout` or named pipes) are
+/// written directly.
+pub fn write_with<E: From<io::Error>>(
+    path: &Path,
+    backup: bool,
+    contents: impl FnOnce(&mut File) -> Result<(), E>,
+) -> Result<(), E> {
+    let canonical = match fs::canonicalize(path) {
+        Ok(canonical) => canonical,
+        // Link to a file which doesn't exist yet creates that file
+        Err(e) if e.kind() == io::ErrorKind::NotFound => match fs::read_link(path) {
+            Ok(target) => path.parent().unwrap_or_else(|| Path::new("")).join(target),
+            Err(_) => path.to_owned(),
+        },
+        Err(e) => return Err(e.into()),
+    };
+    let path = canonical.as_path();
+    if let Ok(metadata) = fs::metadata(path) {
+        if !metadata.is_file() {
+            return contents(&mut OpenOptions::new().write(true).truncate(true).open(path)?);
+        }
+    }
+    let temporary = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        std::process::id()
+    ));
+    let result: Result<(), E> = (|| {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temporary)?;
+        contents(&mut file)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+            if backup {
+                fs::copy(path, backup_path(path))?;
+            }
+        }
+        file.sync_all()?;
+        Ok(fs::rename(&temporary, path)?)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result?;
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_write() {
+        let root = env::temp_dir().join(format!("translitrs-atomic-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("tekst.txt");
+
+        write(&path, b"prvi", true).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"prvi");
+        assert!(!backup_path(&path).exists());
+
+        write(&path, b"drugi", true).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"drugi");
+        assert_eq!(fs::read(backup_path(&path)).unwrap(), b"prvi");
+
+        write(&path, b"treci", false).unwrap();
+        assert_eq!(fs::read(backup_path(&path)).unwrap(), b"prvi");
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_symlink() {
+        let root = env::temp_dir().join(format!("translitrs-symlink-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("cilj")).unwrap();
+        let target = root.join("cilj").join("tekst.txt");
+        let link = root.join("veza.txt");
+        fs::write(&target, b"prvi").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write(&link, b"drugi", true).unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read(&target).unwrap(), b"drugi");
+        assert_eq!(fs::read(backup_path(&target)).unwrap(), b"prvi");
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+
+        // Files which are not regular files are written directly
+        write(Path::new("/dev/null"), b"treci", false).unwrap();
+        let temporary = format!("/dev/.null.{}.tmp", std::process::id());
+        assert!(!Path::new(&temporary).exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::Transliterator;
+
+lazy_static! {
+    /// Code block or span, Discord or Slack entity (ie. `<@123>` or
+    /// `<:name:123>`), emoji code (ie. `:smile:`), mention (ie. `@marko`)
+    /// or Markdown link, whose label is converted
+    static ref TOKEN: Regex = Regex::new(concat!(
+        r"```[\s\S]*?```|``(?:[^`]|`[^`])+?``|`[^`\n]+`",
+        r"|<(?:a?:\w+:\d+|[@#][!&]?\w+|/[\w -]+:\d+|t:-?\d+(?::\w)?|!\w+)(?:\|[^<>\n]*)?>",
+        r"|:[\w+-]+:",
+        r"|\B@\w+",
+        r"|\[(?P<label>[^\[\]\n]+)\]\((?P<url>[^()\s]+)\)"
+    ))
+    .unwrap();
+}
+
+/// Transliterates chat messages with Markdown formatting of Telegram,
+/// Discord and Slack. Code blocks and spans, mentions of users, roles and
+/// channels (ie. `@marko`, `<@123>`), emoji codes (ie. `:smile:`, custom
+/// `<:name:123>`) and URLs of links are left unchanged, while formatting
+/// (ie. `*bold*`, `||spoiler||`) is kept around converted text.
+pub struct ChatProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl ChatProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate a chat message
+    pub fn convert(&self, message: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(message.len());
+        let mut last = 0;
+        for token in TOKEN.captures_iter(message) {
+            let whole = token.get(0).unwrap();
+            output.push_str(&self.processor.process(&message[last..whole.start()])?);
+            match (token.name("label"), token.name("url")) {
+                (Some(label), Some(url)) => {
+                    output.push('[');
+                    output.push_str(&self.convert(label.as_str())?);
+                    output.push_str("](");
+                    output.push_str(url.as_str());
+                    output.push(')');
+                }
+                _ => output.push_str(whole.as_str()),
+            }
+            last = whole.end();
+        }
+        output.push_str(&self.processor.process(&message[last..])?);
+        Ok(output)
+    }
+}
+
+with_backup!(ChatProcessor);
+
+impl FileProcessor for ChatProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_chat() {
+        let p = ChatProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        for (message, expected) in [
+            ("*Zdravo* _svete_ ~~dan~~", "*Здраво* _свете_ ~~дан~~"),
+            ("||tajna|| __**jako**__", "||тајна|| __**јако**__"),
+            ("Pozdrav @marko, piši ana@primer.rs", "Поздрав @marko, пиши ana@primer.rs"),
+            ("Hvala <@!123> i <@&45> u <#67>", "Хвала <@!123> и <@&45> у <#67>"),
+            ("Bravo :tada: <:kolac:89> <a:ples:90>", "Браво :tada: <:kolac:89> <a:ples:90>"),
+            ("Pokreni `npm test` ili ``a`b``", "Покрени `npm test` или ``a`b``"),
+            (
+                "Kod:\n```rust\nlet nije = 1;\n```\nkraj",
+                "Код:\n```rust\nlet nije = 1;\n```\nкрај",
+            ),
+            (
+                "Vidi [*uputstvo*](https://primer.rs/put)",
+                "Види [*упутство*](https://primer.rs/put)",
+            ),
+            ("Kraj <t:1700000000:R> <!here>", "Крај <t:1700000000:R> <!here>"),
+        ] {
+            assert_eq!(p.convert(message).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::json;
+use crate::transliterate::Transliterator;
+
+/// Reports lines which would change after transliteration
+pub struct CheckProcessor {
+    paths: Vec<PathBuf>,
+    processor: Transliterator,
+    json: bool,
+}
+
+impl CheckProcessor {
+    /// Check files at `paths`, or standard input if there are none
+    pub fn new(paths: Vec<PathBuf>, processor: Transliterator) -> Self {
+        Self {
+            paths,
+            processor,
+            json: false,
+        }
+    }
+
+    /// Print results as a single JSON object instead of one line per result
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Returns numbers and contents of lines which would change
+    pub fn check(&self, text: &str) -> Result<Vec<(usize, String)>, Error> {
+        let mut lines = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            if self.processor.process(line)? != line {
+                lines.push((number + 1, line.to_owned()));
+            }
+        }
+        Ok(lines)
+    }
+}
+
+/// Format check results as `{"count": n, "lines": [{"path", "line", "text"}]}`
+fn to_json(results: &[(String, usize, String)]) -> String {
+    let lines = results
+        .iter()
+        .map(|(name, number, line)| {
+            format!(
+                r#"{{"path":{},"line":{},"text":{}}}"#,
+                json::string(name),
+                number,
+                json::string(line)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    format!(r#"{{"count":{},"lines":[{}]}}"#, results.len(), lines)
+}
+
+impl FileProcessor for CheckProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut inputs = Vec::new();
+        if self.paths.is_empty() {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            inputs.push(("<stdin>".to_owned(), text));
+        }
+        for path in &self.paths {
+            inputs.push((path.display().to_string(), fs::read_to_string(path)?));
+        }
+        let mut results = Vec::new();
+        for (name, text) in inputs {
+            for (number, line) in self.check(&text)? {
+                results.push((name.clone(), number, line));
+            }
+        }
+        let count = results.len();
+        let mut output = io::stdout();
+        if self.json {
+            writeln!(output, "{}", to_json(&results))?;
+        } else {
+            for (name, number, line) in results {
+                writeln!(output, "{}:{}: {}", name, number, line)?;
+            }
+        }
+        match count {
+            0 => Ok(()),
+            n => Err(Error::Unconverted(n)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_check() {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let c = CheckProcessor::new(vec![], t);
+        let lines = c
+            .check("Prvi red\nДруги ред\n\nhttps://example.com\nTreći red, Ђ")
+            .unwrap();
+        assert_eq!(lines, vec![(2, "Други ред".to_owned()), (5, "Treći red, Ђ".to_owned())]);
+        assert!(c.check("Sve je latinica.").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(to_json(&[]), r#"{"count":0,"lines":[]}"#);
+        assert_eq!(
+            to_json(&[("a \"b\".md".to_owned(), 3, "Ђ\t".to_owned())]),
+            r#"{"count":1,"lines":[{"path":"a \"b\".md","line":3,"text":"Ђ\t"}]}"#
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::io;
+
+use super::{Error, FileProcessor};
+use crate::options::Options;
+use crate::transliterate::{Charset, Direction};
+
+/// Transliterates contents of the system clipboard in place
+pub struct ClipProcessor {
+    options: Options,
+}
+
+fn other(error: arboard::Error) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::Other, error))
+}
+
+impl ClipProcessor {
+    /// Convert clipboard text with `options`. If direction is not set, it
+    /// is chosen from the script of the text: Cyrillic text is converted
+    /// into Latin, and Latin text into Cyrillic.
+    pub fn new(options: Options) -> Self {
+        Self { options }
+    }
+
+    /// Returns converted text
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        let direction = self
+            .options
+            .direction
+            .unwrap_or(match Charset::detect(text) {
+                Some(Charset::Cyrillic) => Direction {
+                    from: Charset::Cyrillic,
+                    into: Charset::Latin,
+                },
+                _ => Direction {
+                    from: Charset::Latin,
+                    into: Charset::Cyrillic,
+                },
+            });
+        let options = Options {
+            direction: Some(direction),
+            ..self.options.clone()
+        };
+        Ok(options.transliterator()?.process(text)?)
+    }
+}
+
+impl FileProcessor for ClipProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut clipboard = arboard::Clipboard::new().map_err(other)?;
+        let text = clipboard.get_text().map_err(other)?;
+        let output = self.convert(&text)?;
+        if output != text {
+            clipboard.set_text(output).map_err(other)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let auto = ClipProcessor::new(Options::default());
+        assert_eq!(auto.convert("Dobar dan").unwrap(), "Добар дан");
+        assert_eq!(auto.convert("Добар дан, Mr. Smith").unwrap(), "Dobar dan, Mr. Smith");
+        let fixed = ClipProcessor::new(Options {
+            direction: Some(Direction {
+                from: Charset::Cyrillic,
+                into: Charset::Latin,
+            }),
+            ..Default::default()
+        });
+        assert_eq!(fixed.convert("Dobar dan").unwrap(), "Dobar dan");
+    }
+}
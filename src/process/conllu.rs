@@ -0,0 +1,149 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::Transliterator;
+
+/// Names of columns of CoNLL-U files
+const COLUMNS: [&str; 10] = [
+    "id", "form", "lemma", "upos", "xpos", "feats", "head", "deprel", "deps", "misc",
+];
+
+/// Transliterates word forms and lemmas of CoNLL-U treebanks, and words
+/// of vertical corpora (one word per line, with tab separated attributes
+/// and structure tags like `<s>` between them), so corpora can be
+/// published in both alphabets. Indices, tags, features, dependencies
+/// and comments are left unchanged, except for the text of sentences
+/// (`# text = ...`), which has to match their word forms.
+pub struct ConlluProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    /// Numbers of converted columns, starting with zero
+    columns: Vec<usize>,
+    vertical: bool,
+}
+
+impl ConlluProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            columns: Vec::new(),
+            vertical: false,
+        })
+    }
+
+    /// Read a vertical corpus instead of CoNLL-U, whose lines starting
+    /// with `<` are structure tags
+    pub fn with_vertical(mut self, vertical: bool) -> Self {
+        self.vertical = vertical;
+        self
+    }
+
+    /// Convert only these columns, given by number (starting with one) or
+    /// by CoNLL-U name (ie. `lemma` or `3`), while unknown names are
+    /// ignored. Columns `form` and `lemma` of CoNLL-U, or the first column
+    /// of vertical corpora, are converted if empty.
+    pub fn with_columns(mut self, columns: &[String]) -> Self {
+        self.columns = columns
+            .iter()
+            .filter_map(|c| match c.parse::<usize>() {
+                Ok(n) => n.checked_sub(1),
+                Err(_) => COLUMNS.iter().position(|&name| name == c.to_lowercase()),
+            })
+            .collect();
+        self
+    }
+
+    /// Transliterate a CoNLL-U file or a vertical corpus
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        let columns: &[usize] = match (self.columns.is_empty(), self.vertical) {
+            (false, _) => &self.columns,
+            (true, false) => &[1, 2],
+            (true, true) => &[0],
+        };
+        let mut output = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            if let Some(sentence) = content.strip_prefix("# text =") {
+                output.push_str("# text =");
+                output.push_str(&self.processor.process(sentence)?);
+            } else if content.is_empty()
+                || content.starts_with('#')
+                || (self.vertical && content.starts_with('<'))
+            {
+                output.push_str(content);
+            } else {
+                for (n, field) in content.split('\t').enumerate() {
+                    if n > 0 {
+                        output.push('\t');
+                    }
+                    // Underscores are empty fields
+                    match columns.contains(&n) && field != "_" {
+                        true => output.push_str(&self.processor.process(field)?),
+                        false => output.push_str(field),
+                    }
+                }
+            }
+            output.push_str(&line[content.len()..]);
+        }
+        Ok(output)
+    }
+}
+
+with_backup!(ConlluProcessor);
+
+impl FileProcessor for ConlluProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_conllu() -> Result<(), Error> {
+        let p = ConlluProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false),
+        )
+        .unwrap();
+        let treebank = concat!(
+            "# sent_id = ниш-1\n# text = Ниш је град.\n",
+            "1\tНиш\tНиш\tPROPN\tNpmsn\tCase=Nom\t3\tnsubj\t_\t_\n",
+            "2\tје\tбити\tAUX\tVa-p3s-an-y---p\t_\t3\tcop\t_\t_\n",
+            "3-4\tград.\t_\t_\t_\t_\t_\t_\t_\t_\r\n",
+            "\n"
+        );
+        let expected = concat!(
+            "# sent_id = ниш-1\n# text = Niš je grad.\n",
+            "1\tNiš\tNiš\tPROPN\tNpmsn\tCase=Nom\t3\tnsubj\t_\t_\n",
+            "2\tje\tbiti\tAUX\tVa-p3s-an-y---p\t_\t3\tcop\t_\t_\n",
+            "3-4\tgrad.\t_\t_\t_\t_\t_\t_\t_\t_\r\n",
+            "\n"
+        );
+        assert_eq!(p.convert(treebank)?, expected);
+
+        let p = p
+            .with_vertical(true)
+            .with_columns(&["1".to_owned(), "3".to_owned()]);
+        let corpus = "<doc title=\"Ниш\">\n<s>\nНиш\tNpmsn\tНиш\n<g/>\n.\tZ\t.\n</s>\n</doc>\n";
+        let expected = "<doc title=\"Ниш\">\n<s>\nNiš\tNpmsn\tNiš\n<g/>\n.\tZ\t.\n</s>\n</doc>\n";
+        assert_eq!(p.convert(corpus)?, expected);
+        Ok(())
+    }
+}
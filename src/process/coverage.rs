@@ -0,0 +1,141 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::json;
+use crate::transliterate::{CoverageReport, Transliterator};
+
+/// Reports which character map entries and digraph exceptions are used by
+/// a corpus, and which letters of the source script have no mapping.
+/// Bytes which are not valid UTF-8 are read as `�`.
+pub struct CoverageProcessor {
+    paths: Vec<PathBuf>,
+    json: bool,
+    report: CoverageReport,
+}
+
+impl CoverageProcessor {
+    /// Check coverage of files at `paths`, or standard input if there are none
+    pub fn new(paths: Vec<PathBuf>, transliterator: Transliterator) -> Self {
+        Self {
+            paths,
+            json: false,
+            report: CoverageReport::new(transliterator),
+        }
+    }
+
+    /// Print report as a single JSON object instead of plain text
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+}
+
+/// Format report as `{"entries", "exceptions": {"total", "applied"}, "unmapped"}`
+fn to_json(report: &CoverageReport) -> String {
+    let entries = report
+        .entries()
+        .iter()
+        .map(|e| {
+            format!(
+                r#"{{"pattern":{},"replacement":{},"count":{}}}"#,
+                json::string(&e.pattern),
+                json::string(&e.replacement),
+                e.count
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    let exceptions = report.exceptions();
+    let applied = exceptions
+        .iter()
+        .filter(|e| e.count > 0)
+        .map(|e| format!(r#"{{"exception":{},"count":{}}}"#, json::string(&e.exception), e.count))
+        .collect::<Vec<String>>()
+        .join(",");
+    let unmapped = report
+        .unmapped()
+        .iter()
+        .map(|(c, count)| {
+            format!(
+                r#"{{"char":{},"code":"U+{:04X}","count":{}}}"#,
+                json::string(&c.to_string()),
+                *c as u32,
+                count
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    format!(
+        r#"{{"entries":[{}],"exceptions":{{"total":{},"applied":[{}]}},"unmapped":[{}]}}"#,
+        entries,
+        exceptions.len(),
+        applied,
+        unmapped
+    )
+}
+
+impl FileProcessor for CoverageProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut report = self.report.clone();
+        if self.paths.is_empty() {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            report.add(&String::from_utf8_lossy(&bytes))?;
+        }
+        for path in &self.paths {
+            let bytes = fs::read(path)?;
+            report.add(&String::from_utf8_lossy(&bytes))?;
+        }
+        let mut output = io::stdout();
+        if self.json {
+            writeln!(output, "{}", to_json(&report))?;
+            return Ok(());
+        }
+        let entries = report.entries();
+        let (used, unused): (Vec<_>, Vec<_>) = entries.iter().partition(|e| e.count > 0);
+        writeln!(output, "entries: {} of {} used", used.len(), entries.len())?;
+        for e in &used {
+            writeln!(output, "  {} → {} {}", e.pattern, e.replacement, e.count)?;
+        }
+        writeln!(output, "unused entries: {}", unused.len())?;
+        for e in &unused {
+            writeln!(output, "  {} → {}", e.pattern, e.replacement)?;
+        }
+        let exceptions = report.exceptions();
+        let applied = exceptions
+            .iter()
+            .filter(|e| e.count > 0)
+            .collect::<Vec<_>>();
+        writeln!(output, "exceptions: {} of {} applied", applied.len(), exceptions.len())?;
+        for e in applied {
+            writeln!(output, "  {} {}", e.exception, e.count)?;
+        }
+        let unmapped = report.unmapped();
+        writeln!(output, "unmapped: {}", unmapped.len())?;
+        for (c, count) in unmapped {
+            writeln!(output, "  U+{:04X} {} {}", c as u32, c, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_to_json() {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let mut report = CoverageReport::new(t);
+        report.add("ја ё").unwrap();
+        let json = to_json(&report);
+        assert!(json.starts_with(r#"{"entries":[{"pattern":"#));
+        assert!(json.contains(r#"{"pattern":"ј","replacement":"j","count":1}"#));
+        assert!(json.ends_with(
+            r#""exceptions":{"total":0,"applied":[]},"unmapped":[{"char":"ё","code":"U+0451","count":1}]}"#
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::detect::{detect, Script, ScriptReport};
+use crate::json;
+use crate::transliterate::Charset;
+
+/// Reports scripts of files, with percentages of letters and confidence
+/// in the detected script. Bytes which are not valid UTF-8 are read as `�`.
+pub struct DetectProcessor {
+    paths: Vec<PathBuf>,
+    json: bool,
+}
+
+impl DetectProcessor {
+    /// Detect scripts of files at `paths`, or standard input if there are none
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self { paths, json: false }
+    }
+
+    /// Print a JSON object for each file instead of plain text
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+}
+
+fn script_name(script: Option<Charset>) -> &'static str {
+    match script {
+        Some(Charset::Cyrillic) => "cyrillic",
+        Some(_) => "latin",
+        None => "none",
+    }
+}
+
+/// Format report as `{"path", "script", "confidence", "letters", "percent", "words"}`
+fn to_json(path: &str, report: &ScriptReport) -> String {
+    format!(
+        concat!(
+            r#"{{"path":{},"script":{},"confidence":{:.3},"letters":{},"#,
+            r#""percent":{{"latin":{:.1},"cyrillic":{:.1},"other":{:.1}}},"#,
+            r#""words":{{"latin":{},"cyrillic":{},"mixed":{},"other":{}}}}}"#
+        ),
+        json::string(path),
+        match report.script {
+            Some(_) => format!("\"{}\"", script_name(report.script)),
+            None => "null".to_owned(),
+        },
+        report.confidence,
+        report.letters,
+        report.latin,
+        report.cyrillic,
+        report.other,
+        report.count(Script::Latin),
+        report.count(Script::Cyrillic),
+        report.count(Script::Mixed),
+        report.count(Script::Other),
+    )
+}
+
+impl FileProcessor for DetectProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut inputs = Vec::new();
+        if self.paths.is_empty() {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            inputs.push(("<stdin>".to_owned(), bytes));
+        }
+        for path in &self.paths {
+            inputs.push((path.display().to_string(), fs::read(path)?));
+        }
+        let mut output = io::stdout();
+        for (path, bytes) in inputs {
+            let report = detect(&String::from_utf8_lossy(&bytes));
+            if self.json {
+                writeln!(output, "{}", to_json(&path, &report))?;
+                continue;
+            }
+            writeln!(
+                output,
+                "{}: {} ({:.0}% confidence)",
+                path,
+                script_name(report.script),
+                report.confidence * 100.0
+            )?;
+            writeln!(
+                output,
+                "  letters: {:.1}% latin, {:.1}% cyrillic, {:.1}% other",
+                report.latin, report.cyrillic, report.other
+            )?;
+            writeln!(
+                output,
+                "  words: {} latin, {} cyrillic, {} mixed, {} other",
+                report.count(Script::Latin),
+                report.count(Script::Cyrillic),
+                report.count(Script::Mixed),
+                report.count(Script::Other)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            to_json("a.txt", &detect("")),
+            r#"{"path":"a.txt","script":null,"confidence":0.000,"letters":0,"percent":{"latin":0.0,"cyrillic":0.0,"other":0.0},"words":{"latin":0,"cyrillic":0,"mixed":0,"other":0}}"#
+        );
+        assert_eq!(
+            to_json("a.txt", &detect("Кyћa и кућа")),
+            r#"{"path":"a.txt","script":"cyrillic","confidence":0.350,"letters":9,"percent":{"latin":22.2,"cyrillic":77.8,"other":0.0},"words":{"latin":0,"cyrillic":2,"mixed":1,"other":0}}"#
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use quick_xml::escape::partial_escape;
+use quick_xml::events::{BytesCData, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use super::html::convert_html;
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+/// Content of a feed element which is converted
+#[derive(Clone, Copy, PartialEq)]
+enum Content {
+    /// Left unchanged (ie. links, GUIDs, dates)
+    Keep,
+    Text,
+    Html,
+}
+
+/// Returns how content of an RSS or Atom element is converted
+fn content(element: &BytesStart, parent: Content) -> Content {
+    // Text inside Atom XHTML content
+    if parent != Content::Keep {
+        return parent;
+    }
+    let kind = element
+        .try_get_attribute("type")
+        .ok()
+        .flatten()
+        .map(|a| a.value.into_owned());
+    match element.name().as_ref() {
+        // RSS
+        b"description" | b"content:encoded" => Content::Html,
+        b"title" | b"itunes:title" | b"itunes:subtitle" | b"itunes:summary" | b"media:title"
+        | b"media:description" | b"subtitle" | b"summary" | b"content" | b"rights" => {
+            match kind.as_deref() {
+                // Atom text constructs
+                Some(b"html" | b"text/html") => Content::Html,
+                Some(b"xhtml" | b"text" | b"text/plain") | None => Content::Text,
+                Some(_) => Content::Keep,
+            }
+        }
+        _ => Content::Keep,
+    }
+}
+
+fn xml_error(error: quick_xml::Error) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Transliterates titles, descriptions and content of RSS and Atom feeds.
+/// Links, GUIDs, dates and other elements are left unchanged, and HTML
+/// content is converted without changing its markup.
+pub struct FeedProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl FeedProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    fn convert_text(&self, text: &str, content: Content) -> Result<String, Error> {
+        match content {
+            Content::Keep => Ok(text.to_owned()),
+            Content::Text => Ok(self.processor.process(text)?),
+            Content::Html => convert_html(&self.processor, text),
+        }
+    }
+
+    /// Transliterate a feed
+    pub fn convert(&self, feed: &str) -> Result<String, Error> {
+        let mut reader = Reader::from_str(feed);
+        let mut writer = Writer::new(Vec::with_capacity(feed.len()));
+        let mut stack = vec![Content::Keep];
+        loop {
+            let current = *stack.last().unwrap_or(&Content::Keep);
+            let event = match reader.read_event().map_err(xml_error)? {
+                Event::Eof => break,
+                Event::Start(element) => {
+                    stack.push(content(&element, current));
+                    Event::Start(element)
+                }
+                Event::End(element) => {
+                    stack.pop();
+                    Event::End(element)
+                }
+                Event::Text(text) if current != Content::Keep => {
+                    // Text with unknown entities is left unchanged
+                    match text.unescape() {
+                        Ok(raw) => {
+                            let converted = self.convert_text(&raw, current)?;
+                            Event::Text(BytesText::from_escaped(partial_escape(converted)))
+                        }
+                        Err(_) => Event::Text(text),
+                    }
+                }
+                Event::CData(data) if current != Content::Keep => {
+                    let raw = String::from_utf8_lossy(&data).into_owned();
+                    let converted = self.convert_text(&raw, current)?;
+                    Event::CData(BytesCData::new(Cow::Owned(converted)))
+                }
+                event => event,
+            };
+            writer.write_event(event)?;
+        }
+        Ok(String::from_utf8(writer.into_inner()).map_err(transliterate::Error::from)?)
+    }
+}
+
+with_backup!(FeedProcessor);
+
+impl FileProcessor for FeedProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor(from: Charset, into: Charset) -> FeedProcessor {
+        let t = Transliterator::new(from, into, false, false, false);
+        FeedProcessor::new(None, None, t).unwrap()
+    }
+
+    #[test]
+    fn test_rss() {
+        let p = processor(Charset::Cyrillic, Charset::Latin);
+        let rss = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<rss version=\"2.0\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">",
+            "<channel><title>Вести &amp; догађаји</title><link>https://vesti.rs/вести</link>",
+            "<item><title>Нови мост</title><guid>вести-1</guid>",
+            "<pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate>",
+            "<description>&lt;p class=\"lead\"&gt;Мост је отворен&lt;/p&gt;</description>",
+            "<content:encoded><![CDATA[<p>Дуг <b>мост</b></p>]]></content:encoded>",
+            "</item></channel></rss>"
+        );
+        let expected = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<rss version=\"2.0\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">",
+            "<channel><title>Vesti &amp; događaji</title><link>https://vesti.rs/вести</link>",
+            "<item><title>Novi most</title><guid>вести-1</guid>",
+            "<pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate>",
+            "<description>&lt;p class=\"lead\"&gt;Most je otvoren&lt;/p&gt;</description>",
+            "<content:encoded><![CDATA[<p>Dug <b>most</b></p>]]></content:encoded>",
+            "</item></channel></rss>"
+        );
+        assert_eq!(p.convert(rss).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_atom() {
+        let p = processor(Charset::Latin, Charset::Cyrillic);
+        let atom = concat!(
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\">",
+            "<title type=\"text\">Blog</title><id>urn:blog</id>",
+            "<entry><title type=\"html\">Prvi &lt;em&gt;tekst&lt;/em&gt;</title>",
+            "<link href=\"https://blog.rs/prvi\"/><updated>2024-01-01T00:00:00Z</updated>",
+            "<content type=\"xhtml\"><div xmlns=\"http://www.w3.org/1999/xhtml\">",
+            "<p>Dobar dan</p></div></content>",
+            "<content type=\"image/png\" src=\"slika.png\">slika</content></entry></feed>"
+        );
+        let expected = concat!(
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\">",
+            "<title type=\"text\">Блог</title><id>urn:blog</id>",
+            "<entry><title type=\"html\">Први &lt;em&gt;текст&lt;/em&gt;</title>",
+            "<link href=\"https://blog.rs/prvi\"/><updated>2024-01-01T00:00:00Z</updated>",
+            "<content type=\"xhtml\"><div xmlns=\"http://www.w3.org/1999/xhtml\">",
+            "<p>Добар дан</p></div></content>",
+            "<content type=\"image/png\" src=\"slika.png\">slika</content></entry></feed>"
+        );
+        assert_eq!(p.convert(atom).unwrap(), expected);
+    }
+}
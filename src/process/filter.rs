@@ -0,0 +1,68 @@
+use std::io::{self, Read, Write};
+
+use super::{Error, FileProcessor};
+use crate::transliterate::Transliterator;
+
+/// Transliterates standard input into standard output as a git clean or
+/// smudge filter. Input which is not valid UTF-8 (ie. binary files) is
+/// written out unchanged, so the filter never fails on file contents.
+pub struct FilterProcessor {
+    processor: Transliterator,
+}
+
+impl FilterProcessor {
+    pub fn new(processor: Transliterator) -> Self {
+        // Result must depend only on input text
+        #[cfg(feature = "detect-language")]
+        let processor = processor.with_detect_language(false);
+        Self { processor }
+    }
+
+    /// Returns filtered contents of a file
+    pub fn filter(&self, input: Vec<u8>) -> Result<Vec<u8>, Error> {
+        Ok(match String::from_utf8(input) {
+            Ok(text) => self.processor.process(text)?.into_bytes(),
+            Err(e) => e.into_bytes(),
+        })
+    }
+}
+
+impl FileProcessor for FilterProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input)?;
+        let output = self.filter(input)?;
+        let mut stdout = io::stdout();
+        stdout.write_all(&output)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_filter() {
+        let clean = FilterProcessor::new(Transliterator::new(
+            Charset::Latin,
+            Charset::Cyrillic,
+            false,
+            false,
+            false,
+        ));
+        let text = "\u{feff}# Naslov\r\n\r\n\tTekst  sa  razmacima, https://example.com\n";
+        let once = clean.filter(text.as_bytes().to_vec()).unwrap();
+        assert_eq!(
+            String::from_utf8(once.clone()).unwrap(),
+            "\u{feff}# Наслов\r\n\r\n\tТекст  са  размацима, https://example.com\n"
+        );
+        // Filtering is idempotent
+        assert_eq!(clean.filter(once.clone()).unwrap(), once);
+        // Binary files are left intact
+        let binary = vec![0x89, b'P', b'N', b'G', 0xff, 0x00];
+        assert_eq!(clean.filter(binary.clone()).unwrap(), binary);
+    }
+}
@@ -0,0 +1,178 @@
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use quick_xml::escape::partial_escape;
+use quick_xml::events::{BytesCData, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use super::html::convert_html;
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+/// Content of a geodata element which is converted
+#[derive(Clone, Copy, PartialEq)]
+enum Content {
+    /// Left unchanged (ie. coordinates, times, styles)
+    Keep,
+    Text,
+    Html,
+}
+
+/// Returns how content of a GPX or KML element is converted
+fn content(element: &BytesStart) -> Content {
+    // Elements may be prefixed with a namespace (ie. `kml:name`)
+    match element.local_name().as_ref() {
+        // GPX and KML
+        b"name" | b"desc" | b"cmt" | b"Snippet" | b"address" => Content::Text,
+        // KML balloons may contain HTML
+        b"description" => Content::Html,
+        _ => Content::Keep,
+    }
+}
+
+fn xml_error(error: quick_xml::Error) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Transliterates names and descriptions of places, routes and tracks in
+/// GPX tracks and KML (Google Earth) documents. Coordinates, elevations,
+/// times, styles and other elements are left unchanged, and HTML
+/// descriptions are converted without changing their markup.
+pub struct GeoProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl GeoProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    fn convert_text(&self, text: &str, content: Content) -> Result<String, Error> {
+        match content {
+            Content::Keep => Ok(text.to_owned()),
+            Content::Text => Ok(self.processor.process(text)?),
+            Content::Html => convert_html(&self.processor, text),
+        }
+    }
+
+    /// Transliterate a GPX or KML document
+    pub fn convert(&self, document: &str) -> Result<String, Error> {
+        let mut reader = Reader::from_str(document);
+        let mut writer = Writer::new(Vec::with_capacity(document.len()));
+        let mut stack = vec![Content::Keep];
+        loop {
+            let current = *stack.last().unwrap_or(&Content::Keep);
+            let event = match reader.read_event().map_err(xml_error)? {
+                Event::Eof => break,
+                Event::Start(element) => {
+                    stack.push(content(&element));
+                    Event::Start(element)
+                }
+                Event::End(element) => {
+                    stack.pop();
+                    Event::End(element)
+                }
+                Event::Text(text) if current != Content::Keep => {
+                    // Text with unknown entities is left unchanged
+                    match text.unescape() {
+                        Ok(raw) => {
+                            let converted = self.convert_text(&raw, current)?;
+                            Event::Text(BytesText::from_escaped(partial_escape(converted)))
+                        }
+                        Err(_) => Event::Text(text),
+                    }
+                }
+                Event::CData(data) if current != Content::Keep => {
+                    let raw = String::from_utf8_lossy(&data).into_owned();
+                    let converted = self.convert_text(&raw, current)?;
+                    Event::CData(BytesCData::new(Cow::Owned(converted)))
+                }
+                event => event,
+            };
+            writer.write_event(event)?;
+        }
+        Ok(String::from_utf8(writer.into_inner()).map_err(transliterate::Error::from)?)
+    }
+}
+
+with_backup!(GeoProcessor);
+
+impl FileProcessor for GeoProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor(from: Charset, into: Charset) -> GeoProcessor {
+        let t = Transliterator::new(from, into, false, false, false);
+        GeoProcessor::new(None, None, t).unwrap()
+    }
+
+    #[test]
+    fn test_gpx() {
+        let p = processor(Charset::Cyrillic, Charset::Latin);
+        let gpx = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gpx version=\"1.1\" creator=\"Карте\">",
+            "<wpt lat=\"44.8125\" lon=\"20.4612\"><ele>117</ele><name>Теразије</name>",
+            "<cmt>Чесма &amp; сат</cmt><sym>Flag</sym></wpt>",
+            "<trk><name>Ђердап</name><desc>Стаза уз Дунав</desc>",
+            "<trkseg><trkpt lat=\"44.6\" lon=\"22.1\"><time>2024-05-01T10:00:00Z</time></trkpt>",
+            "</trkseg></trk></gpx>"
+        );
+        let expected = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gpx version=\"1.1\" creator=\"Карте\">",
+            "<wpt lat=\"44.8125\" lon=\"20.4612\"><ele>117</ele><name>Terazije</name>",
+            "<cmt>Česma &amp; sat</cmt><sym>Flag</sym></wpt>",
+            "<trk><name>Đerdap</name><desc>Staza uz Dunav</desc>",
+            "<trkseg><trkpt lat=\"44.6\" lon=\"22.1\"><time>2024-05-01T10:00:00Z</time></trkpt>",
+            "</trkseg></trk></gpx>"
+        );
+        assert_eq!(p.convert(gpx).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_kml() {
+        let p = processor(Charset::Latin, Charset::Cyrillic);
+        let kml = concat!(
+            "<kml xmlns=\"http://www.opengis.net/kml/2.2\"><Document><name>Izleti</name>",
+            "<Style id=\"planina\"><BalloonStyle><text>$[name]</text></BalloonStyle></Style>",
+            "<Placemark><name>Kopaonik</name>",
+            "<description><![CDATA[<p>Vrh <b>Pančićev</b></p>]]></description>",
+            "<styleUrl>#planina</styleUrl>",
+            "<Point><coordinates>20.8,43.3,2017</coordinates></Point></Placemark>",
+            "</Document></kml>"
+        );
+        let expected = concat!(
+            "<kml xmlns=\"http://www.opengis.net/kml/2.2\"><Document><name>Излети</name>",
+            "<Style id=\"planina\"><BalloonStyle><text>$[name]</text></BalloonStyle></Style>",
+            "<Placemark><name>Копаоник</name>",
+            "<description><![CDATA[<p>Врх <b>Панчићев</b></p>]]></description>",
+            "<styleUrl>#planina</styleUrl>",
+            "<Point><coordinates>20.8,43.3,2017</coordinates></Point></Placemark>",
+            "</Document></kml>"
+        );
+        assert_eq!(p.convert(kml).unwrap(), expected);
+    }
+}
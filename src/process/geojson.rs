@@ -0,0 +1,349 @@
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::json;
+use crate::transliterate::Transliterator;
+
+/// Deepest nesting of arrays and objects in a document
+const MAX_DEPTH: usize = 128;
+
+/// Properties which are transliterated by default
+const PROPERTIES: &[&str] = &["name"];
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// Parser of a GeoJSON document, which copies it into output with new
+/// properties added to objects of `properties` members
+struct Parser<'a> {
+    processor: &'a GeojsonProcessor,
+    text: &'a str,
+    position: usize,
+    /// Text before this position is already in output
+    copied: usize,
+    output: String,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn error(&self) -> Error {
+        let before = &self.text[..self.position];
+        let line = before.matches('\n').count() + 1;
+        let column = before[before.rfind('\n').map_or(0, |n| n + 1)..]
+            .chars()
+            .count()
+            + 1;
+        match self.text[self.position..].chars().next() {
+            Some(c) => invalid_data(format!(
+                "invalid GeoJSON - unexpected '{}' at line {} column {}",
+                c, line, column
+            )),
+            None => invalid_data(format!(
+                "invalid GeoJSON - unexpected end at line {} column {}",
+                line, column
+            )),
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        match self.text[self.position..].starts_with(token) {
+            true => {
+                self.position += token.len();
+                Ok(())
+            }
+            false => Err(self.error()),
+        }
+    }
+
+    /// Parse a value, returning contents of strings
+    fn value(&mut self, depth: usize, key: Option<&str>) -> Result<Option<String>, Error> {
+        self.whitespace();
+        if depth > MAX_DEPTH {
+            return Err(self.error());
+        }
+        match self.peek() {
+            Some(b'{') => self.object(depth, key == Some("properties"))?,
+            Some(b'[') => {
+                self.position += 1;
+                self.whitespace();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                    return Ok(None);
+                }
+                loop {
+                    self.value(depth + 1, None)?;
+                    self.whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {
+                            self.position += 1;
+                            break;
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'"') => {
+                return match json::parse_string(self.text, &mut self.position) {
+                    Some(contents) => Ok(Some(contents)),
+                    None => Err(self.error()),
+                }
+            }
+            Some(b't') => self.expect("true")?,
+            Some(b'f') => self.expect("false")?,
+            Some(b'n') => self.expect("null")?,
+            _ => match json::NUMBER.find(&self.text[self.position..]) {
+                Some(number) => self.position += number.end(),
+                None => return Err(self.error()),
+            },
+        }
+        Ok(None)
+    }
+
+    /// Parse an object, adding transliterated properties to it if it is
+    /// an object of `properties`
+    fn object(&mut self, depth: usize, properties: bool) -> Result<(), Error> {
+        let text = self.text;
+        self.position += 1;
+        // Keys and string values of members
+        let mut members: Vec<(String, Option<String>)> = Vec::new();
+        // Whitespace before and after the colon of the last member, and end
+        // of its value, so new properties are formatted the same way
+        let (mut indent, mut separator, mut end): (&str, &str, usize);
+        let start = self.position;
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(());
+        }
+        let mut before = start;
+        loop {
+            self.whitespace();
+            indent = &text[before..self.position];
+            let key = json::parse_string(text, &mut self.position).ok_or_else(|| self.error())?;
+            let key_end = self.position;
+            self.whitespace();
+            self.expect(":")?;
+            self.whitespace();
+            separator = &text[key_end..self.position];
+            let value = self.value(depth + 1, Some(&key))?;
+            end = self.position;
+            members.push((key, value));
+            self.whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.position += 1;
+                    before = self.position;
+                }
+                Some(b'}') => {
+                    self.position += 1;
+                    break;
+                }
+                _ => return Err(self.error()),
+            }
+        }
+        if !properties {
+            return Ok(());
+        }
+        let mut added = String::new();
+        for (source, target) in &self.processor.properties {
+            // Existing properties are not overwritten
+            if members.iter().any(|(key, _)| key == target) {
+                continue;
+            }
+            if let Some((_, Some(value))) = members.iter().find(|(key, _)| key == source) {
+                let converted = self.processor.processor.process(value)?;
+                added.push_str(&format!(
+                    ",{}{}{}{}",
+                    indent,
+                    json::string(target),
+                    separator,
+                    json::string(&converted)
+                ));
+                members.push((target.clone(), None));
+            }
+        }
+        if !added.is_empty() {
+            self.output.push_str(&text[self.copied..end]);
+            self.output.push_str(&added);
+            self.copied = end;
+        }
+        Ok(())
+    }
+}
+
+/// Adds transliterated properties of features in GeoJSON documents,
+/// following OpenStreetMap conventions for names in multiple languages
+/// and scripts (ie. `name:sr-Latn` from `name:sr`). Existing properties,
+/// geometry and formatting are left unchanged.
+pub struct GeojsonProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    /// Source and target property names
+    properties: Vec<(String, String)>,
+}
+
+impl GeojsonProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        let mut geojson = Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            properties: Vec::new(),
+        };
+        geojson.properties = PROPERTIES.iter().map(|p| geojson.property(p)).collect();
+        Ok(geojson)
+    }
+
+    /// Returns source and target property names of `property`, which is
+    /// either `source=target` or a source property. Its target is named
+    /// after the language and script of transliterated text, ie. `name` and
+    /// `name:sr` are transliterated into `name:sr-Latn`.
+    fn property(&self, property: &str) -> (String, String) {
+        if let Some((source, target)) = property.split_once('=') {
+            return (source.to_owned(), target.to_owned());
+        }
+        let tag = self.processor.target_tag();
+        let target = match property.split_once(':') {
+            Some(_) => {
+                let base = property.trim_end_matches("-Latn").trim_end_matches("-Cyrl");
+                let script = tag.rsplit('-').next().unwrap_or_default();
+                format!("{}-{}", base, script)
+            }
+            None => format!("{}:{}", property, tag),
+        };
+        (property.to_owned(), target)
+    }
+
+    /// Transliterate these properties instead of `name`, either as
+    /// `source=target` or only a source property
+    pub fn with_properties(mut self, properties: &[String]) -> Self {
+        if !properties.is_empty() {
+            self.properties = properties.iter().map(|p| self.property(p)).collect();
+        }
+        self
+    }
+
+    /// Transliterate a GeoJSON document
+    pub fn convert(&self, document: &str) -> Result<String, Error> {
+        let mut parser = Parser {
+            processor: self,
+            text: document,
+            position: 0,
+            copied: 0,
+            output: String::with_capacity(document.len()),
+        };
+        // Byte order mark
+        if document.starts_with('\u{feff}') {
+            parser.position = '\u{feff}'.len_utf8();
+        }
+        parser.value(0, None)?;
+        parser.whitespace();
+        if parser.position < document.len() {
+            return Err(parser.error());
+        }
+        parser.output.push_str(&document[parser.copied..]);
+        Ok(parser.output)
+    }
+}
+
+with_backup!(GeojsonProcessor);
+
+impl FileProcessor for GeojsonProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor(from: Charset, into: Charset) -> GeojsonProcessor {
+        let t = Transliterator::new(from, into, false, false, false);
+        GeojsonProcessor::new(None, None, t).unwrap()
+    }
+
+    #[test]
+    fn test_geojson() {
+        let p = processor(Charset::Cyrillic, Charset::Latin);
+        let document = concat!(
+            "{\n",
+            "  \"type\": \"FeatureCollection\",\n",
+            "  \"features\": [\n",
+            "    {\n",
+            "      \"type\": \"Feature\",\n",
+            "      \"properties\": {\n",
+            "        \"name\": \"Ђердап\",\n",
+            "        \"ele\": 768\n",
+            "      },\n",
+            "      \"geometry\": {\"type\": \"Point\", \"coordinates\": [22.1, 44.6]}\n",
+            "    },\n",
+            "    {\"type\":\"Feature\",\"properties\":{\"name\":\"Ниш\",\"name:sr-Latn\":\"Niš\"}},\n",
+            "    {\"type\":\"Feature\",\"properties\":{\"name\":7,\"name:en\":\"Nis\"}}\n",
+            "  ]\n",
+            "}\n"
+        );
+        let expected = concat!(
+            "{\n",
+            "  \"type\": \"FeatureCollection\",\n",
+            "  \"features\": [\n",
+            "    {\n",
+            "      \"type\": \"Feature\",\n",
+            "      \"properties\": {\n",
+            "        \"name\": \"Ђердап\",\n",
+            "        \"ele\": 768,\n",
+            "        \"name:sr-Latn\": \"Đerdap\"\n",
+            "      },\n",
+            "      \"geometry\": {\"type\": \"Point\", \"coordinates\": [22.1, 44.6]}\n",
+            "    },\n",
+            "    {\"type\":\"Feature\",\"properties\":{\"name\":\"Ниш\",\"name:sr-Latn\":\"Niš\"}},\n",
+            "    {\"type\":\"Feature\",\"properties\":{\"name\":7,\"name:en\":\"Nis\"}}\n",
+            "  ]\n",
+            "}\n"
+        );
+        assert_eq!(p.convert(document).unwrap(), expected);
+        assert!(p
+            .convert("{\"properties\": {\n\"name\" \"Ниш\"}}")
+            .unwrap_err()
+            .to_string()
+            .contains("unexpected '\"' at line 2 column 8"));
+    }
+
+    #[test]
+    fn test_properties() {
+        let p = processor(Charset::Latin, Charset::Cyrillic).with_properties(&[
+            "name:sr-Latn".to_owned(),
+            "alt_name".to_owned(),
+            "opis=opis:cyr".to_owned(),
+        ]);
+        let document =
+            r#"{"properties":{"name:sr-Latn":"Novi Sad","alt_name":"Atina","opis":"Grad"}}"#;
+        let expected = concat!(
+            r#"{"properties":{"name:sr-Latn":"Novi Sad","alt_name":"Atina","opis":"Grad","#,
+            r#""name:sr-Cyrl":"Нови Сад","alt_name:sr-Cyrl":"Атина","opis:cyr":"Град"}}"#
+        );
+        assert_eq!(p.convert(document).unwrap(), expected);
+    }
+}
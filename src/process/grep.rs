@@ -0,0 +1,99 @@
+use regex::Regex;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::transliterate::{self, Charset, Language, Transliterator};
+
+/// Prints lines matching a regular expression regardless of the script
+/// they are written in. Lines and the pattern are compared in Latin, while
+/// matching lines are printed as they are written. Bytes which are not
+/// valid UTF-8 are read as `�`.
+pub struct GrepProcessor {
+    paths: Vec<PathBuf>,
+    pattern: Regex,
+    normalizer: Transliterator,
+}
+
+impl GrepProcessor {
+    /// Search files at `paths`, or standard input if there are none, for
+    /// lines matching `pattern`, following orthography of `language`.
+    /// Fails if the pattern is not a valid regular expression.
+    pub fn new(pattern: &str, paths: Vec<PathBuf>, language: Language) -> Result<Self, Error> {
+        let normalizer = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, true, true)
+            .with_language(language);
+        let pattern = Regex::new(&normalizer.process(pattern)?)
+            .map_err(transliterate::Error::InvalidPattern)?;
+        Ok(Self {
+            paths,
+            pattern,
+            normalizer,
+        })
+    }
+
+    /// Returns numbers and contents of lines which match the pattern
+    pub fn search(&self, text: &str) -> Result<Vec<(usize, String)>, Error> {
+        let mut lines = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            if self.pattern.is_match(&self.normalizer.process(line)?) {
+                lines.push((number + 1, line.to_owned()));
+            }
+        }
+        Ok(lines)
+    }
+}
+
+impl FileProcessor for GrepProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut inputs = Vec::new();
+        if self.paths.is_empty() {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            inputs.push(("<stdin>".to_owned(), bytes));
+        }
+        for path in &self.paths {
+            inputs.push((path.display().to_string(), fs::read(path)?));
+        }
+        let mut count = 0;
+        let mut output = io::stdout();
+        for (name, bytes) in inputs {
+            for (number, line) in self.search(&String::from_utf8_lossy(&bytes))? {
+                writeln!(output, "{}:{}: {}", name, number, line)?;
+                count += 1;
+            }
+        }
+        match count {
+            0 => Err(Error::Unmatched),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search() -> Result<(), Error> {
+        let text = "Beograd\nБЕОГРАД, Србија\nNovi Sad\nконјугација\nBеоград";
+        let grep = GrepProcessor::new("(?i)beograd", vec![], Language::Serbian)?;
+        assert_eq!(
+            grep.search(text)?,
+            vec![
+                (1, "Beograd".to_owned()),
+                (2, "БЕОГРАД, Србија".to_owned()),
+                (5, "Bеоград".to_owned())
+            ]
+        );
+        let grep = GrepProcessor::new(r"^Срб|коњ\w+", vec![], Language::Serbian)?;
+        assert_eq!(grep.search(text)?, vec![(4, "конјугација".to_owned())]);
+        let grep = GrepProcessor::new(r"Србија$", vec![], Language::Serbian)?;
+        assert_eq!(grep.search(text)?, vec![(2, "БЕОГРАД, Србија".to_owned())]);
+        assert!(matches!(
+            GrepProcessor::new("(", vec![], Language::Serbian),
+            Err(Error::Processing(transliterate::Error::InvalidPattern(_)))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::transliterate::Transliterator;
+
+/// Escape sequences of original words, converted words and their end
+const COLORS: [&str; 3] = ["\x1b[31m", "\x1b[32m", "\x1b[0m"];
+
+/// Markers used instead of colors (ie. with `NO_COLOR` set)
+const MARKERS: [&str; 4] = ["[-", "-]", "{+", "+}"];
+
+/// Prints converted text with words which were changed by the conversion
+/// highlighted, optionally preceded by their originals, to show what a
+/// conversion does before writing files
+pub struct HighlightProcessor {
+    input: Box<dyn Read>,
+    processor: Transliterator,
+    original: bool,
+    color: bool,
+}
+
+impl HighlightProcessor {
+    pub fn new(input: Option<PathBuf>, processor: Transliterator) -> Result<Self, Error> {
+        Ok(Self {
+            input: if let Some(p) = input {
+                Box::from(File::open(p)?)
+            } else {
+                Box::from(io::stdin())
+            },
+            processor,
+            original: false,
+            color: true,
+        })
+    }
+
+    /// Show original words before converted ones
+    pub fn with_original(mut self, original: bool) -> Self {
+        self.original = original;
+        self
+    }
+
+    /// Highlight words with ANSI colors, or with `[-original-]{+converted+}`
+    /// markers if disabled
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns converted `text` with changed words highlighted
+    fn highlight(&self, text: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(text.len() * 2);
+        let mut last = 0;
+        for trace in self.processor.trace(text)? {
+            output.push_str(&text[last..trace.span.start]);
+            last = trace.span.end;
+            let word = &text[trace.span];
+            if trace.output == word {
+                output.push_str(word);
+                continue;
+            }
+            let [removed, added] = match self.color {
+                true => [[COLORS[0], COLORS[2]], [COLORS[1], COLORS[2]]],
+                false => [[MARKERS[0], MARKERS[1]], [MARKERS[2], MARKERS[3]]],
+            };
+            if self.original {
+                output.push_str(removed[0]);
+                output.push_str(word);
+                output.push_str(removed[1]);
+            }
+            output.push_str(added[0]);
+            output.push_str(&trace.output);
+            output.push_str(added[1]);
+        }
+        output.push_str(&text[last..]);
+        Ok(output)
+    }
+}
+
+impl FileProcessor for HighlightProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        io::stdout().write_all(self.highlight(&input)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_highlight() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let h = HighlightProcessor::new(None, t).unwrap();
+        assert_eq!(
+            h.highlight("Word i  reč\n").unwrap(),
+            "Word \x1b[32mи\x1b[0m  \x1b[32mреч\x1b[0m\n"
+        );
+        let h = h.with_original(true).with_color(false);
+        assert_eq!(
+            h.highlight("Word i  reč, 12\n").unwrap(),
+            "Word [-i-]{+и+}  [-reč,-]{+реч,+} 12\n"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use lol_html::errors::RewritingError;
+use lol_html::html_content::{ContentType, TextType};
+use lol_html::{doc_text, HtmlRewriter, Settings};
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    static ref ENTITY: Regex =
+        Regex::new(r"&(?:#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);?").unwrap();
+}
+
+/// Transliterate raw HTML text, keeping character references intact
+fn convert(transliterator: &Transliterator, text: &str) -> Result<String, transliterate::Error> {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for entity in ENTITY.find_iter(text) {
+        output.push_str(&transliterator.process(&text[last..entity.start()])?);
+        output.push_str(entity.as_str());
+        last = entity.end();
+    }
+    output.push_str(&transliterator.process(&text[last..])?);
+    Ok(output)
+}
+
+pub(crate) fn rewriting_error(error: RewritingError) -> Error {
+    match error {
+        RewritingError::ContentHandlerError(e) => match e.downcast::<transliterate::Error>() {
+            Ok(e) => Error::Processing(*e),
+            Err(e) => Error::Io(io::Error::new(io::ErrorKind::Other, e)),
+        },
+        e => Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+/// Transliterates HTML as it arrives, ie. in a proxy which converts
+/// response bodies without buffering them. Text of the document and
+/// its title is converted, while markup, scripts and styles are
+/// passed through unchanged. Output is written to `output` sink.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{Charset, HtmlStream, Transliterator};
+/// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+/// let mut html = Vec::new();
+/// let mut stream = HtmlStream::new(&t, |chunk: &[u8]| html.extend_from_slice(chunk));
+/// stream.write(b"<p class=\"note\">Dobar ").unwrap();
+/// stream.write(b"dan &amp; laku noc</p>").unwrap();
+/// stream.end().unwrap();
+/// assert_eq!(html, "<p class=\"note\">Добар дан &amp; лаку ноц</p>".as_bytes());
+/// ```
+pub struct HtmlStream<'a, O: FnMut(&[u8])> {
+    rewriter: HtmlRewriter<'a, O>,
+}
+
+impl<'a, O: FnMut(&[u8])> HtmlStream<'a, O> {
+    /// Convert HTML with `transliterator` and pass it to `output`
+    pub fn new(transliterator: &'a Transliterator, output: O) -> Self {
+        // Text nodes may be split into several chunks, so they are
+        // collected and converted as a whole
+        let mut text = String::new();
+        let handler = doc_text!(move |chunk| {
+            if !matches!(chunk.text_type(), TextType::Data | TextType::RCData) {
+                return Ok(());
+            }
+            text.push_str(chunk.as_str());
+            if chunk.last_in_text_node() {
+                chunk.replace(&convert(transliterator, &text)?, ContentType::Html);
+                text.clear();
+            } else {
+                chunk.remove();
+            }
+            Ok(())
+        });
+        let settings = Settings {
+            document_content_handlers: vec![handler],
+            ..Settings::new()
+        };
+        Self {
+            rewriter: HtmlRewriter::new(settings, output),
+        }
+    }
+
+    /// Convert next chunk of the document
+    pub fn write(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        self.rewriter.write(chunk).map_err(rewriting_error)
+    }
+
+    /// Finish converting the document
+    pub fn end(self) -> Result<(), Error> {
+        self.rewriter.end().map_err(rewriting_error)
+    }
+}
+
+/// Transliterate text of an HTML document or fragment
+#[cfg(any(feature = "feed", feature = "geo", feature = "mail", feature = "site"))]
+pub(crate) fn convert_html(transliterator: &Transliterator, html: &str) -> Result<String, Error> {
+    let mut output = Vec::with_capacity(html.len());
+    let mut stream =
+        HtmlStream::new(transliterator, |chunk: &[u8]| output.extend_from_slice(chunk));
+    stream.write(html.as_bytes())?;
+    stream.end()?;
+    Ok(String::from_utf8(output).map_err(transliterate::Error::from)?)
+}
+
+/// Transliterates an HTML document, streaming it from input to output
+pub struct HtmlProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl HtmlProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+}
+
+with_backup!(HtmlProcessor);
+
+impl FileProcessor for HtmlProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        // Output file is replaced only after input is processed, so it
+        // can be the same as input file
+        let mut buffer = Vec::new();
+        let mut stdout = io::stdout();
+        let mut written: io::Result<()> = Ok(());
+        let file = self.output.path.is_some();
+        let sink = |chunk: &[u8]| {
+            if file {
+                buffer.extend_from_slice(chunk);
+            } else if written.is_ok() {
+                written = stdout.write_all(chunk);
+            }
+        };
+        let mut stream = HtmlStream::new(&self.processor, sink);
+        let mut chunk = [0; 8192];
+        loop {
+            match self.input.read(&mut chunk)? {
+                0 => break,
+                n => stream.write(&chunk[..n])?,
+            }
+        }
+        stream.end()?;
+        written?;
+        if file {
+            self.output.write(&buffer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn stream(t: &Transliterator, chunks: &[&str]) -> String {
+        let mut html = Vec::new();
+        let mut stream = HtmlStream::new(t, |c: &[u8]| html.extend_from_slice(c));
+        for chunk in chunks {
+            stream.write(chunk.as_bytes()).unwrap();
+        }
+        stream.end().unwrap();
+        String::from_utf8(html).unwrap()
+    }
+
+    #[test]
+    fn test_stream() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let html = concat!(
+            "<!DOCTYPE html><html lang=\"sr\"><head><title>Vesti</title>",
+            "<style>p { color: red; }</style></head>",
+            "<body><p title=\"Naslov\">Džungla &mdash; &#382;aba</p>",
+            "<script>var ljubav = 1;</script><!-- komentar --></body></html>"
+        );
+        let expected = concat!(
+            "<!DOCTYPE html><html lang=\"sr\"><head><title>Вести</title>",
+            "<style>p { color: red; }</style></head>",
+            "<body><p title=\"Naslov\">Џунгла &mdash; &#382;аба</p>",
+            "<script>var ljubav = 1;</script><!-- komentar --></body></html>"
+        );
+        assert_eq!(stream(&t, &[html]), expected);
+        // Words split between chunks are converted as a whole
+        let chars = html.chars().collect::<Vec<char>>();
+        let chunks = chars
+            .chunks(5)
+            .map(|c| c.iter().collect())
+            .collect::<Vec<String>>();
+        let chunks = chunks.iter().map(String::as_str).collect::<Vec<&str>>();
+        assert_eq!(stream(&t, &chunks), expected);
+        assert_eq!(stream(&t, &["<p>Nj", "iva</p>"]), "<p>Њива</p>");
+    }
+}
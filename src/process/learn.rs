@@ -0,0 +1,39 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::learn::Learner;
+
+/// Prints digraph exceptions learned from a Latin file and its Cyrillic
+/// counterpart, one per line. Bytes which are not valid UTF-8 are read as `�`.
+pub struct LearnProcessor {
+    latin: PathBuf,
+    cyrillic: PathBuf,
+    learner: Learner,
+}
+
+impl LearnProcessor {
+    /// Learn exceptions from files at `latin` and `cyrillic`, aligned by lines
+    pub fn new(latin: PathBuf, cyrillic: PathBuf, learner: Learner) -> Self {
+        Self {
+            latin,
+            cyrillic,
+            learner,
+        }
+    }
+}
+
+impl FileProcessor for LearnProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let latin = fs::read(&self.latin)?;
+        let cyrillic = fs::read(&self.cyrillic)?;
+        self.learner
+            .add(&String::from_utf8_lossy(&latin), &String::from_utf8_lossy(&cyrillic));
+        let mut output = io::stdout();
+        for exception in self.learner.exceptions() {
+            writeln!(output, "{}", exception)?;
+        }
+        Ok(())
+    }
+}
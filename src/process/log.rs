@@ -0,0 +1,149 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    /// Field at the start of a line: timestamp (ISO 8601, time of day or
+    /// syslog with its host name), level, bracketed or parenthesized
+    /// field, syslog priority, process with its id (ie. `sshd[42]:`),
+    /// key-value pair, number or separator
+    static ref PREFIX: Regex = Regex::new(concat!(
+        r"^(?:\s+|[-|:>]+(?:\s|$)",
+        r"|\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?)?",
+        r"|\d{2}:\d{2}:\d{2}(?:[.,]\d+)?",
+        r"|(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) [ \d]\d \d{2}:\d{2}:\d{2} \S+",
+        r"|(?:TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|ERR|FATAL|CRITICAL|CRIT|PANIC)\b:?",
+        r"|\[[^\]]*\]:?|\([^)]*\):?|<\d+>",
+        r"|[\w.-]+\[\d+\]:",
+        r#"|[\w.-]+=(?:"(?:[^"\\]|\\.)*"|\S*)"#,
+        r"|\d+(?:\.\d+)?\b)"
+    ))
+    .unwrap();
+    /// Key-value pair (ie. `user=ana`, `msg="Prijava uspela"`)
+    static ref FIELD: Regex =
+        Regex::new(r#"(?:^|\b)([\w.-]+)=(?:"((?:[^"\\]|\\.)*)"|\S*)"#).unwrap();
+}
+
+/// Keys of key-value pairs whose quoted values are messages
+const MESSAGE_KEYS: &[&str] = &["msg", "message"];
+
+/// Transliterates messages of log files, while timestamps, levels,
+/// bracketed fields (ie. `[main]`) and key-value pairs (ie. `user=ana`) at
+/// the start of each line are left unchanged. Key-value pairs within
+/// messages are left unchanged too, except for quoted values of `msg`
+/// and `message` keys, which are messages themselves.
+pub struct LogProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl LogProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate a message, leaving key-value pairs in it unchanged
+    fn convert_message(&self, message: &str) -> Result<String, transliterate::Error> {
+        let mut output = String::with_capacity(message.len());
+        let mut last = 0;
+        for field in FIELD.captures_iter(message) {
+            let (whole, key) = (field.get(0).unwrap(), &field[1]);
+            output.push_str(&self.processor.process(&message[last..whole.start()])?);
+            match field.get(2) {
+                Some(value) if MESSAGE_KEYS.contains(&key) => {
+                    output.push_str(&message[whole.start()..value.start()]);
+                    output.push_str(&self.processor.process(value.as_str())?);
+                    output.push_str(&message[value.end()..whole.end()]);
+                }
+                _ => output.push_str(whole.as_str()),
+            }
+            last = whole.end();
+        }
+        output.push_str(&self.processor.process(&message[last..])?);
+        Ok(output)
+    }
+
+    /// Transliterate a log
+    pub fn convert(&self, log: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(log.len());
+        for line in log.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let mut start = 0;
+            while let Some(field) = PREFIX.find(&content[start..]) {
+                // Quoted messages of key-value pairs are converted
+                let message = FIELD
+                    .captures(field.as_str())
+                    .map_or(false, |f| f.get(2).is_some() && MESSAGE_KEYS.contains(&&f[1]));
+                if field.as_str().is_empty() || message {
+                    break;
+                }
+                start += field.end();
+            }
+            output.push_str(&content[..start]);
+            output.push_str(&self.convert_message(&content[start..])?);
+            output.push_str(&line[content.len()..]);
+        }
+        Ok(output)
+    }
+}
+
+with_backup!(LogProcessor);
+
+impl FileProcessor for LogProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_log() {
+        let p = LogProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        let log = concat!(
+            "2024-05-01T12:00:00.123Z INFO [main] server: Korisnik prijavljen user=ana id=7\n",
+            "2024-05-01 12:00:01,5 WARN  - Disk je skoro pun (90%)\r\n",
+            "May  1 12:00:02 nas sshd[42]: Prihvaćena lozinka\n",
+            "level=error ts=2024-05-01T12:00:03Z msg=\"Greška pri čitanju\" path=/tmp/a\n",
+            "12:00:04.5 <3> [DEBUG] Kraj\n",
+            "Obična poruka\n",
+            "ERROR"
+        );
+        let expected = concat!(
+            "2024-05-01T12:00:00.123Z INFO [main] сервер: Корисник пријављен user=ana id=7\n",
+            "2024-05-01 12:00:01,5 WARN  - Диск је скоро пун (90%)\r\n",
+            "May  1 12:00:02 nas sshd[42]: Прихваћена лозинка\n",
+            "level=error ts=2024-05-01T12:00:03Z msg=\"Грешка при читању\" path=/tmp/a\n",
+            "12:00:04.5 <3> [DEBUG] Крај\n",
+            "Обична порука\n",
+            "ERROR"
+        );
+        assert_eq!(p.convert(log).unwrap(), expected);
+    }
+}
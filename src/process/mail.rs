@@ -0,0 +1,569 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::bytes::Regex;
+
+use super::html::convert_html;
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::Transliterator;
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+lazy_static! {
+    static ref ENCODED_WORD: Regex = Regex::new(r"=\?([^?\s]+)\?([BbQq])\?([^?\s]*)\?=").unwrap();
+    static ref CHARSET: Regex = Regex::new(r#"(?i)charset\s*=\s*"?[^";\s]*"?"#).unwrap();
+}
+
+fn base64_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &b in input {
+        let value = match b {
+            b'=' => break,
+            b if b.is_ascii_whitespace() => continue,
+            b => BASE64.iter().position(|&c| c == b)? as u32,
+        };
+        buffer = buffer << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Some(output)
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity((input.len() + 2) / 3 * 4);
+    for chunk in input.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => output.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char),
+                false => output.push('='),
+            }
+        }
+    }
+    output
+}
+
+fn hex(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decode quoted-printable text. Underscores are spaces in headers.
+fn qp_decode(input: &[u8], header: bool) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' => {
+                let rest = &input[i + 1..];
+                if let (Some(&h), Some(&l)) = (rest.first(), rest.get(1)) {
+                    if let (Some(h), Some(l)) = (hex(h), hex(l)) {
+                        output.push(h << 4 | l);
+                        i += 3;
+                        continue;
+                    }
+                }
+                // Soft line break
+                if rest.starts_with(b"\r\n") {
+                    i += 3;
+                } else if rest.starts_with(b"\n") {
+                    i += 2;
+                } else {
+                    output.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if header => {
+                output.push(b' ');
+                i += 1;
+            }
+            b => {
+                output.push(b);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+/// Encode text as quoted-printable, keeping its line breaks
+fn qp_encode(input: &str, newline: &str) -> String {
+    let mut output = String::with_capacity(input.len() * 2);
+    for (n, line) in input.split('\n').enumerate() {
+        if n > 0 {
+            output.push_str(newline);
+        }
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let mut width = 0;
+        let bytes = line.as_bytes();
+        for (i, &b) in bytes.iter().enumerate() {
+            let last = i + 1 == bytes.len();
+            let encoded = match b {
+                b'=' => format!("={:02X}", b),
+                b' ' | b'\t' if last => format!("={:02X}", b),
+                b' '..=b'~' | b'\t' => (b as char).to_string(),
+                b => format!("={:02X}", b),
+            };
+            if width + encoded.len() > 75 {
+                output.push('=');
+                output.push_str(newline);
+                width = 0;
+            }
+            width += encoded.len();
+            output.push_str(&encoded);
+        }
+    }
+    output
+}
+
+/// Wrap base64 text into lines of 76 characters
+fn base64_lines(input: &[u8], newline: &str) -> String {
+    let encoded = base64_encode(input);
+    let mut output = String::with_capacity(encoded.len() * 78 / 76 + 2);
+    for line in encoded.as_bytes().chunks(76) {
+        output.push_str(std::str::from_utf8(line).unwrap_or_default());
+        output.push_str(newline);
+    }
+    output
+}
+
+fn utf8_charset(charset: &str) -> bool {
+    matches!(charset.to_ascii_lowercase().as_str(), "utf-8" | "utf8" | "us-ascii")
+}
+
+/// Decode encoded words (`=?UTF-8?B?...?=`) of a header. Returns `None`
+/// if header uses a charset other than UTF-8.
+fn decode_header(value: &[u8]) -> Option<String> {
+    let mut output = Vec::with_capacity(value.len());
+    let mut last = 0;
+    for word in ENCODED_WORD.captures_iter(value) {
+        let whole = word.get(0)?;
+        let between = &value[last..whole.start()];
+        // Whitespace between adjacent encoded words is ignored
+        if last == 0 || !between.iter().all(u8::is_ascii_whitespace) {
+            output.extend_from_slice(between);
+        }
+        if !utf8_charset(std::str::from_utf8(&word[1]).ok()?) {
+            return None;
+        }
+        match &word[2] {
+            b"B" | b"b" => output.extend(base64_decode(&word[3])?),
+            _ => output.extend(qp_decode(&word[3], true)),
+        }
+        last = whole.end();
+    }
+    output.extend_from_slice(&value[last..]);
+    let text = String::from_utf8(output).ok()?;
+    // Unfold header lines
+    Some(text.replace("\r\n", "").replace('\n', ""))
+}
+
+/// Encode header value into encoded words if it is not ASCII
+fn encode_header(value: &str, newline: &str) -> String {
+    if value.is_ascii() {
+        return value.to_owned();
+    }
+    // Encoded words are at most 75 characters long, and characters are
+    // never split between words
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in value.chars() {
+        if word.len() + c.len_utf8() > 45 {
+            words.push(format!("=?UTF-8?B?{}?=", base64_encode(word.as_bytes())));
+            word.clear();
+        }
+        word.push(c);
+    }
+    words.push(format!("=?UTF-8?B?{}?=", base64_encode(word.as_bytes())));
+    words.join(&format!("{} ", newline))
+}
+
+/// Set charset of a `Content-Type` field to UTF-8
+fn utf8_content_type(raw: &[u8]) -> Vec<u8> {
+    match CHARSET.is_match(raw) {
+        true => CHARSET.replace(raw, &b"charset=utf-8"[..]).into_owned(),
+        false => {
+            let end = raw.len()
+                - raw
+                    .iter()
+                    .rev()
+                    .take_while(|b| b.is_ascii_whitespace())
+                    .count();
+            let mut output = raw[..end].to_vec();
+            output.extend_from_slice(b"; charset=utf-8");
+            output.extend_from_slice(&raw[end..]);
+            output
+        }
+    }
+}
+
+/// Header field of a message, as name and raw value
+struct Field<'a> {
+    name: &'a [u8],
+    raw: &'a [u8],
+}
+
+impl Field<'_> {
+    fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name.as_bytes())
+    }
+
+    /// Value with line folding
+    fn raw_value(&self) -> &[u8] {
+        self.raw.get(self.name.len() + 1..).unwrap_or_default()
+    }
+
+    /// Value without line folding
+    fn value(&self) -> String {
+        String::from_utf8_lossy(self.raw_value())
+            .replace("\r\n", "")
+            .replace('\n', "")
+            .trim()
+            .to_owned()
+    }
+}
+
+/// Split header into fields, keeping their raw bytes
+fn fields(header: &[u8]) -> Vec<Field<'_>> {
+    let mut fields: Vec<Field> = Vec::new();
+    let mut start = 0;
+    let mut lines = header.split_inclusive(|&b| b == b'\n').peekable();
+    let mut end = 0;
+    while let Some(line) = lines.next() {
+        end += line.len();
+        let folded =
+            matches!(lines.peek(), Some(next) if next.starts_with(b" ") || next.starts_with(b"\t"));
+        if !folded {
+            let raw = &header[start..end];
+            let name = raw.split(|&b| b == b':').next().unwrap_or_default();
+            fields.push(Field { name, raw });
+            start = end;
+        }
+    }
+    fields
+}
+
+/// Returns parameter of a header value (ie. `boundary`)
+fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (key, value) = p.split_once('=')?;
+        match key.trim().eq_ignore_ascii_case(name) {
+            true => Some(value.trim().trim_matches('"').to_owned()),
+            false => None,
+        }
+    })
+}
+
+/// Transliterates email messages (`.eml`). Subject and text parts are
+/// decoded, converted and encoded again, while attachments, other
+/// headers and message structure are left unchanged. Parts in charsets
+/// other than UTF-8 are left unchanged too.
+pub struct MailProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl MailProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate a message
+    pub fn convert(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        self.convert_entity(message)
+    }
+
+    /// Convert a message or a part of multipart message
+    fn convert_entity(&self, entity: &[u8]) -> Result<Vec<u8>, Error> {
+        let (header, newline, body) = match find(entity, b"\r\n\r\n") {
+            Some(i) if find(entity, b"\n\n").map_or(true, |j| i < j) => {
+                (&entity[..i + 2], "\r\n", &entity[i + 4..])
+            }
+            _ => match find(entity, b"\n\n") {
+                Some(i) => (&entity[..i + 1], "\n", &entity[i + 2..]),
+                None => return Ok(entity.to_vec()),
+            },
+        };
+        let fields = fields(header);
+        let get = |name| fields.iter().find(|f| f.is(name)).map(Field::value);
+        let content_type = get("content-type").unwrap_or_else(|| "text/plain".to_owned());
+        let kind = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let charset = parameter(&content_type, "charset").unwrap_or_else(|| "us-ascii".to_owned());
+        let encoding = get("content-transfer-encoding")
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let attachment = get("content-disposition")
+            .map_or(false, |d| d.to_ascii_lowercase().starts_with("attachment"));
+
+        let (body, reencoded) = if kind.starts_with("multipart/") {
+            match parameter(&content_type, "boundary") {
+                Some(boundary) => (self.convert_multipart(body, &boundary)?, None),
+                None => (body.to_vec(), None),
+            }
+        } else if kind == "message/rfc822" {
+            (self.convert_entity(body)?, None)
+        } else if matches!(kind.as_str(), "text/plain" | "text/html")
+            && !attachment
+            && utf8_charset(&charset)
+        {
+            match self.convert_body(body, &encoding, &kind)? {
+                Some(text) => match encoding.as_str() {
+                    "base64" => (base64_lines(text.as_bytes(), newline).into_bytes(), None),
+                    "quoted-printable" => (qp_encode(&text, newline).into_bytes(), None),
+                    // Text which is no longer ASCII is sent as quoted-printable
+                    "" | "7bit" if !text.is_ascii() => {
+                        (qp_encode(&text, newline).into_bytes(), Some("quoted-printable"))
+                    }
+                    _ => (text.into_bytes(), None),
+                },
+                None => (body.to_vec(), None),
+            }
+        } else {
+            (body.to_vec(), None)
+        };
+
+        let mut output = Vec::with_capacity(entity.len());
+        for field in &fields {
+            if field.is("subject") {
+                output.extend(self.convert_subject(field, newline)?);
+            } else if reencoded.is_some() && field.is("content-transfer-encoding") {
+                continue;
+            } else if reencoded.is_some()
+                && field.is("content-type")
+                && !charset.eq_ignore_ascii_case("utf-8")
+            {
+                output.extend(utf8_content_type(field.raw));
+            } else {
+                output.extend_from_slice(field.raw);
+            }
+        }
+        if let Some(encoding) = reencoded {
+            if get("content-type").is_none() {
+                output
+                    .extend(format!("Content-Type: text/plain; charset=utf-8{}", newline).bytes());
+            }
+            output.extend(format!("Content-Transfer-Encoding: {}{}", encoding, newline).bytes());
+        }
+        output.extend_from_slice(newline.as_bytes());
+        output.extend(body);
+        Ok(output)
+    }
+
+    /// Decode, convert and return text of a part
+    fn convert_body(
+        &self,
+        body: &[u8],
+        encoding: &str,
+        kind: &str,
+    ) -> Result<Option<String>, Error> {
+        let decoded = match encoding {
+            "base64" => match base64_decode(body) {
+                Some(decoded) => decoded,
+                None => return Ok(None),
+            },
+            "quoted-printable" => qp_decode(body, false),
+            "" | "7bit" | "8bit" | "binary" => body.to_vec(),
+            _ => return Ok(None),
+        };
+        let text = match String::from_utf8(decoded) {
+            Ok(text) => text,
+            Err(_) => return Ok(None),
+        };
+        Ok(Some(match kind {
+            "text/html" => convert_html(&self.processor, &text)?,
+            _ => self.processor.process(text)?,
+        }))
+    }
+
+    fn convert_subject(&self, field: &Field, newline: &str) -> Result<Vec<u8>, Error> {
+        let decoded = match decode_header(field.raw_value()) {
+            Some(decoded) => decoded,
+            None => return Ok(field.raw.to_vec()),
+        };
+        let converted = self.processor.process(decoded.trim())?;
+        let name = String::from_utf8_lossy(field.name);
+        Ok(format!("{}: {}{}", name, encode_header(&converted, newline), newline).into_bytes())
+    }
+
+    /// Convert parts of a multipart body, keeping preamble and epilogue
+    fn convert_multipart(&self, body: &[u8], boundary: &str) -> Result<Vec<u8>, Error> {
+        let delimiter = format!("--{}", boundary);
+        let mut output = Vec::with_capacity(body.len());
+        let mut part: Option<Vec<u8>> = None;
+        let mut closed = false;
+        for line in body.split_inclusive(|&b| b == b'\n') {
+            let trimmed = line.strip_suffix(b"\n").unwrap_or(line);
+            let trimmed = trimmed.strip_suffix(b"\r").unwrap_or(trimmed);
+            let is_delimiter = !closed && trimmed.starts_with(delimiter.as_bytes()) && {
+                let rest = &trimmed[delimiter.len()..];
+                rest.iter().all(u8::is_ascii_whitespace) || rest.starts_with(b"--")
+            };
+            if is_delimiter {
+                if let Some(part) = part.take() {
+                    output.extend(self.convert_entity(&part)?);
+                }
+                output.extend_from_slice(line);
+                closed = trimmed[delimiter.len()..].starts_with(b"--");
+                if !closed {
+                    part = Some(Vec::new());
+                }
+            } else {
+                match &mut part {
+                    Some(part) => part.extend_from_slice(line),
+                    None => output.extend_from_slice(line),
+                }
+            }
+        }
+        if let Some(part) = part {
+            output.extend(self.convert_entity(&part)?);
+        }
+        Ok(output)
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+with_backup!(MailProcessor);
+
+impl FileProcessor for MailProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = Vec::new();
+        self.input.read_to_end(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(&output)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor() -> MailProcessor {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        MailProcessor::new(Some(PathBuf::from(file!())), None, t).unwrap()
+    }
+
+    #[test]
+    fn test_codecs() {
+        for text in ["", "a", "ab", "abc", "Ђурђевак"] {
+            let encoded = base64_encode(text.as_bytes());
+            assert_eq!(base64_decode(encoded.as_bytes()).unwrap(), text.as_bytes());
+        }
+        assert_eq!(base64_encode(b"Dan"), "RGFu");
+        assert_eq!(qp_decode(b"Dobar=20dan=\r\n!", false), b"Dobar dan!");
+        assert_eq!(qp_encode("a=b \nč", "\r\n"), "a=3Db=20\r\n=C4=8D");
+        let long = "ш".repeat(40);
+        let encoded = qp_encode(&long, "\n");
+        assert!(encoded.lines().all(|l| l.len() <= 76));
+        assert_eq!(qp_decode(encoded.as_bytes(), false), long.as_bytes());
+        assert_eq!(
+            decode_header(b" =?UTF-8?B?0JTQvtCx0LDRgA==?= =?utf-8?Q?_=D0=B4=D0=B0=D0=BD?=")
+                .unwrap(),
+            " Добар дан"
+        );
+        assert_eq!(decode_header(b" =?koi8-r?B?4sXM2Q==?="), None);
+        assert_eq!(encode_header("Dobar dan", "\r\n"), "Dobar dan");
+        let encoded = encode_header(&"Ђ".repeat(30), "\r\n");
+        assert!(encoded.split("\r\n ").all(|w| w.len() <= 75));
+        assert_eq!(decode_header(encoded.as_bytes()).unwrap(), "Ђ".repeat(30));
+    }
+
+    #[test]
+    fn test_message() {
+        let message = concat!(
+            "From: =?UTF-8?B?0J/QtdGC0LDRgA==?= <petar@example.rs>\r\n",
+            "Subject: =?UTF-8?B?0J/QvtC30LTRgNCw0LI=?=\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"granica\"\r\n",
+            "\r\n",
+            "Preamble\r\n",
+            "--granica\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: quoted-printable\r\n",
+            "\r\n",
+            "=D0=94=D0=BE=D0=B1=D0=B0=D1=80 =D0=B4=D0=B0=D0=BD\r\n",
+            "--granica\r\n",
+            "Content-Type: text/html; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "PHA+0JTQsNC9PC9wPg==\r\n",
+            "--granica\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "Content-Disposition: attachment; filename=\"pismo.txt\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "0J/QuNGB0LzQvg==\r\n",
+            "--granica--\r\n"
+        );
+        let expected = concat!(
+            "From: =?UTF-8?B?0J/QtdGC0LDRgA==?= <petar@example.rs>\r\n",
+            "Subject: Pozdrav\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"granica\"\r\n",
+            "\r\n",
+            "Preamble\r\n",
+            "--granica\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: quoted-printable\r\n",
+            "\r\n",
+            "Dobar dan\r\n",
+            "--granica\r\n",
+            "Content-Type: text/html; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "PHA+RGFuPC9wPg==\r\n",
+            "--granica\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "Content-Disposition: attachment; filename=\"pismo.txt\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "0J/QuNGB0LzQvg==\r\n",
+            "--granica--\r\n"
+        );
+        let output = processor().convert(message.as_bytes()).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_seven_bit() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let p = MailProcessor::new(Some(PathBuf::from(file!())), None, t).unwrap();
+        let message = "Subject: Dan\nContent-Type: text/plain; charset=us-ascii\n\nDan\n";
+        let output = String::from_utf8(p.convert(message.as_bytes()).unwrap()).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                "Subject: =?UTF-8?B?0JTQsNC9?=\n",
+                "Content-Type: text/plain; charset=utf-8\n",
+                "Content-Transfer-Encoding: quoted-printable\n",
+                "\n",
+                "=D0=94=D0=B0=D0=BD\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::json;
+use crate::transliterate::Transliterator;
+
+/// Deepest nesting of arrays and objects in a line
+const MAX_DEPTH: usize = 128;
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// Parser of a single JSON value, which copies it into output with
+/// selected string values transliterated
+struct Parser<'a> {
+    processor: &'a NdjsonProcessor,
+    text: &'a str,
+    position: usize,
+    /// Text before this position is already in output
+    copied: usize,
+    path: Vec<String>,
+    output: String,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn error(&self) -> Error {
+        let column = self.text[..self.position].chars().count() + 1;
+        match self.text[self.position..].chars().next() {
+            Some(c) => invalid_data(format!("unexpected '{}' at column {}", c, column)),
+            None => invalid_data(format!("unexpected end at column {}", column)),
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        match self.text[self.position..].starts_with(token) {
+            true => {
+                self.position += token.len();
+                Ok(())
+            }
+            false => Err(self.error()),
+        }
+    }
+
+    /// Parse a string, returning its start and unescaped contents
+    fn string(&mut self) -> Result<(usize, String), Error> {
+        let start = self.position;
+        match json::parse_string(self.text, &mut self.position) {
+            Some(contents) => Ok((start, contents)),
+            None => Err(self.error()),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<(), Error> {
+        self.whitespace();
+        if depth > MAX_DEPTH {
+            return Err(self.error());
+        }
+        match self.peek() {
+            Some(b'{') => {
+                self.position += 1;
+                self.whitespace();
+                if self.peek() == Some(b'}') {
+                    self.position += 1;
+                } else {
+                    loop {
+                        self.whitespace();
+                        let (_, key) = self.string()?;
+                        self.whitespace();
+                        self.expect(":")?;
+                        self.path.push(key);
+                        self.value(depth + 1)?;
+                        self.path.pop();
+                        match self.peek() {
+                            Some(b',') => self.position += 1,
+                            Some(b'}') => {
+                                self.position += 1;
+                                break;
+                            }
+                            _ => return Err(self.error()),
+                        }
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.position += 1;
+                self.whitespace();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                } else {
+                    // Items of arrays have the path of their parent key
+                    loop {
+                        self.value(depth + 1)?;
+                        match self.peek() {
+                            Some(b',') => self.position += 1,
+                            Some(b']') => {
+                                self.position += 1;
+                                break;
+                            }
+                            _ => return Err(self.error()),
+                        }
+                    }
+                }
+            }
+            Some(b'"') => {
+                let (start, contents) = self.string()?;
+                if self.processor.selected(&self.path) {
+                    let converted = self.processor.processor.process(&contents)?;
+                    // Unchanged strings keep their original escapes
+                    if converted != contents {
+                        self.output.push_str(&self.text[self.copied..start]);
+                        self.output.push_str(&json::string(&converted));
+                        self.copied = self.position;
+                    }
+                }
+            }
+            Some(b't') => self.expect("true")?,
+            Some(b'f') => self.expect("false")?,
+            Some(b'n') => self.expect("null")?,
+            _ => match json::NUMBER.find(&self.text[self.position..]) {
+                Some(number) => self.position += number.end(),
+                None => return Err(self.error()),
+            },
+        }
+        self.whitespace();
+        Ok(())
+    }
+}
+
+/// Transliterates string values of JSON Lines (NDJSON) streams, ie. event
+/// exports and logs. Each line is written out as soon as it is converted,
+/// so streams of any length can be piped through. Keys, numbers and
+/// formatting of lines are left unchanged.
+pub struct NdjsonProcessor {
+    input: Box<dyn BufRead>,
+    output: Output,
+    processor: Transliterator,
+    keys: Vec<Vec<String>>,
+}
+
+impl NdjsonProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: Box::from(BufReader::new(open(input)?)),
+            output: Output::new(output),
+            processor,
+            keys: Vec::new(),
+        })
+    }
+
+    /// Convert only values with these key paths (ie. `title` or `user.*`),
+    /// and values nested in them. All values are converted if empty.
+    pub fn with_keys(mut self, keys: &[String]) -> Self {
+        self.keys = keys
+            .iter()
+            .map(|k| k.split('.').map(str::to_owned).collect())
+            .collect();
+        self
+    }
+
+    /// Returns whether values with key `path` are converted
+    fn selected(&self, path: &[String]) -> bool {
+        self.keys.is_empty()
+            || self.keys.iter().any(|keys| {
+                keys.len() <= path.len()
+                    && keys
+                        .iter()
+                        .zip(path)
+                        .all(|(key, name)| key == "*" || key == name)
+            })
+    }
+
+    /// Transliterate a line with a single JSON value. Blank lines are
+    /// left unchanged.
+    pub fn convert_line(&self, line: &str) -> Result<String, Error> {
+        if line.trim().is_empty() {
+            return Ok(line.to_owned());
+        }
+        let mut parser = Parser {
+            processor: self,
+            text: line,
+            position: 0,
+            copied: 0,
+            path: Vec::new(),
+            output: String::with_capacity(line.len()),
+        };
+        parser.value(0)?;
+        if parser.position < line.len() {
+            return Err(parser.error());
+        }
+        parser.output.push_str(&line[parser.copied..]);
+        Ok(parser.output)
+    }
+
+    /// Transliterate lines of `input` into `output`, one at a time
+    pub fn convert(&self, input: &mut dyn BufRead, output: &mut dyn Write) -> Result<(), Error> {
+        let mut line = String::new();
+        let mut number = 0;
+        while input.read_line(&mut line)? > 0 {
+            number += 1;
+            let content = line.trim_end_matches(['\n', '\r']);
+            let converted = self.convert_line(content).map_err(|e| match e {
+                Error::Io(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    invalid_data(format!("invalid JSON on line {} - {}", number, e))
+                }
+                e => e,
+            })?;
+            output.write_all(converted.as_bytes())?;
+            output.write_all(&line.as_bytes()[content.len()..])?;
+            line.clear();
+        }
+        output.flush()?;
+        Ok(())
+    }
+}
+
+with_backup!(NdjsonProcessor);
+
+impl FileProcessor for NdjsonProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = std::mem::replace(&mut self.input, Box::new(io::empty()));
+        self.output
+            .write_with(|output| self.convert(&mut input, output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor() -> NdjsonProcessor {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        NdjsonProcessor::new(None, None, t).unwrap()
+    }
+
+    #[test]
+    fn test_line() {
+        let p = processor();
+        let line = r#"{"id": 7, "ime":"Petar", "tagovi":["novi","škola"], "ok":true, "x":null, "ocena":-1.5e3, "kod":"\u00e9\ud83d\ude00 Ko\u0161"}"#;
+        let expected = r#"{"id": 7, "ime":"Петар", "tagovi":["нови","школа"], "ok":true, "x":null, "ocena":-1.5e3, "kod":"é😀 Кош"}"#;
+        assert_eq!(p.convert_line(line).unwrap(), expected);
+        assert_eq!(p.convert_line(r#""Reci \"da\"""#).unwrap(), r#""Реци \"да\"""#);
+        assert_eq!(p.convert_line("  ").unwrap(), "  ");
+        for line in [r#"{"a":}"#, r#"{"a":"b"} x"#, "[1,]", r#""\x""#, "01"] {
+            assert!(p.convert_line(line).is_err());
+        }
+    }
+
+    #[test]
+    fn test_keys() {
+        let p = processor().with_keys(&["naslov".to_owned(), "autor.*".to_owned()]);
+        let line =
+            r#"{"naslov":"Vesti","autor":{"ime":"Ana","uloga":["urednik"]},"kategorija":"Sport"}"#;
+        let expected =
+            r#"{"naslov":"Вести","autor":{"ime":"Ана","uloga":["уредник"]},"kategorija":"Sport"}"#;
+        assert_eq!(p.convert_line(line).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_stream() {
+        let p = processor();
+        let mut output = Vec::new();
+        p.convert(&mut "{\"a\":\"Dan\"}\r\n\n[\"Noć\"]".as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "{\"a\":\"Дан\"}\r\n\n[\"Ноћ\"]");
+        let error = p
+            .convert(&mut "{}\n{\"a\" \"b\"}\n".as_bytes(), &mut Vec::new())
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("invalid JSON on line 2 - unexpected '\"' at column 6"));
+    }
+}
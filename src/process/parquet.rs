@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+
+use arrow_array::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+
+use super::{Error, FileProcessor, Output};
+use crate::arrow::transliterate_batch;
+use crate::transliterate::Transliterator;
+
+fn invalid_data(error: impl ToString) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
+}
+
+/// Transliterates string columns of Apache Parquet files. Other columns,
+/// schema and compression of the file are kept, while row groups and
+/// encodings may be written differently.
+pub struct ParquetProcessor {
+    input: PathBuf,
+    output: Output,
+    processor: Transliterator,
+    columns: Vec<String>,
+}
+
+impl ParquetProcessor {
+    pub fn new(
+        input: PathBuf,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input,
+            output: Output::new(output),
+            processor,
+            columns: Vec::new(),
+        })
+    }
+
+    /// Transliterate only columns with these names, which must be string
+    /// columns. All string columns are transliterated if empty.
+    pub fn with_columns(mut self, columns: &[String]) -> Self {
+        self.columns = columns.to_vec();
+        self
+    }
+
+    /// Returns transliterated record batches of the file
+    pub fn batches(&self) -> Result<Vec<RecordBatch>, Error> {
+        ParquetRecordBatchReaderBuilder::try_new(File::open(&self.input)?)
+            .and_then(|builder| builder.build())
+            .map_err(invalid_data)?
+            .map(|batch| {
+                let batch = batch.map_err(invalid_data)?;
+                transliterate_batch(&self.processor, &batch, &self.columns)
+            })
+            .collect()
+    }
+
+    /// Returns the transliterated file
+    pub fn convert(&self) -> Result<Vec<u8>, Error> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&self.input)?)
+            .map_err(invalid_data)?;
+        let schema = builder.schema().clone();
+        // Compression of the first column applies to the whole file
+        let mut properties = WriterProperties::builder();
+        if let Some(column) = builder
+            .metadata()
+            .row_groups()
+            .first()
+            .and_then(|group| group.columns().first())
+        {
+            properties = properties.set_compression(column.compression());
+        }
+        let mut output = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut output, schema, Some(properties.build()))
+            .map_err(invalid_data)?;
+        for batch in builder.build().map_err(invalid_data)? {
+            let batch = batch.map_err(invalid_data)?;
+            let batch = transliterate_batch(&self.processor, &batch, &self.columns)?;
+            writer.write(&batch).map_err(invalid_data)?;
+        }
+        writer.close().map_err(invalid_data)?;
+        Ok(output)
+    }
+}
+
+with_backup!(ParquetProcessor);
+
+impl FileProcessor for ParquetProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let output = self.convert()?;
+        self.output.write(&output)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::atomic;
+    use crate::transliterate::Charset;
+    use arrow_array::cast::AsArray;
+    use arrow_array::{ArrayRef, Int32Array, StringArray};
+    use parquet::basic::{Compression, ZstdLevel};
+    use std::{env, fs, sync::Arc};
+
+    #[test]
+    fn test_parquet() {
+        let root = env::temp_dir().join(format!("translitrs-parquet-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("gradovi.parquet");
+
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef),
+            ("grad", Arc::new(StringArray::from(vec![Some("Novi Sad"), None]))),
+        ])
+        .unwrap();
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), Some(properties))
+                .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let p = ParquetProcessor::new(path.clone(), Some(path.clone()), t)
+            .unwrap()
+            .with_columns(&["grad".to_owned()]);
+        let batches = p.batches().unwrap();
+        assert_eq!(
+            batches[0].column(1).as_string::<i32>(),
+            &StringArray::from(vec![Some("Нови Сад"), None])
+        );
+
+        p.with_backup(true).run().unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let compression = builder.metadata().row_group(0).column(1).compression();
+        assert!(matches!(compression, Compression::ZSTD(_)));
+        let converted = builder.build().unwrap().next().unwrap().unwrap();
+        assert_eq!(converted.column(0), batch.column(0));
+        assert_eq!(converted.column(1), batches[0].column(1));
+        assert!(atomic::backup_path(&path).exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write as _};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream, StringFormat};
+use ttf_parser::{name_id, Face, GlyphId};
+
+use super::{Error, FileProcessor, Output};
+use crate::transliterate::Transliterator;
+
+/// Size of A4 pages, in points
+const PAGE: (f32, f32) = (595.0, 842.0);
+const MARGIN: f32 = 56.0;
+const FONT_SIZE: f32 = 11.0;
+const LEADING: f32 = 14.0;
+
+fn invalid_data(error: impl Display) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
+}
+
+/// Split `text` into lines no wider than `width`, where `measure`
+/// returns width of a character
+fn wrap(text: &str, width: f32, measure: impl Fn(char) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0.0;
+        for (i, word) in paragraph.split(' ').enumerate() {
+            let word_width = word.chars().map(&measure).sum::<f32>();
+            if i > 0 {
+                if line_width + measure(' ') + word_width <= width {
+                    line.push(' ');
+                    line_width += measure(' ');
+                } else {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+            }
+            // Words longer than a line are split
+            for c in word.chars() {
+                if line_width + measure(c) > width && !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+                line.push(c);
+                line_width += measure(c);
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Returns ToUnicode CMap of glyphs, so text of the document can be copied
+fn to_unicode(glyphs: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::from(concat!(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n",
+        "/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n",
+        "/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n",
+        "1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n"
+    ));
+    let glyphs = glyphs.iter().collect::<Vec<_>>();
+    for chunk in glyphs.chunks(100) {
+        let _ = writeln!(cmap, "{} beginbfchar", chunk.len());
+        for (glyph, c) in chunk {
+            let mut units = [0; 2];
+            let unicode = c
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|u| format!("{:04X}", u))
+                .collect::<String>();
+            let _ = writeln!(cmap, "<{:04X}> <{}>", glyph, unicode);
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
+
+/// Returns a PDF document with `pages` of plain text, written with
+/// TrueType font `font` which is embedded into the document
+fn build_pdf(pages: &[String], font: &[u8]) -> Result<Vec<u8>, Error> {
+    let face = Face::parse(font, 0).map_err(invalid_data)?;
+    let scale = 1000.0 / face.units_per_em() as f32;
+    let glyph = |c: char| face.glyph_index(c).map_or(0, |g| g.0);
+    let advance = |g: u16| face.glyph_hor_advance(GlyphId(g)).unwrap_or(0) as f32 * scale;
+    let measure = |c: char| advance(glyph(c)) * FONT_SIZE / 1000.0;
+
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let mut used = BTreeMap::new();
+    let mut kids = Vec::new();
+    let lines_per_page = ((PAGE.1 - 2.0 * MARGIN) / LEADING) as usize;
+    for page in pages {
+        let mut lines = wrap(page, PAGE.0 - 2.0 * MARGIN, measure);
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        for lines in lines.chunks(lines_per_page) {
+            let mut operations = vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), FONT_SIZE.into()]),
+                Operation::new("TL", vec![LEADING.into()]),
+                Operation::new("Td", vec![MARGIN.into(), (PAGE.1 - MARGIN - FONT_SIZE).into()]),
+            ];
+            for line in lines {
+                let mut glyphs = Vec::with_capacity(line.len() * 2);
+                for c in line.chars() {
+                    let g = glyph(c);
+                    if g != 0 {
+                        used.entry(g).or_insert(c);
+                    }
+                    glyphs.extend_from_slice(&g.to_be_bytes());
+                }
+                operations.push(Operation::new(
+                    "Tj",
+                    vec![Object::String(glyphs, StringFormat::Hexadecimal)],
+                ));
+                operations.push(Operation::new("T*", vec![]));
+            }
+            operations.push(Operation::new("ET", vec![]));
+            let content = Content { operations }.encode().map_err(invalid_data)?;
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+    }
+
+    let name = face
+        .names()
+        .into_iter()
+        .find(|n| n.name_id == name_id::POST_SCRIPT_NAME)
+        .and_then(|n| n.to_string())
+        .unwrap_or_else(|| "Embedded".to_owned());
+    let bbox = face.global_bounding_box();
+    let widths = used
+        .keys()
+        .flat_map(|&g| [(g as i64).into(), vec![advance(g).round().into()].into()])
+        .collect::<Vec<Object>>();
+    let font_file =
+        doc.add_object(Stream::new(dictionary! { "Length1" => font.len() as i64 }, font.to_vec()));
+    let descriptor = doc.add_object(dictionary! {
+        "Type" => "FontDescriptor",
+        "FontName" => Object::Name(name.clone().into_bytes()),
+        "Flags" => 32,
+        "FontBBox" => [bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max]
+            .iter()
+            .map(|&v| (v as f32 * scale).round().into())
+            .collect::<Vec<Object>>(),
+        "ItalicAngle" => 0,
+        "Ascent" => (face.ascender() as f32 * scale).round(),
+        "Descent" => (face.descender() as f32 * scale).round(),
+        "CapHeight" => (face.capital_height().unwrap_or(face.ascender()) as f32 * scale).round(),
+        "StemV" => 80,
+        "FontFile2" => font_file,
+    });
+    let cid_font = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "CIDFontType2",
+        "BaseFont" => Object::Name(name.clone().into_bytes()),
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::string_literal("Adobe"),
+            "Ordering" => Object::string_literal("Identity"),
+            "Supplement" => 0,
+        },
+        "FontDescriptor" => descriptor,
+        "CIDToGIDMap" => "Identity",
+        "W" => widths,
+    });
+    let cmap = doc.add_object(Stream::new(dictionary! {}, to_unicode(&used).into_bytes()));
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => Object::Name(name.into_bytes()),
+        "Encoding" => "Identity-H",
+        "DescendantFonts" => vec![cid_font.into()],
+        "ToUnicode" => cmap,
+    });
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+            "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+            "MediaBox" => vec![0.into(), 0.into(), PAGE.0.into(), PAGE.1.into()],
+        }),
+    );
+    let catalog = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog);
+    doc.compress();
+    let mut output = Vec::new();
+    doc.save_to(&mut output).map_err(invalid_data)?;
+    Ok(output)
+}
+
+/// Extracts and transliterates text of a PDF document. Output is plain
+/// text with pages separated by form feeds, or a simple text PDF if a
+/// font is set. Layout of the original document is not preserved.
+pub struct PdfProcessor {
+    input: Vec<u8>,
+    output: Output,
+    processor: Transliterator,
+    font: Option<PathBuf>,
+}
+
+impl PdfProcessor {
+    pub fn new(
+        input: PathBuf,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: fs::read(input)?,
+            output: Output::new(output),
+            processor,
+            font: None,
+        })
+    }
+
+    /// Write a PDF document with this TrueType font instead of plain text.
+    /// The font needs to contain letters of the output character set.
+    pub fn with_font(mut self, font: Option<PathBuf>) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Returns transliterated text of each page
+    pub fn pages(&self) -> Result<Vec<String>, Error> {
+        pdf_extract::extract_text_from_mem_by_pages(&self.input)
+            .map_err(invalid_data)?
+            .iter()
+            .map(|page| Ok(self.processor.process(page)?))
+            .collect()
+    }
+}
+
+with_backup!(PdfProcessor);
+
+impl FileProcessor for PdfProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let pages = self.pages()?;
+        let output = match &self.font {
+            Some(font) => build_pdf(&pages, &fs::read(font)?)?,
+            None => pages.join("\u{c}").into_bytes(),
+        };
+        self.output.write(&output)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        let lines = wrap("Dobar dan\n\nsvima  ovde nekakvoslovo", 9.0, |_| 1.0);
+        assert_eq!(lines, ["Dobar dan", "", "svima ", "ovde", "nekakvosl", "ovo"]);
+        let map = BTreeMap::from([(36, 'A'), (600, 'Ж')]);
+        assert!(to_unicode(&map).contains("2 beginbfchar\n<0024> <0041>\n<0258> <0416>\n"));
+    }
+}
@@ -0,0 +1,77 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::phonetic::PhoneticMap;
+use crate::transliterate::Transliterator;
+
+/// Converts text typed phonetically on keyboards without Serbian letters
+/// (ie. `zhivot`, `chovek`), whose triggers are replaced with letters of
+/// Serbian Latin before transliterating
+pub struct PhoneticProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    map: PhoneticMap,
+}
+
+impl PhoneticProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            map: PhoneticMap::default(),
+        })
+    }
+
+    /// Replace these triggers instead of the default ones
+    pub fn with_map(mut self, map: PhoneticMap) -> Self {
+        self.map = map;
+        self
+    }
+
+    /// Transliterate phonetically typed text
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        Ok(self.processor.process(self.map.to_latin(text))?)
+    }
+}
+
+with_backup!(PhoneticProcessor);
+
+impl FileProcessor for PhoneticProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_phonetic() {
+        let p = PhoneticProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        assert_eq!(
+            p.convert("Dzhon je odjednom shvatio: zhivot je lep, chovecje!\n")
+                .unwrap(),
+            "Џон је одједном схватио: живот је леп, човеће!\n"
+        );
+        let p = p.with_map("x=ж,cc=ћ".parse().unwrap());
+        assert_eq!(p.convert("Xiveo Djordje iz kucce").unwrap(), "Живео Ђорђе из куће");
+    }
+}
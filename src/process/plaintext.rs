@@ -1,13 +1,12 @@
-use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io::Read;
 use std::path::PathBuf;
 
-use super::{Error, FileProcessor};
+use super::{open, Error, FileProcessor, Output};
 use crate::transliterate::Transliterator;
 
 pub struct PlaintextProcessor {
     input: Box<dyn Read>,
-    output: Box<dyn Write>,
+    output: Output,
     processor: Transliterator,
 }
 
@@ -18,28 +17,21 @@ impl PlaintextProcessor {
         processor: Transliterator,
     ) -> Result<Self, Error> {
         Ok(Self {
-            input: if let Some(p) = input {
-                Box::from(File::open(p)?)
-            } else {
-                Box::from(io::stdin())
-            },
-            output: if let Some(p) = output {
-                // TODO: Open file later for in-place edits
-                Box::from(File::create(p)?)
-            } else {
-                Box::from(io::stdout())
-            },
+            input: open(input)?,
+            output: Output::new(output),
             processor,
         })
     }
 }
 
+with_backup!(PlaintextProcessor);
+
 impl FileProcessor for PlaintextProcessor {
     fn run(&mut self) -> Result<(), Error> {
         let mut input_string = String::new();
         self.input.read_to_string(&mut input_string)?;
         let output_string = self.processor.process(input_string)?;
-        self.output.write_all(output_string.as_bytes())?;
+        self.output.write(output_string.as_bytes())?;
         Ok(())
     }
 }
@@ -0,0 +1,444 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::options::Options;
+use crate::transliterate::{Charset, Direction};
+
+/// Keys shown at the bottom of the screen
+const HELP: &str = "d direction  x exceptions  f foreign  l links  r roman  \
+    e/p/s add exception/protected/pattern  u undo  q quit";
+
+/// Columns scrolled by left and right arrows
+const SCROLL: usize = 8;
+
+/// Key pressed in the preview
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+}
+
+/// Lists of words which can be extended in the preview
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum List {
+    Exceptions,
+    Protected,
+    SkipPatterns,
+}
+
+/// Returns keys read from terminal input
+fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let (key, length) = match &bytes[i..] {
+            [0x1b, b'[' | b'O', b'A', ..] => (Some(Key::Up), 3),
+            [0x1b, b'[' | b'O', b'B', ..] => (Some(Key::Down), 3),
+            [0x1b, b'[' | b'O', b'C', ..] => (Some(Key::Right), 3),
+            [0x1b, b'[' | b'O', b'D', ..] => (Some(Key::Left), 3),
+            [0x1b, b'[', b'5', b'~', ..] => (Some(Key::PageUp), 4),
+            [0x1b, b'[', b'6', b'~', ..] => (Some(Key::PageDown), 4),
+            // Unknown escape sequences are ignored
+            [0x1b, b'[', rest @ ..] => {
+                let end = rest.iter().position(|b| (0x40..=0x7e).contains(b));
+                (None, end.map_or(bytes.len() - i, |end| end + 3))
+            }
+            [0x1b | 0x03, ..] => (Some(Key::Escape), 1),
+            [b'\r' | b'\n', ..] => (Some(Key::Enter), 1),
+            [0x7f | 0x08, ..] => (Some(Key::Backspace), 1),
+            [b, ..] if *b < 0x20 => (None, 1),
+            rest => {
+                let end = rest.iter().position(|b| *b < 0x20 || *b == 0x7f);
+                let end = end.unwrap_or(rest.len());
+                keys.extend(String::from_utf8_lossy(&rest[..end]).chars().map(Key::Char));
+                (None, end)
+            }
+        };
+        keys.extend(key);
+        i += length;
+    }
+    keys
+}
+
+/// Returns `text` as a TOML string
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns `width` characters of `line` starting at `column`, padded with
+/// spaces, with control characters replaced by spaces
+fn cell(line: &str, column: usize, width: usize) -> String {
+    let mut cell = line
+        .chars()
+        .skip(column)
+        .take(width)
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>();
+    let length = cell.chars().count();
+    cell.extend(std::iter::repeat(' ').take(width - length));
+    cell
+}
+
+/// State of the preview: text, its conversion with current options and
+/// position of the view
+struct Preview {
+    text: String,
+    options: Options,
+    converted: String,
+    /// Why the last conversion failed (ie. an invalid skip pattern)
+    error: Option<String>,
+    line: usize,
+    column: usize,
+    /// List which is being extended and the word typed so far
+    prompt: Option<(List, String)>,
+    /// Lists extended in the preview, most recent last
+    added: Vec<List>,
+}
+
+impl Preview {
+    fn new(text: String, options: Options) -> Self {
+        let mut preview = Self {
+            text,
+            options,
+            converted: String::new(),
+            error: None,
+            line: 0,
+            column: 0,
+            prompt: None,
+            added: Vec::new(),
+        };
+        preview.convert();
+        preview
+    }
+
+    fn direction(&self) -> Direction {
+        self.options.direction.unwrap_or(Direction {
+            from: Charset::Latin,
+            into: Charset::Cyrillic,
+        })
+    }
+
+    fn list(&mut self, list: List) -> &mut Vec<String> {
+        match list {
+            List::Exceptions => &mut self.options.exceptions,
+            List::Protected => &mut self.options.protected,
+            List::SkipPatterns => &mut self.options.skip_patterns,
+        }
+    }
+
+    /// Convert text with current options, keeping the previous conversion
+    /// if options are invalid
+    fn convert(&mut self) {
+        let converted = self
+            .options
+            .transliterator()
+            .and_then(|transliterator| Ok(transliterator.process(&self.text)?));
+        match converted {
+            Ok(converted) => {
+                self.converted = converted;
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error.to_string().trim_end().to_owned()),
+        }
+    }
+
+    /// Handle a key pressed in a terminal `height` lines high. Returns
+    /// `false` if the preview is closed.
+    fn key(&mut self, key: Key, height: usize) -> bool {
+        if let Some((list, mut word)) = self.prompt.take() {
+            match key {
+                Key::Enter if !word.is_empty() => {
+                    self.list(list).push(word);
+                    self.added.push(list);
+                    self.convert();
+                }
+                Key::Enter | Key::Escape => {}
+                Key::Backspace => {
+                    word.pop();
+                    self.prompt = Some((list, word));
+                }
+                Key::Char(c) => {
+                    word.push(c);
+                    self.prompt = Some((list, word));
+                }
+                _ => self.prompt = Some((list, word)),
+            }
+            return true;
+        }
+        let toggle = |flag: &mut Option<bool>| *flag = Some(!flag.unwrap_or(false));
+        let page = height.saturating_sub(2).max(1);
+        let last = self.text.lines().count().saturating_sub(1);
+        match key {
+            Key::Char('q') | Key::Escape => return false,
+            Key::Char('d') => {
+                let direction = self.direction();
+                self.options.direction = Some(Direction {
+                    from: direction.into,
+                    into: direction.from,
+                });
+            }
+            Key::Char('x') => toggle(&mut self.options.skip_digraph),
+            Key::Char('f') => toggle(&mut self.options.force_foreign),
+            Key::Char('l') => toggle(&mut self.options.force_links),
+            Key::Char('r') => toggle(&mut self.options.force_roman),
+            Key::Char('e') => self.prompt = Some((List::Exceptions, String::new())),
+            Key::Char('p') => self.prompt = Some((List::Protected, String::new())),
+            Key::Char('s') => self.prompt = Some((List::SkipPatterns, String::new())),
+            Key::Char('u') => {
+                if let Some(list) = self.added.pop() {
+                    self.list(list).pop();
+                }
+            }
+            Key::Up | Key::Char('k') => self.line = self.line.saturating_sub(1),
+            Key::Down | Key::Char('j') => self.line = (self.line + 1).min(last),
+            Key::PageUp => self.line = self.line.saturating_sub(page),
+            Key::PageDown => self.line = (self.line + page).min(last),
+            Key::Left => self.column = self.column.saturating_sub(SCROLL),
+            Key::Right => self.column += SCROLL,
+            _ => return true,
+        }
+        if matches!(key, Key::Char('d' | 'x' | 'f' | 'l' | 'r' | 'u')) {
+            self.convert();
+        }
+        true
+    }
+
+    /// Returns lines of the screen: options, source and converted text
+    /// side by side, and help, prompt or error
+    fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let on = |flag: Option<bool>| if flag.unwrap_or(false) { "on" } else { "off" };
+        let direction = self.direction();
+        let status = format!(
+            " {} │ skip exceptions {} │ force foreign {} │ force links {} │ force roman {} │ \
+             {} exceptions, {} protected, {} patterns",
+            direction,
+            on(self.options.skip_digraph),
+            on(self.options.force_foreign),
+            on(self.options.force_links),
+            on(self.options.force_roman),
+            self.options.exceptions.len(),
+            self.options.protected.len(),
+            self.options.skip_patterns.len(),
+        );
+        let mut screen = vec![format!("\x1b[7m{}\x1b[0m", cell(&status, 0, width))];
+        let left = width.saturating_sub(1) / 2;
+        let right = width.saturating_sub(1 + left);
+        let mut source = self.text.lines().skip(self.line);
+        let mut converted = self.converted.lines().skip(self.line);
+        for _ in 0..height.saturating_sub(2) {
+            screen.push(format!(
+                "{}│{}",
+                cell(source.next().unwrap_or_default(), self.column, left),
+                cell(converted.next().unwrap_or_default(), self.column, right)
+            ));
+        }
+        let footer = match (&self.prompt, &self.error) {
+            (Some((list, word)), _) => {
+                let name = match list {
+                    List::Exceptions => "exception",
+                    List::Protected => "protected word",
+                    List::SkipPatterns => "skip pattern",
+                };
+                format!(" {}: {}", name, word)
+            }
+            (None, Some(error)) => format!(" {}", error),
+            (None, None) => format!(" {}", HELP),
+        };
+        screen.push(format!("\x1b[7m{}\x1b[0m", cell(&footer, 0, width)));
+        screen
+    }
+
+    /// Returns options which can be changed in the preview in the format
+    /// of the configuration file
+    fn config(&self) -> String {
+        let direction = self.direction();
+        let list = |words: &[String]| {
+            let words = words.iter().map(|w| quote(w)).collect::<Vec<String>>();
+            format!("[{}]", words.join(", "))
+        };
+        format!(
+            "direction = \"{}\"\nskip-digraph = {}\nforce-foreign = {}\nforce-links = {}\n\
+             force-roman = {}\nexceptions = {}\nprotected = {}\nskip-patterns = {}\n",
+            direction,
+            self.options.skip_digraph.unwrap_or(false),
+            self.options.force_foreign.unwrap_or(false),
+            self.options.force_links.unwrap_or(false),
+            self.options.force_roman.unwrap_or(false),
+            list(&self.options.exceptions),
+            list(&self.options.protected),
+            list(&self.options.skip_patterns),
+        )
+    }
+}
+
+/// Terminal in raw mode showing the alternate screen, which is restored
+/// when dropped
+struct Terminal {
+    original: libc::termios,
+}
+
+impl Terminal {
+    fn open() -> io::Result<Self> {
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let original = termios;
+        unsafe { libc::cfmakeraw(&mut termios) };
+        // Reads wait for a tenth of a second, so resizing is noticed
+        termios.c_cc[libc::VMIN] = 0;
+        termios.c_cc[libc::VTIME] = 1;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut stdout = io::stdout();
+        stdout.write_all(b"\x1b[?1049h\x1b[?25l")?;
+        stdout.flush()?;
+        Ok(Self { original })
+    }
+
+    /// Returns width and height of the terminal
+    fn size(&self) -> (usize, usize) {
+        let mut size = unsafe { std::mem::zeroed::<libc::winsize>() };
+        match unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } {
+            0 if size.ws_col > 0 && size.ws_row > 0 => (size.ws_col as usize, size.ws_row as usize),
+            _ => (80, 24),
+        }
+    }
+
+    fn draw(&self, screen: &[String]) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        let mut frame = String::new();
+        for (row, line) in screen.iter().enumerate() {
+            frame.push_str(&format!("\x1b[{};1H{}", row + 1, line));
+        }
+        stdout.write_all(frame.as_bytes())?;
+        stdout.flush()
+    }
+
+    /// Returns keys pressed since the last read, if any
+    fn keys(&self) -> io::Result<Vec<Key>> {
+        let mut buffer = [0; 64];
+        let length = io::stdin().read(&mut buffer)?;
+        Ok(parse_keys(&buffer[..length]))
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x1b[?25h\x1b[?1049l");
+        let _ = stdout.flush();
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+/// Shows a file and its conversion side by side in the terminal, with
+/// options (ie. direction, digraph exceptions, skipped words) which can
+/// be changed while the conversion is updated. Changed options are
+/// printed when the preview is closed, in the format of the
+/// configuration file.
+pub struct PreviewProcessor {
+    input: PathBuf,
+    options: Options,
+}
+
+impl PreviewProcessor {
+    pub fn new(input: PathBuf, options: Options) -> Self {
+        Self { input, options }
+    }
+}
+
+impl FileProcessor for PreviewProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let text = fs::read_to_string(&self.input)?;
+        let mut preview = Preview::new(text, self.options.clone());
+        {
+            let terminal = Terminal::open()?;
+            let mut size = (0, 0);
+            let mut changed = true;
+            'preview: loop {
+                if changed || terminal.size() != size {
+                    size = terminal.size();
+                    terminal.draw(&preview.render(size.0, size.1))?;
+                }
+                let keys = terminal.keys()?;
+                changed = !keys.is_empty();
+                for key in keys {
+                    if !preview.key(key, size.1) {
+                        break 'preview;
+                    }
+                }
+            }
+        }
+        io::stdout().write_all(preview.config().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            parse_keys("q\x1b[A\x1b[6~\x1b[1;5Cž\r\x7f\x1b".as_bytes()),
+            vec![
+                Key::Char('q'),
+                Key::Up,
+                Key::PageDown,
+                Key::Char('ž'),
+                Key::Enter,
+                Key::Backspace,
+                Key::Escape,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut preview = Preview::new("Konjugacija\nXIV vek\n".to_owned(), Options::default());
+        assert_eq!(preview.converted, "Конјугација\nXIV век\n");
+        assert_eq!(preview.render(21, 4)[1], "Konjugacij│Конјугациј");
+        assert!(preview.key(Key::Char('x'), 4));
+        assert!(preview.key(Key::Char('r'), 4));
+        assert_eq!(preview.converted, "Коњугација\nXIV век\n");
+        for key in [Key::Char('p'), Key::Char('v'), Key::Backspace] {
+            preview.key(key, 4);
+        }
+        for c in "vek".chars() {
+            preview.key(Key::Char(c), 4);
+        }
+        assert_eq!(preview.render(21, 4)[3], "\x1b[7m protected word: vek \x1b[0m");
+        preview.key(Key::Enter, 4);
+        assert_eq!(preview.converted, "Коњугација\nXIV vek\n");
+        preview.key(Key::Char('s'), 4);
+        preview.key(Key::Char('('), 4);
+        preview.key(Key::Enter, 4);
+        assert_eq!(preview.converted, "Коњугација\nXIV vek\n");
+        assert!(preview.error.is_some());
+        preview.key(Key::Char('u'), 4);
+        assert_eq!(preview.error, None);
+        preview.key(Key::Char('d'), 4);
+        preview.key(Key::Down, 4);
+        assert_eq!(preview.render(21, 4)[1], "XIV vek   │XIV vek   ");
+        assert_eq!(
+            preview.config(),
+            "direction = \"cyr2lat\"\nskip-digraph = true\nforce-foreign = false\n\
+             force-links = false\nforce-roman = true\nexceptions = []\n\
+             protected = [\"vek\"]\nskip-patterns = []\n"
+        );
+        assert!(!preview.key(Key::Char('q'), 4));
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::Transliterator;
+
+/// Pseudolocalizes strings of a user interface, one per line, so
+/// rendering of the target script can be tested with realistic text.
+/// Each string is transliterated, wrapped with `[` and `]` and padded,
+/// while empty lines are left empty.
+pub struct PseudoProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    /// Length of padding, in percent of the length of strings
+    padding: usize,
+}
+
+impl PseudoProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            padding: 30,
+        })
+    }
+
+    /// Pad strings by this percentage of their length (30 by default)
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Pseudolocalize strings, one per line
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(text.len() * 2);
+        for line in text.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            if !content.is_empty() {
+                output.push_str(&self.processor.pseudolocalize(content, self.padding)?);
+            }
+            output.push_str(&line[content.len()..]);
+        }
+        Ok(output)
+    }
+}
+
+with_backup!(PseudoProcessor);
+
+impl FileProcessor for PseudoProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_pseudo() {
+        let p = PseudoProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        assert_eq!(
+            p.convert("Otvori\r\n\nZatvori {n} prozora\n").unwrap(),
+            "[Отвори ЉЊ]\r\n\n[Затвори {n} прозора ЉЊЖШЂЋ]\n"
+        );
+        let p = p.with_padding(0);
+        assert_eq!(p.convert("Otvori").unwrap(), "[Отвори]");
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Error, FileProcessor};
+use crate::ascii::AsciiMap;
+use crate::transliterate::Transliterator;
+
+pub struct RenameProcessor {
+    paths: Vec<PathBuf>,
+    processor: Transliterator,
+    ascii: bool,
+    ascii_map: AsciiMap,
+    recursive: bool,
+    dry_run: bool,
+}
+
+impl RenameProcessor {
+    pub fn new(paths: Vec<PathBuf>, processor: Transliterator) -> Self {
+        Self {
+            paths,
+            processor,
+            ascii: false,
+            ascii_map: AsciiMap::default(),
+            recursive: false,
+            dry_run: false,
+        }
+    }
+
+    /// Replace non-ASCII characters in new names (ie. for FAT file systems)
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Override ASCII replacements of letters (ie. `đ` → `d`)
+    pub fn with_ascii_map(mut self, ascii_map: AsciiMap) -> Self {
+        self.ascii_map = ascii_map;
+        self
+    }
+
+    /// Rename contents of directories too
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Only print new names without renaming anything
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    fn collect(&self, path: &Path, entries: &mut Vec<PathBuf>) -> Result<(), Error> {
+        // Directory contents are renamed before the directory itself
+        if self.recursive && fs::symlink_metadata(path)?.is_dir() {
+            let mut children = fs::read_dir(path)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<PathBuf>, _>>()?;
+            children.sort();
+            for child in children {
+                self.collect(&child, entries)?;
+            }
+        }
+        entries.push(path.to_path_buf());
+        Ok(())
+    }
+
+    fn new_name(&self, path: &Path) -> Result<Option<OsString>, Error> {
+        let name = match path.file_name() {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let mut new_name = self.processor.transliterate_os_str(name)?;
+        if self.ascii {
+            if let Some(folded) = new_name.to_str().map(|name| self.ascii_map.fold(name)) {
+                new_name = folded.into();
+            }
+        }
+        Ok(if new_name != name {
+            Some(new_name)
+        } else {
+            None
+        })
+    }
+
+    /// Returns pairs of old and new paths. Fails if a new path already
+    /// exists or multiple paths would be renamed to the same path.
+    pub fn plan(&self) -> Result<Vec<(PathBuf, PathBuf)>, Error> {
+        let mut entries = Vec::new();
+        for path in &self.paths {
+            self.collect(path, &mut entries)?;
+        }
+        let mut renames = Vec::new();
+        let mut targets = HashSet::new();
+        for path in entries {
+            if let Some(name) = self.new_name(&path)? {
+                let target = path.with_file_name(name);
+                if fs::symlink_metadata(&target).is_ok() || !targets.insert(target.clone()) {
+                    return Err(Error::Collision(target));
+                }
+                renames.push((path, target));
+            }
+        }
+        Ok(renames)
+    }
+}
+
+impl FileProcessor for RenameProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        for (path, target) in self.plan()? {
+            println!("{} -> {}", path.display(), target.display());
+            if !self.dry_run {
+                fs::rename(path, target)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+    use std::env;
+
+    #[test]
+    fn test_rename() {
+        let root = env::temp_dir().join(format!("translitrs-rename-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("Музика")).unwrap();
+        fs::write(root.join("Музика/Ђорђе.mp3"), "").unwrap();
+        fs::write(root.join("Музика/Pesma.txt"), "").unwrap();
+        let t = || Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+
+        let plan = RenameProcessor::new(vec![root.join("Музика")], t())
+            .plan()
+            .unwrap();
+        assert_eq!(plan, vec![(root.join("Музика"), root.join("Muzika"))]);
+
+        let mut p = RenameProcessor::new(vec![root.join("Музика")], t())
+            .with_recursive(true)
+            .with_ascii(true);
+        p.run().unwrap();
+        assert!(root.join("Muzika/Djordje.mp3").exists());
+        assert!(root.join("Muzika/Pesma.txt").exists());
+
+        fs::write(root.join("Мама"), "").unwrap();
+        fs::write(root.join("Mama"), "").unwrap();
+        let p = RenameProcessor::new(vec![root.join("Мама")], t());
+        assert!(matches!(p.plan(), Err(Error::Collision(_))));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use super::{Error, FileProcessor, Output};
+use crate::transliterate::{Charset, Decision, Trace, Transliterator};
+
+/// File in the working directory where decisions are remembered by default
+pub const SESSION_FILE: &str = ".translit-session";
+
+/// Characters of context shown on each side of a reviewed word
+const CONTEXT: usize = 40;
+
+/// Converts a file, asking the user to confirm or override conversions of
+/// ambiguous words: words with a digraph exception applied, words which
+/// are skipped (ie. foreign words, protected words, Roman numerals) and
+/// words which mix Latin and Cyrillic letters. Decisions are remembered
+/// in a session file as a word and its conversion separated by a tab,
+/// without punctuation around them, and are applied without asking when
+/// the same word is found again.
+pub struct ReviewProcessor {
+    input: PathBuf,
+    output: Output,
+    transliterator: Transliterator,
+    /// Transliterator converting words which `transliterator` skips
+    forced: Transliterator,
+    session: PathBuf,
+}
+
+impl ReviewProcessor {
+    /// Review conversion of `input`. Words which are skipped can be
+    /// converted with `forced` instead (ie. a transliterator which forces
+    /// foreign words, links and Roman numerals).
+    pub fn new(
+        input: PathBuf,
+        output: Option<PathBuf>,
+        transliterator: Transliterator,
+        forced: Transliterator,
+    ) -> Self {
+        Self {
+            input,
+            output: Output::new(output),
+            transliterator,
+            forced,
+            session: PathBuf::from(SESSION_FILE),
+        }
+    }
+
+    /// Remember decisions in `session` instead of `.translit-session`
+    pub fn with_session(mut self, session: PathBuf) -> Self {
+        self.session = session;
+        self
+    }
+
+    /// Returns the other conversion of a word which is reviewed, or `None`
+    /// if the word is not reviewed
+    fn alternative(&self, word: &str, trace: &Trace) -> Result<Option<String>, Error> {
+        let alternative = match trace.decision {
+            Decision::Transliterated if mixed_scripts(word) => word.to_owned(),
+            Decision::Transliterated | Decision::Link => return Ok(None),
+            Decision::Exception => self.transliterator.transliterate_word(word, false)?,
+            _ => self.forced.process_word(word)?,
+        };
+        Ok(Some(alternative).filter(|alternative| *alternative != trace.output))
+    }
+
+    /// Convert `text`, reading answers to prompts about reviewed words from
+    /// `answers`. Known `decisions` are applied without asking, and new ones
+    /// are added to them and written to `session`. Remaining words are
+    /// accepted if answers end.
+    fn review<R: BufRead, W: Write>(
+        &self,
+        text: &str,
+        decisions: &mut HashMap<String, String>,
+        answers: &mut R,
+        prompts: &mut W,
+        session: &mut dyn Write,
+    ) -> Result<String, Error> {
+        let mut output = String::with_capacity(text.len());
+        let mut accept_all = false;
+        let mut last = 0;
+        for trace in self.transliterator.trace(text)? {
+            output.push_str(&text[last..trace.span.start]);
+            last = trace.span.end;
+            let word = &text[trace.span.clone()];
+            let alternative = match self.alternative(word, &trace)? {
+                Some(alternative) => alternative,
+                None => {
+                    output.push_str(&trace.output);
+                    continue;
+                }
+            };
+            let letters = letters(word);
+            if let Some(decision) = decisions.get(letters) {
+                let start = letters.as_ptr() as usize - word.as_ptr() as usize;
+                output.push_str(&word[..start]);
+                output.push_str(decision);
+                output.push_str(&word[start + letters.len()..]);
+                continue;
+            }
+            if accept_all {
+                output.push_str(&trace.output);
+                continue;
+            }
+            self.prompt(prompts, text, &trace, &alternative)?;
+            let decision = loop {
+                write!(prompts, "  [y] accept, [n] other, [e] edit, [a] accept all: ")?;
+                prompts.flush()?;
+                match read_answer(answers)?.as_deref() {
+                    Some("" | "y") => break trace.output.clone(),
+                    Some("n") => break alternative.clone(),
+                    Some("e") => {
+                        write!(prompts, "  {} → ", word)?;
+                        prompts.flush()?;
+                        match read_answer(answers)? {
+                            Some(edited) => break edited,
+                            None => continue,
+                        }
+                    }
+                    Some("a") | None => {
+                        accept_all = true;
+                        break trace.output.clone();
+                    }
+                    Some(_) => continue,
+                }
+            };
+            if !accept_all {
+                writeln!(session, "{}\t{}", letters, self::letters(&decision))?;
+                decisions.insert(letters.to_owned(), self::letters(&decision).to_owned());
+            }
+            output.push_str(&decision);
+        }
+        output.push_str(&text[last..]);
+        Ok(output)
+    }
+
+    /// Show line of a reviewed word with the word in brackets
+    fn prompt<W: Write>(
+        &self,
+        prompts: &mut W,
+        text: &str,
+        trace: &Trace,
+        alternative: &str,
+    ) -> io::Result<()> {
+        let start = trace.span.start;
+        let end = trace.span.end;
+        let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[end..].find('\n').map_or(text.len(), |i| end + i);
+        let before = &text[line_start..start];
+        let before = match before.char_indices().rev().nth(CONTEXT - 1) {
+            Some((i, _)) => &before[i..],
+            None => before,
+        };
+        let after = &text[end..line_end];
+        let after = match after.char_indices().nth(CONTEXT) {
+            Some((i, _)) => &after[..i],
+            None => after,
+        };
+        writeln!(
+            prompts,
+            "{}:{}: {}[{}]{}",
+            self.input.display(),
+            text[..start].matches('\n').count() + 1,
+            before,
+            &text[start..end],
+            after.trim_end_matches('\r'),
+        )?;
+        writeln!(
+            prompts,
+            "  {} → {} ({}), or {}",
+            &text[start..end],
+            trace.output,
+            reason(&text[start..end], &trace.decision),
+            alternative
+        )
+    }
+}
+
+/// Returns whether `word` has both Latin and Cyrillic letters
+fn mixed_scripts(word: &str) -> bool {
+    let mut cyrillic = false;
+    let mut latin = false;
+    for c in word.chars() {
+        match Charset::of(c) {
+            Some(Charset::Cyrillic) => cyrillic = true,
+            Some(_) => latin = true,
+            None => {}
+        }
+    }
+    cyrillic && latin
+}
+
+/// Returns `word` without punctuation around it
+fn letters(word: &str) -> &str {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// Returns why `word` is reviewed
+fn reason(word: &str, decision: &Decision) -> String {
+    match decision {
+        _ if mixed_scripts(word) => "mixed scripts".to_owned(),
+        Decision::Transliterated => "mixed scripts".to_owned(),
+        Decision::Exception => "digraph exception".to_owned(),
+        Decision::Foreign => "foreign word".to_owned(),
+        Decision::Link => "link".to_owned(),
+        Decision::Roman => "Roman numeral".to_owned(),
+        Decision::Language(code) => format!("foreign language {}", code),
+        Decision::Protected => "protected word".to_owned(),
+        Decision::Model => "model name".to_owned(),
+        Decision::Letter => "standalone letter".to_owned(),
+    }
+}
+
+/// Returns a line of `answers` without the line break, or `None` at the end
+fn read_answer<R: BufRead>(answers: &mut R) -> io::Result<Option<String>> {
+    let mut answer = String::new();
+    if answers.read_line(&mut answer)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(answer.trim_end_matches(['\n', '\r']).to_owned()))
+}
+
+/// Read decisions remembered in a session file, if it exists
+fn load_session(path: &Path) -> Result<HashMap<String, String>, Error> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(word, decision)| (word.to_owned(), decision.to_owned()))
+        .collect())
+}
+
+with_backup!(ReviewProcessor);
+
+impl FileProcessor for ReviewProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let text = fs::read_to_string(&self.input)?;
+        let mut decisions = load_session(&self.session)?;
+        let mut session = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.session)?;
+        let stdin = io::stdin();
+        let output =
+            self.review(&text, &mut decisions, &mut stdin.lock(), &mut io::stderr(), &mut session)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_protected(["Nikon"]);
+        let forced = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, true, true)
+            .with_force_roman(true);
+        let r = ReviewProcessor::new(PathBuf::from("a.txt"), None, t, forced);
+        let text = "Konjugacija, Nikon i XIV\nKonjugacija Кућa i Nikon.\n";
+        let mut decisions = HashMap::new();
+        let mut prompts = Vec::new();
+        let mut session = Vec::new();
+        let output = r
+            .review(
+                text,
+                &mut decisions,
+                &mut "n\nx\n\ne\nIV\n".as_bytes(),
+                &mut prompts,
+                &mut session,
+            )
+            .unwrap();
+        // Remaining words are accepted when answers end
+        assert_eq!(output, "Коњугација, Nikon и IV\nКоњугација Кућa и Nikon.\n");
+        assert_eq!(
+            String::from_utf8(session).unwrap(),
+            "Konjugacija\tКоњугација\nNikon\tNikon\nXIV\tIV\n"
+        );
+        let prompts = String::from_utf8(prompts).unwrap();
+        assert!(prompts.starts_with("a.txt:1: [Konjugacija,] Nikon i XIV\n"));
+        assert!(prompts.contains("  XIV → XIV (Roman numeral), or XИВ\n"));
+        assert!(prompts.contains("a.txt:2: Konjugacija [Кућa] i Nikon.\n"));
+        assert!(prompts.contains("  Кућa → Кућa (mixed scripts), or Кућа\n"));
+
+        // Remembered decisions are applied without asking
+        let mut prompts = Vec::new();
+        let output = r
+            .review(
+                "(XIV) Nikon.",
+                &mut decisions,
+                &mut "".as_bytes(),
+                &mut prompts,
+                &mut Vec::new(),
+            )
+            .unwrap();
+        assert_eq!(output, "(IV) Nikon.");
+        assert!(prompts.is_empty());
+
+        assert!(mixed_scripts("Кyћa"));
+        assert!(!mixed_scripts("Кућа"));
+    }
+}
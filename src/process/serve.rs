@@ -0,0 +1,206 @@
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::thread;
+
+use serde::Deserialize;
+
+use super::{Error, FileProcessor};
+use crate::json;
+use crate::options::Options;
+
+/// Body of a conversion request, ie.
+/// `{"text": "Zdravo", "options": {"direction": "lat2cyr"}}`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConvertRequest {
+    text: String,
+    #[serde(default)]
+    options: Options,
+}
+
+/// HTTP server which transliterates text for other services.
+///
+/// Endpoints:
+/// * `POST /convert` - convert text with options from the request body,
+///   which take precedence over options of the server, except for limits
+///   of input and word length, which can only be lowered
+/// * `GET /health` - report that the server is running
+#[derive(Clone)]
+pub struct ServeProcessor {
+    address: String,
+    defaults: Options,
+    workers: usize,
+    max_body: usize,
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, format!(r#"{{"error":{}}}"#, json::string(message)))
+}
+
+/// Returns limit of a request, which can't exceed `server` limit
+fn clamp(limit: Option<usize>, server: Option<usize>) -> Option<usize> {
+    match (limit, server) {
+        (Some(limit), Some(server)) => Some(limit.min(server)),
+        _ => limit,
+    }
+}
+
+impl ServeProcessor {
+    /// Listen on `address` (ie. `127.0.0.1:8080`) and convert text with
+    /// `defaults` where a request doesn't set an option
+    pub fn new(address: String, defaults: Options) -> Self {
+        Self {
+            address,
+            defaults,
+            workers: 4,
+            max_body: 1 << 20,
+        }
+    }
+
+    /// Number of requests handled at the same time (default: 4)
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Largest accepted request body in bytes (default: 1 MiB)
+    pub fn with_max_body(mut self, max_body: usize) -> Self {
+        self.max_body = max_body;
+        self
+    }
+
+    /// Returns status code and JSON body of the response to a request
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> (u16, String) {
+        match (method, path) {
+            ("GET", "/health") => (200, r#"{"status":"ok"}"#.to_owned()),
+            ("POST", "/convert") => {
+                if body.len() > self.max_body {
+                    return error(413, "request body is too large");
+                }
+                let request: ConvertRequest = match serde_json::from_slice(body) {
+                    Ok(request) => request,
+                    Err(e) => return error(400, &e.to_string()),
+                };
+                let mut options = request.options.merge(self.defaults.clone());
+                options.max_input = clamp(options.max_input, self.defaults.max_input);
+                options.max_word = clamp(options.max_word, self.defaults.max_word);
+                let result = options
+                    .transliterator()
+                    .and_then(|t| Ok(t.process(request.text)?));
+                match result {
+                    Ok(text) => (200, format!(r#"{{"text":{}}}"#, json::string(&text))),
+                    Err(e) => error(400, e.to_string().trim_end()),
+                }
+            }
+            (_, "/health" | "/convert") => error(405, "method not allowed"),
+            _ => error(404, "not found"),
+        }
+    }
+
+    /// Read body of a request, up to one byte over the limit
+    fn body(&self, request: &mut tiny_http::Request) -> io::Result<Vec<u8>> {
+        let mut body = Vec::new();
+        if request
+            .body_length()
+            .map_or(true, |length| length <= self.max_body)
+        {
+            request
+                .as_reader()
+                .take(self.max_body as u64 + 1)
+                .read_to_end(&mut body)?;
+        } else {
+            body.resize(self.max_body + 1, 0);
+        }
+        Ok(body)
+    }
+}
+
+impl FileProcessor for ServeProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let server = tiny_http::Server::http(&self.address)
+            .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::Other, e)))?;
+        let server = Arc::new(server);
+        let processor = Arc::new(self.clone());
+        eprintln!("Listening on http://{}", self.address);
+        // Each worker handles one request at a time
+        let workers = (0..self.workers)
+            .map(|_| {
+                let server = Arc::clone(&server);
+                let processor = Arc::clone(&processor);
+                thread::spawn(move || {
+                    for mut request in server.incoming_requests() {
+                        let (status, body) = match processor.body(&mut request) {
+                            Ok(body) => processor.handle(
+                                request.method().as_str(),
+                                request.url().split('?').next().unwrap_or_default(),
+                                &body,
+                            ),
+                            Err(e) => error(400, &e.to_string()),
+                        };
+                        let header = tiny_http::Header::from_bytes(
+                            &b"Content-Type"[..],
+                            &b"application/json; charset=utf-8"[..],
+                        )
+                        .expect("valid header");
+                        let response = tiny_http::Response::from_string(body)
+                            .with_status_code(status)
+                            .with_header(header);
+                        let _ = request.respond(response);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            let _ = worker.join();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle() {
+        let s =
+            ServeProcessor::new("127.0.0.1:0".to_owned(), Options::default()).with_max_body(128);
+        assert_eq!(s.handle("GET", "/health", b""), (200, r#"{"status":"ok"}"#.to_owned()));
+        assert_eq!(
+            s.handle("POST", "/convert", br#"{"text": "Dobar \"dan\""}"#),
+            (200, r#"{"text":"Добар \"дан\""}"#.to_owned())
+        );
+        assert_eq!(
+            s.handle(
+                "POST",
+                "/convert",
+                r#"{"text": "Добар дан", "options": {"direction": "cyr2lat"}}"#.as_bytes()
+            ),
+            (200, r#"{"text":"Dobar dan"}"#.to_owned())
+        );
+        for (method, path, body, status) in [
+            ("POST", "/convert", &b"{}"[..], 400),
+            ("POST", "/convert", br#"{"text": "", "options": {"language": "xx"}}"#, 400),
+            ("POST", "/convert", &[b' '; 129][..], 413),
+            ("GET", "/convert", b"", 405),
+            ("GET", "/", b"", 404),
+        ] {
+            assert_eq!(s.handle(method, path, body).0, status);
+        }
+
+        // Requests can't raise limits of the server
+        let defaults = Options {
+            max_word: Some(8),
+            ..Default::default()
+        };
+        let s = ServeProcessor::new("127.0.0.1:0".to_owned(), defaults);
+        for (body, status) in [
+            (r#"{"text": "Ljubičica"}"#, 400),
+            (r#"{"text": "Ljubičica", "options": {"max-word": 1000}}"#, 400),
+            (r#"{"text": "Ljubav", "options": {"max-word": 1000}}"#, 200),
+            (r#"{"text": "Ljubav", "options": {"max-word": 4}}"#, 400),
+        ] {
+            assert_eq!(s.handle("POST", "/convert", body.as_bytes()).0, status);
+        }
+    }
+}
@@ -0,0 +1,535 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use lol_html::{element, rewrite_str, RewriteStrSettings};
+use regex::{Captures, Regex};
+
+use super::html::{convert_html, rewriting_error};
+use super::{atomic, Error, FileProcessor, YamlProcessor};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    /// Fenced code block, code span, Hugo or Zola shortcode (ie.
+    /// `{{< figure src="a.jpg" >}}`, `{% quote() %}`), link reference
+    /// definition, inline link or image, whose label and title are
+    /// converted, autolink or raw HTML tag
+    static ref MARKDOWN: Regex = Regex::new(concat!(
+        r"(?m:^ {0,3}```[\s\S]*?(?:^ {0,3}```|\z)|^ {0,3}~~~[\s\S]*?(?:^ {0,3}~~~|\z))",
+        r"|``(?:[^`]|`[^`])+?``|`[^`\n]+`",
+        r"|\{\{<[\s\S]*?>\}\}|\{\{%[\s\S]*?%\}\}|\{\{[\s\S]*?\}\}|\{%[\s\S]*?%\}",
+        r"|(?m:^ {0,3}\[(?P<reference>[^\[\]\n]+)\]:[ \t]*(?P<definition>\S+))",
+        r#"|\[(?P<label>[^\[\]\n]*)\]\((?P<url>[^()\s]*)(?:(?P<space>\s+)"(?P<title>[^"\n]*)")?\)"#,
+        r"|<[A-Za-z][\w+.-]*:[^<>\s]*>|<[^<>@\s]+@[^<>@\s]+>",
+        r"|</?[A-Za-z][^<>]*>|<!--[\s\S]*?-->"
+    ))
+    .unwrap();
+    /// Link attribute of a raw HTML tag in Markdown
+    static ref HREF: Regex = Regex::new(r#"(\shref\s*=\s*)(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    /// Table header of TOML front matter (ie. `[extra]`)
+    static ref TABLE: Regex = Regex::new(r"^\s*\[\[?([^\[\]]+)\]\]?\s*(?:#.*)?$").unwrap();
+    /// Key-value pair of TOML front matter
+    static ref ENTRY: Regex = Regex::new(r#"^\s*([\w.\- "']+?)\s*=\s*"#).unwrap();
+    /// Basic or literal string of TOML front matter
+    static ref STRING: Regex = Regex::new(r#""((?:[^"\\\n]|\\.)*)"|'([^'\n]*)'"#).unwrap();
+    /// Escape sequence in a basic string
+    static ref ESCAPE: Regex = Regex::new(r"\\(?:u[0-9a-fA-F]{4}|U[0-9a-fA-F]{8}|.)").unwrap();
+}
+
+/// Front matter keys whose values are converted by default
+const KEYS: &[&str] = &["title", "description"];
+
+/// Extensions of Markdown files
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// Extensions of HTML files
+const HTML_EXTENSIONS: &[&str] = &["html", "htm"];
+
+fn hidden(path: &Path) -> bool {
+    path.file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with('.'))
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .map_or(false, |e| extensions.contains(&e.to_lowercase().as_str()))
+}
+
+/// Returns URL path `base` with a trailing slash
+fn directory(base: &str) -> String {
+    format!("{}/", base.trim_end_matches('/'))
+}
+
+/// Generates a parallel tree of a static site in the other script, so
+/// bilingual sites can be published from a single source. Text of HTML
+/// and Markdown files is converted, and links to pages of the site are
+/// rewritten to point into the parallel tree (ie. `/vesti/` into
+/// `/lat/vesti/`). Hugo and Zola shortcodes are left unchanged, and only
+/// values of `title` and `description` keys of front matter are
+/// converted. Other files are copied unchanged, and hidden files are
+/// ignored.
+pub struct SiteProcessor {
+    source: PathBuf,
+    output: PathBuf,
+    processor: Transliterator,
+    /// URL paths under which the source and output trees are published
+    base: (String, String),
+    /// Paths of converted keys of TOML front matter
+    keys: Vec<Vec<String>>,
+    /// Converter of YAML front matter
+    yaml: YamlProcessor,
+}
+
+impl SiteProcessor {
+    /// Convert site in directory `source` into `output`. Output tree is
+    /// published under the name of its directory (ie. `/lat/`), next to
+    /// the source tree published at `/`. Fails if `output` is inside
+    /// `source`.
+    pub fn new(source: PathBuf, output: PathBuf, processor: Transliterator) -> Result<Self, Error> {
+        fs::create_dir_all(&output)?;
+        let (source, output) = (source.canonicalize()?, output.canonicalize()?);
+        if output.starts_with(&source) {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "output directory is inside source directory",
+            )));
+        }
+        let name = output.file_name().unwrap_or_default().to_string_lossy();
+        let base = ("/".to_owned(), directory(&format!("/{}", name)));
+        let keys = KEYS.iter().map(|&k| k.to_owned()).collect::<Vec<String>>();
+        let yaml = YamlProcessor::new(None, None, processor.clone())?
+            .with_front_matter(true)
+            .with_keys(&keys);
+        Ok(Self {
+            source,
+            output,
+            processor,
+            base,
+            keys: Vec::new(),
+            yaml,
+        }
+        .with_keys(&keys))
+    }
+
+    /// Convert values of these front matter keys (ie. `title` or
+    /// `extra.*`) instead of `title` and `description`
+    pub fn with_keys(mut self, keys: &[String]) -> Self {
+        if !keys.is_empty() {
+            self.keys = keys
+                .iter()
+                .map(|k| k.split('.').map(str::to_owned).collect())
+                .collect();
+            self.yaml = self.yaml.with_keys(keys);
+        }
+        self
+    }
+
+    /// Publish the output tree under URL path `base` (ie. `/sr-Latn/`),
+    /// or under `output` with the source tree under `source` if it is
+    /// `source=output`
+    pub fn with_base(mut self, base: &str) -> Self {
+        self.base = match base.split_once('=') {
+            Some((source, output)) => (directory(source), directory(output)),
+            None => ("/".to_owned(), directory(base)),
+        };
+        self
+    }
+
+    /// Returns link into the output tree which replaces `url`, if it is a
+    /// link into the source tree. Relative links are left unchanged, as
+    /// both trees have the same structure.
+    fn link(&self, url: &str) -> Option<String> {
+        let (source, output) = (&self.base.0, &self.base.1);
+        // Links into the output tree are kept, ie. to switch scripts
+        if url.starts_with("//") || !source.starts_with(output.as_str()) && url.starts_with(output)
+        {
+            return None;
+        }
+        url.strip_prefix(source.as_str())
+            .map(|path| format!("{}{}", output, path))
+    }
+
+    /// Transliterate text of an HTML document and rewrite its links
+    pub fn convert_html(&self, html: &str) -> Result<String, Error> {
+        let html = convert_html(&self.processor, html)?;
+        let settings = RewriteStrSettings {
+            element_content_handlers: vec![element!("a[href], area[href]", |element| {
+                if let Some(link) = element.get_attribute("href").and_then(|h| self.link(&h)) {
+                    element.set_attribute("href", &link)?;
+                }
+                Ok(())
+            })],
+            ..RewriteStrSettings::new()
+        };
+        rewrite_str(&html, settings).map_err(rewriting_error)
+    }
+
+    /// Transliterate text of a Markdown document and rewrite its links,
+    /// leaving code, shortcodes, URLs and raw HTML tags unchanged. Only
+    /// values of selected keys of YAML or TOML front matter are converted.
+    pub fn convert_markdown(&self, text: &str) -> Result<String, Error> {
+        let start = front_matter(text);
+        let mut output = String::with_capacity(text.len());
+        match text.starts_with("+++") {
+            true => output.push_str(&self.convert_toml(&text[..start])?),
+            false => output.push_str(&self.yaml.convert(&text[..start])?),
+        }
+        output.push_str(&self.markdown(&text[start..])?);
+        Ok(output)
+    }
+
+    /// Returns whether values with key `path` are converted
+    fn selected(&self, path: &[String]) -> bool {
+        self.keys.iter().any(|keys| {
+            keys.len() <= path.len()
+                && keys
+                    .iter()
+                    .zip(path)
+                    .all(|(key, name)| key == "*" || key == name)
+        })
+    }
+
+    /// Transliterate contents of a basic or literal string, keeping
+    /// escape sequences of basic strings intact
+    fn string(&self, text: &str, basic: bool) -> Result<String, transliterate::Error> {
+        if !basic {
+            return self.processor.process(text);
+        }
+        let mut output = String::with_capacity(text.len());
+        let mut last = 0;
+        for escape in ESCAPE.find_iter(text) {
+            output.push_str(&self.processor.process(&text[last..escape.start()])?);
+            output.push_str(escape.as_str());
+            last = escape.end();
+        }
+        output.push_str(&self.processor.process(&text[last..])?);
+        Ok(output)
+    }
+
+    /// Transliterate string values of selected keys of TOML front matter,
+    /// including strings in arrays. Multi-line strings are converted
+    /// until their closing delimiter.
+    fn convert_toml(&self, toml: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(toml.len());
+        let mut table: Vec<String> = Vec::new();
+        // Delimiter of a multi-line string which is not closed yet, and
+        // whether it is converted
+        let mut multiline: Option<(&str, bool)> = None;
+        let unquote = |key: &str| {
+            key.trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .to_owned()
+        };
+        for line in toml.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let (mut value, mut convert, mut start) = ("", false, 0);
+            if let Some((delimiter, converted)) = multiline {
+                let end = content.find(delimiter).unwrap_or(content.len());
+                match converted {
+                    true => output.push_str(&self.string(&content[..end], delimiter == "\"\"\"")?),
+                    false => output.push_str(&content[..end]),
+                }
+                if end < content.len() {
+                    multiline = None;
+                }
+                output.push_str(&line[end..]);
+                continue;
+            }
+            if let Some(header) = TABLE.captures(content) {
+                table = header[1].split('.').map(unquote).collect();
+            } else if let Some(entry) = ENTRY.captures(content) {
+                let mut path = table.clone();
+                path.extend(entry[1].split('.').map(unquote));
+                convert = self.selected(&path);
+                start = entry.get(0).unwrap().end();
+                value = &content[start..];
+            }
+            output.push_str(&content[..start]);
+            let delimiter = ["\"\"\"", "\'\'\'"].iter().find(|d| value.starts_with(*d));
+            if let Some(delimiter) = delimiter {
+                let rest = &value[3..];
+                let end = rest.find(delimiter).unwrap_or(rest.len());
+                output.push_str(delimiter);
+                match convert {
+                    true => output.push_str(&self.string(&rest[..end], *delimiter == "\"\"\"")?),
+                    false => output.push_str(&rest[..end]),
+                }
+                if end == rest.len() {
+                    multiline = Some((delimiter, convert));
+                }
+                output.push_str(&rest[end..]);
+            } else if convert && value.starts_with(['"', '\'', '[']) {
+                let mut last = 0;
+                for string in STRING.captures_iter(value) {
+                    let (contents, basic) = match string.get(1) {
+                        Some(contents) => (contents, true),
+                        None => (string.get(2).unwrap(), false),
+                    };
+                    output.push_str(&value[last..contents.start()]);
+                    output.push_str(&self.string(contents.as_str(), basic)?);
+                    last = contents.end();
+                }
+                output.push_str(&value[last..]);
+            } else {
+                output.push_str(&content[start..]);
+            }
+            output.push_str(&line[content.len()..]);
+        }
+        Ok(output)
+    }
+
+    fn markdown(&self, text: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(text.len());
+        let mut last = 0;
+        for token in MARKDOWN.captures_iter(text) {
+            let whole = token.get(0).unwrap();
+            output.push_str(&self.processor.process(&text[last..whole.start()])?);
+            if let (Some(reference), Some(definition)) =
+                (token.name("reference"), token.name("definition"))
+            {
+                // Labels are converted everywhere, so references still match
+                output.push_str(&text[whole.start()..reference.start()]);
+                output.push_str(&self.processor.process(reference.as_str())?);
+                output.push_str(&text[reference.end()..definition.start()]);
+                output.push_str(
+                    &self
+                        .link(definition.as_str())
+                        .unwrap_or_else(|| definition.as_str().to_owned()),
+                );
+            } else if let (Some(label), Some(url)) = (token.name("label"), token.name("url")) {
+                output.push_str(&text[whole.start()..label.start()]);
+                output.push_str(&self.markdown(label.as_str())?);
+                output.push_str("](");
+                output.push_str(
+                    &self
+                        .link(url.as_str())
+                        .unwrap_or_else(|| url.as_str().to_owned()),
+                );
+                if let (Some(space), Some(title)) = (token.name("space"), token.name("title")) {
+                    output.push_str(space.as_str());
+                    output.push('"');
+                    output.push_str(&self.processor.process(title.as_str())?);
+                    output.push('"');
+                }
+                output.push(')');
+            } else {
+                let tag = HREF.replace_all(whole.as_str(), |href: &Captures| {
+                    match href.get(2).or_else(|| href.get(3)) {
+                        Some(url) => match self.link(url.as_str()) {
+                            Some(link) => {
+                                let quote = if href.get(2).is_some() { '"' } else { '\'' };
+                                format!("{}{}{}{}", &href[1], quote, link, quote)
+                            }
+                            None => href[0].to_owned(),
+                        },
+                        None => href[0].to_owned(),
+                    }
+                });
+                output.push_str(&tag);
+            }
+            last = whole.end();
+        }
+        output.push_str(&self.processor.process(&text[last..])?);
+        Ok(output)
+    }
+
+    /// Convert file at `path` into the output tree. Returns path of the
+    /// converted copy.
+    fn update(&self, path: &Path) -> Result<PathBuf, Error> {
+        let target = self
+            .output
+            .join(path.strip_prefix(&self.source).unwrap_or(path));
+        let contents = fs::read(path)?;
+        let markdown = has_extension(path, MARKDOWN_EXTENSIONS);
+        let output = match std::str::from_utf8(&contents) {
+            Ok(text) if markdown => self.convert_markdown(text)?.into_bytes(),
+            Ok(text) if has_extension(path, HTML_EXTENSIONS) => {
+                self.convert_html(text)?.into_bytes()
+            }
+            _ => contents,
+        };
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic::write(&target, &output, false)?;
+        Ok(target)
+    }
+
+    /// Convert all files of the site, returning paths of converted copies
+    pub fn build(&self) -> Result<Vec<PathBuf>, Error> {
+        let mut written = Vec::new();
+        let mut directories = vec![self.source.clone()];
+        while let Some(directory) = directories.pop() {
+            let mut children = fs::read_dir(&directory)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<PathBuf>, io::Error>>()?;
+            children.sort();
+            for child in children {
+                if hidden(&child) {
+                    continue;
+                }
+                if child.is_dir() {
+                    directories.push(child);
+                } else {
+                    written.push(self.update(&child)?);
+                }
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// Returns length of YAML (`---`) or TOML (`+++`) front matter at the
+/// start of a Markdown document
+fn front_matter(text: &str) -> usize {
+    let mut lines = text.split_inclusive('\n');
+    let delimiter = match lines.next().map(str::trim_end) {
+        Some(delimiter @ ("---" | "+++")) => delimiter,
+        _ => return 0,
+    };
+    let mut position = delimiter.len();
+    position += text[position..].find('\n').map_or(0, |n| n + 1);
+    for line in lines {
+        position += line.len();
+        if line.trim_end() == delimiter {
+            return position;
+        }
+    }
+    0
+}
+
+impl FileProcessor for SiteProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut output = io::stdout();
+        for target in self.build()? {
+            writeln!(output, "{}", target.display())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+    use std::env;
+
+    #[test]
+    fn test_build() {
+        let root = env::temp_dir().join(format!("translitrs-site-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/vesti")).unwrap();
+        fs::write(
+            root.join("src/index.html"),
+            concat!(
+                "<html><body><a href=\"/vesti/prva.html\">Prva vest</a> ",
+                "<a href=\"vesti/\">Sve vesti</a> <a href=\"/cir/\">Ćirilica</a> ",
+                "<a href=\"https://primer.rs/\">Primer</a></body></html>"
+            ),
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/vesti/prva.md"),
+            concat!(
+                "---\ntitle: Prva vest\n---\n",
+                "# Dobar dan\n\n",
+                "Vidi [početnu](/index.html \"Početna strana\") i [arhivu][arhiva].\n",
+                "Pokreni `ljubav --sve` ili <a href='/vesti/'>vesti</a>.\n\n",
+                "```sh\necho ljubav\n```\n\n",
+                "[arhiva]: /arhiva/\n"
+            ),
+        )
+        .unwrap();
+        fs::write(root.join("src/stil.css"), "body { font: serif; }").unwrap();
+        fs::write(root.join("src/.draft.md"), "Nacrt").unwrap();
+
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let site = SiteProcessor::new(root.join("src"), root.join("out/cir"), t).unwrap();
+        assert_eq!(site.build().unwrap().len(), 3);
+        assert_eq!(
+            fs::read_to_string(root.join("out/cir/index.html")).unwrap(),
+            concat!(
+                "<html><body><a href=\"/cir/vesti/prva.html\">Прва вест</a> ",
+                "<a href=\"vesti/\">Све вести</a> <a href=\"/cir/\">Ћирилица</a> ",
+                "<a href=\"https://primer.rs/\">Пример</a></body></html>"
+            )
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("out/cir/vesti/prva.md")).unwrap(),
+            concat!(
+                "---\ntitle: Прва вест\n---\n",
+                "# Добар дан\n\n",
+                "Види [почетну](/cir/index.html \"Почетна страна\") и [архиву][архива].\n",
+                "Покрени `ljubav --sve` или <a href='/cir/vesti/'>вести</a>.\n\n",
+                "```sh\necho ljubav\n```\n\n",
+                "[архива]: /cir/arhiva/\n"
+            )
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("out/cir/stil.css")).unwrap(),
+            "body { font: serif; }"
+        );
+        assert!(!root.join("out/cir/.draft.md").exists());
+
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let site = SiteProcessor::new(root.join("src"), root.join("out/cir"), t)
+            .unwrap()
+            .with_base("/sr-Latn=/sr-Cyrl");
+        assert_eq!(site.link("/sr-Latn/vesti/"), Some("/sr-Cyrl/vesti/".to_owned()));
+        assert_eq!(site.link("/sr-Cyrl/vesti/"), None);
+        assert_eq!(site.link("/slike/a.png"), None);
+
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        assert!(SiteProcessor::new(root.join("src"), root.join("src/out"), t).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_markdown() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let site = SiteProcessor {
+            source: PathBuf::new(),
+            output: PathBuf::new(),
+            processor: t.clone(),
+            base: ("/".to_owned(), "/cir/".to_owned()),
+            keys: Vec::new(),
+            yaml: YamlProcessor::new(None, None, t)
+                .unwrap()
+                .with_front_matter(true),
+        }
+        .with_keys(&[
+            "title".to_owned(),
+            "description".to_owned(),
+            "extra.*".to_owned(),
+        ]);
+        let yaml = concat!(
+            "---\ntitle: \"Dobar dan\"\nslug: dobar-dan\ndescription: Prvi tekst\n---\n",
+            "Slika {{< figure src=\"/slike/reka.jpg\" title=\"Reka\" >}} i ",
+            "{{% napomena %}}Važno je{{% /napomena %}}\n"
+        );
+        let expected = concat!(
+            "---\ntitle: \"Добар дан\"\nslug: dobar-dan\ndescription: Први текст\n---\n",
+            "Слика {{< figure src=\"/slike/reka.jpg\" title=\"Reka\" >}} и ",
+            "{{% napomena %}}Важно је{{% /napomena %}}\n"
+        );
+        assert_eq!(site.convert_markdown(yaml).unwrap(), expected);
+        let toml = concat!(
+            "+++\ntitle = \"Dobar\\tdan\" # naslov\ntemplate = 'strana.html'\n",
+            "description = \"\"\"\nPrvi\ntekst\"\"\"\n",
+            "[extra]\nautori = [\"Ana\", 'Marko']\n[taxonomies]\ntags = [\"vesti\"]\n+++\n",
+            "{{ youtube(id=\"abc\", naslov=\"Video\") }}\n",
+            "{% citat(autor=\"Ana\") %}\nLepa reč\n{% end %}\n"
+        );
+        let expected = concat!(
+            "+++\ntitle = \"Добар\\tдан\" # naslov\ntemplate = 'strana.html'\n",
+            "description = \"\"\"\nПрви\nтекст\"\"\"\n",
+            "[extra]\nautori = [\"Ана\", 'Марко']\n[taxonomies]\ntags = [\"vesti\"]\n+++\n",
+            "{{ youtube(id=\"abc\", naslov=\"Video\") }}\n",
+            "{% citat(autor=\"Ana\") %}\nЛепа реч\n{% end %}\n"
+        );
+        assert_eq!(site.convert_markdown(toml).unwrap(), expected);
+    }
+}
@@ -0,0 +1,558 @@
+use std::io::Read;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    /// Code spans in comments (ie. `Vec<String>`)
+    static ref COMMENT_KEEP: Regex = Regex::new(r"`[^`\n]*`").unwrap();
+    /// Escape sequences, interpolations and format specifiers in strings
+    static ref STRING_KEEP: Regex = Regex::new(concat!(
+        r"\\(?:u\{[0-9a-fA-F]*\}|u[0-9a-fA-F]{4}|x[0-9a-fA-F]{2}|[0-7]{1,3}|(?s:.))",
+        r"|\{[^{}]*\}",
+        r"|%[-+ #0]*[0-9]*(?:\.[0-9]+)?[hlLqjzt]*[diouxXeEfFgGaAcspn%]"
+    ))
+    .unwrap();
+}
+
+/// Programming languages whose comments and string literals can be converted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Syntax {
+    Rust,
+    /// C and languages with similar syntax (C++, C#, Java, Go, Kotlin, Swift)
+    C,
+    Python,
+    /// JavaScript and TypeScript
+    JavaScript,
+}
+
+impl FromStr for Syntax {
+    type Err = ();
+
+    /// Parse name of a language or extension of its source files
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rust" | "rs" => Ok(Syntax::Rust),
+            "c" | "h" | "cpp" | "cc" | "cxx" | "hpp" | "c++" | "cs" | "csharp" | "java" | "go"
+            | "kt" | "kotlin" | "swift" => Ok(Syntax::C),
+            "python" | "py" | "pyi" => Ok(Syntax::Python),
+            "javascript" | "js" | "mjs" | "cjs" | "jsx" | "typescript" | "ts" | "tsx" => {
+                Ok(Syntax::JavaScript)
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+impl Syntax {
+    /// Names of supported languages
+    pub const NAMES: &'static [&'static str] = &["rust", "c", "python", "javascript"];
+}
+
+/// Kind of a source file part
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Code,
+    Comment,
+    /// Contents of a string literal, without its quotes and prefix
+    Text,
+}
+
+/// Parts of a source file, in order
+struct Tokens {
+    tokens: Vec<(Kind, Range<usize>)>,
+    /// Start of code which follows the last token
+    code: usize,
+}
+
+impl Tokens {
+    fn push(&mut self, kind: Kind, range: Range<usize>) {
+        if self.code < range.start {
+            self.tokens.push((Kind::Code, self.code..range.start));
+        }
+        self.code = range.end;
+        self.tokens.push((kind, range));
+    }
+}
+
+fn is_ident(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80
+}
+
+/// Returns index of the closing `quote` of a literal whose contents
+/// start at `start`, or length of `source` if it is not closed
+fn closing(source: &[u8], start: usize, quote: &[u8], escapes: bool) -> usize {
+    let mut i = start;
+    while i < source.len() {
+        if escapes && source[i] == b'\\' {
+            i += 2;
+        } else if source[i..].starts_with(quote) {
+            return i;
+        } else {
+            i += 1;
+        }
+    }
+    source.len()
+}
+
+/// String literal, as (contents, length of closing quote, converted)
+type Literal = (Range<usize>, usize, bool);
+
+/// Functions and macros whose string arguments name modules or files
+const LOADERS: &[&str] = &[
+    "require",
+    "import",
+    "import_module",
+    "__import__",
+    "include",
+    "include_str",
+    "include_bytes",
+];
+
+/// Returns whether only spaces precede `start` on its line
+fn line_start(source: &[u8], start: usize) -> bool {
+    source[..start]
+        .iter()
+        .rev()
+        .take_while(|&&c| c != b'\n')
+        .all(|&c| c == b' ' || c == b'\t')
+}
+
+/// Returns the identifier at the end of `code`
+fn last_ident(code: &str) -> &str {
+    let start = code
+        .bytes()
+        .rposition(|c| !is_ident(c))
+        .map_or(0, |n| n + 1);
+    &code[start..]
+}
+
+impl Syntax {
+    fn line_comment(&self) -> &'static [u8] {
+        match self {
+            Syntax::Python => b"#",
+            _ => b"//",
+        }
+    }
+
+    /// Returns string literal with `prefix` which starts at `start`
+    fn literal(&self, source: &[u8], start: usize, prefix: &[u8]) -> Option<Literal> {
+        let prefix = prefix.to_ascii_lowercase();
+        let mut i = start + prefix.len();
+        match self {
+            Syntax::Rust => {
+                if !matches!(&prefix[..], b"" | b"b" | b"c" | b"r" | b"br" | b"cr") {
+                    return None;
+                }
+                let raw = prefix.ends_with(b"r");
+                let hashes = match raw {
+                    true => source[i..].iter().take_while(|&&c| c == b'#').count(),
+                    false => 0,
+                };
+                i += hashes;
+                if source.get(i) != Some(&b'"') {
+                    return None;
+                }
+                let mut quote = vec![b'"'];
+                quote.extend(std::iter::repeat(b'#').take(hashes));
+                let end = closing(source, i + 1, &quote, !raw);
+                // Byte strings can't contain converted characters
+                Some((i + 1..end, quote.len(), !prefix.starts_with(b"b")))
+            }
+            Syntax::C => {
+                if &prefix[..] == b"r" && source.get(i) == Some(&b'"') {
+                    // C++ raw string R"delimiter(...)delimiter"
+                    let open = i + 1 + source[i + 1..].iter().position(|&c| c == b'(')?;
+                    let mut quote = vec![b')'];
+                    quote.extend_from_slice(&source[i + 1..open]);
+                    quote.push(b'"');
+                    let end = closing(source, open + 1, &quote, false);
+                    return Some((open + 1..end, quote.len(), true));
+                }
+                if !matches!(&prefix[..], b"" | b"l" | b"u" | b"u8") || source.get(i) != Some(&b'"')
+                {
+                    return None;
+                }
+                let end = closing(source, i + 1, b"\"", true);
+                Some((i + 1..end, 1, true))
+            }
+            Syntax::Python => {
+                let valid = prefix.len() <= 2
+                    && prefix.iter().all(|c| b"rbuf".contains(c))
+                    && (prefix.len() == 1 || !prefix.contains(&b'u'));
+                let quote = *source.get(i)?;
+                if !valid || !matches!(quote, b'"' | b'\'') {
+                    return None;
+                }
+                let triple = [quote; 3];
+                let quote = match source[i..].starts_with(&triple) {
+                    true => &triple[..],
+                    false => &triple[..1],
+                };
+                i += quote.len();
+                let end = closing(source, i, quote, true);
+                Some((i..end, quote.len(), !prefix.contains(&b'b')))
+            }
+            Syntax::JavaScript => {
+                let quote = *source.get(i)?;
+                if !prefix.is_empty() || !matches!(quote, b'"' | b'\'' | b'`') {
+                    return None;
+                }
+                let end = closing(source, i + 1, &[quote], true);
+                Some((i + 1..end, 1, true))
+            }
+        }
+    }
+
+    /// Returns length of a character literal or Rust lifetime at `start`
+    fn character(&self, source: &str, start: usize) -> usize {
+        let bytes = source.as_bytes();
+        let next = source[start + 1..].chars().next().map_or(0, char::len_utf8);
+        if *self == Syntax::Rust && bytes.get(start + 1) != Some(&b'\\') {
+            // Lifetimes and loop labels (ie. 'a) are not closed
+            return match bytes.get(start + 1 + next) {
+                Some(b'\'') => next + 2,
+                _ => 1,
+            };
+        }
+        (closing(bytes, start + 1, b"'", true) + 1).min(bytes.len()) - start
+    }
+
+    /// Returns length of a block comment at `start`
+    fn block_comment(&self, source: &[u8], start: usize) -> usize {
+        // Only Rust block comments can be nested
+        let mut depth = 0;
+        let mut i = start;
+        while i < source.len() {
+            if source[i..].starts_with(b"/*") {
+                depth += 1;
+                i += 2;
+            } else if source[i..].starts_with(b"*/") {
+                depth -= 1;
+                i += 2;
+                if depth == 0 || *self != Syntax::Rust {
+                    break;
+                }
+            } else {
+                i += 1;
+            }
+        }
+        i.min(source.len()) - start
+    }
+
+    /// Returns whether a string literal between `start` and `end` names a
+    /// module, a file or a key (ie. `require("fs")`, `obj['ime']`), which
+    /// must not be changed
+    fn reference(&self, source: &str, start: usize, end: usize) -> bool {
+        let before = source[..start].trim_end();
+        let after = source[end..].trim_start();
+        // Keys of subscripts, but not arrays (ie. `obj['ime']`)
+        if let Some(object) = before.strip_suffix('[') {
+            if after.starts_with(']')
+                && object
+                    .ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == ')' || c == ']')
+            {
+                return true;
+            }
+        }
+        // Arguments of loaders (ie. `require("fs")`, `include_str!("a.txt")`)
+        if let Some(callee) = before.strip_suffix('(') {
+            if LOADERS.contains(&last_ident(callee.trim_end().trim_end_matches('!'))) {
+                return true;
+            }
+        }
+        match self {
+            // Modules of imports and exports (ie. `import x from "y"`)
+            Syntax::JavaScript => matches!(last_ident(before), "from" | "import"),
+            // Go imports, which can be grouped (ie. `import ("fmt"; "os")`)
+            Syntax::C => {
+                last_ident(before) == "import"
+                    || before
+                        .rfind("import (")
+                        .map_or(false, |n| !before[n..].contains(')'))
+            }
+            _ => false,
+        }
+    }
+
+    /// Split source code into code, comments and contents of string literals
+    fn tokenize(&self, source: &str) -> Vec<(Kind, Range<usize>)> {
+        let bytes = source.as_bytes();
+        let mut tokens = Tokens {
+            tokens: Vec::new(),
+            code: 0,
+        };
+        // Brackets of an attribute, annotation or decorator whose strings
+        // are kept (ie. `#[cfg(feature = "serde")]`), and their depth
+        let mut attribute: Option<(u8, u8, usize)> = None;
+        // Annotation or decorator whose arguments follow (ie. `@app.route`)
+        let mut annotation = false;
+        // Preprocessor directive, which lasts until the end of its line
+        let mut directive = false;
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &bytes[i..];
+            if *self == Syntax::C && rest[0] == b'#' && line_start(bytes, i) {
+                directive = true;
+            }
+            if attribute.is_none() {
+                if *self == Syntax::Rust && (rest.starts_with(b"#[") || rest.starts_with(b"#![")) {
+                    let length = rest.iter().position(|&c| c == b'[').unwrap_or(0) + 1;
+                    attribute = Some((b'[', b']', 1));
+                    i += length;
+                    continue;
+                }
+                // C# attributes (ie. `[Obsolete("...")]`)
+                if *self == Syntax::C && rest[0] == b'[' && line_start(bytes, i) {
+                    attribute = Some((b'[', b']', 1));
+                    i += 1;
+                    continue;
+                }
+            }
+            if rest.starts_with(self.line_comment()) {
+                let length = rest.iter().position(|&c| c == b'\n').unwrap_or(rest.len());
+                tokens.push(Kind::Comment, i..i + length);
+                i += length;
+            } else if *self != Syntax::Python && rest.starts_with(b"/*") {
+                let length = self.block_comment(bytes, i);
+                tokens.push(Kind::Comment, i..i + length);
+                i += length;
+            } else if is_ident(rest[0]) || matches!(rest[0], b'"' | b'\'' | b'`') {
+                // Identifiers are skipped as a whole, unless they are
+                // prefixes of string literals
+                let length = rest.iter().take_while(|&&c| is_ident(c)).count();
+                match self.literal(bytes, i, &rest[..length]) {
+                    Some((contents, quote, convert)) => {
+                        let end = (contents.end + quote).min(bytes.len());
+                        if convert
+                            && attribute.is_none()
+                            && !directive
+                            && !self.reference(source, i, end)
+                        {
+                            tokens.push(Kind::Text, contents);
+                        }
+                        i = end;
+                    }
+                    None if rest[0] == b'\'' => i += self.character(source, i),
+                    None => i += length.max(1),
+                }
+            } else {
+                let c = rest[0];
+                attribute = match attribute {
+                    Some((open, close, depth)) if c == open => Some((open, close, depth + 1)),
+                    Some((_, close, 1)) if c == close => None,
+                    Some((open, close, depth)) if c == close => Some((open, close, depth - 1)),
+                    None if c == b'(' && annotation => Some((b'(', b')', 1)),
+                    attribute => attribute,
+                };
+                annotation = c == b'@' || (annotation && c == b'.');
+                if c == b'\n' && !(i > 0 && bytes[i - 1] == b'\\') {
+                    directive = false;
+                }
+                i += 1;
+            }
+        }
+        if tokens.code < bytes.len() {
+            tokens.push(Kind::Code, tokens.code..bytes.len());
+        }
+        tokens.tokens
+    }
+}
+
+/// Transliterate `text`, keeping parts matched by `keep` intact
+fn convert(
+    transliterator: &Transliterator,
+    text: &str,
+    keep: &Regex,
+) -> Result<String, transliterate::Error> {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for kept in keep.find_iter(text) {
+        output.push_str(&transliterator.process(&text[last..kept.start()])?);
+        output.push_str(kept.as_str());
+        last = kept.end();
+    }
+    output.push_str(&transliterator.process(&text[last..])?);
+    Ok(output)
+}
+
+/// Transliterates comments and string literals of source code, leaving
+/// identifiers, keywords and other code unchanged. Escape sequences,
+/// interpolations and format specifiers in strings (ie. `\n`, `{name}`
+/// and `%s`) and code spans in comments are kept as well, and so are
+/// strings which name modules, files or keys (ie. `#include "fajl.h"`,
+/// `require("fs")` and `obj['ime']`).
+pub struct SourceProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    syntax: Syntax,
+}
+
+impl SourceProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+        syntax: Syntax,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            syntax,
+        })
+    }
+
+    /// Transliterate comments and strings of source code
+    pub fn convert(&self, source: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(source.len());
+        for (kind, range) in self.syntax.tokenize(source) {
+            let text = &source[range];
+            match kind {
+                Kind::Code => output.push_str(text),
+                Kind::Comment => output.push_str(&convert(&self.processor, text, &COMMENT_KEEP)?),
+                Kind::Text => output.push_str(&convert(&self.processor, text, &STRING_KEEP)?),
+            }
+        }
+        Ok(output)
+    }
+}
+
+with_backup!(SourceProcessor);
+
+impl FileProcessor for SourceProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn convert(syntax: Syntax, source: &str) -> String {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let p = SourceProcessor::new(None, None, t, syntax).unwrap();
+        p.convert(source).unwrap()
+    }
+
+    #[test]
+    fn test_rust() {
+        let source = concat!(
+            "/// Vraća `broj_reči` reči\n",
+            "fn broj<'a>(tekst: &'a str) -> usize {\n",
+            "    /* spoljni /* unutrašnji */ komentar */\n",
+            "    let c = '\"'; // navodnik\n",
+            "    let b = b\"bajt\";\n",
+            "    println!(\"Reči: {broj}\\n\", broj = tekst.len());\n",
+            "    r#\"sirov \"tekst\"\"#.len()\n",
+            "}\n"
+        );
+        let expected = concat!(
+            "/// Враћа `broj_reči` речи\n",
+            "fn broj<'a>(tekst: &'a str) -> usize {\n",
+            "    /* спољни /* унутрашњи */ коментар */\n",
+            "    let c = '\"'; // наводник\n",
+            "    let b = b\"bajt\";\n",
+            "    println!(\"Речи: {broj}\\n\", broj = tekst.len());\n",
+            "    r#\"сиров \"текст\"\"#.len()\n",
+            "}\n"
+        );
+        assert_eq!(convert(Syntax::Rust, source), expected);
+    }
+
+    #[test]
+    fn test_c() {
+        let source = concat!(
+            "/* Pozdrav */ int main() {\n",
+            "    char c = 'x'; // slovo\n",
+            "    printf(\"Dan %d: %s\\n\", dan, \"sunce\");\n",
+            "    auto s = R\"tekst(\"navodnik\")tekst\";\n",
+            "}"
+        );
+        let expected = concat!(
+            "/* Поздрав */ int main() {\n",
+            "    char c = 'x'; // слово\n",
+            "    printf(\"Дан %d: %s\\n\", dan, \"сунце\");\n",
+            "    auto s = R\"tekst(\"наводник\")tekst\";\n",
+            "}"
+        );
+        assert_eq!(convert(Syntax::C, source), expected);
+    }
+
+    #[test]
+    fn test_python() {
+        let source = concat!(
+            "def pozdrav(ime):\n",
+            "    \"\"\"Vraća \"pozdrav\" za ime\"\"\"\n",
+            "    # f-string\n",
+            "    return f'Zdravo, {ime}!' + b'bajt'.decode() + r\"\\d\"\n"
+        );
+        let expected = concat!(
+            "def pozdrav(ime):\n",
+            "    \"\"\"Враћа \"поздрав\" за име\"\"\"\n",
+            "    # ф-стринг\n",
+            "    return f'Здраво, {ime}!' + b'bajt'.decode() + r\"\\d\"\n"
+        );
+        assert_eq!(convert(Syntax::Python, source), expected);
+    }
+
+    #[test]
+    fn test_javascript() {
+        let source = "const ime = 'Ana'; // ime\nconsole.log(`Zdravo ${ime}`, \"kraj\");";
+        let expected = "const ime = 'Ана'; // име\nconsole.log(`Здраво ${ime}`, \"крај\");";
+        assert_eq!(convert(Syntax::JavaScript, source), expected);
+        assert_eq!(Syntax::from_str("ts"), Ok(Syntax::JavaScript));
+        assert_eq!(Syntax::from_str("cobol"), Err(()));
+    }
+
+    #[test]
+    fn test_references() {
+        for (syntax, source, expected) in [
+            (
+                Syntax::Rust,
+                "#[cfg(feature = \"serde\")]\n#![doc = \"tekst\"]\nlet s = \"tekst\";",
+                "#[cfg(feature = \"serde\")]\n#![doc = \"tekst\"]\nlet s = \"текст\";",
+            ),
+            (
+                Syntax::Rust,
+                "let t = include_str!(\"tekst.txt\"); mapa[\"ključ\"] = [\"reč\"];",
+                "let t = include_str!(\"tekst.txt\"); mapa[\"ključ\"] = [\"реч\"];",
+            ),
+            (
+                Syntax::C,
+                "#include \"fajl.h\"\n#define IME \\\n    \"ime\"\nputs(\"fajl\");",
+                "#include \"fajl.h\"\n#define IME \\\n    \"ime\"\nputs(\"фајл\");",
+            ),
+            (
+                Syntax::C,
+                "import (\n    \"fmt\"\n)\n[Obsolete(\"staro\")]\nvar s = \"staro\";",
+                "import (\n    \"fmt\"\n)\n[Obsolete(\"staro\")]\nvar s = \"старо\";",
+            ),
+            (
+                Syntax::Python,
+                "@app.route(\"/ime\")\ndef ime(d):\n    return d['ime'] + __import__('os').sep + 'ime'",
+                "@app.route(\"/ime\")\ndef ime(d):\n    return d['ime'] + __import__('os').sep + 'име'",
+            ),
+            (
+                Syntax::JavaScript,
+                "import ime from 'ime';\nconst fs = require(\"fs\");\nobj['ime'] = 'ime';",
+                "import ime from 'ime';\nconst fs = require(\"fs\");\nobj['ime'] = 'име';",
+            ),
+        ] {
+            assert_eq!(convert(syntax, source), expected);
+        }
+    }
+}
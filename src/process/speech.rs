@@ -0,0 +1,81 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{Expand, Transliterator};
+
+/// Normalizes transcripts for speech synthesis and recognition datasets.
+/// Text is written in the target script only, without symbols and
+/// punctuation, with whitespace collapsed, and optionally with numbers
+/// and abbreviations expanded into words. Lines are normalized
+/// separately, so they stay aligned with their recordings.
+pub struct SpeechProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    expand: Expand,
+}
+
+impl SpeechProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            expand: Expand::default(),
+        })
+    }
+
+    /// Expand numbers and abbreviations into words
+    pub fn with_expand(mut self, expand: Expand) -> Self {
+        self.expand = expand;
+        self
+    }
+
+    /// Normalize a transcript
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        Ok(self.processor.normalize_speech(text, self.expand)?)
+    }
+}
+
+with_backup!(SpeechProcessor);
+
+impl FileProcessor for SpeechProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_speech() {
+        let p = SpeechProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        let transcript = "Snimak 1: \"Dobar dan!\"\nSnimak 2:   Kupio sam 3 kg, itd.\n";
+        assert_eq!(
+            p.convert(transcript).unwrap(),
+            "Снимак 1 Добар дан\nСнимак 2 Купио сам 3 кг итд\n"
+        );
+        let p = p.with_expand(Expand::ALL);
+        assert_eq!(
+            p.convert(transcript).unwrap(),
+            "Снимак један Добар дан\nСнимак два Купио сам три кг и тако даље\n"
+        );
+    }
+}
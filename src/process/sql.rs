@@ -0,0 +1,247 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    static ref BACKSLASH_ESCAPE: Regex = Regex::new(r"\\(?s:.)").unwrap();
+    static ref STANDARD_STRINGS: Regex =
+        Regex::new(r"(?i)^SET\s+standard_conforming_strings\s*(?:=|TO)\s*'?(on|off)'?").unwrap();
+    static ref COPY: Regex = Regex::new(r"(?i)^COPY\s.*\sFROM\s+stdin;\s*$").unwrap();
+    static ref DOLLAR_QUOTE: Regex = Regex::new(r"^\$(?:[A-Za-z_][A-Za-z0-9_]*)?\$").unwrap();
+}
+
+/// String introducers (ie. `_utf8mb4'...'`) whose contents are converted
+const INTRODUCERS: &[&str] = &["", "n", "e", "_utf8", "_utf8mb3", "_utf8mb4"];
+
+fn is_ident(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80
+}
+
+/// Returns index of the closing `quote`, whose contents start at `start`.
+/// Quotes are escaped by repeating them, or with backslashes if `escapes`.
+fn closing(source: &[u8], start: usize, quote: u8, escapes: bool) -> usize {
+    let mut i = start;
+    while i < source.len() {
+        if escapes && source[i] == b'\\' {
+            i += 2;
+        } else if source[i] == quote {
+            if source.get(i + 1) != Some(&quote) {
+                return i;
+            }
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    source.len()
+}
+
+/// Transliterates contents of string literals in SQL dumps (ie. from
+/// `mysqldump` or `pg_dump`), including PostgreSQL `COPY` data, so data
+/// can be migrated without a running database server. Statements,
+/// identifiers, comments and dollar-quoted function bodies are left
+/// unchanged, as well as binary strings, strings with charset introducers
+/// other than UTF-8 and single words with digits (ie. hashes and dates).
+pub struct SqlProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl SqlProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate a string value, keeping backslash escapes if `escapes`
+    fn convert_value(&self, value: &str, escapes: bool) -> Result<String, transliterate::Error> {
+        if !value.contains(char::is_whitespace) && value.contains(|c: char| c.is_ascii_digit()) {
+            return Ok(value.to_owned());
+        }
+        if !escapes {
+            return self.processor.process(value);
+        }
+        let mut output = String::with_capacity(value.len());
+        let mut last = 0;
+        for escape in BACKSLASH_ESCAPE.find_iter(value) {
+            output.push_str(&self.processor.process(&value[last..escape.start()])?);
+            output.push_str(escape.as_str());
+            last = escape.end();
+        }
+        output.push_str(&self.processor.process(&value[last..])?);
+        Ok(output)
+    }
+
+    /// Transliterate a row of `COPY` data, whose tab separated columns
+    /// use backslash escapes and `\N` for null values
+    fn convert_row(&self, row: &str) -> Result<String, transliterate::Error> {
+        let columns = row
+            .split('\t')
+            .map(|column| match column {
+                "\\N" => Ok(column.to_owned()),
+                _ => self.convert_value(column, true),
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(columns.join("\t"))
+    }
+
+    /// Transliterate a SQL dump
+    pub fn convert(&self, dump: &str) -> Result<String, Error> {
+        let bytes = dump.as_bytes();
+        let mut output = String::with_capacity(dump.len());
+        // MySQL strings use backslash escapes, and PostgreSQL strings
+        // only if standard conforming strings are off
+        let mut escapes = true;
+        let mut code = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &bytes[i..];
+            if i == 0 || bytes[i - 1] == b'\n' {
+                let line = &dump[i..dump[i..].find('\n').map_or(dump.len(), |p| i + p)];
+                if let Some(mode) = STANDARD_STRINGS.captures(line) {
+                    escapes = mode[1].eq_ignore_ascii_case("off");
+                }
+                if COPY.is_match(line) {
+                    // Data rows follow until a line with `\.`
+                    output.push_str(&dump[code..i]);
+                    let mut lines = dump[i..].split_inclusive('\n');
+                    let header = lines.next().unwrap_or_default();
+                    output.push_str(header);
+                    i += header.len();
+                    for row in lines {
+                        i += row.len();
+                        let content = row.trim_end_matches(['\n', '\r']);
+                        if content == "\\." {
+                            output.push_str(row);
+                            break;
+                        }
+                        output.push_str(&self.convert_row(content)?);
+                        output.push_str(&row[content.len()..]);
+                    }
+                    code = i;
+                    continue;
+                }
+            }
+            if rest.starts_with(b"--") {
+                i += rest.iter().position(|&c| c == b'\n').unwrap_or(rest.len());
+            } else if rest.starts_with(b"/*") {
+                i += dump[i + 2..].find("*/").map_or(rest.len(), |p| p + 4);
+            } else if let Some(tag) = (rest[0] == b'$')
+                .then(|| DOLLAR_QUOTE.find(&dump[i..]))
+                .flatten()
+            {
+                let tag = tag.as_str();
+                let body = i + tag.len();
+                i = dump[body..]
+                    .find(tag)
+                    .map_or(bytes.len(), |p| body + p + tag.len());
+            } else if matches!(rest[0], b'"' | b'`') {
+                i = (closing(bytes, i + 1, rest[0], false) + 1).min(bytes.len());
+            } else if is_ident(rest[0]) || rest[0] == b'\'' {
+                let length = rest.iter().take_while(|&&c| is_ident(c)).count();
+                if rest.get(length) != Some(&b'\'') {
+                    i += length.max(1);
+                    continue;
+                }
+                let introducer = dump[i..i + length].to_ascii_lowercase();
+                let e = introducer == "e";
+                let start = i + length + 1;
+                let end = closing(bytes, start, b'\'', escapes || e);
+                if INTRODUCERS.contains(&introducer.as_str()) {
+                    output.push_str(&dump[code..start]);
+                    output.push_str(&self.convert_value(&dump[start..end], escapes || e)?);
+                    code = end;
+                }
+                i = (end + 1).min(bytes.len());
+            } else {
+                i += 1;
+            }
+        }
+        output.push_str(&dump[code..]);
+        Ok(output)
+    }
+}
+
+with_backup!(SqlProcessor);
+
+impl FileProcessor for SqlProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor() -> SqlProcessor {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        SqlProcessor::new(None, None, t).unwrap()
+    }
+
+    #[test]
+    fn test_mysql() {
+        let dump = concat!(
+            "-- Dump of table `korisnici`\n",
+            "/*!40101 SET NAMES utf8mb4 */;\n",
+            "INSERT INTO `korisnici` (`ime`, `opis`) VALUES ",
+            "('Petar','Voli \\'more\\'\\nplanine'),",
+            "(_utf8mb4'Ana', _latin1'Ana'),",
+            "(_binary'bajt', X'4142'),",
+            "('Ne''ma', '5f4dcc3b5aa765d61d8327deb882cf99');\n"
+        );
+        let expected = concat!(
+            "-- Dump of table `korisnici`\n",
+            "/*!40101 SET NAMES utf8mb4 */;\n",
+            "INSERT INTO `korisnici` (`ime`, `opis`) VALUES ",
+            "('Петар','Воли \\'море\\'\\nпланине'),",
+            "(_utf8mb4'Ана', _latin1'Ana'),",
+            "(_binary'bajt', X'4142'),",
+            "('Не''ма', '5f4dcc3b5aa765d61d8327deb882cf99');\n"
+        );
+        assert_eq!(processor().convert(dump).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_postgres() {
+        let dump = concat!(
+            "SET standard_conforming_strings = on;\n",
+            "CREATE FUNCTION pozdrav() RETURNS text AS $$ SELECT 'Zdravo' $$ LANGUAGE sql;\n",
+            "INSERT INTO \"grad\" VALUES ('Novi Sad\\', E'Ni\\u0161 i Pirot');\n",
+            "COPY public.grad (id, ime, opis) FROM stdin;\n",
+            "1\tBeograd\tGlavni\\tgrad\n",
+            "2\tNiš\t\\N\n",
+            "\\.\n",
+            "COMMENT ON TABLE grad IS 'Gradovi';\n"
+        );
+        let expected = concat!(
+            "SET standard_conforming_strings = on;\n",
+            "CREATE FUNCTION pozdrav() RETURNS text AS $$ SELECT 'Zdravo' $$ LANGUAGE sql;\n",
+            "INSERT INTO \"grad\" VALUES ('Нови Сад\\', E'Ни\\u0161 и Пирот');\n",
+            "COPY public.grad (id, ime, opis) FROM stdin;\n",
+            "1\tБеоград\tГлавни\\tград\n",
+            "2\tНиш\t\\N\n",
+            "\\.\n",
+            "COMMENT ON TABLE grad IS 'Градови';\n"
+        );
+        assert_eq!(processor().convert(dump).unwrap(), expected);
+    }
+}
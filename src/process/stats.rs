@@ -0,0 +1,102 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::json;
+use crate::transliterate::{Stats, Transliterator};
+
+/// Reports counts of transliterated and skipped words, letters, applied
+/// exceptions and warnings of files, without writing converted text.
+/// Bytes which are not valid UTF-8 are read as `�`.
+pub struct StatsProcessor {
+    paths: Vec<PathBuf>,
+    processor: Transliterator,
+    json: bool,
+}
+
+impl StatsProcessor {
+    /// Count words of files at `paths`, or standard input if there are none
+    pub fn new(paths: Vec<PathBuf>, processor: Transliterator) -> Self {
+        Self {
+            paths,
+            processor,
+            json: false,
+        }
+    }
+
+    /// Print a JSON object for each file instead of plain text
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+}
+
+/// Format counts as `{"path", "words", "transliterated", "exceptions", "skipped", "characters", "warnings"}`
+fn to_json(path: &str, stats: &Stats) -> String {
+    format!(
+        concat!(
+            r#"{{"path":{},"words":{},"transliterated":{},"exceptions":{},"#,
+            r#""skipped":{},"characters":{},"warnings":{}}}"#
+        ),
+        json::string(path),
+        stats.words,
+        stats.transliterated,
+        stats.exceptions,
+        stats.skipped,
+        stats.characters,
+        stats.warnings
+    )
+}
+
+impl FileProcessor for StatsProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut inputs = Vec::new();
+        if self.paths.is_empty() {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            inputs.push(("<stdin>".to_owned(), bytes));
+        }
+        for path in &self.paths {
+            inputs.push((path.display().to_string(), fs::read(path)?));
+        }
+        let mut output = io::stdout();
+        for (path, bytes) in inputs {
+            let stats = self.processor.stats(String::from_utf8_lossy(&bytes))?;
+            if self.json {
+                writeln!(output, "{}", to_json(&path, &stats))?;
+                continue;
+            }
+            writeln!(output, "{}: {} words", path, stats.words)?;
+            writeln!(
+                output,
+                "  transliterated: {} ({} exceptions, {} letters)",
+                stats.transliterated, stats.exceptions, stats.characters
+            )?;
+            writeln!(output, "  skipped: {}", stats.skipped)?;
+            writeln!(output, "  warnings: {}", stats.warnings)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let stats = Stats {
+            words: 3,
+            transliterated: 2,
+            exceptions: 1,
+            skipped: 1,
+            characters: 14,
+            warnings: 1,
+        };
+        assert_eq!(
+            to_json("a.txt", &stats),
+            r#"{"path":"a.txt","words":3,"transliterated":2,"exceptions":1,"skipped":1,"characters":14,"warnings":1}"#
+        );
+    }
+}
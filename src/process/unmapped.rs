@@ -0,0 +1,119 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::json;
+use crate::unmapped::UnmappedReport;
+
+/// Reports characters which are neither Latin nor Cyrillic letters nor
+/// pass through transliteration unchanged. Bytes which are not valid
+/// UTF-8 are reported as `�`.
+pub struct UnmappedProcessor {
+    paths: Vec<PathBuf>,
+    json: bool,
+    report: UnmappedReport,
+}
+
+impl UnmappedProcessor {
+    /// Check files at `paths`, or standard input if there are none
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            json: false,
+            report: UnmappedReport::new(),
+        }
+    }
+
+    /// Print report as a single JSON object instead of plain text
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Report characters outside of these character classes instead of
+    /// the default ones, as in [`UnmappedReport::with_passthrough`].
+    /// Default classes are kept if there are none.
+    pub fn with_passthrough(mut self, classes: &[String]) -> Result<Self, Error> {
+        if !classes.is_empty() {
+            self.report = UnmappedReport::with_passthrough(classes)?;
+        }
+        Ok(self)
+    }
+}
+
+/// Format report as `{"count": n, "chars": [{"char", "code", "count", "samples"}]}`
+fn to_json(report: &UnmappedReport) -> String {
+    let chars = report.chars();
+    let count = chars.iter().map(|c| c.count).sum::<usize>();
+    let chars = chars
+        .iter()
+        .map(|c| {
+            let samples = c
+                .samples
+                .iter()
+                .map(|s| {
+                    format!(
+                        r#"{{"path":{},"line":{},"context":{}}}"#,
+                        json::string(&s.source),
+                        s.line,
+                        json::string(&s.context)
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(",");
+            format!(
+                r#"{{"char":{},"code":"U+{:04X}","count":{},"samples":[{}]}}"#,
+                json::string(&c.character.to_string()),
+                c.character as u32,
+                c.count,
+                samples
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    format!(r#"{{"count":{},"chars":[{}]}}"#, count, chars)
+}
+
+impl FileProcessor for UnmappedProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut report = self.report.clone();
+        if self.paths.is_empty() {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            report.add("<stdin>", &String::from_utf8_lossy(&bytes));
+        }
+        for path in &self.paths {
+            let bytes = fs::read(path)?;
+            report.add(&path.display().to_string(), &String::from_utf8_lossy(&bytes));
+        }
+        let mut output = io::stdout();
+        if self.json {
+            writeln!(output, "{}", to_json(&report))?;
+            return Ok(());
+        }
+        for c in report.chars() {
+            writeln!(output, "U+{:04X} {} {}", c.character as u32, c.character, c.count)?;
+            for s in &c.samples {
+                writeln!(output, "  {}:{}: {}", s.source, s.line, s.context)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let mut report = UnmappedReport::new();
+        assert_eq!(to_json(&report), r#"{"count":0,"chars":[]}"#);
+        report.add("a.txt", "Ko\u{fffd}ulja");
+        assert_eq!(
+            to_json(&report),
+            r#"{"count":1,"chars":[{"char":"�","code":"U+FFFD","count":1,"samples":[{"path":"a.txt","line":1,"context":"Ko�ulja"}]}]}"#
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use super::{Error, FileProcessor};
+use crate::json;
+use crate::spell::Dictionary;
+use crate::transliterate::{Charset, Transliterator};
+
+/// Reports words which are not in the dictionary of the target character
+/// set after transliteration, which are usually misresolved digraphs or
+/// foreign words which should have been skipped
+pub struct ValidateProcessor {
+    paths: Vec<PathBuf>,
+    processor: Transliterator,
+    dictionary: Dictionary,
+    charset: Charset,
+    json: bool,
+}
+
+impl ValidateProcessor {
+    /// Validate files at `paths`, or standard input if there are none,
+    /// transliterated into `charset` with `processor`
+    pub fn new(
+        paths: Vec<PathBuf>,
+        processor: Transliterator,
+        dictionary: Dictionary,
+        charset: Charset,
+    ) -> Self {
+        Self {
+            paths,
+            processor,
+            dictionary,
+            charset,
+            json: false,
+        }
+    }
+
+    /// Print results as a single JSON object instead of one line per result
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Returns line numbers and words which are not in the dictionary
+    /// after transliterating `text`
+    pub fn validate(&self, text: &str) -> Result<Vec<(usize, String)>, Error> {
+        let mut words = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let output = self.processor.process(line)?;
+            for word in self.dictionary.unknown(&output, self.charset) {
+                words.push((number + 1, word.to_owned()));
+            }
+        }
+        Ok(words)
+    }
+}
+
+/// Format results as `{"count": n, "words": [{"path", "line", "word"}]}`
+fn to_json(results: &[(String, usize, String)]) -> String {
+    let words = results
+        .iter()
+        .map(|(name, number, word)| {
+            format!(
+                r#"{{"path":{},"line":{},"word":{}}}"#,
+                json::string(name),
+                number,
+                json::string(word)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    format!(r#"{{"count":{},"words":[{}]}}"#, results.len(), words)
+}
+
+impl FileProcessor for ValidateProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut inputs = Vec::new();
+        if self.paths.is_empty() {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            inputs.push(("<stdin>".to_owned(), text));
+        }
+        for path in &self.paths {
+            inputs.push((path.display().to_string(), fs::read_to_string(path)?));
+        }
+        let mut results = Vec::new();
+        for (name, text) in inputs {
+            for (number, word) in self.validate(&text)? {
+                results.push((name.clone(), number, word));
+            }
+        }
+        let count = results.len();
+        let mut output = io::stdout();
+        if self.json {
+            writeln!(output, "{}", to_json(&results))?;
+        } else {
+            for (name, number, word) in results {
+                writeln!(output, "{}:{}: {}", name, number, word)?;
+            }
+        }
+        match count {
+            0 => Ok(()),
+            n => Err(Error::Misspelled(n)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let dictionary = Dictionary::new("SFX A Y 1\nSFX A а е а\n", "2\nкњига/A\nи\n");
+        let v = ValidateProcessor::new(vec![], t, dictionary, Charset::Cyrillic);
+        assert_eq!(
+            v.validate("Knjige i\n\ninjekcja, Wien 12\nKnjigu").unwrap(),
+            vec![(3, "инјекцја".to_owned()), (4, "Књигу".to_owned())]
+        );
+        assert_eq!(
+            to_json(&[("a.txt".to_owned(), 3, "ињекција".to_owned())]),
+            r#"{"count":1,"words":[{"path":"a.txt","line":3,"word":"ињекција"}]}"#
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    /// Escaped character (ie. `\n` or `\,`) or separator of components of
+    /// structured values (ie. `N` and `ADR`)
+    static ref ESCAPE: Regex = Regex::new(r"\\.|;").unwrap();
+}
+
+/// Properties with display text of vCard contacts and iCalendar components
+const PROPERTIES: &[&str] = &[
+    "FN",
+    "N",
+    "NICKNAME",
+    "ORG",
+    "TITLE",
+    "ROLE",
+    "NOTE",
+    "ADR",
+    "LABEL",
+    "SUMMARY",
+    "DESCRIPTION",
+    "LOCATION",
+    "COMMENT",
+    "CONTACT",
+];
+
+/// Longest line of a folded content line in octets, without line break
+const LINE_LENGTH: usize = 75;
+
+/// Transliterates display text of vCard (`.vcf`) contacts and iCalendar
+/// (`.ics`) calendars, such as names (`FN`, `N`), notes and summaries and
+/// descriptions of events. Property names and parameters, escaped
+/// characters (ie. `\n`) and other properties are left unchanged, as well
+/// as values with an `ENCODING` parameter (ie. quoted-printable). Content
+/// lines which are changed or longer than 75 octets are folded again, so
+/// no line is longer than 75 octets (RFC 6350).
+pub struct VcardProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+/// Fold a content line into lines of at most [`LINE_LENGTH`] octets,
+/// without splitting characters
+fn fold(line: &str, indent: char, newline: &str) -> String {
+    let mut output = String::with_capacity(line.len() + line.len() / LINE_LENGTH * 3);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LENGTH {
+            output.push_str(newline);
+            output.push(indent);
+            length = indent.len_utf8();
+        }
+        output.push(c);
+        length += c.len_utf8();
+    }
+    output
+}
+
+impl VcardProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate a property value, leaving escaped characters unchanged
+    /// and converting each component of structured values on its own
+    fn convert_value(&self, value: &str) -> Result<String, transliterate::Error> {
+        let mut output = String::with_capacity(value.len());
+        let mut last = 0;
+        for escape in ESCAPE.find_iter(value) {
+            output.push_str(&self.processor.process(&value[last..escape.start()])?);
+            output.push_str(escape.as_str());
+            last = escape.end();
+        }
+        output.push_str(&self.processor.process(&value[last..])?);
+        Ok(output)
+    }
+
+    /// Transliterate an unfolded content line, or return `None` if it is
+    /// not a property with display text
+    fn convert_line(&self, line: &str) -> Result<Option<String>, transliterate::Error> {
+        // Parameter values may be quoted and contain colons
+        let mut quoted = false;
+        let colon = line.char_indices().find(|&(_, c)| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            !quoted && c == ':'
+        });
+        let colon = match colon {
+            Some((colon, _)) => colon,
+            None => return Ok(None),
+        };
+        let mut parameters = line[..colon].split(';');
+        // Property names may be prefixed with a group (ie. `item1.ADR`)
+        let name = parameters.next().unwrap_or_default();
+        let name = name.rsplit('.').next().unwrap_or_default();
+        if !PROPERTIES.iter().any(|p| p.eq_ignore_ascii_case(name))
+            || parameters.any(|p| {
+                p.split('=')
+                    .next()
+                    .map_or(false, |p| p.trim().eq_ignore_ascii_case("ENCODING"))
+            })
+        {
+            return Ok(None);
+        }
+        let value = self.convert_value(&line[colon + 1..])?;
+        Ok(Some(format!("{}{}", &line[..=colon], value)))
+    }
+
+    /// Transliterate a vCard or iCalendar file
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(text.len());
+        // Line break of the file, or CRLF required by RFC 6350
+        let newline = match text.find('\n') {
+            Some(n) if !text[..n].ends_with('\r') => "\n",
+            _ => "\r\n",
+        };
+        let lines = text.split_inclusive('\n').collect::<Vec<&str>>();
+        let mut start = 0;
+        while start < lines.len() {
+            // Content lines continue on lines starting with whitespace
+            let mut end = start + 1;
+            while end < lines.len() && lines[end].starts_with([' ', '\t']) {
+                end += 1;
+            }
+            let physical = &lines[start..end];
+            start = end;
+            let mut line = String::new();
+            for (n, p) in physical.iter().enumerate() {
+                let p = p.trim_end_matches(['\n', '\r']);
+                line.push_str(if n == 0 { p } else { &p[1..] });
+            }
+            let long = physical
+                .iter()
+                .any(|p| p.trim_end_matches(['\n', '\r']).len() > LINE_LENGTH);
+            let converted = match self.convert_line(&line)? {
+                Some(converted) if converted != line => converted,
+                _ if long => line,
+                _ => {
+                    physical.iter().for_each(|p| output.push_str(p));
+                    continue;
+                }
+            };
+            let last = physical[physical.len() - 1];
+            let ending = &last[last.trim_end_matches(['\n', '\r']).len()..];
+            let indent = physical
+                .get(1)
+                .and_then(|p| p.chars().next())
+                .unwrap_or(' ');
+            output.push_str(&fold(&converted, indent, newline));
+            output.push_str(ending);
+        }
+        Ok(output)
+    }
+}
+
+with_backup!(VcardProcessor);
+
+impl FileProcessor for VcardProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_vcard() {
+        let p = VcardProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        let contact = concat!(
+            "BEGIN:VCARD\r\n",
+            "VERSION:3.0\r\n",
+            "N:Petrović;Ana;;;\r\n",
+            "FN:Ana Petrović\r\n",
+            "item1.ADR;TYPE=\"home,pref\":;;Njegoševa 5;Novi Sad;;21000;Srbija\r\n",
+            "NOTE;LANGUAGE=sr-Latn:Prva linija\\nnjiva\\, druga\r\n",
+            "NOTE;ENCODING=QUOTED-PRINTABLE:Ko=C5=A1ava\r\n",
+            "EMAIL;TYPE=work:ana@primer.rs\r\n",
+            "END:VCARD\r\n"
+        );
+        let expected = concat!(
+            "BEGIN:VCARD\r\n",
+            "VERSION:3.0\r\n",
+            "N:Петровић;Ана;;;\r\n",
+            "FN:Ана Петровић\r\n",
+            "item1.ADR;TYPE=\"home,pref\":;;Његошева 5;Нови Сад;;21000;Ср\r\n",
+            " бија\r\n",
+            "NOTE;LANGUAGE=sr-Latn:Прва линија\\nњива\\, друга\r\n",
+            "NOTE;ENCODING=QUOTED-PRINTABLE:Ko=C5=A1ava\r\n",
+            "EMAIL;TYPE=work:ana@primer.rs\r\n",
+            "END:VCARD\r\n"
+        );
+        assert_eq!(p.convert(contact).unwrap(), expected);
+
+        let calendar = concat!(
+            "BEGIN:VEVENT\n",
+            "DTSTART:20240501T120000Z\n",
+            "SUMMARY:Sastanak\n",
+            "DESCRIPTION:Dogovor o planu rada za sledeću godinu\\, budžetu i novim\n",
+            "  projektima\n",
+            "LOCATION:Velika \n",
+            "\tsala\n",
+            "UID:sastanak@primer.rs\n",
+            "END:VEVENT"
+        );
+        let expected = concat!(
+            "BEGIN:VEVENT\n",
+            "DTSTART:20240501T120000Z\n",
+            "SUMMARY:Састанак\n",
+            "DESCRIPTION:Договор о плану рада за следећу го\n",
+            " дину\\, буџету и новим пројектима\n",
+            "LOCATION:Велика сала\n",
+            "UID:sastanak@primer.rs\n",
+            "END:VEVENT"
+        );
+        assert_eq!(p.convert(calendar).unwrap(), expected);
+
+        // Lines longer than 75 octets are folded on character boundaries
+        let contact = concat!(
+            "BEGIN:VCARD\r\n",
+            "FN:Aleksandra Jovanović Petrović Nikolić Đorđević\r\n",
+            "NOTE:Beleška o kontaktu koja je napisana u jednom redu\\, bez prelamanja\r\n",
+            "URL:https://primer.rs/kontakti/aleksandra-jovanovic-petrovic-nikolic-djordjevic\r\n",
+            "END:VCARD"
+        );
+        let expected = concat!(
+            "BEGIN:VCARD\r\n",
+            "FN:Александра Јовановић Петровић Николић \r\n",
+            " Ђорђевић\r\n",
+            "NOTE:Белешка о контакту која је написана у \r\n",
+            " једном реду\\, без преламања\r\n",
+            "URL:https://primer.rs/kontakti/aleksandra-jovanovic-petrovic-nikolic-djordj\r\n",
+            " evic\r\n",
+            "END:VCARD"
+        );
+        let converted = p.convert(contact).unwrap();
+        assert_eq!(converted, expected);
+        assert!(converted.lines().all(|l| l.len() <= LINE_LENGTH));
+    }
+}
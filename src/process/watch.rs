@@ -0,0 +1,176 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use notify::{RecursiveMode, Watcher};
+
+use super::{atomic, Error, FileProcessor};
+use crate::transliterate::Transliterator;
+
+/// Keeps a transliterated copy of a directory up to date. All files are
+/// converted when started, and then again whenever they change. Files
+/// which are not valid UTF-8 are copied unchanged, and hidden files
+/// (ie. editor swap files) are ignored.
+pub struct WatchProcessor {
+    source: PathBuf,
+    output: PathBuf,
+    processor: Transliterator,
+}
+
+fn other(error: notify::Error) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::Other, error))
+}
+
+fn hidden(path: &Path) -> bool {
+    path.file_name()
+        .map_or(false, |name| name.to_string_lossy().starts_with('.'))
+}
+
+impl WatchProcessor {
+    /// Watch directory `source` and write converted files into `output`.
+    /// Fails if `output` is inside `source`.
+    pub fn new(source: PathBuf, output: PathBuf, processor: Transliterator) -> Result<Self, Error> {
+        fs::create_dir_all(&output)?;
+        let (source, output) = (source.canonicalize()?, output.canonicalize()?);
+        if output.starts_with(&source) {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "output directory is inside watched directory",
+            )));
+        }
+        Ok(Self {
+            source,
+            output,
+            processor,
+        })
+    }
+
+    /// Returns path in the output directory which mirrors `path`
+    fn target(&self, path: &Path) -> Option<PathBuf> {
+        let relative = path.strip_prefix(&self.source).ok()?;
+        if relative.components().any(|c| hidden(Path::new(&c))) {
+            return None;
+        }
+        Some(self.output.join(relative))
+    }
+
+    /// Convert file at `path` into the output directory, or remove its
+    /// converted copy if it no longer exists. Returns path of the
+    /// converted copy if anything was done.
+    pub fn update(&self, path: &Path) -> Result<Option<PathBuf>, Error> {
+        let target = match self.target(path) {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+        if path.is_dir() {
+            fs::create_dir_all(&target)?;
+            return Ok(None);
+        }
+        if !path.exists() {
+            return Ok(match target.is_dir() {
+                true => fs::remove_dir_all(&target).map(|_| Some(target))?,
+                false if target.exists() => fs::remove_file(&target).map(|_| Some(target))?,
+                false => None,
+            });
+        }
+        let output = match String::from_utf8(fs::read(path)?) {
+            Ok(text) => self.processor.process(text)?.into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::read(&target).ok().as_deref() != Some(&output[..]) {
+            atomic::write(&target, &output, false)?;
+        }
+        Ok(Some(target))
+    }
+
+    /// Convert all files in the watched directory
+    pub fn sync(&self) -> Result<Vec<PathBuf>, Error> {
+        let mut updated = Vec::new();
+        let mut directories = vec![self.source.clone()];
+        while let Some(directory) = directories.pop() {
+            let mut children = fs::read_dir(&directory)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<PathBuf>, io::Error>>()?;
+            children.sort();
+            for child in children {
+                if hidden(&child) {
+                    continue;
+                }
+                if child.is_dir() {
+                    directories.push(child);
+                } else if let Some(target) = self.update(&child)? {
+                    updated.push(target);
+                }
+            }
+        }
+        Ok(updated)
+    }
+}
+
+impl FileProcessor for WatchProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut output = io::stdout();
+        for target in self.sync()? {
+            writeln!(output, "{}", target.display())?;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(other)?;
+        watcher
+            .watch(&self.source, RecursiveMode::Recursive)
+            .map_err(other)?;
+        for event in receiver {
+            let event = event.map_err(other)?;
+            if event.kind.is_access() {
+                continue;
+            }
+            for path in event.paths {
+                // Report errors and keep watching, ie. when file is being written
+                match self.update(&path) {
+                    Ok(Some(target)) => writeln!(output, "{}", target.display())?,
+                    Ok(None) => {}
+                    Err(e) => eprint!("{}", e),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+    use std::env;
+
+    #[test]
+    fn test_sync() {
+        let root = env::temp_dir().join(format!("translitrs-watch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/posts")).unwrap();
+        fs::write(root.join("src/index.md"), "# Početna").unwrap();
+        fs::write(root.join("src/posts/prvi.md"), "Prvi tekst").unwrap();
+        fs::write(root.join("src/slika.png"), [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+        fs::write(root.join("src/.index.md.swp"), "Početna").unwrap();
+
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let w = WatchProcessor::new(root.join("src"), root.join("dist"), t).unwrap();
+        assert_eq!(w.sync().unwrap().len(), 3);
+        assert_eq!(fs::read_to_string(root.join("dist/index.md")).unwrap(), "# Почетна");
+        assert_eq!(fs::read_to_string(root.join("dist/posts/prvi.md")).unwrap(), "Први текст");
+        assert_eq!(fs::read(root.join("dist/slika.png")).unwrap(), [0x89, b'P', b'N', b'G', 0xff]);
+        assert!(!root.join("dist/.index.md.swp").exists());
+
+        let source = root.join("src").canonicalize().unwrap();
+        fs::remove_file(source.join("posts/prvi.md")).unwrap();
+        assert!(w.update(&source.join("posts/prvi.md")).unwrap().is_some());
+        assert!(!root.join("dist/posts/prvi.md").exists());
+
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        assert!(WatchProcessor::new(root.join("src"), root.join("src/dist"), t).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
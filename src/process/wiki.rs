@@ -0,0 +1,370 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::Transliterator;
+
+lazy_static! {
+    /// HTML or extension tag (ie. `<ref name="a">`, `<br />`)
+    static ref TAG: Regex = Regex::new(r"^<(/?)([A-Za-z][\w-]*)(?:\s[^<>]*?)?(/?)>").unwrap();
+    /// Behavior switch (ie. `__TOC__`)
+    static ref SWITCH: Regex = Regex::new(r"^__[A-Z]+__").unwrap();
+    /// Redirect at the start of a page
+    static ref REDIRECT: Regex = Regex::new(r"(?i)^\s*#(?:redirect|preusmeri|преусмери)").unwrap();
+    /// Target of an external link
+    static ref URL: Regex = Regex::new(r"(?i)^\[(?:https?://|ftp://|//|mailto:)").unwrap();
+    /// Option of an image link (ie. `thumb`, `200px`, `alt=Grad`)
+    static ref IMAGE_OPTION: Regex = Regex::new(concat!(
+        r"(?i)^\s*(?:thumb|thumbnail|mini|мини|frame|framed|frameless|border|left|right|center|centre|none",
+        r"|baseline|sub|super|top|text-top|middle|bottom|text-bottom|upright(?:\s*=.*)?",
+        r"|[0-9]*x?[0-9]+\s*px|[\w-]+\s*=.*)\s*$"
+    ))
+    .unwrap();
+}
+
+/// Extension tags whose content is left unchanged
+const PROTECTED: &[&str] = &[
+    "ref",
+    "references",
+    "nowiki",
+    "pre",
+    "math",
+    "chem",
+    "code",
+    "syntaxhighlight",
+    "source",
+    "score",
+    "timeline",
+    "graph",
+    "templatedata",
+    "gallery",
+    "mapframe",
+    "maplink",
+];
+
+/// Namespaces of links to images and other files
+const FILES: &[&str] = &[
+    "file",
+    "image",
+    "media",
+    "datoteka",
+    "slika",
+    "medij",
+    "датотека",
+    "слика",
+    "медиј",
+];
+
+/// Namespaces of links which are not displayed
+const CATEGORIES: &[&str] = &["category", "kategorija", "категорија"];
+
+/// Returns position after the end of a construct starting at `start` of
+/// `text` with `open`, where constructs with the same delimiters may be
+/// nested (ie. templates in templates)
+fn matching(text: &str, start: usize, open: &str, close: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start;
+    while i < text.len() {
+        if text.as_bytes()[i..].starts_with(open.as_bytes()) {
+            depth += 1;
+            i += open.len();
+        } else if text.as_bytes()[i..].starts_with(close.as_bytes()) {
+            depth -= 1;
+            i += close.len();
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Split `text` on `separator`, except within links and templates
+fn split_outer<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let bytes = text.as_bytes();
+    let mut parts = Vec::new();
+    let (mut depth, mut last, mut i) = (0usize, 0, 0);
+    while i < bytes.len() {
+        match &bytes[i..] {
+            [b'[', b'[', ..] | [b'{', b'{', ..] => {
+                depth += 1;
+                i += 2;
+            }
+            [b']', b']', ..] | [b'}', b'}', ..] => {
+                depth = depth.saturating_sub(1);
+                i += 2;
+            }
+            rest if depth == 0 && rest.starts_with(separator.as_bytes()) => {
+                parts.push(&text[last..i]);
+                i += separator.len();
+                last = i;
+            }
+            _ => i += 1,
+        }
+    }
+    parts.push(&text[last..]);
+    parts
+}
+
+/// Transliterates prose of MediaWiki wikitext (ie. Wikipedia articles).
+/// Templates (`{{...}}`), link targets (only labels of `[[Target|label]]`
+/// are converted), references, comments, extension tags (ie. `<math>`),
+/// table syntax and text protected from the language converter
+/// (`-{...}-`) are left unchanged.
+pub struct WikiProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl WikiProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate contents of an internal link, without its brackets
+    fn convert_link(&self, inner: &str, output: &mut String) -> Result<(), Error> {
+        let parts = split_outer(inner, "|");
+        let namespace = parts[0]
+            .trim_start()
+            .trim_start_matches(':')
+            .split_once(':')
+            .map(|(namespace, _)| namespace.trim().to_lowercase())
+            .unwrap_or_default();
+        // Links without labels display their targets, and categories
+        // are not displayed
+        if parts.len() == 1 || CATEGORIES.contains(&namespace.as_str()) {
+            output.push_str(inner);
+            return Ok(());
+        }
+        output.push_str(parts[0]);
+        let image = FILES.contains(&namespace.as_str());
+        for part in &parts[1..] {
+            output.push('|');
+            match image && IMAGE_OPTION.is_match(part) {
+                true => output.push_str(part),
+                false => self.convert_text(part, false, output)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Transliterate a line of table cells, without its first character
+    fn convert_cells(&self, line: &str, header: bool, output: &mut String) -> Result<(), Error> {
+        let separator = if header { "!!" } else { "||" };
+        for (n, cell) in split_outer(line, separator).into_iter().enumerate() {
+            if n > 0 {
+                output.push_str(separator);
+            }
+            // Cells may start with attributes (ie. `style="..." | text`)
+            let parts = split_outer(cell, "|");
+            let content = match parts.as_slice() {
+                [attributes, _, ..] if !attributes.is_empty() => {
+                    output.push_str(attributes);
+                    output.push('|');
+                    &cell[attributes.len() + 1..]
+                }
+                _ => cell,
+            };
+            self.convert_text(content, false, output)?;
+        }
+        Ok(())
+    }
+
+    /// Transliterate wikitext into `output`. Lines of tables and redirects
+    /// are recognized only in whole pages, not in labels and cells.
+    fn convert_text(&self, text: &str, page: bool, output: &mut String) -> Result<(), Error> {
+        let bytes = text.as_bytes();
+        let mut tables = 0;
+        // Prose before this position is already in output
+        let mut last = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let start = i;
+            if page && (i == 0 || bytes[i - 1] == b'\n') {
+                let end = text[i..].find('\n').map_or(text.len(), |n| i + n);
+                let line = &text[i..end];
+                let trimmed = line.trim_start();
+                let indent = &line[..line.len() - trimmed.len()];
+                if i == 0 && REDIRECT.is_match(line) {
+                    i += REDIRECT.find(line).map_or(0, |m| m.end());
+                } else if trimmed.starts_with("{|") {
+                    tables += 1;
+                    i = end;
+                } else if tables > 0 && (trimmed.starts_with("|}") || trimmed.starts_with("|-")) {
+                    if trimmed.starts_with("|}") {
+                        tables -= 1;
+                    }
+                    i = end;
+                } else if tables > 0 && (trimmed.starts_with('|') || trimmed.starts_with('!')) {
+                    output.push_str(&self.processor.process(&text[last..start])?);
+                    // Captions (`|+`) may have attributes as cells do
+                    let marker = match trimmed.starts_with("|+") {
+                        true => 2,
+                        false => 1,
+                    };
+                    output.push_str(indent);
+                    output.push_str(&trimmed[..marker]);
+                    self.convert_cells(&trimmed[marker..], trimmed.starts_with('!'), output)?;
+                    i = end;
+                    last = end;
+                    continue;
+                }
+            }
+            if i == start {
+                let rest = &bytes[i..];
+                let end = match rest {
+                    [b'<', b'!', b'-', b'-', ..] => {
+                        Some(text[i..].find("-->").map_or(text.len(), |n| i + n + 3))
+                    }
+                    [b'-', b'{', ..] => text[i..].find("}-").map(|n| i + n + 2),
+                    [b'{', b'{', ..] => matching(text, i, "{{", "}}"),
+                    [b'_', b'_', ..] => SWITCH.find(&text[i..]).map(|m| i + m.end()),
+                    [b'<', ..] => TAG.captures(&text[i..]).map(|tag| {
+                        let whole = i + tag[0].len();
+                        let name = tag[2].to_lowercase();
+                        match tag[1].is_empty() && tag[3].is_empty() && PROTECTED.contains(&&*name)
+                        {
+                            true => {
+                                let close = Regex::new(&format!(r"(?i)</{}\s*>", name)).unwrap();
+                                close
+                                    .find(&text[whole..])
+                                    .map_or(text.len(), |m| whole + m.end())
+                            }
+                            false => whole,
+                        }
+                    }),
+                    [b'[', b'[', ..] => match matching(text, i, "[[", "]]") {
+                        Some(end) => {
+                            output.push_str(&self.processor.process(&text[last..i])?);
+                            output.push_str("[[");
+                            self.convert_link(&text[i + 2..end - 2], output)?;
+                            output.push_str("]]");
+                            i = end;
+                            last = end;
+                            continue;
+                        }
+                        None => None,
+                    },
+                    [b'[', ..] if URL.is_match(&text[i..]) => match text[i..].find(']') {
+                        Some(n) => {
+                            let link = &text[i..i + n];
+                            let target = link.find(' ').unwrap_or(link.len());
+                            output.push_str(&self.processor.process(&text[last..i])?);
+                            output.push_str(&link[..target]);
+                            self.convert_text(&link[target..], false, output)?;
+                            output.push(']');
+                            i += n + 1;
+                            last = i;
+                            continue;
+                        }
+                        None => None,
+                    },
+                    _ => None,
+                };
+                match end {
+                    Some(end) => i = end,
+                    None => {
+                        i += 1;
+                        continue;
+                    }
+                }
+            }
+            // Construct from `start` to `i` is left unchanged
+            output.push_str(&self.processor.process(&text[last..start])?);
+            output.push_str(&text[start..i]);
+            last = i;
+        }
+        output.push_str(&self.processor.process(&text[last..])?);
+        Ok(())
+    }
+
+    /// Transliterate a wikitext page
+    pub fn convert(&self, page: &str) -> Result<String, Error> {
+        let mut output = String::with_capacity(page.len());
+        self.convert_text(page, true, &mut output)?;
+        Ok(output)
+    }
+}
+
+with_backup!(WikiProcessor);
+
+impl FileProcessor for WikiProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_wikitext() {
+        let p = WikiProcessor::new(
+            None,
+            None,
+            Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false),
+        )
+        .unwrap();
+        let page = concat!(
+            "{{Infobox grad|ime=Novi Sad|{{lang|sr|Novi Sad}}}}__NOTOC__\n",
+            "'''Novi Sad''' je [[Grad (Srbija)|grad]] u [[Vojvodina|Vojvodini]] na [[Dunav]]u.",
+            "<ref name=\"pop\">Popis {{cite|godina=2022}}</ref><ref name=\"pop\" />\n",
+            "== Istorija ==\n",
+            "[[Datoteka:Petrovaradin.jpg|mini|200px|alt=Tvrđava|Tvrđava [[Petrovaradin]]]]\n",
+            "Grad je <!-- proveriti -->osnovan <small>1694.</small> godine, ",
+            "<math>x^2</math> -{Novi Sad}- [https://novisad.rs zvanični sajt].\n",
+            "{| class=\"wikitable\"\n",
+            "|+ style=\"text-align:left\" | Stanovništvo\n",
+            "|-\n",
+            "! Godina !! Broj\n",
+            "|-\n",
+            "| style=\"color:red\" | Ukupno || [[Popis|popis]]\n",
+            "|}\n",
+            "! Kraj\n",
+            "[[Kategorija:Gradovi u Srbiji|Novi Sad]]"
+        );
+        let expected = concat!(
+            "{{Infobox grad|ime=Novi Sad|{{lang|sr|Novi Sad}}}}__NOTOC__\n",
+            "'''Нови Сад''' је [[Grad (Srbija)|град]] у [[Vojvodina|Војводини]] на [[Dunav]]у.",
+            "<ref name=\"pop\">Popis {{cite|godina=2022}}</ref><ref name=\"pop\" />\n",
+            "== Историја ==\n",
+            "[[Datoteka:Petrovaradin.jpg|mini|200px|alt=Tvrđava|Тврђава [[Petrovaradin]]]]\n",
+            "Град је <!-- proveriti -->основан <small>1694.</small> године, ",
+            "<math>x^2</math> -{Novi Sad}- [https://novisad.rs званични сајт].\n",
+            "{| class=\"wikitable\"\n",
+            "|+ style=\"text-align:left\" | Становништво\n",
+            "|-\n",
+            "! Година !! Број\n",
+            "|-\n",
+            "| style=\"color:red\" | Укупно || [[Popis|попис]]\n",
+            "|}\n",
+            "! Крај\n",
+            "[[Kategorija:Gradovi u Srbiji|Novi Sad]]"
+        );
+        assert_eq!(p.convert(page).unwrap(), expected);
+        assert_eq!(
+            p.convert("#REDIRECT [[Novi Sad]] {{R}}").unwrap(),
+            "#REDIRECT [[Novi Sad]] {{R}}"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::io::{self, Cursor, Read, Write};
+use std::path::PathBuf;
+
+use quick_xml::escape::partial_escape;
+use quick_xml::events::{BytesText, Event};
+use quick_xml::{Reader, Writer};
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+/// Table of strings which cells of all worksheets refer to
+const SHARED_STRINGS: &str = "xl/sharedStrings.xml";
+/// Worksheets, whose cells may contain inline strings
+const WORKSHEETS: &str = "xl/worksheets/";
+
+fn xml_error(error: quick_xml::Error) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+fn zip_error(error: ZipError) -> Error {
+    Error::Io(error.into())
+}
+
+/// Transliterates text of cells in Excel workbooks (`.xlsx`), which is
+/// stored in the shared strings table and in inline strings. Formulas,
+/// numbers, sheet names, styles and other parts of the workbook are
+/// left unchanged, as well as phonetic hints of strings.
+pub struct XlsxProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+}
+
+impl XlsxProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+        })
+    }
+
+    /// Transliterate text of strings (`<si>` and `<is>` elements)
+    /// in shared strings table or worksheet XML
+    fn convert_xml(&self, xml: &str) -> Result<String, Error> {
+        let mut reader = Reader::from_str(xml);
+        let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+        // Depth inside string, text and phonetic hint elements
+        let (mut string, mut text, mut phonetic) = (0, 0, 0);
+        loop {
+            let event = match reader.read_event().map_err(xml_error)? {
+                Event::Eof => break,
+                Event::Start(element) => {
+                    match element.name().as_ref() {
+                        b"si" | b"is" => string += 1,
+                        b"t" => text += 1,
+                        b"rPh" => phonetic += 1,
+                        _ => {}
+                    }
+                    Event::Start(element)
+                }
+                Event::End(element) => {
+                    match element.name().as_ref() {
+                        b"si" | b"is" => string -= 1,
+                        b"t" => text -= 1,
+                        b"rPh" => phonetic -= 1,
+                        _ => {}
+                    }
+                    Event::End(element)
+                }
+                Event::Text(t) if string > 0 && text > 0 && phonetic == 0 => {
+                    // Text with unknown entities is left unchanged
+                    match t.unescape() {
+                        Ok(raw) => {
+                            let converted = self.processor.process(&raw)?;
+                            Event::Text(BytesText::from_escaped(partial_escape(converted)))
+                        }
+                        Err(_) => Event::Text(t),
+                    }
+                }
+                event => event,
+            };
+            writer.write_event(event)?;
+        }
+        Ok(String::from_utf8(writer.into_inner()).map_err(transliterate::Error::from)?)
+    }
+
+    /// Transliterate a workbook
+    pub fn convert(&self, workbook: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut archive = ZipArchive::new(Cursor::new(workbook)).map_err(zip_error)?;
+        let mut writer = ZipWriter::new(Cursor::new(Vec::with_capacity(workbook.len())));
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).map_err(zip_error)?;
+            let name = file.name().to_owned();
+            let worksheet = name.starts_with(WORKSHEETS) && name.ends_with(".xml");
+            if name != SHARED_STRINGS && !worksheet {
+                writer.raw_copy_file(file).map_err(zip_error)?;
+                continue;
+            }
+            let mut xml = String::new();
+            file.read_to_string(&mut xml)?;
+            let options = SimpleFileOptions::default()
+                .compression_method(file.compression())
+                .last_modified_time(file.last_modified().unwrap_or_default());
+            writer.start_file(name, options).map_err(zip_error)?;
+            writer.write_all(self.convert_xml(&xml)?.as_bytes())?;
+        }
+        Ok(writer.finish().map_err(zip_error)?.into_inner())
+    }
+}
+
+with_backup!(XlsxProcessor);
+
+impl FileProcessor for XlsxProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = Vec::new();
+        self.input.read_to_end(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(&output)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor() -> XlsxProcessor {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        XlsxProcessor::new(None, None, t).unwrap()
+    }
+
+    #[test]
+    fn test_strings() {
+        let strings = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="3">"#,
+            "<si><t>Prihodi &amp; rashodi</t></si>",
+            r#"<si><r><rPr><b/></rPr><t>Ukupno</t></r><r><t xml:space="preserve"> za godinu</t></r></si>"#,
+            "<si><t>Tokio</t><rPh sb=\"0\" eb=\"2\"><t>Tokijo</t></rPh></si>",
+            "</sst>"
+        );
+        let expected = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="3">"#,
+            "<si><t>Приходи &amp; расходи</t></si>",
+            r#"<si><r><rPr><b/></rPr><t>Укупно</t></r><r><t xml:space="preserve"> за годину</t></r></si>"#,
+            "<si><t>Токио</t><rPh sb=\"0\" eb=\"2\"><t>Tokijo</t></rPh></si>",
+            "</sst>"
+        );
+        assert_eq!(processor().convert_xml(strings).unwrap(), expected);
+        let sheet = concat!(
+            "<worksheet><sheetData><row r=\"1\">",
+            "<c r=\"A1\" t=\"inlineStr\"><is><t>Zarada</t></is></c>",
+            "<c r=\"B1\" t=\"str\"><f>CONCAT(\"Zbir \",C1)</f><v>Zbir 42</v></c>",
+            "<c r=\"C1\"><v>42</v></c>",
+            "</row></sheetData></worksheet>"
+        );
+        let expected = sheet.replace("<t>Zarada</t>", "<t>Зарада</t>");
+        assert_eq!(processor().convert_xml(sheet).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_workbook() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        writer.start_file("[Content_Types].xml", options).unwrap();
+        writer.write_all(b"<Types/>").unwrap();
+        writer.start_file("xl/workbook.xml", options).unwrap();
+        writer
+            .write_all(b"<workbook><sheets><sheet name=\"Prodaja\"/></sheets></workbook>")
+            .unwrap();
+        writer.start_file(SHARED_STRINGS, options).unwrap();
+        writer
+            .write_all(b"<sst><si><t>Prodaja</t></si></sst>")
+            .unwrap();
+        let workbook = writer.finish().unwrap().into_inner();
+
+        let output = processor().convert(&workbook).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(output)).unwrap();
+        let mut read = |name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+        assert_eq!(read("[Content_Types].xml"), "<Types/>");
+        assert_eq!(
+            read("xl/workbook.xml"),
+            "<workbook><sheets><sheet name=\"Prodaja\"/></sheets></workbook>"
+        );
+        assert_eq!(read(SHARED_STRINGS), "<sst><si><t>Продаја</t></si></sst>");
+    }
+}
@@ -0,0 +1,469 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{open, Error, FileProcessor, Output};
+use crate::transliterate::{self, Transliterator};
+
+lazy_static! {
+    /// Plain scalars which are not strings (booleans, null, numbers and dates)
+    static ref SPECIAL: Regex = Regex::new(concat!(
+        r"^(?i:true|false|yes|no|on|off|y|n|null|~|[-+]?\.inf|\.nan)$",
+        r"|^[-+]?[0-9.][0-9a-fA-FxXoO._:eE+-]*$",
+        r"|^[0-9]{4}-[0-9]{1,2}-[0-9]{1,2}(?:[Tt ].*)?$"
+    ))
+    .unwrap();
+    /// Escape sequences in double-quoted scalars
+    static ref ESCAPE: Regex =
+        Regex::new(r"\\(?:x[0-9a-fA-F]{2}|u[0-9a-fA-F]{4}|U[0-9a-fA-F]{8}|.)").unwrap();
+}
+
+/// Returns index of the closing `quote` of a quoted scalar,
+/// whose contents start at the beginning of `text`
+fn quoted_end(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            // Single quotes are escaped by repeating them
+            '\'' if quote == '\'' && chars.peek().map(|(_, c)| *c) == Some('\'') => {
+                chars.next();
+            }
+            c if c == quote => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns key of a mapping entry at the beginning of `text`,
+/// and index after its colon
+fn key(text: &str) -> Option<(String, usize)> {
+    let separator = |i: usize| {
+        text[i..].starts_with(':')
+            && text[i + 1..]
+                .chars()
+                .next()
+                .map_or(true, char::is_whitespace)
+    };
+    if let Some(quote) = text.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+        let end = 1 + quoted_end(&text[1..], quote)?;
+        return separator(end + 1).then(|| (text[1..end].to_owned(), end + 2));
+    }
+    if text.starts_with(|c| "-?[]{}#&*!|>%@`,".contains(c)) {
+        return None;
+    }
+    let comment = text.find(" #").unwrap_or(text.len());
+    let colon = text[..comment]
+        .char_indices()
+        .find(|&(i, _)| separator(i))?
+        .0;
+    Some((text[..colon].trim_end().to_owned(), colon + 1))
+}
+
+/// Multi-line scalar which continues on the next line
+enum State {
+    None,
+    /// Block scalar (`|` or `>`) whose lines are indented more than `indent`
+    Block {
+        indent: usize,
+        convert: bool,
+    },
+    /// Quoted scalar which is not closed yet
+    Quoted {
+        quote: char,
+        convert: bool,
+    },
+}
+
+/// Converts a YAML document line by line
+struct Converter<'a> {
+    processor: &'a YamlProcessor,
+    /// Keys of the current value, with their indentation
+    path: Vec<(usize, String)>,
+    state: State,
+}
+
+impl Converter<'_> {
+    fn text(&self, text: &str, convert: bool) -> Result<String, transliterate::Error> {
+        match convert {
+            true => self.processor.processor.process(text),
+            false => Ok(text.to_owned()),
+        }
+    }
+
+    fn quoted(&self, text: &str, quote: char, convert: bool) -> Result<String, Error> {
+        if !convert || quote == '\'' {
+            return Ok(self.text(text, convert)?);
+        }
+        let mut output = String::with_capacity(text.len());
+        let mut last = 0;
+        for escape in ESCAPE.find_iter(text) {
+            output.push_str(&self.text(&text[last..escape.start()], true)?);
+            output.push_str(escape.as_str());
+            last = escape.end();
+        }
+        output.push_str(&self.text(&text[last..], true)?);
+        Ok(output)
+    }
+
+    fn plain(&self, text: &str, convert: bool) -> Result<String, Error> {
+        Ok(self.text(text, convert && !SPECIAL.is_match(text))?)
+    }
+
+    /// Convert items of a flow sequence (ie. `[a, "b"]`). Other flow
+    /// collections are left unchanged.
+    fn flow(&self, text: &str, convert: bool) -> Result<String, Error> {
+        let close = match text.rfind(']') {
+            Some(close) if convert => close,
+            _ => return Ok(text.to_owned()),
+        };
+        let inner = &text[1..close];
+        if inner.contains(|c| "[]{}#:".contains(c)) {
+            return Ok(text.to_owned());
+        }
+        let mut items = Vec::new();
+        for item in inner.split(',') {
+            let value = item.trim();
+            let start = item.len() - item.trim_start().len();
+            let converted = match value.chars().next() {
+                Some(q @ ('"' | '\'')) if value.len() > 1 && value.ends_with(q) => {
+                    let inner = &value[1..value.len() - 1];
+                    if quoted_end(inner, q).is_some() {
+                        return Ok(text.to_owned());
+                    }
+                    format!("{q}{}{q}", self.quoted(inner, q, true)?)
+                }
+                _ if value.contains(['"', '\'']) => return Ok(text.to_owned()),
+                _ => self.plain(value, true)?,
+            };
+            items.push(format!("{}{}{}", &item[..start], converted, &item[start + value.len()..]));
+        }
+        Ok(format!("[{}{}", items.join(","), &text[close..]))
+    }
+
+    /// Convert value of a node, whose block scalar lines are indented
+    /// more than `parent`
+    fn value(&mut self, text: &str, parent: usize, convert: bool) -> Result<String, Error> {
+        let mut rest = text.trim_start();
+        let mut output = text[..text.len() - rest.len()].to_owned();
+        // Anchors and tags
+        while rest.starts_with(['&', '!']) {
+            let end = rest.find(' ').unwrap_or(rest.len());
+            let next = rest[end..].trim_start();
+            output.push_str(&rest[..rest.len() - next.len()]);
+            rest = next;
+        }
+        match rest.chars().next() {
+            Some('|' | '>') => {
+                self.state = State::Block {
+                    indent: parent,
+                    convert,
+                };
+                output.push_str(rest);
+            }
+            Some(quote @ ('"' | '\'')) => {
+                output.push(quote);
+                let inner = &rest[1..];
+                match quoted_end(inner, quote) {
+                    Some(end) => {
+                        output.push_str(&self.quoted(&inner[..end], quote, convert)?);
+                        output.push_str(&inner[end..]);
+                    }
+                    None => {
+                        output.push_str(&self.quoted(inner, quote, convert)?);
+                        self.state = State::Quoted { quote, convert };
+                    }
+                }
+            }
+            Some('[') => output.push_str(&self.flow(rest, convert)?),
+            Some('*' | '{' | '#') | None => output.push_str(rest),
+            Some(_) => {
+                let comment = rest.find(" #").unwrap_or(rest.len());
+                let scalar = rest[..comment].trim_end();
+                output.push_str(&self.plain(scalar, convert)?);
+                output.push_str(&rest[scalar.len()..]);
+            }
+        }
+        Ok(output)
+    }
+
+    /// End nodes which are indented at least as much as `column`
+    fn pop(&mut self, column: usize) {
+        while matches!(self.path.last(), Some((indent, _)) if *indent >= column) {
+            self.path.pop();
+        }
+    }
+
+    fn line(&mut self, line: &str) -> Result<String, Error> {
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        match self.state {
+            State::Block {
+                indent: parent,
+                convert,
+            } => {
+                if line.trim().is_empty() {
+                    return Ok(line.to_owned());
+                }
+                if indent > parent {
+                    let text = self.text(&line[indent..], convert)?;
+                    return Ok(format!("{}{}", &line[..indent], text));
+                }
+                self.state = State::None;
+            }
+            State::Quoted { quote, convert } => {
+                return Ok(match quoted_end(line, quote) {
+                    Some(end) => {
+                        self.state = State::None;
+                        self.quoted(&line[..end], quote, convert)? + &line[end..]
+                    }
+                    None => self.quoted(line, quote, convert)?,
+                });
+            }
+            State::None => {}
+        }
+        let mut rest = &line[indent..];
+        if rest.trim().is_empty() || rest.starts_with(['#', '%']) {
+            return Ok(line.to_owned());
+        }
+        let marker = rest.starts_with("---") || rest.starts_with("...");
+        if indent == 0 && marker && (rest.len() == 3 || rest[3..].starts_with(char::is_whitespace))
+        {
+            // Document markers
+            self.path.clear();
+            return Ok(line.to_owned());
+        }
+        let mut output = line[..indent].to_owned();
+        let mut column = indent;
+        // Sequence items are part of their parent's path
+        while rest == "-" || rest.starts_with("- ") {
+            self.pop(column);
+            let next = rest[1..].trim_start();
+            let length = rest.len() - next.len();
+            output.push_str(&rest[..length]);
+            column += length;
+            rest = next;
+        }
+        let parent = match key(rest) {
+            Some((key, colon)) => {
+                self.pop(column);
+                self.path.push((column, key));
+                output.push_str(&rest[..colon]);
+                rest = &rest[colon..];
+                column
+            }
+            None => {
+                self.pop(column);
+                column.saturating_sub(1)
+            }
+        };
+        let convert = self.processor.selected(&self.path);
+        output.push_str(&self.value(rest, parent, convert)?);
+        Ok(output)
+    }
+}
+
+/// Transliterates string values of YAML documents, ie. localization files
+/// and front matter of static site pages. Keys, anchors, tags, comments
+/// and formatting are left unchanged, as well as booleans, numbers and
+/// dates written as plain scalars.
+pub struct YamlProcessor {
+    input: Box<dyn Read>,
+    output: Output,
+    processor: Transliterator,
+    keys: Vec<Vec<String>>,
+    front_matter: bool,
+}
+
+impl YamlProcessor {
+    pub fn new(
+        input: Option<PathBuf>,
+        output: Option<PathBuf>,
+        processor: Transliterator,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            input: open(input)?,
+            output: Output::new(output),
+            processor,
+            keys: Vec::new(),
+            front_matter: false,
+        })
+    }
+
+    /// Convert only values with these key paths (ie. `title` or `seo.*`),
+    /// and values nested in them. All values are converted if empty.
+    pub fn with_keys(mut self, keys: &[String]) -> Self {
+        self.keys = keys
+            .iter()
+            .map(|k| k.split('.').map(str::to_owned).collect())
+            .collect();
+        self
+    }
+
+    /// Input is a document (ie. Markdown) which starts with YAML front
+    /// matter between `---` lines. Text after it is converted as well.
+    pub fn with_front_matter(mut self, front_matter: bool) -> Self {
+        self.front_matter = front_matter;
+        self
+    }
+
+    /// Returns whether values with key `path` are converted
+    fn selected(&self, path: &[(usize, String)]) -> bool {
+        self.keys.is_empty()
+            || self.keys.iter().any(|keys| {
+                keys.len() <= path.len()
+                    && keys
+                        .iter()
+                        .zip(path)
+                        .all(|(key, (_, name))| key == "*" || key == name)
+            })
+    }
+
+    fn convert_yaml(&self, yaml: &str) -> Result<String, Error> {
+        let mut converter = Converter {
+            processor: self,
+            path: Vec::new(),
+            state: State::None,
+        };
+        let mut output = String::with_capacity(yaml.len());
+        for line in yaml.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            output.push_str(&converter.line(content)?);
+            output.push_str(&line[content.len()..]);
+        }
+        Ok(output)
+    }
+
+    /// Transliterate a YAML document, or a document with front matter
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        if !self.front_matter {
+            return self.convert_yaml(text);
+        }
+        let mut lines = text.split_inclusive('\n');
+        if lines.next().map(str::trim_end) != Some("---") {
+            return Ok(self.processor.process(text)?);
+        }
+        let mut end = None;
+        let mut position = text.find('\n').map_or(text.len(), |p| p + 1);
+        for line in lines {
+            position += line.len();
+            if matches!(line.trim_end(), "---" | "...") {
+                end = Some(position);
+                break;
+            }
+        }
+        match end {
+            Some(end) => {
+                Ok(self.convert_yaml(&text[..end])? + &self.processor.process(&text[end..])?)
+            }
+            None => self.convert_yaml(text),
+        }
+    }
+}
+
+with_backup!(YamlProcessor);
+
+impl FileProcessor for YamlProcessor {
+    fn run(&mut self) -> Result<(), Error> {
+        let mut input = String::new();
+        self.input.read_to_string(&mut input)?;
+        let output = self.convert(&input)?;
+        self.output.write(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn processor() -> YamlProcessor {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        YamlProcessor::new(None, None, t).unwrap()
+    }
+
+    #[test]
+    fn test_yaml() {
+        let yaml = concat!(
+            "# Prevodi\n",
+            "naslov: Dobar dan # pozdrav\n",
+            "opis: &opis \"Prvi\\tred\\n\"\n",
+            "kopija: *opis\n",
+            "aktivan: yes\n",
+            "datum: 2024-01-01\n",
+            "oznake: [more, 'leto', planina]\n",
+            "meni:\n",
+            "  - ime: Početna\n",
+            "    link: https://primer.rs/pocetna\n",
+            "  - Kontakt\n",
+            "tekst: |\n",
+            "  Prvi red\n",
+            "\n",
+            "  drugi red\n",
+            "kraj: 'Ne''ma\n",
+            "  više'\n",
+            "---\n",
+            "broj: 1e3\n"
+        );
+        let expected = concat!(
+            "# Prevodi\n",
+            "naslov: Добар дан # pozdrav\n",
+            "opis: &opis \"Први\\tред\\n\"\n",
+            "kopija: *opis\n",
+            "aktivan: yes\n",
+            "datum: 2024-01-01\n",
+            "oznake: [море, 'лето', планина]\n",
+            "meni:\n",
+            "  - ime: Почетна\n",
+            "    link: https://primer.rs/pocetna\n",
+            "  - Контакт\n",
+            "tekst: |\n",
+            "  Први ред\n",
+            "\n",
+            "  други ред\n",
+            "kraj: 'Не''ма\n",
+            "  више'\n",
+            "---\n",
+            "broj: 1e3\n"
+        );
+        assert_eq!(processor().convert(yaml).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_keys() {
+        let p = processor().with_keys(&["naslov".to_owned(), "seo.*".to_owned()]);
+        let yaml = concat!(
+            "naslov: Vesti\n",
+            "slug: vesti\n",
+            "seo:\n",
+            "  opis: Najnovije vesti\n",
+            "  kljucne:\n",
+            "    - politika\n",
+            "slika: vesti.png\n"
+        );
+        let expected = concat!(
+            "naslov: Вести\n",
+            "slug: vesti\n",
+            "seo:\n",
+            "  opis: Најновије вести\n",
+            "  kljucne:\n",
+            "    - политика\n",
+            "slika: vesti.png\n"
+        );
+        assert_eq!(p.convert(yaml).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_front_matter() {
+        let p = processor().with_front_matter(true);
+        let text = "---\ntitle: Dobar dan\ndraft: false\n---\n# Dobar dan\n\nTekst\n";
+        let expected = "---\ntitle: Добар дан\ndraft: false\n---\n# Добар дан\n\nТекст\n";
+        assert_eq!(p.convert(text).unwrap(), expected);
+        assert_eq!(p.convert("Bez zaglavlja\n").unwrap(), "Без заглавља\n");
+    }
+}
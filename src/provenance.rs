@@ -0,0 +1,173 @@
+use std::fmt::{Display, Write as _};
+
+use sha2::{Digest, Sha256};
+
+use crate::json;
+use crate::options::Options;
+use crate::process::Error;
+use crate::transliterate::{rules_checksum, Profile};
+
+/// Returns SHA-256 digest of `data` in hexadecimal
+fn sha256(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .fold(String::with_capacity(64), |mut output, byte| {
+            let _ = write!(output, "{:02x}", byte);
+            output
+        })
+}
+
+/// Returns options as `key = "value"` lines, in a fixed order and without
+/// unset options, so their digest doesn't depend on features or
+/// formatting of configuration files
+fn canonical(options: &Options) -> String {
+    let mut output = String::new();
+    let mut value = |key: &str, value: Option<&dyn Display>| {
+        if let Some(value) = value {
+            let _ = writeln!(output, "{} = {}", key, json::string(&value.to_string()));
+        }
+    };
+    value("direction", options.direction.as_ref().map(|v| v as _));
+    value("language", options.language.as_ref().map(|v| v as _));
+    #[cfg(feature = "dialect")]
+    value("dialect", options.dialect.as_ref().map(|v| v as _));
+    value("archaic", options.archaic.as_ref().map(|v| v as _));
+    value("compounds", options.compounds.as_ref().map(|v| v as _));
+    value("alphanumeric", options.alphanumeric.as_ref().map(|v| v as _));
+    value("letters", options.letters.as_ref().map(|v| v as _));
+    value("skip-digraph", options.skip_digraph.as_ref().map(|v| v as _));
+    value("force-foreign", options.force_foreign.as_ref().map(|v| v as _));
+    value("force-links", options.force_links.as_ref().map(|v| v as _));
+    value("force-roman", options.force_roman.as_ref().map(|v| v as _));
+    value("fix-ocr", options.fix_ocr.as_ref().map(|v| v as _));
+    value("fold", options.fold.as_ref().map(|v| v as _));
+    value("titlecase-digraphs", options.titlecase_digraphs.as_ref().map(|v| v as _));
+    #[cfg(feature = "dates")]
+    value("dates", options.dates.as_ref().map(|v| v as _));
+    value("skip-converted", options.skip_converted.as_ref().map(|v| v as _));
+    #[cfg(feature = "ngram")]
+    value("ngram", options.ngram.as_ref().map(|v| v as _));
+    #[cfg(feature = "detect-language")]
+    value("detect-language", options.detect_language.as_ref().map(|v| v as _));
+    #[cfg(feature = "gazetteer")]
+    value("gazetteer", options.gazetteer.as_ref().map(|v| v as _));
+    value("ansi", options.ansi.as_ref().map(|v| v as _));
+    value("strict", options.strict.as_ref().map(|v| v as _));
+    value("format", options.format.as_ref().map(|v| v as _));
+    for (key, list) in [
+        ("exceptions", &options.exceptions),
+        ("protected", &options.protected),
+        ("skip-patterns", &options.skip_patterns),
+        ("keys", &options.keys),
+        ("add-properties", &options.add_properties),
+        ("passthrough", &options.passthrough),
+    ] {
+        if !list.is_empty() {
+            let list = list
+                .iter()
+                .map(|item| json::string(item))
+                .collect::<Vec<String>>();
+            let _ = writeln!(output, "{} = [{}]", key, list.join(", "));
+        }
+    }
+    output
+}
+
+/// Record of how a document was converted, which can be stored with the
+/// converted document to prove how it was produced (ie. by archives)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Provenance {
+    /// Version of the crate
+    pub version: String,
+    /// Identifier of rules with their checksum (ie. `translit-v1-…`), or
+    /// only their checksum if they are not a released profile
+    pub rules: String,
+    /// SHA-256 digest of options
+    pub options: String,
+    /// SHA-256 digest of the original document
+    pub input: String,
+    /// SHA-256 digest of the converted document
+    pub output: String,
+}
+
+impl Provenance {
+    /// Returns record of converting `input` into `output` with `options`
+    pub fn new(options: &Options, input: &[u8], output: &[u8]) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            rules: match Profile::LATEST.is_supported() {
+                true => Profile::LATEST.id(),
+                false => format!("{:016x}", rules_checksum()),
+            },
+            options: sha256(canonical(options).as_bytes()),
+            input: sha256(input),
+            output: sha256(output),
+        }
+    }
+
+    /// Convert `text` with `options`. Returns converted text and record
+    /// of the conversion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Options, Provenance};
+    /// let options = Options {
+    ///     direction: Some("cyr2lat".parse().unwrap()),
+    ///     ..Default::default()
+    /// };
+    /// let (text, provenance) = Provenance::convert(&options, "Повеља").unwrap();
+    /// assert_eq!(text, "Povelja");
+    /// assert_eq!(provenance, Provenance::new(&options, "Повеља".as_bytes(), b"Povelja"));
+    /// assert!(provenance.to_json().starts_with("{\"version\":"));
+    /// ```
+    pub fn convert(options: &Options, text: &str) -> Result<(String, Self), Error> {
+        let output = options.transliterator()?.process(text)?;
+        let provenance = Self::new(options, text.as_bytes(), output.as_bytes());
+        Ok((output, provenance))
+    }
+
+    /// Returns this record as a JSON object
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"version\":{},\"rules\":{},\"options\":{},\"input\":{},\"output\":{}}}",
+            json::string(&self.version),
+            json::string(&self.rules),
+            json::string(&self.options),
+            json::string(&self.input),
+            json::string(&self.output),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::{Direction, Fold};
+
+    #[test]
+    fn test_provenance() {
+        assert_eq!(sha256(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        let options = Options {
+            direction: "lat2cyr".parse::<Direction>().ok(),
+            force_roman: Some(true),
+            fold: Some(Fold::ALL),
+            protected: vec!["GitHub".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(
+            canonical(&options),
+            "direction = \"lat2cyr\"\nforce-roman = \"true\"\n\
+             fold = \"fullwidth,superscripts,subscripts,circled\"\nprotected = [\"GitHub\"]\n"
+        );
+        let (text, provenance) = Provenance::convert(&options, "GitHub i XIV vek").unwrap();
+        assert_eq!(text, "GitHub и XIV век");
+        assert_eq!(provenance.rules, Profile::LATEST.id());
+        assert_eq!(provenance.input, sha256(b"GitHub i XIV vek"));
+        assert_eq!(provenance.output, sha256(text.as_bytes()));
+        // Unset options are left out of the digest
+        let defaults = Provenance::new(&Options::default(), b"", b"");
+        assert_ne!(provenance.options, defaults.options);
+        assert_eq!(defaults.options, sha256(b""));
+    }
+}
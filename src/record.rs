@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::process::Error;
+use crate::transliterate::Transliterator;
+
+/// Values whose text is transliterated in place: strings, optional
+/// strings, their collections and structs whose text fields are listed
+/// with [`record!`](crate::record), so typed records (ie. rows of ETL
+/// pipelines) don't need field-by-field conversion code
+pub trait Record {
+    /// Transliterate text of this value in place
+    fn transliterate(&mut self, transliterator: &Transliterator) -> Result<(), Error>;
+}
+
+impl Record for String {
+    fn transliterate(&mut self, transliterator: &Transliterator) -> Result<(), Error> {
+        *self = transliterator.process(&*self)?;
+        Ok(())
+    }
+}
+
+impl<T: Record> Record for Option<T> {
+    fn transliterate(&mut self, transliterator: &Transliterator) -> Result<(), Error> {
+        match self {
+            Some(value) => value.transliterate(transliterator),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Record> Record for Box<T> {
+    fn transliterate(&mut self, transliterator: &Transliterator) -> Result<(), Error> {
+        (**self).transliterate(transliterator)
+    }
+}
+
+impl<T: Record> Record for Vec<T> {
+    fn transliterate(&mut self, transliterator: &Transliterator) -> Result<(), Error> {
+        self.iter_mut()
+            .try_for_each(|value| value.transliterate(transliterator))
+    }
+}
+
+/// Only values of maps are transliterated, since keys can't be changed in
+/// place
+impl<K, V: Record, S> Record for HashMap<K, V, S> {
+    fn transliterate(&mut self, transliterator: &Transliterator) -> Result<(), Error> {
+        self.values_mut()
+            .try_for_each(|value| value.transliterate(transliterator))
+    }
+}
+
+impl<K, V: Record> Record for BTreeMap<K, V> {
+    fn transliterate(&mut self, transliterator: &Transliterator) -> Result<(), Error> {
+        self.values_mut()
+            .try_for_each(|value| value.transliterate(transliterator))
+    }
+}
+
+/// Implement [`Record`] for a struct, transliterating listed fields in
+/// place. Fields can be of any type implementing [`Record`], including
+/// other records, while fields which are not listed are left intact.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{record, Charset, Record, Transliterator};
+///
+/// struct Address {
+///     street: String,
+///     number: u32,
+/// }
+///
+/// struct Person {
+///     id: u32,
+///     name: String,
+///     nickname: Option<String>,
+///     code: String,
+///     addresses: Vec<Address>,
+/// }
+///
+/// record!(Address { street });
+/// record!(Person { name, nickname, addresses });
+///
+/// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+/// let mut person = Person {
+///     id: 1,
+///     name: "Ljubica".to_owned(),
+///     nickname: None,
+///     code: "LJ01".to_owned(),
+///     addresses: vec![Address { street: "Knez Mihailova".to_owned(), number: 5 }],
+/// };
+/// person.transliterate(&t).unwrap();
+/// assert_eq!(person.name, "Љубица");
+/// assert_eq!(person.code, "LJ01");
+/// assert_eq!(person.addresses[0].street, "Кнез Михаилова");
+/// ```
+#[macro_export]
+macro_rules! record {
+    ($record:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::Record for $record {
+            fn transliterate(
+                &mut self,
+                transliterator: &$crate::Transliterator,
+            ) -> ::std::result::Result<(), $crate::Error> {
+                $($crate::Record::transliterate(&mut self.$field, transliterator)?;)*
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    struct Row {
+        title: String,
+        tags: Vec<String>,
+        notes: HashMap<u32, Option<String>>,
+        raw: String,
+    }
+
+    record!(Row { title, tags, notes });
+
+    #[test]
+    fn test_record() {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let mut row = Row {
+            title: "Његош".to_owned(),
+            tags: vec!["песник".to_owned(), "владика".to_owned()],
+            notes: HashMap::from([(1, Some("Цетиње".to_owned())), (2, None)]),
+            raw: "Његош".to_owned(),
+        };
+        row.transliterate(&t).unwrap();
+        assert_eq!(row.title, "Njegoš");
+        assert_eq!(row.tags, ["pesnik", "vladika"]);
+        assert_eq!(row.notes[&1].as_deref(), Some("Cetinje"));
+        assert_eq!(row.notes[&2], None);
+        assert_eq!(row.raw, "Његош");
+    }
+}
@@ -0,0 +1,121 @@
+use unicode_segmentation::{USentenceBoundIndices, UnicodeSegmentation};
+
+/// Iterator over sentences of a text
+pub struct Sentences<'a> {
+    inner: USentenceBoundIndices<'a>,
+}
+
+/// Iterator over paragraphs of a text
+pub struct Paragraphs<'a> {
+    text: &'a str,
+    cursor: usize,
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, s)| s)
+    }
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor >= self.text.len() {
+            return None;
+        }
+        let rest = &self.text[self.cursor..];
+        let mut end = rest.len();
+        let mut offset = 0;
+        // Paragraph ends with the first blank line, which is kept along
+        // with all following blank lines
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            if line.trim().is_empty() && offset > line.len() {
+                end = offset;
+                for line in rest[offset..].split_inclusive('\n') {
+                    if !line.trim().is_empty() {
+                        break;
+                    }
+                    end += line.len();
+                }
+                break;
+            }
+        }
+        let paragraph = &rest[..end];
+        self.cursor += end;
+        Some(paragraph)
+    }
+}
+
+/// Split text into sentences using Unicode sentence boundaries (UAX #29).
+/// Trailing whitespace is kept with each sentence, so joining all of them
+/// gives back the original text.
+///
+/// # Arguments
+///
+/// * `text` - Text to split
+///
+/// # Example
+///
+/// ```
+/// use translitrs::sentences;
+///
+/// let s: Vec<&str> = sentences("Prva rečenica. Druga rečenica!").collect();
+/// assert_eq!(s, vec!["Prva rečenica. ", "Druga rečenica!"]);
+/// ```
+pub fn sentences(text: &str) -> Sentences<'_> {
+    Sentences {
+        inner: text.split_sentence_bound_indices(),
+    }
+}
+
+/// Split text into paragraphs separated by blank lines.
+/// Separating blank lines are kept with the preceding paragraph, so joining
+/// all of them gives back the original text.
+///
+/// # Arguments
+///
+/// * `text` - Text to split
+///
+/// # Example
+///
+/// ```
+/// use translitrs::paragraphs;
+///
+/// let p: Vec<&str> = paragraphs("Prvi\nred\n\nDrugi").collect();
+/// assert_eq!(p, vec!["Prvi\nred\n\n", "Drugi"]);
+/// ```
+pub fn paragraphs(text: &str) -> Paragraphs<'_> {
+    Paragraphs { text, cursor: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentences() {
+        let text = "Rekao je: „Dobar dan.” Zatim je otišao. Da li?\nNe.";
+        let s: Vec<&str> = sentences(text).collect();
+        assert_eq!(s.concat(), text);
+        assert_eq!(s.len(), 4);
+        assert_eq!(sentences("").count(), 0);
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        for (text, expected) in [
+            ("", vec![]),
+            ("Jedan", vec!["Jedan"]),
+            ("Jedan\n", vec!["Jedan\n"]),
+            ("\n\nJedan", vec!["\n\n", "Jedan"]),
+            ("Jedan\n\n\nDva\n \nTri\n", vec!["Jedan\n\n\n", "Dva\n \n", "Tri\n"]),
+            ("Prvi\r\nred\r\n\r\nDrugi", vec!["Prvi\r\nred\r\n\r\n", "Drugi"]),
+        ] {
+            assert_eq!(paragraphs(text).collect::<Vec<&str>>(), expected);
+        }
+    }
+}
@@ -0,0 +1,421 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::process::Error;
+use crate::transliterate::Charset;
+
+/// Directories searched for dictionaries after those in `DICPATH`
+const DIRECTORIES: &[&str] = &[
+    "/usr/share/hunspell",
+    "/usr/share/myspell",
+    "/usr/share/myspell/dicts",
+    "/usr/local/share/hunspell",
+    "/Library/Spelling",
+];
+
+/// Names of Serbian Cyrillic dictionaries
+const CYRILLIC: &[&str] = &["sr", "sr_RS", "sr-Cyrl", "sr_Cyrl_RS"];
+
+/// Names of Serbian Latin dictionaries
+const LATIN: &[&str] = &[
+    "sr-Latn",
+    "sr_Latn",
+    "sr_RS@latin",
+    "sr_Latn_RS",
+    "sr-Latn-RS",
+];
+
+/// Encoding of affix flags in a dictionary
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FlagType {
+    /// Single characters
+    Char,
+    /// Pairs of characters
+    Long,
+    /// Comma separated numbers
+    Number,
+}
+
+impl FlagType {
+    fn parse(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => flags
+                .chars()
+                .collect::<Vec<char>>()
+                .chunks(2)
+                .map(|pair| pair.iter().collect())
+                .collect(),
+            FlagType::Number => flags.split(',').map(|f| f.trim().to_owned()).collect(),
+        }
+    }
+}
+
+/// Decode contents of affix and word files, which must be in UTF-8 instead
+/// of being decoded lossily, so words aren't misread
+fn decode(aff: Vec<u8>, dic: Vec<u8>) -> Result<(String, String), Error> {
+    let encoding = aff
+        .split(|&b| b == b'\n')
+        .find_map(|line| line.strip_prefix(b"SET"))
+        .map(|encoding| String::from_utf8_lossy(encoding).trim().to_owned());
+    match encoding {
+        Some(encoding) if !encoding.eq_ignore_ascii_case("UTF-8") => {
+            return Err(Error::Config(format!("unsupported dictionary encoding - {}", encoding)));
+        }
+        _ => {}
+    }
+    let utf8 = |bytes| {
+        String::from_utf8(bytes)
+            .map_err(|_| Error::Config("dictionary is not valid UTF-8".to_owned()))
+    };
+    Ok((utf8(aff)?, utf8(dic)?))
+}
+
+/// Prefix or suffix rule
+#[derive(Clone, Debug)]
+struct Affix {
+    flag: String,
+    /// Can be combined with affixes of the other kind
+    cross: bool,
+    strip: String,
+    add: String,
+    /// Condition on the stem, anchored to the start of prefixes and the
+    /// end of suffixes
+    condition: Option<Regex>,
+}
+
+/// Word list read from a Hunspell dictionary, used to find words which are
+/// probably misspelled after transliteration (ie. a misresolved digraph or
+/// a foreign word which should have been skipped). This is a parser of a
+/// subset of the Hunspell format, not a binding to Hunspell: words are
+/// looked up directly and with a single prefix, a single suffix or both,
+/// while continuation flags (twofold affixes), compounding, `ICONV` and
+/// `OCONV` conversions and other advanced options are ignored, so some
+/// words which Hunspell accepts are not found.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::Dictionary;
+///
+/// let d = Dictionary::new("SFX A Y 1\nSFX A 0 а .\n", "2\nкњига/A\nњива\n");
+/// assert!(d.contains("књига"));
+/// assert!(d.contains("Књигаа"));
+/// assert!(d.contains("ЊИВА"));
+/// assert!(!d.contains("кнјига"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dictionary {
+    words: HashMap<String, Vec<String>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    /// Words with this flag are forbidden
+    forbidden: Option<String>,
+    /// Words with this flag are valid only with an affix
+    need_affix: Option<String>,
+}
+
+impl Dictionary {
+    /// Parse dictionary from contents of its affix (`.aff`) and word
+    /// (`.dic`) files. Lines which can't be parsed are skipped.
+    pub fn new(aff: &str, dic: &str) -> Self {
+        let mut flag_type = FlagType::Char;
+        let mut prefixes = Vec::new();
+        let mut suffixes = Vec::new();
+        let mut forbidden = None;
+        let mut need_affix = None;
+        let mut cross = HashMap::new();
+        for line in aff.lines() {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => flag_type = FlagType::Long,
+                ["FLAG", "num", ..] => flag_type = FlagType::Number,
+                ["FORBIDDENWORD", flag, ..] => forbidden = Some(flag.to_string()),
+                ["NEEDAFFIX", flag, ..] => need_affix = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, product, count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    cross.insert((kind.to_string(), flag.to_string()), *product == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, condition, ..] => {
+                    let empty = |s: &str| {
+                        if s == "0" {
+                            String::new()
+                        } else {
+                            s.to_owned()
+                        }
+                    };
+                    // Continuation flags of twofold affixes are ignored
+                    let add = add.split('/').next().unwrap_or_default();
+                    let condition = match (*condition, *kind) {
+                        (".", _) => None,
+                        (condition, "PFX") => match Regex::new(&format!("^{}", condition)) {
+                            Ok(regex) => Some(regex),
+                            Err(_) => continue,
+                        },
+                        (condition, _) => match Regex::new(&format!("{}$", condition)) {
+                            Ok(regex) => Some(regex),
+                            Err(_) => continue,
+                        },
+                    };
+                    let affix = Affix {
+                        flag: flag.to_string(),
+                        cross: cross
+                            .get(&(kind.to_string(), flag.to_string()))
+                            .copied()
+                            .unwrap_or(false),
+                        strip: empty(strip),
+                        add: empty(add),
+                        condition,
+                    };
+                    match *kind {
+                        "PFX" => prefixes.push(affix),
+                        _ => suffixes.push(affix),
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut words: HashMap<String, Vec<String>> = HashMap::new();
+        // The first line holds the number of words
+        for line in dic.lines().skip(1) {
+            let entry = line.split('\t').next().unwrap_or_default().trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, flag_type.parse(flags)),
+                None => (entry, Vec::new()),
+            };
+            words.entry(word.to_owned()).or_default().extend(flags);
+        }
+        Self {
+            words,
+            prefixes,
+            suffixes,
+            forbidden,
+            need_affix,
+        }
+    }
+
+    /// Read dictionary from a `.dic` file and the `.aff` file next to it.
+    /// Fails if either file can't be read or is not in UTF-8, which is the
+    /// only supported encoding (`SET`).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the `.dic` file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let dic = fs::read(path)?;
+        let aff = fs::read(path.with_extension("aff"))?;
+        let (aff, dic) = decode(aff, dic)?;
+        Ok(Self::new(&aff, &dic))
+    }
+
+    /// Find the Serbian dictionary of `charset` (`sr` or `sr-Latn`) in
+    /// directories listed in `DICPATH` and the usual system directories
+    pub fn find(charset: Charset) -> Option<PathBuf> {
+        let names = match charset {
+            Charset::Cyrillic => CYRILLIC,
+            _ => LATIN,
+        };
+        let paths = env::var_os("DICPATH")
+            .map(|paths| env::split_paths(&paths).collect::<Vec<PathBuf>>())
+            .unwrap_or_default();
+        paths
+            .into_iter()
+            .chain(DIRECTORIES.iter().map(PathBuf::from))
+            .flat_map(|directory| {
+                names
+                    .iter()
+                    .map(move |name| directory.join(format!("{}.dic", name)))
+            })
+            .find(|path| path.is_file())
+    }
+
+    fn has_flag(&self, flags: &[String], flag: &Option<String>) -> bool {
+        matches!(flag, Some(flag) if flags.contains(flag))
+    }
+
+    /// Returns whether `stem` is a word with affix `flag`
+    fn stem(&self, stem: &str, flag: &str) -> bool {
+        matches!(self.words.get(stem), Some(flags)
+            if flags.iter().any(|f| f == flag) && !self.has_flag(flags, &self.forbidden))
+    }
+
+    /// Returns stems of `word` with `suffix` removed
+    fn strip_suffix(word: &str, suffix: &Affix) -> Option<String> {
+        let stem = word.strip_suffix(suffix.add.as_str())?;
+        if stem.is_empty() {
+            return None;
+        }
+        let stem = format!("{}{}", stem, suffix.strip);
+        match &suffix.condition {
+            Some(condition) if !condition.is_match(&stem) => None,
+            _ => Some(stem),
+        }
+    }
+
+    fn strip_prefix(word: &str, prefix: &Affix) -> Option<String> {
+        let stem = word.strip_prefix(prefix.add.as_str())?;
+        if stem.is_empty() {
+            return None;
+        }
+        let stem = format!("{}{}", prefix.strip, stem);
+        match &prefix.condition {
+            Some(condition) if !condition.is_match(&stem) => None,
+            _ => Some(stem),
+        }
+    }
+
+    fn contains_exact(&self, word: &str) -> bool {
+        if let Some(flags) = self.words.get(word) {
+            if self.has_flag(flags, &self.forbidden) {
+                return false;
+            }
+            if !self.has_flag(flags, &self.need_affix) {
+                return true;
+            }
+        }
+        for suffix in &self.suffixes {
+            if let Some(stem) = Self::strip_suffix(word, suffix) {
+                if self.stem(&stem, &suffix.flag) {
+                    return true;
+                }
+            }
+        }
+        for prefix in &self.prefixes {
+            if let Some(stem) = Self::strip_prefix(word, prefix) {
+                if self.stem(&stem, &prefix.flag) {
+                    return true;
+                }
+                if !prefix.cross {
+                    continue;
+                }
+                for suffix in self.suffixes.iter().filter(|s| s.cross) {
+                    if let Some(stem) = Self::strip_suffix(&stem, suffix) {
+                        if self.stem(&stem, &suffix.flag) && self.stem(&stem, &prefix.flag) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns whether `word` is in the dictionary. Capitalized and
+    /// uppercase words are also looked up in lowercase.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.contains_exact(word) {
+            return true;
+        }
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) if first.is_uppercase() => {
+                let lowercase = word.to_lowercase();
+                if self.contains_exact(&lowercase) {
+                    return true;
+                }
+                // Uppercase words may be capitalized names (ie. `БЕОГРАД`)
+                let capitalized = first.to_string() + &chars.as_str().to_lowercase();
+                capitalized != word && self.contains_exact(&capitalized)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns words of `text` which are not in the dictionary, in order.
+    /// Words without letters and words with letters of other scripts than
+    /// `charset` (ie. skipped foreign words) are ignored.
+    pub fn unknown<'a>(&self, text: &'a str, charset: Charset) -> Vec<&'a str> {
+        let script = |c: char| match Charset::of(c) {
+            Some(Charset::Cyrillic) => Some(Charset::Cyrillic),
+            Some(_) => Some(Charset::Latin),
+            None => None,
+        };
+        let target = match charset {
+            Charset::Cyrillic => Charset::Cyrillic,
+            _ => Charset::Latin,
+        };
+        text.unicode_words()
+            .filter(|word| {
+                word.chars()
+                    .filter(|c| c.is_alphabetic())
+                    .all(|c| script(c) == Some(target))
+                    && word.chars().any(char::is_alphabetic)
+            })
+            .filter(|word| !self.contains(word))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "\
+SET UTF-8
+FLAG long
+FORBIDDENWORD !!
+NEEDAFFIX Na
+
+PFX Pn Y 1
+PFX Pn 0 не .
+
+SFX Sa Y 3
+SFX Sa а е [^и]а
+SFX Sa а у а
+SFX Sa 0 ови/Xx [^а]
+";
+
+    const DIC: &str = "\
+5
+књига/SaPn
+њива/Sa
+град/Sa
+градови/!!
+корен/Na\tpo:noun
+";
+
+    #[test]
+    fn test_dictionary() {
+        let d = Dictionary::new(AFF, DIC);
+        for word in ["књига", "књиге", "књигу", "њиве", "некњига", "некњиге"]
+        {
+            assert!(d.contains(word), "{}", word);
+        }
+        // Forbidden words aren't valid even if an affix produces them
+        for word in ["градови", "корен", "нењива", "кнјига", "књигови", "ге"]
+        {
+            assert!(!d.contains(word), "{}", word);
+        }
+        assert!(d.contains("Књиге"));
+        assert!(d.contains("КЊИГУ"));
+        assert!(!d.contains("кЊИГУ"));
+
+        assert_eq!(
+            d.unknown("Кнјига и њиве, 12 sveska (Knjiga)", Charset::Cyrillic),
+            ["Кнјига", "и"]
+        );
+        assert_eq!(d.unknown("njive", Charset::Latin), ["njive"]);
+    }
+
+    #[test]
+    fn test_decode() {
+        let (aff, dic) = decode(AFF.into(), DIC.into()).unwrap();
+        assert_eq!((aff.as_str(), dic.as_str()), (AFF, DIC));
+        assert!(decode(b"SFX A Y 1\n".to_vec(), b"1\nknjiga\n".to_vec()).is_ok());
+        for (aff, dic) in [
+            (&b"SET ISO8859-5\n"[..], &b"1\n\xba\xdd\xd8\xd3\xd0\n"[..]),
+            (&b"SET UTF-8\n"[..], &b"1\nknji\xbea\n"[..]),
+        ] {
+            assert!(matches!(decode(aff.to_vec(), dic.to_vec()), Err(Error::Config(_))));
+        }
+    }
+}
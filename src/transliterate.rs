@@ -1,31 +1,241 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
 use std::{cmp, error, fmt, str, string};
-use subslice::bmh;
 
+#[cfg(feature = "dialect")]
+use crate::dialect::DialectConverter;
+#[cfg(feature = "detect-language")]
+use crate::segment;
+use crate::unmapped;
+
+mod accents;
+mod align;
+mod alphanumeric;
+mod archaic;
+mod case;
 mod charmaps;
+mod compose;
+mod compounds;
+mod coverage;
+#[cfg(feature = "dates")]
+mod dates;
+mod exceptions;
+mod fold;
+#[cfg(feature = "gazetteer")]
+mod gazetteer;
+mod letters;
+mod lines;
+#[cfg(feature = "ngram")]
+mod ngram;
+mod ocr;
+mod profile;
+mod pseudo;
+mod speech;
+mod stats;
+mod tags;
+mod truncate;
+#[cfg(feature = "uzbek")]
+mod uzbek;
+mod warnings;
 
-use charmaps::{Case as LetterCase, Character};
+pub use align::AlignedToken;
+pub use alphanumeric::{Alphanumeric, WordClass};
+pub use archaic::Archaic;
+use case::Pattern;
+use charmaps::{Case as LetterCase, Character, Orthography};
+pub use compose::{Composer, Update};
+pub use compounds::Compounds;
+pub use coverage::{CoverageReport, EntryUsage, ExceptionUsage};
+use exceptions::Exceptions;
+pub use fold::Fold;
+pub use letters::Letters;
+pub use lines::{LineError, LineErrorKind, Lines, MAX_LINE_LENGTH};
+pub use profile::{rules_checksum, Profile};
+pub use speech::Expand;
+pub use stats::Stats;
+pub use tags::negotiate_script;
+pub use warnings::{Warning, WarningKind};
 
-#[derive(Clone)]
+/// Titlecase Latin digraphs and their single letters
+const TITLECASE_DIGRAPHS: &[(&[char], char)] =
+    &[(&['D', 'ž'], 'ǅ'), (&['L', 'j'], 'ǈ'), (&['N', 'j'], 'ǋ')];
+
+/// Share of letters in the source script up to which text is already
+/// converted, so brand names and abbreviations are tolerated
+pub const CONVERTED_TOLERANCE: f64 = 0.1;
+
+/// Words written in uppercase which are also valid Roman numerals (ie.
+/// `VI` in `DA LI VI ZNATE`)
+const ROMAN_WORDS: &[&str] = &["MI", "VI", "LI", "DI", "DIV", "CIV"];
+
+/// Length in bytes of words whose buffers are kept on the stack
+const STACK_CHARS: usize = 32;
+
+/// State shared by words of a single text
+struct Context {
+    /// Outputs of words which needed a digraph exception search
+    memo: HashMap<String, (String, Decision)>,
+    /// Search for digraph exceptions in words
+    exceptions: bool,
+    /// Current word is next to a word written in uppercase
+    uppercase: bool,
+}
+
+impl Context {
+    fn new(exceptions: bool) -> Self {
+        Self {
+            memo: HashMap::new(),
+            exceptions,
+            uppercase: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Charset {
     Latin,
     LatinUnicode,
     Cyrillic,
 }
 
+/// All character sets
+const CHARSETS: [Charset; 3] = [Charset::Latin, Charset::LatinUnicode, Charset::Cyrillic];
+
+/// Pair of character sets to transliterate between (ie. `cyr2lat`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Direction {
+    pub from: Charset,
+    pub into: Charset,
+}
+
+/// Language whose orthography conventions are followed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    Serbian,
+    Croatian,
+    Bosnian,
+    #[cfg(feature = "kazakh")]
+    Kazakh,
+    #[cfg(feature = "uzbek")]
+    Uzbek,
+}
+
+#[derive(Clone)]
 pub struct Transliterator {
+    from: Charset,
+    into: Charset,
     charset_from: &'static [Character<'static>],
     charset_into: &'static [Character<'static>],
+    index: charmaps::Index,
     exceptions: bool,
     skip_digraph: bool,
     force_foreign: bool,
     force_links: bool,
+    force_roman: bool,
+    fix_ocr: bool,
+    fold: Fold,
+    titlecase_digraphs: bool,
+    #[cfg(feature = "dates")]
+    dates: bool,
+    skip_converted: Option<f64>,
+    max_input: Option<usize>,
+    max_word: Option<usize>,
+    #[cfg(feature = "ngram")]
+    ngram: bool,
+    memo_exceptions: bool,
+    language: Language,
+    archaic: Archaic,
+    compounds: Compounds,
+    alphanumeric: Alphanumeric,
+    letters: Letters,
+    #[cfg(feature = "dialect")]
+    dialect: Option<DialectConverter>,
+    digraph_exceptions: [Vec<String>; 3],
+    exception_tries: Exceptions,
+    protected: Vec<String>,
+    #[cfg(feature = "gazetteer")]
+    gazetteer: bool,
+    ansi: bool,
+    skip_patterns: Vec<Regex>,
+    strict: bool,
+    passthrough: Option<Regex>,
+    #[cfg(feature = "detect-language")]
+    detect_language: bool,
+}
+
+/// Reason for the outcome of transliterating a single word
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision {
+    /// Word is transliterated
+    Transliterated,
+    /// Word is transliterated with a digraph exception applied
+    Exception,
+    /// Word is skipped because it contains foreign characters
+    Foreign,
+    /// Word is skipped because it looks like a hyperlink, email address or unit
+    Link,
+    /// Word is skipped because it looks like a Roman numeral
+    Roman,
+    /// Word is skipped because its sentence is in a foreign language
+    /// (ISO 639-3 code)
+    Language(&'static str),
+    /// Word is skipped because it is protected or matches a skip pattern
+    Protected,
+    /// Word is skipped because it looks like a product or model name, or
+    /// mixes digits and letters (ie. `A4`, `iPhone 12 Pro`)
+    Model,
+    /// Word is skipped because it is a standalone letter (ie. `a)`)
+    Letter,
+}
+
+/// Outcome of transliterating a single word
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace {
+    /// Byte range of the word in input text
+    pub span: Range<usize>,
+    /// Transliterated word
+    pub output: String,
+    /// Reason for the outcome
+    pub decision: Decision,
+    /// Language detected for the sentence containing the word (ISO 639-3 code)
+    pub language: Option<&'static str>,
+}
+
+/// Outcome of transliterating a word directly into an output buffer
+struct Outcome {
+    /// Byte range of the transliterated word in the output buffer
+    output: Range<usize>,
+    decision: Decision,
+    language: Option<&'static str>,
 }
 
 #[derive(Debug)]
 pub enum Error {
+    UnknownCharset(String),
+    UnknownLanguage(String),
+    UnknownArchaic(String),
+    UnknownCompounds(String),
+    UnknownAlphanumeric(String),
+    UnknownLetters(String),
+    UnknownFold(String),
+    UnknownExpansion(String),
+    InvalidAsciiMap(String),
+    InvalidPhoneticMap(String),
+    UnsupportedProfile(String),
+    UnknownDialect(String),
+    UnknownDirection(String),
+    UnknownClass(String),
+    InvalidPattern(regex::Error),
+    Unmapped(char, usize),
+    UnsupportedTag(String, &'static str),
     BufferOverflow,
+    InputTooLong(usize, usize),
+    WordTooLong(usize, usize),
     Utf8(str::Utf8Error),
     FromUtf8(string::FromUtf8Error),
 }
@@ -45,7 +255,32 @@ impl From<string::FromUtf8Error> for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::UnknownCharset(s) => writeln!(f, "Unknown charset - {}", s),
+            Self::UnknownLanguage(s) => writeln!(f, "Unknown language - {}", s),
+            Self::UnknownArchaic(s) => writeln!(f, "Unknown archaic letters mode - {}", s),
+            Self::UnknownCompounds(s) => writeln!(f, "Unknown compound words mode - {}", s),
+            Self::UnknownAlphanumeric(s) => writeln!(f, "Unknown alphanumeric words mode - {}", s),
+            Self::UnknownLetters(s) => writeln!(f, "Unknown standalone letters mode - {}", s),
+            Self::UnknownFold(s) => writeln!(f, "Unknown compatibility character class - {}", s),
+            Self::UnknownExpansion(s) => writeln!(f, "Unknown speech expansion - {}", s),
+            Self::UnsupportedProfile(s) => writeln!(f, "Unsupported profile - {}", s),
+            Self::InvalidAsciiMap(s) => writeln!(f, "Invalid ASCII replacement - {}", s),
+            Self::InvalidPhoneticMap(s) => writeln!(f, "Invalid phonetic trigger - {}", s),
+            Self::UnknownDialect(s) => writeln!(f, "Unknown dialect - {}", s),
+            Self::UnknownDirection(s) => writeln!(f, "Unknown direction - {}", s),
+            Self::UnknownClass(s) => writeln!(f, "Unknown character class - {}", s),
+            Self::InvalidPattern(e) => writeln!(f, "Invalid pattern - {}", e),
+            Self::Unmapped(c, offset) => {
+                writeln!(f, "Unmapped character - U+{:04X} {} at byte {}", *c as u32, c, offset)
+            }
+            Self::UnsupportedTag(s, r) => writeln!(f, "Unsupported language tag {} - {}", s, r),
             Self::BufferOverflow => writeln!(f, "Buffer Overflow"),
+            Self::InputTooLong(length, limit) => {
+                writeln!(f, "Input is too long - {} bytes, limit is {}", length, limit)
+            }
+            Self::WordTooLong(length, limit) => {
+                writeln!(f, "Word is too long - {} bytes, limit is {}", length, limit)
+            }
             Self::Utf8(e) => writeln!(f, "UTF-8 error - {}", e),
             Self::FromUtf8(e) => writeln!(f, "From UTF-8 error - {}", e),
         }
@@ -54,15 +289,434 @@ impl fmt::Display for Error {
 
 impl error::Error for Error {}
 
+impl str::FromStr for Language {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "serbian" | "srp" | "sr" => Ok(Language::Serbian),
+            "croatian" | "hrv" | "hr" => Ok(Language::Croatian),
+            "bosnian" | "bos" | "bs" => Ok(Language::Bosnian),
+            #[cfg(feature = "kazakh")]
+            "kazakh" | "kaz" | "kk" => Ok(Language::Kazakh),
+            #[cfg(feature = "uzbek")]
+            "uzbek" | "uzb" | "uz" => Ok(Language::Uzbek),
+            _ => Err(Error::UnknownLanguage(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::Serbian => "sr",
+            Language::Croatian => "hr",
+            Language::Bosnian => "bs",
+            #[cfg(feature = "kazakh")]
+            Language::Kazakh => "kk",
+            #[cfg(feature = "uzbek")]
+            Language::Uzbek => "uz",
+        })
+    }
+}
+
+impl Language {
+    fn orthography(&self) -> &'static Orthography<'static> {
+        match self {
+            Language::Serbian => &charmaps::ORTHOGRAPHY_SERBIAN,
+            Language::Croatian => &charmaps::ORTHOGRAPHY_CROATIAN,
+            Language::Bosnian => &charmaps::ORTHOGRAPHY_BOSNIAN,
+            // Digraph exceptions are used only for South Slavic languages
+            #[cfg(feature = "kazakh")]
+            Language::Kazakh => &charmaps::ORTHOGRAPHY_SERBIAN,
+            #[cfg(feature = "uzbek")]
+            Language::Uzbek => &charmaps::ORTHOGRAPHY_SERBIAN,
+        }
+    }
+
+    /// Returns whether apostrophes are part of letters (ie. Uzbek `oʻ`)
+    fn apostrophe_letters(&self) -> bool {
+        match self {
+            #[cfg(feature = "uzbek")]
+            Language::Uzbek => true,
+            _ => false,
+        }
+    }
+
+    fn charmaps(
+        &self,
+        from: Charset,
+        into: Charset,
+    ) -> (&'static [Character<'static>], &'static [Character<'static>], bool) {
+        match self {
+            Language::Serbian | Language::Croatian | Language::Bosnian => match (from, into) {
+                (Charset::Latin, Charset::Latin) => (charmaps::EMPTY, charmaps::EMPTY, false),
+                (Charset::LatinUnicode, Charset::LatinUnicode) => {
+                    (charmaps::EMPTY, charmaps::EMPTY, false)
+                }
+                (Charset::Cyrillic, Charset::Cyrillic) => (charmaps::EMPTY, charmaps::EMPTY, false),
+                //
+                (Charset::Latin, Charset::LatinUnicode) => {
+                    (charmaps::LATIN_DIRTY, charmaps::LATIN_DIRTY_UNICODE, true)
+                }
+                (Charset::LatinUnicode, Charset::Latin) => {
+                    (charmaps::LATIN_CLEAN_UNICODE, charmaps::LATIN_CLEAN, false)
+                }
+                //
+                (Charset::Latin, Charset::Cyrillic) => {
+                    (charmaps::LATIN_DIRTY, charmaps::CYRILLIC_DIRTY, true)
+                }
+                (Charset::LatinUnicode, Charset::Cyrillic) => {
+                    (charmaps::LATIN_CLEAN_UNICODE, charmaps::CYRILLIC_CLEAN, true)
+                }
+                //
+                (Charset::Cyrillic, Charset::Latin) => {
+                    (charmaps::CYRILLIC_CLEAN, charmaps::LATIN_CLEAN, false)
+                }
+                (Charset::Cyrillic, Charset::LatinUnicode) => {
+                    (charmaps::CYRILLIC_CLEAN, charmaps::LATIN_CLEAN_UNICODE, false)
+                }
+            },
+            // Kazakh Latin has no Unicode variant
+            #[cfg(feature = "kazakh")]
+            Language::Kazakh => match (from, into) {
+                (Charset::Cyrillic, Charset::Latin | Charset::LatinUnicode) => {
+                    (charmaps::KAZAKH_CYRILLIC, charmaps::KAZAKH_LATIN_FROM_CYRILLIC, false)
+                }
+                (Charset::Latin | Charset::LatinUnicode, Charset::Cyrillic) => {
+                    (charmaps::KAZAKH_LATIN, charmaps::KAZAKH_CYRILLIC_FROM_LATIN, false)
+                }
+                _ => (charmaps::EMPTY, charmaps::EMPTY, false),
+            },
+            // Uzbek Latin uses ASCII apostrophes, and Latin Unicode uses
+            // modifier letters `ʻ` and `ʼ`
+            #[cfg(feature = "uzbek")]
+            Language::Uzbek => match (from, into) {
+                (Charset::Cyrillic, Charset::Latin) => {
+                    (charmaps::UZBEK_CYRILLIC, charmaps::UZBEK_LATIN_ASCII_FROM_CYRILLIC, false)
+                }
+                (Charset::Cyrillic, Charset::LatinUnicode) => {
+                    (charmaps::UZBEK_CYRILLIC, charmaps::UZBEK_LATIN_FROM_CYRILLIC, false)
+                }
+                (Charset::Latin | Charset::LatinUnicode, Charset::Cyrillic) => {
+                    (charmaps::UZBEK_LATIN, charmaps::UZBEK_CYRILLIC_FROM_LATIN, false)
+                }
+                (Charset::Latin | Charset::LatinUnicode, Charset::Latin) => {
+                    (charmaps::UZBEK_LATIN, charmaps::UZBEK_LATIN_ASCII, false)
+                }
+                (Charset::Latin | Charset::LatinUnicode, Charset::LatinUnicode) => {
+                    (charmaps::UZBEK_LATIN, charmaps::UZBEK_LATIN, false)
+                }
+                (Charset::Cyrillic, Charset::Cyrillic) => (charmaps::EMPTY, charmaps::EMPTY, false),
+            },
+        }
+    }
+}
+
+impl str::FromStr for Archaic {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ignore" => Ok(Archaic::Ignore),
+            "modernize" => Ok(Archaic::Modernize),
+            "preserve" => Ok(Archaic::Preserve),
+            "annotate" => Ok(Archaic::Annotate),
+            _ => Err(Error::UnknownArchaic(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Archaic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Archaic::Ignore => "ignore",
+            Archaic::Modernize => "modernize",
+            Archaic::Preserve => "preserve",
+            Archaic::Annotate => "annotate",
+        })
+    }
+}
+
+impl str::FromStr for Compounds {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "whole" => Ok(Compounds::Whole),
+            "hyphens" => Ok(Compounds::Hyphens),
+            "apostrophes" => Ok(Compounds::Apostrophes),
+            "all" => Ok(Compounds::All),
+            _ => Err(Error::UnknownCompounds(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Compounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Compounds::Whole => "whole",
+            Compounds::Hyphens => "hyphens",
+            Compounds::Apostrophes => "apostrophes",
+            Compounds::All => "all",
+        })
+    }
+}
+
+impl str::FromStr for Alphanumeric {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transliterate" => Ok(Alphanumeric::Transliterate),
+            "suffixes" => Ok(Alphanumeric::Suffixes),
+            "keep" => Ok(Alphanumeric::Keep),
+            _ => Err(Error::UnknownAlphanumeric(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Alphanumeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Alphanumeric::Transliterate => "transliterate",
+            Alphanumeric::Suffixes => "suffixes",
+            Alphanumeric::Keep => "keep",
+        })
+    }
+}
+
+impl str::FromStr for Letters {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "convert" => Ok(Letters::Convert),
+            "keep" => Ok(Letters::Keep),
+            "serbian" => Ok(Letters::Serbian),
+            _ => Err(Error::UnknownLetters(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Letters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Letters::Convert => "convert",
+            Letters::Keep => "keep",
+            Letters::Serbian => "serbian",
+        })
+    }
+}
+
+impl str::FromStr for Fold {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fold = Fold::default();
+        for class in s.split(',').map(str::trim) {
+            match class {
+                "all" => fold = Fold::ALL,
+                "none" => {}
+                "fullwidth" => fold.fullwidth = true,
+                "superscripts" => fold.superscripts = true,
+                "subscripts" => fold.subscripts = true,
+                "circled" => fold.circled = true,
+                _ => return Err(Error::UnknownFold(class.to_owned())),
+            }
+        }
+        Ok(fold)
+    }
+}
+
+impl fmt::Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let classes = [
+            (self.fullwidth, "fullwidth"),
+            (self.superscripts, "superscripts"),
+            (self.subscripts, "subscripts"),
+            (self.circled, "circled"),
+        ]
+        .iter()
+        .filter(|(folded, _)| *folded)
+        .map(|(_, class)| *class)
+        .collect::<Vec<&str>>();
+        match classes.is_empty() {
+            true => f.write_str("none"),
+            false => f.write_str(&classes.join(",")),
+        }
+    }
+}
+
+impl str::FromStr for Charset {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latin" | "lat" | "l" => Ok(Charset::Latin),
+            "latin8" | "lat8" | "l8" => Ok(Charset::LatinUnicode),
+            "cyrillic" | "cyr" | "c" => Ok(Charset::Cyrillic),
+            _ => Err(Error::UnknownCharset(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Charset::Latin => "lat",
+            Charset::LatinUnicode => "lat8",
+            Charset::Cyrillic => "cyr",
+        })
+    }
+}
+
+impl Charset {
+    /// Returns script of a letter, or `None` if `c` is neither a Latin
+    /// nor a Cyrillic letter
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::Charset;
+    /// assert_eq!(Charset::of('ž'), Some(Charset::Latin));
+    /// assert_eq!(Charset::of('ж'), Some(Charset::Cyrillic));
+    /// assert_eq!(Charset::of('7'), None);
+    /// ```
+    pub fn of(c: char) -> Option<Charset> {
+        match c {
+            '\u{0400}'..='\u{052f}' if c.is_alphabetic() => Some(Charset::Cyrillic),
+            'A'..='Z' | 'a'..='z' => Some(Charset::Latin),
+            '\u{00c0}'..='\u{024f}' if c.is_alphabetic() => Some(Charset::Latin),
+            _ => None,
+        }
+    }
+
+    /// Returns character set used by most letters of `text`, or `None`
+    /// if it contains neither Latin nor Cyrillic letters
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::Charset;
+    /// assert_eq!(Charset::detect("Ћирилица (cyr)"), Some(Charset::Cyrillic));
+    /// assert_eq!(Charset::detect("Latinica"), Some(Charset::Latin));
+    /// assert_eq!(Charset::detect("1, 2, 3"), None);
+    /// ```
+    pub fn detect(text: &str) -> Option<Charset> {
+        let (mut latin, mut cyrillic) = (0, 0);
+        for c in text.chars() {
+            match Charset::of(c) {
+                Some(Charset::Cyrillic) => cyrillic += 1,
+                Some(_) => latin += 1,
+                None => {}
+            }
+        }
+        match (latin, cyrillic) {
+            (0, 0) => None,
+            (l, c) if c > l => Some(Charset::Cyrillic),
+            _ => Some(Charset::Latin),
+        }
+    }
+
+    /// Returns script of letters of this character set
+    fn script(self) -> Charset {
+        match self {
+            Charset::LatinUnicode => Charset::Latin,
+            charset => charset,
+        }
+    }
+}
+
+impl str::FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || Error::UnknownDirection(s.to_owned());
+        let (from, into) = s.split_once('2').ok_or_else(unknown)?;
+        Ok(Direction {
+            from: from.parse().map_err(|_| unknown())?,
+            into: into.parse().map_err(|_| unknown())?,
+        })
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}2{}", self.from, self.into)
+    }
+}
+
+/// Returns whether `word` is transliterated in `direction` and back into
+/// the same word, with default transliterator options. Serbian words
+/// round-trip if they are written with letters of the alphabet (ie. not
+/// with ligatures or `DJ` instead of `Đ`), and digraphs are written in
+/// uppercase only in uppercase words. Cyrillic words don't round-trip if
+/// they have separate letters which are written as a digraph in Latin,
+/// unless they are a digraph exception (ie. `Нјутн`, but not `конјугација`).
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{is_roundtrip_safe, Direction};
+/// let lat2cyr = "lat2cyr".parse::<Direction>().unwrap();
+/// let cyr2lat = "cyr2lat".parse::<Direction>().unwrap();
+/// assert!(is_roundtrip_safe("Ljubiša", lat2cyr));
+/// assert!(!is_roundtrip_safe("LJubiša", lat2cyr));
+/// assert!(is_roundtrip_safe("конјугација", cyr2lat));
+/// assert!(!is_roundtrip_safe("Нјутн", cyr2lat));
+/// ```
+pub fn is_roundtrip_safe(word: &str, direction: Direction) -> bool {
+    let forward = Transliterator::new(direction.from, direction.into, false, false, false);
+    let backward = Transliterator::new(direction.into, direction.from, false, false, false);
+    forward
+        .process(word)
+        .and_then(|converted| backward.process(converted))
+        .map_or(false, |converted| converted == word)
+}
+
 impl Default for Transliterator {
     fn default() -> Self {
         Self {
+            from: Charset::Latin,
+            into: Charset::Cyrillic,
             charset_from: charmaps::LATIN_DIRTY,
             charset_into: charmaps::CYRILLIC_DIRTY,
+            index: charmaps::LATIN_DIRTY_INDEX,
             exceptions: true,
             skip_digraph: false,
             force_foreign: false,
             force_links: false,
+            force_roman: false,
+            fix_ocr: false,
+            fold: Fold::default(),
+            titlecase_digraphs: false,
+            #[cfg(feature = "dates")]
+            dates: false,
+            skip_converted: None,
+            max_input: None,
+            max_word: None,
+            #[cfg(feature = "ngram")]
+            ngram: false,
+            memo_exceptions: true,
+            language: Language::Serbian,
+            archaic: Archaic::Ignore,
+            compounds: Compounds::Whole,
+            alphanumeric: Alphanumeric::Suffixes,
+            letters: Letters::Convert,
+            #[cfg(feature = "dialect")]
+            dialect: None,
+            digraph_exceptions: Default::default(),
+            exception_tries: Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default()),
+            protected: Vec::new(),
+            #[cfg(feature = "gazetteer")]
+            gazetteer: false,
+            ansi: false,
+            skip_patterns: Vec::new(),
+            strict: false,
+            passthrough: None,
+            #[cfg(feature = "detect-language")]
+            detect_language: false,
         }
     }
 }
@@ -95,94 +749,800 @@ impl Transliterator {
         force_foreign: bool,
         force_links: bool,
     ) -> Self {
-        let (f, i, e) = match (from, into) {
-            (Charset::Latin, Charset::Latin) => (charmaps::EMPTY, charmaps::EMPTY, false),
-            (Charset::LatinUnicode, Charset::LatinUnicode) => {
-                (charmaps::EMPTY, charmaps::EMPTY, false)
-            }
-            (Charset::Cyrillic, Charset::Cyrillic) => (charmaps::EMPTY, charmaps::EMPTY, false),
-            //
-            (Charset::Latin, Charset::LatinUnicode) => {
-                (charmaps::LATIN_DIRTY, charmaps::LATIN_DIRTY_UNICODE, true)
-            }
-            (Charset::LatinUnicode, Charset::Latin) => {
-                (charmaps::LATIN_CLEAN_UNICODE, charmaps::LATIN_CLEAN, false)
-            }
-            //
-            (Charset::Latin, Charset::Cyrillic) => {
-                (charmaps::LATIN_DIRTY, charmaps::CYRILLIC_DIRTY, true)
-            }
-            (Charset::LatinUnicode, Charset::Cyrillic) => {
-                (charmaps::LATIN_CLEAN_UNICODE, charmaps::CYRILLIC_CLEAN, true)
-            }
-            //
-            (Charset::Cyrillic, Charset::Latin) => {
-                (charmaps::CYRILLIC_CLEAN, charmaps::LATIN_CLEAN, false)
-            }
-            (Charset::Cyrillic, Charset::LatinUnicode) => {
-                (charmaps::CYRILLIC_CLEAN, charmaps::LATIN_CLEAN_UNICODE, false)
-            }
-        };
+        let language = Language::Serbian;
+        let (f, i, e) = language.charmaps(from, into);
         assert_eq!(f.len(), i.len());
         Self {
+            from,
+            into,
             charset_from: f,
             charset_into: i,
+            index: charmaps::index(f),
             exceptions: e,
             skip_digraph,
             force_foreign,
             force_links,
+            force_roman: false,
+            fix_ocr: false,
+            fold: Fold::default(),
+            titlecase_digraphs: false,
+            #[cfg(feature = "dates")]
+            dates: false,
+            skip_converted: None,
+            max_input: None,
+            max_word: None,
+            #[cfg(feature = "ngram")]
+            ngram: false,
+            memo_exceptions: true,
+            language,
+            archaic: Archaic::Ignore,
+            compounds: Compounds::Whole,
+            alphanumeric: Alphanumeric::Suffixes,
+            letters: Letters::Convert,
+            #[cfg(feature = "dialect")]
+            dialect: None,
+            digraph_exceptions: Default::default(),
+            exception_tries: Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default()),
+            protected: Vec::new(),
+            #[cfg(feature = "gazetteer")]
+            gazetteer: false,
+            ansi: false,
+            skip_patterns: Vec::new(),
+            strict: false,
+            passthrough: None,
+            #[cfg(feature = "detect-language")]
+            detect_language: false,
         }
     }
 
-    fn chars_to_utf8(input: &[char], output: &mut [u8]) -> Result<usize, Error> {
-        let mut cursor: usize = 0;
-        for c in input {
-            let length = c.len_utf8();
-            if cursor + length > output.len() {
-                return Err(Error::BufferOverflow);
-            }
-            c.encode_utf8(&mut output[cursor..cursor + length]);
-            cursor += length;
+    /// Returns transliterator between character sets of `direction` with
+    /// default options, which is created once and shared by all callers.
+    /// Its options never change, so results depend only on the text, as
+    /// deterministic functions (ie. of database extensions) require.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Charset, Direction, Transliterator};
+    /// let direction = Direction {
+    ///     from: Charset::Cyrillic,
+    ///     into: Charset::Latin,
+    /// };
+    /// let t = Transliterator::for_direction(direction);
+    /// assert_eq!(t.process("Њива").unwrap(), "Njiva");
+    /// assert!(std::ptr::eq(t, Transliterator::for_direction(direction)));
+    /// ```
+    pub fn for_direction(direction: Direction) -> &'static Transliterator {
+        lazy_static! {
+            /// Transliterators between each pair of character sets
+            static ref TRANSLITERATORS: Vec<Transliterator> = CHARSETS
+                .iter()
+                .flat_map(|&from| {
+                    CHARSETS
+                        .iter()
+                        .map(move |&into| Transliterator::new(from, into, false, false, false))
+                })
+                .collect();
         }
-        Ok(cursor)
+        let index = |charset| CHARSETS.iter().position(|&c| c == charset).unwrap_or(0);
+        &TRANSLITERATORS[index(direction.from) * CHARSETS.len() + index(direction.into)]
     }
 
-    fn digraph_exception<'a>(
-        word: &[char],
-        character: &'a [char],
-        latinize: bool,
-    ) -> Result<Option<&'a Character<'a>>, Error> {
-        for exception in charmaps::DIGRAPH_EXCEPTIONS {
-            for i in 0..exception.latin.len() {
-                if exception.latin[i].value == character {
-                    let mut lowercase: Vec<u8> = vec![0; word.len() * 4];
-                    let mut cursor: usize = 0;
-                    for letter in word {
-                        for c in letter.to_lowercase() {
-                            cursor += Self::chars_to_utf8(&[c], &mut lowercase[cursor..])?;
-                        }
-                    }
-                    for e in exception.exceptions {
-                        if bmh::find(&lowercase, e.as_bytes()).is_some() {
-                            if latinize {
-                                return Ok(Some(&exception.latinized[i]));
-                            } else {
-                                return Ok(Some(&exception.cyrillic[i]));
-                            }
-                        }
-                    }
-                }
+    /// Returns BCP 47 language tag of transliterated text (ie. `sr-Latn`)
+    pub(crate) fn target_tag(&self) -> String {
+        let script = match self.into.script() {
+            Charset::Cyrillic => "Cyrl",
+            _ => "Latn",
+        };
+        format!("{}-{}", self.language, script)
+    }
+
+    /// Returns string transliterator between two BCP 47 language tags.
+    /// Language, script and dialect (`ekavsk`, `ijekavsk`) are resolved
+    /// from the tags. Latin script with single character digraphs is
+    /// selected with private use subtag `x-unicode`.
+    /// Fails if a tag is not supported or languages of tags differ.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Transliterate words from this language tag
+    /// * `into` - Transliterate words into this language tag
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::Transliterator;
+    /// let t = Transliterator::for_tags("sr-Latn", "sr-Cyrl").unwrap();
+    /// assert_eq!(t.process("Ljubičica").unwrap(), "Љубичица".to_owned());
+    /// # #[cfg(feature = "dialect")] {
+    /// let t = Transliterator::for_tags("sr-Cyrl-ijekavsk", "sr-Latn-ekavsk").unwrap();
+    /// assert_eq!(t.process("Лијепа ријеч").unwrap(), "Lepa reč".to_owned());
+    /// # }
+    /// # #[cfg(feature = "kazakh")] {
+    /// let t = Transliterator::for_tags("kk-Cyrl", "kk-Latn").unwrap();
+    /// assert_eq!(t.process("Қазақстан").unwrap(), "Qazaqstan".to_owned());
+    /// # }
+    /// assert!(Transliterator::for_tags("sr-Latn", "kk-Cyrl").is_err());
+    /// ```
+    pub fn for_tags(from: &str, into: &str) -> Result<Self, Error> {
+        let source = tags::parse(from)?;
+        let target = tags::parse(into)?;
+        if source.language != target.language {
+            return Err(Error::UnsupportedTag(
+                into.to_owned(),
+                "language differs from source language",
+            ));
+        }
+        let transliterator = Self::new(source.charset, target.charset, false, false, false)
+            .with_language(target.language)
+            .with_archaic(source.archaic);
+        #[cfg(feature = "dialect")]
+        let transliterator = transliterator.with_dialect(match (source.dialect, target.dialect) {
+            (Some(s), Some(t)) if s == t => None,
+            (_, target) => target.map(DialectConverter::new),
+        });
+        Ok(transliterator)
+    }
+
+    /// Transliterate words that look like Roman numerals (ie. `XIX`, `II`)
+    /// instead of preserving them
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("IV vek").unwrap(), "IV век".to_owned());
+    /// let t = t.with_force_roman(true);
+    /// assert_eq!(t.process("IV vek").unwrap(), "ИВ век".to_owned());
+    /// ```
+    pub fn with_force_roman(mut self, force_roman: bool) -> Self {
+        self.force_roman = force_roman;
+        self
+    }
+
+    /// Fix common OCR errors in words before transliterating them,
+    /// ie. Latin letters in Cyrillic words, digits between letters
+    /// (`ko1ač`) and `rn` recognized instead of `m` (`rnesto`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+    /// assert_eq!(t.process("Дo6ар дан").unwrap(), "Дo6ар dan".to_owned());
+    /// let t = t.with_fix_ocr(true);
+    /// assert_eq!(t.process("Дo6ар дан").unwrap(), "Dobar dan".to_owned());
+    /// ```
+    pub fn with_fix_ocr(mut self, fix_ocr: bool) -> Self {
+        self.fix_ocr = fix_ocr;
+        self
+    }
+
+    /// Leave lines of text intact if they are already in the character set
+    /// they are transliterated into, with at most `tolerance` of their
+    /// letters in the source script (see
+    /// [`Transliterator::already_in_target_script`]), so converting
+    /// partially converted content again changes nothing
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset, CONVERTED_TOLERANCE};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_skip_converted(Some(CONVERTED_TOLERANCE));
+    /// assert_eq!(t.process("Конјугација глагола и nj").unwrap(), "Конјугација глагола и nj".to_owned());
+    /// assert_eq!(t.process("Konjugacija").unwrap(), "Конјугација".to_owned());
+    /// ```
+    pub fn with_skip_converted(mut self, tolerance: Option<f64>) -> Self {
+        self.skip_converted = tolerance;
+        self
+    }
+
+    /// Fail on texts longer than `limit` bytes instead of transliterating
+    /// them, which bounds memory used for untrusted input (ie. by services)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_max_input(Some(16));
+    /// assert_eq!(t.process("Dobar dan").unwrap(), "Добар дан".to_owned());
+    /// assert!(t.process("Dobar dan i laku noć").is_err());
+    /// ```
+    pub fn with_max_input(mut self, limit: Option<usize>) -> Self {
+        self.max_input = limit;
+        self
+    }
+
+    /// Fail on words longer than `limit` bytes instead of transliterating
+    /// them, since every word is copied into scratch buffers several times
+    /// its length
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_max_word(Some(8));
+    /// assert_eq!(t.process("Dobar dan").unwrap(), "Добар дан".to_owned());
+    /// assert!(t.process("Dobar dan, Ljubičice").is_err());
+    /// ```
+    pub fn with_max_word(mut self, limit: Option<usize>) -> Self {
+        self.max_word = limit;
+        self
+    }
+
+    /// Fail if `word` is longer than the word length limit
+    fn check_word_length(&self, word: &str) -> Result<(), Error> {
+        match self.max_word {
+            Some(limit) if word.len() > limit => Err(Error::WordTooLong(word.len(), limit)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns whether `text` is already in the character set it is
+    /// transliterated into, with at most `tolerance` of its Latin and
+    /// Cyrillic letters in the source script (ie. brand names). Text
+    /// without letters is already converted, but text is never converted
+    /// between character sets of the same script.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+    /// assert!(t.already_in_target_script("Ljubičica i Ђ", 0.1));
+    /// assert!(!t.already_in_target_script("Љубичица и Đ", 0.1));
+    /// assert!(!t.already_in_target_script("Ljubičica i Ђ", 0.0));
+    /// ```
+    pub fn already_in_target_script(&self, text: &str, tolerance: f64) -> bool {
+        let from = self.from.script();
+        if from == self.into.script() {
+            return false;
+        }
+        let (mut letters, mut source) = (0, 0);
+        for charset in text.chars().filter_map(Charset::of) {
+            letters += 1;
+            if charset == from {
+                source += 1;
             }
         }
-        Ok(None)
+        source as f64 <= tolerance * letters as f64
     }
 
-    fn foreign_pattern_exception(word: &str) -> bool {
-        lazy_static! {
-            // Borrowed from https://stackoverflow.com/a/26093611
-            static ref RE_DOMAIN: Regex = Regex::new(
-                r"[a-zA-Z0-9][a-zA-Z0-9-]{1,61}[a-zA-Z0-9](?:\.[a-zA-Z]{2,})+"
-            ).unwrap();
+    /// Fold compatibility characters (ie. fullwidth `Ａ`, superscript `ᵃ`,
+    /// circled `Ⓐ`) of words into plain letters and digits before
+    /// transliterating them, so they are converted instead of being
+    /// foreign. No characters are folded by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Fold, Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("Ｄｏｂａｒ dan").unwrap(), "Ｄｏｂａｒ дан".to_owned());
+    /// let t = t.with_fold("fullwidth".parse::<Fold>().unwrap());
+    /// assert_eq!(t.process("Ｄｏｂａｒ dan").unwrap(), "Добар дан".to_owned());
+    /// ```
+    pub fn with_fold(mut self, fold: Fold) -> Self {
+        self.fold = fold;
+        self
+    }
+
+    /// Write titlecase digraphs in Latin as single letters `ǅ`, `ǈ` and `ǋ`
+    /// instead of `Dž`, `Lj` and `Nj` (ie. `Џон` as `ǅon`), so typesetting
+    /// treats them as single letters. Lowercase and uppercase digraphs are
+    /// still written as two letters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+    /// assert_eq!(t.process("Љубљана").unwrap(), "Ljubljana".to_owned());
+    /// let t = t.with_titlecase_digraphs(true);
+    /// assert_eq!(t.process("Љубљана").unwrap(), "ǈubljana".to_owned());
+    /// assert_eq!(t.process("ЉУБЉАНА").unwrap(), "LJUBLJANA".to_owned());
+    /// ```
+    pub fn with_titlecase_digraphs(mut self, titlecase_digraphs: bool) -> Self {
+        self.titlecase_digraphs = titlecase_digraphs;
+        self
+    }
+
+    /// Transliterate dates and ordinal numbers written without spaces
+    /// (ie. `15.januar`, `1.jan.`, `1-vi`), which are otherwise kept as
+    /// alphanumeric words, if their letters are month and day names or
+    /// ordinal suffixes of Serbian, Croatian or Bosnian
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("ponedeljak, 15.jan.").unwrap(), "понедељак, 15.jan.".to_owned());
+    /// let t = t.with_dates(true);
+    /// assert_eq!(t.process("ponedeljak, 15.jan.").unwrap(), "понедељак, 15.јан.".to_owned());
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn with_dates(mut self, dates: bool) -> Self {
+        self.dates = dates;
+        self
+    }
+
+    /// Remember outputs of words which needed a digraph exception search
+    /// within a single text, so recurring words (ie. `konjugacija` in a
+    /// book) are searched only once. Enabled by default, and can be
+    /// disabled to save memory on texts with many distinct words.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_exception_memo(false);
+    /// assert_eq!(t.process("injekcija").unwrap(), "инјекција".to_owned());
+    /// ```
+    pub fn with_exception_memo(mut self, memo_exceptions: bool) -> Self {
+        self.memo_exceptions = memo_exceptions;
+        self
+    }
+
+    /// Resolve ambiguous digraphs `dj`, `dž` and `nj` which don't match any
+    /// digraph exception with a character n-gram model trained on Serbian
+    /// words, which picks the likelier of a single letter and two letters
+    /// (ie. prefix boundaries in `predžetveni`). Available with the `ngram`
+    /// cargo feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("predžetveni konji").unwrap(), "преџетвени коњи".to_owned());
+    /// let t = t.with_ngram(true);
+    /// assert_eq!(t.process("predžetveni konji").unwrap(), "преджетвени коњи".to_owned());
+    /// ```
+    #[cfg(feature = "ngram")]
+    pub fn with_ngram(mut self, ngram: bool) -> Self {
+        self.ngram = ngram;
+        self
+    }
+
+    /// Follow orthography conventions of a language. Croatian and Bosnian
+    /// always write out `đ`, so `dj` is transliterated as two letters.
+    /// Kazakh uses its own alphabets (2021 Latin alphabet).
+    /// Uzbek Latin accepts any apostrophe-like character in `oʻ`, `gʻ` and `ʼ`.
+    /// Kazakh and Uzbek are available with cargo features of the same name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset, Language};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("Djeca").unwrap(), "Дјеца".to_owned());
+    /// assert_eq!(t.process("Djordje").unwrap(), "Ђорђе".to_owned());
+    /// let t = t.with_language(Language::Bosnian);
+    /// assert_eq!(t.process("Djordje").unwrap(), "Дјордје".to_owned());
+    ///
+    /// # #[cfg(feature = "kazakh")] {
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false)
+    ///     .with_language(Language::Kazakh);
+    /// assert_eq!(t.process("Қазақстан").unwrap(), "Qazaqstan".to_owned());
+    /// # }
+    ///
+    /// # #[cfg(feature = "uzbek")] {
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_language(Language::Uzbek);
+    /// assert_eq!(t.process("O'zbekiston").unwrap(), "Ўзбекистон".to_owned());
+    /// # }
+    /// ```
+    pub fn with_language(mut self, language: Language) -> Self {
+        let (f, i, e) = language.charmaps(self.from, self.into);
+        assert_eq!(f.len(), i.len());
+        self.charset_from = f;
+        self.charset_into = i;
+        self.index = charmaps::index(f);
+        self.exceptions = e;
+        self.language = language;
+        self.exception_tries = Exceptions::new(language.orthography(), &self.digraph_exceptions);
+        self
+    }
+
+    /// Treatment of pre-reform Cyrillic letters (ie. `ѣ`, `і`, `ы`, `ъ`)
+    /// when transliterating historic texts from Cyrillic
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Archaic, Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+    /// assert_eq!(t.process("Сербскій народъ").unwrap(), "Сербскій народъ".to_owned());
+    /// let t = t.with_archaic(Archaic::Modernize);
+    /// assert_eq!(t.process("Сербскій народъ").unwrap(), "Serbskij narod".to_owned());
+    /// let t = t.with_archaic(Archaic::Annotate);
+    /// assert_eq!(t.process("Сербскій народъ").unwrap(), "Serbski[і]j[й] narod[ъ]".to_owned());
+    /// ```
+    pub fn with_archaic(mut self, archaic: Archaic) -> Self {
+        self.archaic = archaic;
+        self
+    }
+
+    /// Split compound words on internal hyphens or apostrophes, and
+    /// transliterate each component with its own digraph exceptions,
+    /// foreign characters and Roman numerals. Apostrophes are never
+    /// split for languages where they are part of letters (ie. Uzbek).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Compounds, Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("Wolfgang-Njegoš").unwrap(), "Wolfgang-Njegoš".to_owned());
+    /// let t = t.with_compounds(Compounds::Hyphens);
+    /// assert_eq!(t.process("Wolfgang-Njegoš").unwrap(), "Wolfgang-Његош".to_owned());
+    /// ```
+    pub fn with_compounds(mut self, compounds: Compounds) -> Self {
+        self.compounds = compounds;
+        self
+    }
+
+    /// Treatment of words which mix digits and letters, as classified by
+    /// [`WordClass::of`]. Product and model names are written in Latin, so
+    /// they are kept intact only when transliterating from Latin. Numbers
+    /// and capitalized ASCII words following a model name are part of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Alphanumeric, Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(
+    ///     t.process("Od 19-og veka, iPhone 12 Pro").unwrap(),
+    ///     "Од 19-ог века, iPhone 12 Pro".to_owned()
+    /// );
+    /// let t = t.with_alphanumeric(Alphanumeric::Transliterate);
+    /// assert_eq!(t.process("A4 papir").unwrap(), "А4 папир".to_owned());
+    /// ```
+    pub fn with_alphanumeric(mut self, alphanumeric: Alphanumeric) -> Self {
+        self.alphanumeric = alphanumeric;
+        self
+    }
+
+    /// Returns whether `word` is kept intact by the alphanumeric words
+    /// policy, and whether it follows a product or model name (`model`)
+    fn alphanumeric_word(&self, word: &str, model: bool) -> bool {
+        let latin = self.from != Charset::Cyrillic;
+        match (self.alphanumeric, WordClass::of(word)) {
+            (Alphanumeric::Transliterate, _) => false,
+            (Alphanumeric::Keep, WordClass::Suffixed | WordClass::Model) => true,
+            (_, WordClass::Model) => latin,
+            (_, WordClass::Number) => model,
+            (_, WordClass::Plain) => {
+                model
+                    && latin
+                    && word.starts_with(|c: char| c.is_ascii_uppercase())
+                    && word.chars().all(|c| c.is_ascii_graphic())
+            }
+            (_, WordClass::Suffixed) => false,
+        }
+    }
+
+    /// Treatment of standalone letters, which may be labels (ie. `tačka a)`,
+    /// `vitamin D`) or words (ie. `a`, `i`, `u`). Letters are transliterated
+    /// by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Letters, Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("tačka a) plan B").unwrap(), "тачка а) план Б".to_owned());
+    /// let t = t.with_letters(Letters::Keep);
+    /// assert_eq!(t.process("tačka a) plan B").unwrap(), "тачка a) план B".to_owned());
+    /// ```
+    pub fn with_letters(mut self, letters: Letters) -> Self {
+        self.letters = letters;
+        self
+    }
+
+    /// Convert words between ekavian and ijekavian before transliterating
+    /// them. Used only for Serbian, Croatian and Bosnian.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Dialect, DialectConverter, Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_dialect(Some(DialectConverter::new(Dialect::Ekavian)));
+    /// assert_eq!(t.process("Lijepa riječ").unwrap(), "Лепа реч".to_owned());
+    /// ```
+    #[cfg(feature = "dialect")]
+    pub fn with_dialect(mut self, dialect: Option<DialectConverter>) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Split digraphs which are part of any of `exceptions` in a word, in
+    /// addition to built-in digraph exceptions. Exceptions are written in lowercase
+    /// Latin and must contain the digraph `dj`, `dž` or `nj`
+    /// (ie. `injunkc` for `injunkcija`). Other exceptions are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process("Injunkcija").unwrap(), "Ињункција".to_owned());
+    /// let t = t.with_exceptions(["injunkc"]);
+    /// assert_eq!(t.process("Injunkcija").unwrap(), "Инјункција".to_owned());
+    /// ```
+    pub fn with_exceptions<I, S>(mut self, exceptions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for exception in exceptions {
+            let exception = exception.as_ref().to_lowercase();
+            for (n, digraph) in exceptions::DIGRAPHS.iter().enumerate() {
+                if exception.contains(digraph) {
+                    self.digraph_exceptions[n].push(exception.clone());
+                }
+            }
+        }
+        self.exception_tries =
+            Exceptions::new(self.language.orthography(), &self.digraph_exceptions);
+        self
+    }
+
+    /// Never transliterate words which are equal to any of `words`,
+    /// ignoring case and surrounding punctuation (ie. brand names)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_protected(["Nikon"]);
+    /// assert_eq!(t.process("Kupio sam Nikon.").unwrap(), "Купио сам Nikon.".to_owned());
+    /// ```
+    pub fn with_protected<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.protected
+            .extend(words.into_iter().map(|w| w.as_ref().to_lowercase()));
+        self
+    }
+
+    /// Never transliterate names in the bundled gazetteer of international
+    /// brands, software and personal names (ie. `Google`, `GitHub`,
+    /// `Michael`), ignoring case and surrounding punctuation. Names which
+    /// are also Serbian words are not included. Protected words are
+    /// checked as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_gazetteer(true)
+    ///     .with_protected(["Nikola"]);
+    /// assert_eq!(
+    ///     t.process("Michael i Nikola koriste Google.").unwrap(),
+    ///     "Michael и Nikola користе Google.".to_owned()
+    /// );
+    /// ```
+    #[cfg(feature = "gazetteer")]
+    pub fn with_gazetteer(mut self, gazetteer: bool) -> Self {
+        self.gazetteer = gazetteer;
+        self
+    }
+
+    /// Pass ANSI escape sequences (ie. colors of terminal output, window
+    /// titles and hyperlinks) through verbatim, so words next to them are
+    /// transliterated and their letters are not
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Charset, Transliterator};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_ansi(true);
+    /// assert_eq!(
+    ///     t.process("\x1b[31mCrveno\x1b[0m i").unwrap(),
+    ///     "\x1b[31mЦрвено\x1b[0m и".to_owned()
+    /// );
+    /// ```
+    pub fn with_ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    /// Never transliterate words matching any of regular expressions
+    /// `patterns`. Fails if a pattern is not a valid regular expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_skip_patterns([r"^\{\{\w+\}\}$"])
+    ///     .unwrap();
+    /// assert_eq!(t.process("Zdravo {{ime}}").unwrap(), "Здраво {{ime}}".to_owned());
+    /// ```
+    pub fn with_skip_patterns<I, S>(mut self, patterns: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for pattern in patterns {
+            self.skip_patterns
+                .push(Regex::new(pattern.as_ref()).map_err(Error::InvalidPattern)?);
+        }
+        Ok(self)
+    }
+
+    /// Fail on texts with characters which are neither Latin nor Cyrillic
+    /// letters nor in passthrough classes (see
+    /// [`with_passthrough`](Self::with_passthrough)) instead of passing
+    /// them through, for pipelines which must not let stray symbols or
+    /// damaged text (ie. `�`) through
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_strict(true);
+    /// assert_eq!(t.process("Strana 5, red 2.").unwrap(), "Страна 5, ред 2.".to_owned());
+    /// assert!(t.process("Ko\u{fffd}ulja").is_err());
+    /// ```
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Pass characters of `classes` through in strict mode instead of the
+    /// default ones, as in [`UnmappedReport::with_passthrough`](crate::UnmappedReport::with_passthrough).
+    /// Default classes are kept if there are none. Fails if a class is
+    /// unknown.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_strict(true)
+    ///     .with_passthrough(&["digits", "punctuation", "currency"])
+    ///     .unwrap();
+    /// assert_eq!(t.process("Cena: 10 €").unwrap(), "Цена: 10 €".to_owned());
+    /// assert!(t.process("Cena: 10 € ☺").is_err());
+    /// ```
+    pub fn with_passthrough<S: AsRef<str>>(mut self, classes: &[S]) -> Result<Self, Error> {
+        self.passthrough = match classes.is_empty() {
+            true => None,
+            false => Some(unmapped::unmapped(classes)?),
+        };
+        Ok(self)
+    }
+
+    fn protected_word(&self, word: &str) -> bool {
+        let letters = || {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        };
+        if !self.protected.is_empty() && self.protected.contains(&letters()) {
+            return true;
+        }
+        #[cfg(feature = "gazetteer")]
+        if self.gazetteer && gazetteer::contains(&letters()) {
+            return true;
+        }
+        self.skip_patterns.iter().any(|p| p.is_match(word))
+    }
+
+    /// Detect language of each sentence and skip sentences which are not
+    /// in a Slavic language (ie. English quotations)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+    ///     .with_detect_language(true);
+    /// assert_eq!(
+    ///     t.process("Rekao je tada. He said that he would never come back to this town.").unwrap(),
+    ///     "Рекао је тада. He said that he would never come back to this town.".to_owned()
+    /// );
+    /// ```
+    #[cfg(feature = "detect-language")]
+    pub fn with_detect_language(mut self, detect_language: bool) -> Self {
+        self.detect_language = detect_language;
+        self
+    }
+
+    /// Returns whether `character` has digraph exceptions to search for
+    fn has_digraph_exceptions(character: &[char]) -> bool {
+        charmaps::DIGRAPH_EXCEPTIONS
+            .iter()
+            .any(|exception| exception.latin.iter().any(|c| c.value == character))
+    }
+
+    /// Returns replacement of digraph `dj` if `Đ` is always written out in
+    /// the `orthography`, so `dj` is never a digraph
+    fn split_digraph<'a>(
+        character: &'a [char],
+        latinize: bool,
+        orthography: &Orthography,
+    ) -> Option<&'a Character<'a>> {
+        let exception = &charmaps::DIGRAPH_EXCEPTIONS[0];
+        if !orthography.split_dj || character.len() != 2 {
+            return None;
+        }
+        let i = exception.latin.iter().position(|c| c.value == character)?;
+        Some(match latinize {
+            true => &exception.latinized[i],
+            false => &exception.cyrillic[i],
+        })
+    }
+
+    /// Returns replacement of digraph `character` at `position` of `word`
+    /// if it is not a digraph in this word
+    fn digraph_exception<'a>(
+        word: &[char],
+        position: usize,
+        character: &'a [char],
+        latinize: bool,
+        orthography: &Orthography,
+        exceptions: &Exceptions,
+    ) -> Option<&'a Character<'a>> {
+        for (n, exception) in charmaps::DIGRAPH_EXCEPTIONS.iter().enumerate() {
+            for i in 0..exception.latin.len() {
+                if exception.latin[i].value == character {
+                    let result = match latinize {
+                        true => &exception.latinized[i],
+                        false => &exception.cyrillic[i],
+                    };
+                    if exceptions.matches(n, word, position, character.len()) {
+                        return Some(result);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns replacement of digraph `character` at `position` of `word`
+    /// if the n-gram model finds two letters likelier than a single one
+    #[cfg(feature = "ngram")]
+    fn ngram_exception(
+        &self,
+        word: &[char],
+        position: usize,
+        character: &[char],
+        latinize: bool,
+    ) -> Option<&'static Character<'static>> {
+        if !self.ngram || character.len() != 2 || !ngram::SERBIAN.split(word, position) {
+            return None;
+        }
+        charmaps::DIGRAPH_EXCEPTIONS.iter().find_map(|exception| {
+            let i = exception.latin.iter().position(|c| c.value == character)?;
+            Some(match latinize {
+                true => &exception.latinized[i],
+                false => &exception.cyrillic[i],
+            })
+        })
+    }
+
+    fn foreign_pattern_exception(word: &str) -> bool {
+        lazy_static! {
+            // Borrowed from https://stackoverflow.com/a/26093611
+            static ref RE_DOMAIN: Regex = Regex::new(
+                r"[a-zA-Z0-9][a-zA-Z0-9-]{1,61}[a-zA-Z0-9](?:\.[a-zA-Z]{2,})+"
+            ).unwrap();
             // Borrowed from https://www.emailregex.com/
             static ref RE_EMAIL: Regex =
                 Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,6}\b").unwrap();
@@ -195,233 +1555,934 @@ impl Transliterator {
                 r"(\d+([\.,]\d)*)((K|°[FC]|[kKMGTPEY](ib|b|iB|B|Hz)|[pnμmcdhk]m[²³]?|m[²³]|[mcdkh][lg])|([zafpnμmcdhKMGTPEY]?([BVWJFSHCΩATNhlmg]|m[²³]|s[²]|cd|Pa|Wb|Hz|deg|rad)))"
             ).unwrap();
         }
-        RE_DOMAIN.is_match(word)
-            || RE_EMAIL.is_match(word)
-            || RE_URL.is_match(word)
-            || RE_MEASUREMENT.is_match(word)
+        RE_DOMAIN.is_match(word)
+            || RE_EMAIL.is_match(word)
+            || RE_URL.is_match(word)
+            || RE_MEASUREMENT.is_match(word)
+    }
+
+    fn roman_numeral_exception(word: &str) -> bool {
+        lazy_static! {
+            static ref RE_ROMAN: Regex =
+                Regex::new(r"^\pP*M{0,3}(CM|CD|D?C{0,3})(XC|XL|L?X{0,3})(IX|IV|V?I{0,3})\pP*$")
+                    .unwrap();
+        }
+        let letters = word.trim_matches(|c: char| !c.is_alphanumeric());
+        // Single letters are ambiguous without context
+        letters.chars().count() > 1 && !ROMAN_WORDS.contains(&letters) && RE_ROMAN.is_match(word)
+    }
+
+    /// Try transliterating a single word (without spaces).
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - Word to transliterate
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// // Transliterate Latin word to Cyrillic
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.process_word("Ljubičica").unwrap(), "Љубичица".to_owned());
+    ///
+    /// // Transliterate Cyrillic word to Latin Unicode
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::LatinUnicode, false, false, false);
+    /// assert_eq!(t.process_word("Љубичица").unwrap(), "ǈubičica".to_owned());
+    /// ```
+    pub fn process_word(&self, word: &str) -> Result<String, Error> {
+        self.transliterate_word(word, true)
+    }
+
+    /// Try transliterating a single word (without spaces), such as a token
+    /// of an NLP pipeline, choosing whether to search for digraph
+    /// exceptions. Skipping the search is the cheapest conversion of a
+    /// word, while `exceptions` has no effect if the transliterator skips
+    /// digraph exceptions. Unlike [`process`](Self::process), words are not
+    /// split on spaces, and OCR fixes and dialect conversion are not applied.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - Word to transliterate
+    /// * `exceptions` - Search for digraph exceptions (ie. `injekcija`)
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(t.transliterate_word("injekcija", true).unwrap(), "инјекција".to_owned());
+    /// assert_eq!(t.transliterate_word("injekcija", false).unwrap(), "ињекција".to_owned());
+    /// ```
+    pub fn transliterate_word(&self, word: &str, exceptions: bool) -> Result<String, Error> {
+        let mut output = String::with_capacity(word.len());
+        self.word_into(word, &mut Context::new(exceptions), &mut output)?;
+        Ok(output)
+    }
+
+    /// Try transliterating a single word (without spaces), appending it to
+    /// `output` instead of allocating a new string.
+    /// Fails if provided string is not valid UTF-8, in which case part of
+    /// the word may already be appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - Word to transliterate
+    /// * `output` - Buffer to append the transliterated word to
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let mut output = String::from("Реч: ");
+    /// t.process_word_into("Ljubičica", &mut output).unwrap();
+    /// assert_eq!(output, "Реч: Љубичица");
+    /// ```
+    pub fn process_word_into(&self, word: &str, output: &mut String) -> Result<(), Error> {
+        self.word_into(word, &mut Context::new(true), output)
+    }
+
+    fn word_into(
+        &self,
+        word: &str,
+        context: &mut Context,
+        output: &mut String,
+    ) -> Result<(), Error> {
+        self.check_word_length(word)?;
+        let skipped = if self.protected_word(word) {
+            Some(Decision::Protected)
+        } else if self.alphanumeric_word(word, false) {
+            Some(Decision::Model)
+        } else if self.letters.keep(word) {
+            Some(Decision::Letter)
+        } else {
+            None
+        };
+        if let Some(_decision) = skipped {
+            #[cfg(feature = "tracing")]
+            Self::log_decision(word, word, &_decision);
+            output.push_str(word);
+            return Ok(());
+        }
+        let _start = output.len();
+        let _decision = self.process_word_decision(word, context, output)?;
+        #[cfg(feature = "tracing")]
+        Self::log_decision(word, &output[_start..], &_decision);
+        Ok(())
+    }
+
+    /// Append transliterated `word` to `output`, component by component
+    /// if it's a compound word
+    fn process_word_decision(
+        &self,
+        word: &str,
+        context: &mut Context,
+        output: &mut String,
+    ) -> Result<Decision, Error> {
+        let compounds = match self.compounds {
+            Compounds::All if self.language.apostrophe_letters() => Compounds::Hyphens,
+            Compounds::Apostrophes if self.language.apostrophe_letters() => Compounds::Whole,
+            compounds => compounds,
+        };
+        // Links are never split (ie. `moj-sajt.rs`)
+        if compounds == Compounds::Whole || Self::foreign_pattern_exception(word) {
+            return self.process_component(word, context, output);
+        }
+        let mut decision: Option<Decision> = None;
+        for (component, separator) in compounds.split(word) {
+            let current = self.process_component(component, context, output)?;
+            output.push_str(separator);
+            // Compound is transliterated if any of its components is
+            decision = match (decision, current) {
+                (Some(Decision::Exception), _) | (_, Decision::Exception) => {
+                    Some(Decision::Exception)
+                }
+                (Some(Decision::Transliterated), _) | (_, Decision::Transliterated) => {
+                    Some(Decision::Transliterated)
+                }
+                (decision, current) => decision.or(Some(current)),
+            };
+        }
+        Ok(decision.unwrap_or(Decision::Transliterated))
+    }
+
+    fn process_component(
+        &self,
+        word: &str,
+        context: &mut Context,
+        output: &mut String,
+    ) -> Result<Decision, Error> {
+        let start = output.len();
+        if self.from == Charset::Cyrillic
+            && matches!(self.language, Language::Serbian | Language::Croatian | Language::Bosnian)
+        {
+            if let Some((modern, originals)) = archaic::modernize(word, self.archaic) {
+                let decision = self.process_word_charmap(&modern, context, output)?;
+                let restored = match decision {
+                    Decision::Foreign => word.to_string(),
+                    _ => archaic::restore(&output[start..], &originals, self.archaic),
+                };
+                output.truncate(start);
+                output.push_str(&restored);
+                return Ok(decision);
+            }
+        }
+        #[cfg(feature = "uzbek")]
+        if self.language == Language::Uzbek {
+            let decision = match self.from {
+                Charset::Cyrillic => self.process_word_charmap(word, context, output)?,
+                _ => {
+                    match self.process_word_charmap(
+                        &uzbek::normalize_apostrophes(word),
+                        context,
+                        output,
+                    )? {
+                        Decision::Foreign => {
+                            output.truncate(start);
+                            output.push_str(word);
+                            Decision::Foreign
+                        }
+                        decision => decision,
+                    }
+                }
+            };
+            if decision == Decision::Transliterated
+                && (self.into == Charset::Cyrillic || self.from == Charset::Cyrillic)
+            {
+                let mut fixed = output.split_off(start);
+                uzbek::fix_initial(word, &mut fixed, self.from == Charset::Cyrillic);
+                output.push_str(&fixed);
+            }
+            return Ok(decision);
+        }
+        self.process_word_charmap(word, context, output)
+    }
+
+    fn process_word_charmap(
+        &self,
+        word: &str,
+        context: &mut Context,
+        output: &mut String,
+    ) -> Result<Decision, Error> {
+        let start = output.len();
+        let mut cursor_in: usize = 0;
+        let mut force_process: bool = false;
+        let mut decision = Decision::Transliterated;
+        if Self::foreign_pattern_exception(word) {
+            if self.force_links {
+                force_process = true;
+            } else {
+                output.push_str(word);
+                return Ok(Decision::Link);
+            }
+        }
+        if !self.force_roman && Self::roman_numeral_exception(word) {
+            output.push_str(word);
+            return Ok(Decision::Roman);
+        }
+        if let Some((memoized, decision)) = context.memo.get(word) {
+            output.push_str(memoized);
+            return Ok(decision.clone());
+        }
+        let mut searched = false;
+        // Characters of short words are kept on the stack
+        let mut stack = ['\0'; STACK_CHARS];
+        let mut heap = Vec::new();
+        // Combining marks (ie. accents) are transliterated with letters
+        // they follow, so they don't split digraphs and exceptions
+        let index = self.index;
+        let mut letters = accents::Letters::new(word, |c| {
+            index.binary_search_by_key(&c, |(first, _)| *first).is_ok()
+        });
+        let chars: &[char] = if word.len() <= STACK_CHARS {
+            let mut length = 0;
+            for (slot, c) in stack.iter_mut().zip(&mut letters) {
+                *slot = c;
+                length += 1;
+            }
+            &stack[..length]
+        } else {
+            heap.extend(&mut letters);
+            &heap
+        };
+        let marks = letters.marks;
+        let mut attached = 0;
+        'outer: while cursor_in < chars.len() {
+            accents::reattach(&marks, &mut attached, cursor_in, output);
+            // Try only patterns starting with the current character
+            let candidates = match self
+                .index
+                .binary_search_by_key(&chars[cursor_in], |(first, _)| *first)
+            {
+                Ok(n) => self.index[n].1,
+                Err(_) => &[],
+            };
+            'inner: for &i in candidates {
+                let Character { value: c, case: lc } = &self.charset_from[i];
+                if chars[cursor_in..].starts_with(c) && !accents::splits(&marks, cursor_in, c.len())
+                {
+                    // If transliterating to latin8, transliterate exception too
+                    let latinize = self.charset_into == charmaps::LATIN_CLEAN_UNICODE
+                        || self.charset_into == charmaps::LATIN_DIRTY_UNICODE;
+                    // Written out `dj` is not a digraph, even if digraph
+                    // exceptions are skipped
+                    if let Some(split) =
+                        Self::split_digraph(c, latinize, self.language.orthography())
+                    {
+                        output.extend(split.value);
+                        cursor_in += split.value.len();
+                        decision = Decision::Exception;
+                        continue 'outer;
+                    }
+                    if !self.skip_digraph && self.exceptions && context.exceptions {
+                        // Start from bottom to catch digraphs first
+                        searched |= Self::has_digraph_exceptions(c);
+                        let exception = Self::digraph_exception(
+                            chars,
+                            cursor_in,
+                            c,
+                            latinize,
+                            self.language.orthography(),
+                            &self.exception_tries,
+                        );
+                        #[cfg(feature = "ngram")]
+                        let exception = exception
+                            .or_else(|| self.ngram_exception(chars, cursor_in, c, latinize));
+                        if let Some(exception) = exception {
+                            output.extend(exception.value);
+                            cursor_in += exception.value.len();
+                            decision = Decision::Exception;
+                            continue 'outer;
+                        }
+                    }
+                    // Titlecase pattern is skipped in uppercase words
+                    if lc == &LetterCase::Mixed && Self::uppercase_letter(chars, cursor_in, context)
+                    {
+                        continue 'inner;
+                    }
+                    // Exception is not found, proceed to transliterate
+                    let value = self.charset_into[i].value;
+                    match TITLECASE_DIGRAPHS.iter().find(|(pair, _)| *pair == value) {
+                        Some((_, letter)) if self.titlecase_digraphs => output.push(*letter),
+                        _ => output.extend(value),
+                    }
+                    cursor_in += c.len();
+                    continue 'outer;
+                }
+            }
+            if !force_process && !self.force_foreign && chars[cursor_in].is_alphabetic() {
+                // Foreign character is found, return original
+                output.truncate(start);
+                output.push_str(word);
+                return Ok(Decision::Foreign);
+            } else {
+                // Add found non-alphabetic or foreign character
+                output.push(chars[cursor_in]);
+                cursor_in += 1;
+            }
+        }
+        accents::reattach(&marks, &mut attached, cursor_in, output);
+        if searched && self.memo_exceptions {
+            context
+                .memo
+                .insert(word.to_owned(), (output[start..].to_owned(), decision.clone()));
+        }
+        Ok(decision)
+    }
+
+    /// Returns whether the uppercase letter at `position` of `word` is
+    /// written in uppercase, following case pattern of the run of letters
+    /// around it. Single capital letters are written in uppercase in
+    /// uppercase words and in runs of initials next to uppercase words
+    /// (ie. `ЏЕЈМС Џ. БОНД`, `М. Њ. ЛИК`).
+    fn uppercase_letter(word: &[char], position: usize, context: &Context) -> bool {
+        let start = word[..position]
+            .iter()
+            .rposition(|c| !c.is_alphabetic())
+            .map_or(0, |i| i + 1);
+        let end = word[position..]
+            .iter()
+            .position(|c| !c.is_alphabetic())
+            .map_or(word.len(), |i| position + i);
+        match Pattern::of(word[start..end].iter().copied()) {
+            Some(Pattern::Upper) => true,
+            Some(Pattern::Title) if end - start == 1 => {
+                context.uppercase || Pattern::of(word.iter().copied()) == Some(Pattern::Upper)
+            }
+            Some(Pattern::Title) => false,
+            // Letters of mixed case words follow the next letter
+            _ => matches!(word.get(position + 1), Some(c) if !c.is_lowercase()),
+        }
+    }
+
+    /// Try transliterating arbitrary text.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to transliterate
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// // Transliterate Latin sentence to Cyrillic without digraph exceptions
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, true, false, false);
+    /// assert_eq!(
+    ///     t.process_word("Konjugacija je promena glagola po vremenima i licima.").unwrap(),
+    ///     "Коњугација је промена глагола по временима и лицима.".to_owned()
+    /// );
+    /// ```
+    pub fn process<S: AsRef<str>>(&self, input: S) -> Result<String, Error> {
+        let input = input.as_ref();
+        let mut output = String::with_capacity(input.len());
+        self.process_into(input, &mut output)?;
+        Ok(output)
+    }
+
+    /// Try transliterating arbitrary text, appending it to `output`.
+    /// Words are transliterated directly into `output`, so a buffer reused
+    /// across texts (ie. lines of a large corpus) avoids allocations.
+    /// Fails if provided string is not valid UTF-8, in which case part of
+    /// the text may already be appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to transliterate
+    /// * `output` - Buffer to append the transliterated text to
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let mut output = String::new();
+    /// for line in ["Dobar dan", "Laku noć"] {
+    ///     output.clear();
+    ///     t.process_into(line, &mut output).unwrap();
+    /// }
+    /// assert_eq!(output, "Лаку ноћ");
+    /// ```
+    pub fn process_into(&self, input: &str, output: &mut String) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "process",
+            from = ?self.from,
+            into = ?self.into,
+            language = ?self.language,
+            bytes = input.len()
+        )
+        .entered();
+        output.reserve(input.len());
+        self.walk(input, output, &mut |output, span, word| {
+            // Words are already in the output, so only copy spaces
+            if word.is_none() {
+                output.push_str(&input[span]);
+            }
+        })
+    }
+
+    /// Try transliterating arbitrary text, returning it with non-fatal
+    /// issues found in its words: unmapped letters, words mixing scripts,
+    /// unusually long words and placeholders which were transliterated.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to transliterate
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset, Warning, WarningKind};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let (output, warnings) = t.process_with_warnings("Zdravo {ime}").unwrap();
+    /// assert_eq!(output, "Здраво {име}");
+    /// assert_eq!(warnings, vec![Warning { span: 7..12, kind: WarningKind::Placeholder }]);
+    /// ```
+    pub fn process_with_warnings<S: AsRef<str>>(
+        &self,
+        input: S,
+    ) -> Result<(String, Vec<Warning>), Error> {
+        let input = input.as_ref();
+        let mut output = String::with_capacity(input.len());
+        let mut warnings = Vec::new();
+        self.walk(input, &mut output, &mut |output, span, word| match word {
+            Some(word) => self.check_word(
+                &input[span.clone()],
+                span,
+                &output[word.output],
+                word.decision,
+                &mut warnings,
+            ),
+            None => output.push_str(&input[span]),
+        })?;
+        Ok((output, warnings))
+    }
+
+    /// Try transliterating arbitrary text, returning the outcome for every
+    /// word instead of the transliterated text.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to transliterate
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset, Decision};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let trace = t.trace("Konjugacija example.com").unwrap();
+    /// assert_eq!(trace[0].output, "Конјугација");
+    /// assert_eq!(trace[0].decision, Decision::Exception);
+    /// assert_eq!(trace[1].span, 12..23);
+    /// assert_eq!(trace[1].decision, Decision::Link);
+    /// ```
+    pub fn trace<S: AsRef<str>>(&self, input: S) -> Result<Vec<Trace>, Error> {
+        let mut traces = Vec::new();
+        self.walk(input.as_ref(), &mut String::new(), &mut |output, span, word| {
+            if let Some(word) = word {
+                traces.push(Trace {
+                    span,
+                    output: output[word.output].to_owned(),
+                    decision: word.decision,
+                    language: word.language,
+                });
+                output.clear();
+            }
+        })?;
+        Ok(traces)
+    }
+
+    /// Emit an event describing the outcome of transliterating a word
+    #[cfg(feature = "tracing")]
+    fn log_decision(word: &str, output: &str, decision: &Decision) {
+        match decision {
+            Decision::Transliterated => tracing::trace!(word, output, "word processed"),
+            Decision::Exception => tracing::debug!(word, output, "digraph exception applied"),
+            decision => tracing::debug!(word, ?decision, "word skipped"),
+        }
+    }
+
+    /// Transliterate words of `input` into `output`, calling `visit` after
+    /// every word and every run of spaces, which aren't copied into `output`
+    fn walk<F>(&self, input: &str, output: &mut String, visit: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&mut String, Range<usize>, Option<Outcome>),
+    {
+        if let Some(limit) = self.max_input {
+            if input.len() > limit {
+                return Err(Error::InputTooLong(input.len(), limit));
+            }
+        }
+        if self.strict {
+            let unmapped = self.passthrough.as_ref().unwrap_or(&unmapped::RE_UNMAPPED);
+            if let Some(m) = unmapped.find(input) {
+                let c = m.as_str().chars().next().unwrap_or_default();
+                return Err(Error::Unmapped(c, m.start()));
+            }
+        }
+        let mut context = Context::new(true);
+        let tolerance = match self.skip_converted {
+            Some(tolerance) => tolerance,
+            None => return self.walk_escaped(input, 0, &mut context, output, visit),
+        };
+        // Partially converted text is checked line by line
+        let mut offset = 0;
+        for line in input.split_inclusive('\n') {
+            match self.already_in_target_script(line, tolerance) {
+                true => visit(output, offset..offset + line.len(), None),
+                false => self.walk_escaped(line, offset, &mut context, output, visit)?,
+            }
+            offset += line.len();
+        }
+        Ok(())
+    }
+
+    /// Walk text around ANSI escape sequences (ie. colors of terminal
+    /// output) if they are passed through, which are treated like runs of
+    /// spaces, so their letters are not transliterated and words next to
+    /// them are
+    fn walk_escaped<F>(
+        &self,
+        input: &str,
+        offset: usize,
+        context: &mut Context,
+        output: &mut String,
+        visit: &mut F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&mut String, Range<usize>, Option<Outcome>),
+    {
+        lazy_static! {
+            // Control sequences (ie. `\x1b[31m`), operating system commands
+            // (ie. window titles and hyperlinks), strings and other escapes
+            static ref RE_ANSI: Regex = Regex::new(concat!(
+                r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)",
+                r"|[PX^_][^\x1b]*\x1b\\|[ -/]*[0-~])"
+            ))
+            .unwrap();
+        }
+        if !self.ansi || !input.contains('\x1b') {
+            return self.walk_text(input, offset, context, output, visit);
+        }
+        let mut left = 0;
+        for escape in RE_ANSI.find_iter(input) {
+            self.walk_text(&input[left..escape.start()], offset + left, context, output, visit)?;
+            visit(output, offset + escape.start()..offset + escape.end(), None);
+            left = escape.end();
+        }
+        self.walk_text(&input[left..], offset + left, context, output, visit)
+    }
+
+    fn walk_text<F>(
+        &self,
+        input: &str,
+        offset: usize,
+        context: &mut Context,
+        output: &mut String,
+        visit: &mut F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&mut String, Range<usize>, Option<Outcome>),
+    {
+        #[cfg(feature = "detect-language")]
+        if self.detect_language {
+            let mut offset = offset;
+            for sentence in segment::sentences(input) {
+                let language = detect_language(sentence);
+                self.walk_words(sentence, offset, language, context, output, visit)?;
+                offset += sentence.len();
+            }
+            return Ok(());
+        }
+        self.walk_words(input, offset, None, context, output, visit)
+    }
+
+    fn walk_words<F>(
+        &self,
+        input: &str,
+        offset: usize,
+        language: Option<(&'static str, bool)>,
+        context: &mut Context,
+        output: &mut String,
+        visit: &mut F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&mut String, Range<usize>, Option<Outcome>),
+    {
+        let mut cursor_left = 0;
+        fn next_occurence(input: &str, left: usize, match_text: bool) -> usize {
+            let criterion = match match_text {
+                true => |c: char| c.is_whitespace(),
+                false => |c: char| !c.is_whitespace(),
+            };
+            if let Some(res) = input[left..].find(criterion) {
+                cmp::min(left + res, input.len())
+            } else {
+                input.len()
+            }
+        }
+        fn regnal_numeral(previous: Option<&str>, word: &str, next: Option<&str>) -> bool {
+            // Single letter numerals and numerals which are also words are
+            // recognized only after a capitalized name on the same line
+            // (ie. `Petar I`, `Luj VI`), and single letters only if they
+            // are not followed by another name (ie. `Ana I Marko`)
+            let name = |p: &str| {
+                let mut chars = p.chars();
+                matches!(chars.next(), Some(c) if c.is_uppercase())
+                    && chars.all(char::is_lowercase)
+                    && p.chars().count() > 2
+            };
+            let capitalized = |n: &str| matches!(n.chars().find(|c| c.is_alphabetic()), Some(c) if c.is_uppercase());
+            let word = word.trim_end_matches(|c: char| c.is_ascii_punctuation());
+            let letter = matches!(word, "I" | "V" | "X" | "L" | "C" | "D" | "M");
+            (letter && !next.map_or(false, capitalized) || ROMAN_WORDS.contains(&word))
+                && matches!(previous, Some(p) if name(p))
+        }
+        let mut match_text: bool = false;
+        let mut previous: Option<&str> = None;
+        // Previous word other than an initial is written in uppercase
+        let mut upper = false;
+        let mut model = false;
+        while cursor_left < input.len() {
+            let cursor_right = next_occurence(input, cursor_left, match_text);
+            let span = offset + cursor_left..offset + cursor_right;
+            if match_text {
+                let word = &input[cursor_left..cursor_right];
+                self.check_word_length(word)?;
+                let start = output.len();
+                // Runs of capital initials (ie. `Џ. Б.`) take case of the
+                // nearest words around them
+                let initial = |word: &str| {
+                    let mut letters = word.chars().filter(|c| c.is_alphabetic());
+                    matches!((letters.next(), letters.next()), (Some(c), None) if c.is_uppercase())
+                };
+                context.uppercase = initial(word)
+                    && (upper
+                        || input[cursor_right..]
+                            .split_whitespace()
+                            .find(|w| !initial(w))
+                            .map_or(false, |w| Pattern::of(w.chars()) == Some(Pattern::Upper)));
+                let decision = match language {
+                    Some((code, false)) => {
+                        output.push_str(word);
+                        Decision::Language(code)
+                    }
+                    _ if !self.force_roman
+                        && regnal_numeral(
+                            previous,
+                            word,
+                            input[cursor_right..].split_whitespace().next(),
+                        ) =>
+                    {
+                        output.push_str(word);
+                        Decision::Roman
+                    }
+                    _ if self.protected_word(word) => {
+                        output.push_str(word);
+                        Decision::Protected
+                    }
+                    #[cfg(feature = "dates")]
+                    _ if self.dates
+                        && matches!(
+                            self.language,
+                            Language::Serbian | Language::Croatian | Language::Bosnian
+                        )
+                        && self.date_into(word, output)? =>
+                    {
+                        Decision::Transliterated
+                    }
+                    _ if self.alphanumeric_word(word, model) => {
+                        output.push_str(word);
+                        Decision::Model
+                    }
+                    _ if self.letters.keep(word) => {
+                        output.push_str(word);
+                        Decision::Letter
+                    }
+                    _ => {
+                        let word = match (self.fix_ocr, self.fold.apply(word)) {
+                            (true, Cow::Borrowed(word)) => ocr::fix(word),
+                            (true, Cow::Owned(word)) => Cow::Owned(ocr::fix(&word).into_owned()),
+                            (false, word) => word,
+                        };
+                        #[cfg(feature = "dialect")]
+                        let word = match &self.dialect {
+                            Some(dialect)
+                                if matches!(
+                                    self.language,
+                                    Language::Serbian | Language::Croatian | Language::Bosnian
+                                ) =>
+                            {
+                                Cow::Owned(dialect.process_word(&word).into_owned())
+                            }
+                            _ => word,
+                        };
+                        self.process_word_decision(&word, context, output)?
+                    }
+                };
+                #[cfg(feature = "tracing")]
+                Self::log_decision(word, &output[start..], &decision);
+                // Model names end with punctuation (ie. `iPhone 12, ...`)
+                model = decision == Decision::Model
+                    && !word.ends_with(|c: char| c.is_ascii_punctuation());
+                let outcome = Outcome {
+                    output: start..output.len(),
+                    decision,
+                    language: language.map(|(code, _)| code),
+                };
+                visit(output, span, Some(outcome));
+                if !initial(word) {
+                    upper = Pattern::of(word.chars()) == Some(Pattern::Upper);
+                }
+                previous = Some(word);
+            } else {
+                if input[cursor_left..cursor_right].contains('\n') {
+                    previous = None;
+                }
+                visit(output, span, None);
+            }
+            cursor_left = cursor_right;
+            // Toggle between processing whitespace and other characters
+            match_text = !match_text;
+        }
+        Ok(())
     }
 
-    /// Try transliterating a single word (without spaces).
+    /// Try transliterating only selected segments of a text, leaving other
+    /// segments intact. Segments are concatenated into the output in order.
     /// Fails if provided string is not valid UTF-8.
     ///
     /// # Arguments
     ///
-    /// * `word` - Word to transliterate
+    /// * `segments` - Text segments (ie. from `sentences` or `paragraphs`)
+    /// * `predicate` - Transliterate segment only if this returns `true`
     /// # Examples
     ///
     /// ```
-    /// use translitrs::{Transliterator, Charset};
+    /// use translitrs::{sentences, Transliterator, Charset};
     ///
-    /// // Transliterate Latin word to Cyrillic
+    /// // Transliterate only questions
     /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
-    /// assert_eq!(t.process_word("Ljubičica").unwrap(), "Љубичица".to_owned());
-    ///
-    /// // Transliterate Cyrillic word to Latin Unicode
-    /// let t = Transliterator::new(Charset::Cyrillic, Charset::LatinUnicode, false, false, false);
-    /// assert_eq!(t.process_word("Љубичица").unwrap(), "ǈubičica".to_owned());
+    /// assert_eq!(
+    ///     t.process_if(sentences("Dobar dan. Kako ste?"), |s| s.trim_end().ends_with('?')).unwrap(),
+    ///     "Dobar dan. Како сте?".to_owned()
+    /// );
     /// ```
-    pub fn process_word(&self, word: &str) -> Result<String, Error> {
-        let mut out: Vec<u8> = vec![0; word.len() * 4];
-        let chars = word.chars().into_iter().collect::<Vec<char>>();
-        let mut cursor_in: usize = 0;
-        let mut cursor_out: usize = 0;
-        let mut force_process: bool = false;
-        if Self::foreign_pattern_exception(word) {
-            if self.force_links {
-                force_process = true;
-            } else {
-                return Ok(word.to_string());
-            }
-        }
-        'outer: while cursor_in < chars.len() {
-            'inner: for (i, Character { value: c, case: lc }) in
-                self.charset_from.iter().enumerate().rev()
-            {
-                if chars[cursor_in..].starts_with(c) {
-                    if !self.skip_digraph && self.exceptions {
-                        // If transliterating to latin8, transliterate exception too
-                        let latinize = self.charset_into == charmaps::LATIN_CLEAN_UNICODE
-                            || self.charset_into == charmaps::LATIN_DIRTY_UNICODE;
-                        // Start from bottom to catch digraphs first
-                        if let Some(exception) = Self::digraph_exception(&chars, c, latinize)? {
-                            cursor_out +=
-                                Self::chars_to_utf8(exception.value, &mut out[cursor_out..])?;
-                            cursor_in += exception.value.len();
-                            continue 'outer;
-                        }
-                    }
-                    // Check if digraph is preceded or followed by same case
-                    if lc == &LetterCase::Mixed {
-                        let prev_lower = if cursor_in > 0 {
-                            Some(chars[cursor_in - 1].is_lowercase())
-                        } else {
-                            None
-                        };
-                        let next_lower = if cursor_in < chars.len() - 1 {
-                            Some(chars[cursor_in + 1].is_lowercase())
-                        } else {
-                            None
-                        };
-                        if matches!((prev_lower, next_lower), (_, Some(false))) {
-                            continue 'inner;
-                        }
-                    }
-                    // Exception is not found, proceed to transliterate
-                    cursor_out +=
-                        Self::chars_to_utf8(self.charset_into[i].value, &mut out[cursor_out..])?;
-                    cursor_in += c.len();
-                    continue 'outer;
-                }
-            }
-            if !force_process && !self.force_foreign && chars[cursor_in].is_alphabetic() {
-                // Foreign character is found, return original
-                return Ok(word.to_string());
+    pub fn process_if<'a, I, P>(&self, segments: I, mut predicate: P) -> Result<String, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+        P: FnMut(&str) -> bool,
+    {
+        let mut output = String::new();
+        for segment in segments {
+            if predicate(segment) {
+                self.process_into(segment, &mut output)?;
             } else {
-                // Add found non-alphabetic or foreign character
-                cursor_out += Self::chars_to_utf8(&[chars[cursor_in]], &mut out[cursor_out..])?;
-                cursor_in += 1;
+                #[cfg(feature = "tracing")]
+                tracing::debug!(segment, "segment skipped by filter");
+                output.push_str(segment);
             }
         }
-        out.resize(cursor_out, 0);
-        let out = String::from_utf8(out)?;
-        Ok(out)
+        Ok(output)
     }
 
-    /// Try transliterating arbitrary text.
-    /// Fails if provided string is not valid UTF-8.
+    /// Try transliterating a file name or path. Every path component is
+    /// transliterated separately, keeping file extensions intact.
+    /// Words in a file name can be separated by any non-alphanumeric
+    /// character (ie. `_`, `-`, `.`).
+    /// Names which are not valid UTF-8 are returned unchanged.
     ///
     /// # Arguments
     ///
-    /// * `input` - Text to transliterate
+    /// * `input` - File name or path to transliterate
     /// # Examples
     ///
     /// ```
+    /// use std::ffi::OsStr;
     /// use translitrs::{Transliterator, Charset};
     ///
-    /// // Transliterate Latin sentence to Cyrillic without digraph exceptions
-    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, true, false, false);
+    /// let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
     /// assert_eq!(
-    ///     t.process_word("Konjugacija je promena glagola po vremenima i licima.").unwrap(),
-    ///     "Коњугација је промена глагола по временима и лицима.".to_owned()
+    ///     t.transliterate_os_str(OsStr::new("Музика/Ђорђе_Балашевић-Девојка.mp3")).unwrap(),
+    ///     "Muzika/Đorđe_Balašević-Devojka.mp3"
     /// );
     /// ```
-    pub fn process<S: AsRef<str>>(&self, input: S) -> Result<String, Error> {
-        let input = input.as_ref();
-        let mut output = String::with_capacity(input.len());
-        let mut cursor_left = 0;
-        fn next_occurence(input: &str, left: usize, match_text: bool) -> usize {
-            let criterion = match match_text {
-                true => |c: char| c.is_whitespace(),
-                false => |c: char| !c.is_whitespace(),
-            };
-            if let Some(res) = input[left..].find(criterion) {
-                cmp::min(left + res, input.len())
-            } else {
-                input.len()
+    pub fn transliterate_os_str(&self, input: &OsStr) -> Result<OsString, Error> {
+        let mut output = PathBuf::new();
+        for component in Path::new(input).components() {
+            match component {
+                Component::Normal(name) => match name.to_str() {
+                    Some(name) => output.push(self.process_file_name(name)?),
+                    None => output.push(name),
+                },
+                component => output.push(component),
             }
         }
-        let mut match_text: bool = false;
-        while cursor_left < input.len() {
-            let cursor_right = next_occurence(input, cursor_left, match_text);
-            if match_text {
-                let res = self.process_word(&input[cursor_left..cursor_right])?;
-                output.push_str(&res);
-            } else {
-                // Skip processing space characters
-                output.push_str(&input[cursor_left..cursor_right]);
+        Ok(output.into_os_string())
+    }
+
+    fn process_file_name(&self, name: &str) -> Result<String, Error> {
+        // Extensions are short and alphanumeric (ie. `.mp3`, `.jpeg`)
+        let (stem, extension) = match name.rfind('.') {
+            Some(dot)
+                if dot > 0
+                    && (2..=6).contains(&(name.len() - dot))
+                    && name[dot + 1..].chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                name.split_at(dot)
             }
-            cursor_left = cursor_right;
-            // Toggle between processing whitespace and other characters
-            match_text = !match_text;
+            _ => (name, ""),
+        };
+        let mut output = String::with_capacity(name.len());
+        let mut word_start: Option<usize> = None;
+        for (i, c) in stem.char_indices() {
+            match (c.is_alphanumeric(), word_start) {
+                (true, None) => word_start = Some(i),
+                (false, Some(start)) => {
+                    self.process_word_into(&stem[start..i], &mut output)?;
+                    output.push(c);
+                    word_start = None;
+                }
+                (false, None) => output.push(c),
+                (true, Some(_)) => {}
+            }
+        }
+        if let Some(start) = word_start {
+            self.process_word_into(&stem[start..], &mut output)?;
         }
+        output.push_str(extension);
         Ok(output)
     }
 }
 
+/// Returns ISO 639-3 code of detected sentence language and whether the
+/// sentence should be transliterated
+#[cfg(feature = "detect-language")]
+fn detect_language(sentence: &str) -> Option<(&'static str, bool)> {
+    use whatlang::Lang;
+    let info = whatlang::detect(sentence)?;
+    let slavic = matches!(
+        info.lang(),
+        Lang::Srp
+            | Lang::Hrv
+            | Lang::Slv
+            | Lang::Mkd
+            | Lang::Bul
+            | Lang::Rus
+            | Lang::Ukr
+            | Lang::Bel
+            | Lang::Pol
+            | Lang::Ces
+            | Lang::Slk
+    );
+    // Unreliable detection is common for short sentences, so convert them
+    Some((info.lang().code(), slavic || info.lang() == Lang::Uzb || !info.is_reliable()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLES: &'static [(&str, &str, bool)] = &[
-        ("", "", true),
-        ("1234567890", "1234567890", true),
-        (
-            "A B V G D Đ E Ž Z I J K L Lj M N Nj O P R S T Ć U F H C Č Dž Š a b v g d đ e ž z i j k l lj m n nj o p r s t ć u f h c č dž š",
-            "А Б В Г Д Ђ Е Ж З И Ј К Л Љ М Н Њ О П Р С Т Ћ У Ф Х Ц Ч Џ Ш а б в г д ђ е ж з и ј к л љ м н њ о п р с т ћ у ф х ц ч џ ш",
-            true,
-        ),
-        (
-            "Stala mala Mara na kraj stara hana sama.",
-            "Стала мала Мара на крај стара хана сама.",
-            true,
-        ),
-        (
-            "Nevesele snene žene plele teške mreže",
-            "Невеселе снене жене плеле тешке мреже",
-            true,
-        ),
-        (
-            "Javorov jaram, javorova ralica, ralo drvo javorovo.",
-            "Јаворов јарам, јаворова ралица, рало дрво јаворово.",
-            true,
-        ),
-        (
-            "Jesi li to ti to tu? Jesi li to tu ti? Jesi li to ti tu? Jesi li tu ti to?",
-            "Јеси ли то ти то ту? Јеси ли то ту ти? Јеси ли то ти ту? Јеси ли ту ти то?",
-            true,
-        ),
-        (
-            "Adjektivisati|ZABLUDJE|odžvać|PredŽivot|kenjon|konjug|TANJug",
-            "Адјективисати|ЗАБЛУДЈЕ|оджваћ|ПредЖивот|кенјон|конјуг|ТАНЈуг",
-            true,
-        ),
-        (
-            "A B V G D Đ Ð DJ Dj E Z Ž Ž I J K L LJ Ǉ Lj ǈ M N NJ Ǌ Nj ǋ O P R S T Ć Ć U F H C Č Č DŽ Ǆ DŽ Dž ǅ Dž Š Š",
-            "А Б В Г Д Ђ Ђ Ђ Ђ Е З Ж Ж И Ј К Л Љ Љ Љ Љ М Н Њ Њ Њ Њ О П Р С Т Ћ Ћ У Ф Х Ц Ч Ч Џ Џ Џ Џ Џ Џ Ш Ш",
-            false,
-        ),
-        (
-            "a æ b v g d đ dj e z ž ž i ĳ j k l lj ǉ m n nj ǌ o œ p r s ﬆ t ć ć u f ﬁ ﬂ h c č č dž ǆ dž š š",
-            "а ае б в г д ђ ђ е з ж ж и иј ј к л љ љ м н њ њ о ое п р с ст т ћ ћ у ф фи фл х ц ч ч џ џ џ ш ш",
-            false,
-        ),
-        (
-            "A B V G D Đ Ð DJ Dj e z ž ž i ĳ j k l lj ǉ M N NJ Ǌ Nj ǋ O P R s ﬆ t ć ć u f ﬁ ﬂ h c č č dž Ǆ DŽ Dž ǅ Dž Š Š",
-            "А Б В Г Д Ђ Ђ Ђ Ђ е з ж ж и иј ј к л љ љ М Н Њ Њ Њ Њ О П Р с ст т ћ ћ у ф фи фл х ц ч ч џ Џ Џ Џ Џ Џ Ш Ш",
-            false,
-        ),
-    ];
+    use crate::conformance::EXAMPLES;
 
     #[test]
     fn test_charsets() -> Result<(), Error> {
-        let charsets = vec![Charset::Latin, Charset::LatinUnicode, Charset::Cyrillic];
-        for f in charsets.clone() {
-            for i in charsets.clone() {
-                let _ = Transliterator::new(f.clone(), i.clone(), false, false, false);
+        let charsets = [Charset::Latin, Charset::LatinUnicode, Charset::Cyrillic];
+        let languages = [
+            Language::Serbian,
+            Language::Croatian,
+            Language::Bosnian,
+            #[cfg(feature = "kazakh")]
+            Language::Kazakh,
+            #[cfg(feature = "uzbek")]
+            Language::Uzbek,
+        ];
+        for f in charsets {
+            for i in charsets {
+                for l in languages {
+                    let _ = Transliterator::new(f, i, false, false, false).with_language(l);
+                }
             }
         }
         Ok(())
     }
 
     #[test]
-    fn test_chars_to_utf8() -> Result<(), Error> {
-        let mut output: Vec<u8> = vec![0; 100];
-        let len = Transliterator::chars_to_utf8(
-            &['В', 'у', 'к', ' ', 'к', 'a', 'r', 'a', 'd', 'ž'],
-            &mut output,
-        )?;
-        assert_eq!(String::from_utf8_lossy(&output[..len]), "Вук кaradž");
-        Ok(())
-    }
-
-    #[test]
-    fn test_digraph_exception() -> Result<(), Error> {
+    fn test_digraph_exception() {
         assert_eq!(
             Transliterator::digraph_exception(
                 &['a', 'D', 'r', 'u', 'g', 'd', 'j', 'e', 'd'],
-                &['đ'],
-                false
-            )?
+                5,
+                &['d', 'j'],
+                false,
+                &charmaps::ORTHOGRAPHY_SERBIAN,
+                &Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default())
+            )
             .unwrap()
             .value,
             &['д', 'ј']
@@ -429,9 +2490,12 @@ mod tests {
         assert_eq!(
             Transliterator::digraph_exception(
                 &['a', 'D', 'r', 'u', 'g', 'd', 'j', 'e', 'd'],
-                &['đ'],
-                true
-            )?
+                5,
+                &['d', 'j'],
+                true,
+                &charmaps::ORTHOGRAPHY_SERBIAN,
+                &Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default())
+            )
             .unwrap()
             .value,
             &['d', 'j']
@@ -439,9 +2503,12 @@ mod tests {
         assert_eq!(
             Transliterator::digraph_exception(
                 &['n', 'a', 'D', 'ž', 'i', 'v', 'e', 't', 'i'],
+                2,
                 &['D', 'ž'],
-                false
-            )?
+                false,
+                &charmaps::ORTHOGRAPHY_SERBIAN,
+                &Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default())
+            )
             .unwrap()
             .value,
             &['Д', 'ж']
@@ -449,9 +2516,12 @@ mod tests {
         assert_eq!(
             Transliterator::digraph_exception(
                 &['n', 'a', 'D', 'ž', 'i', 'v', 'e', 't', 'i'],
+                2,
                 &['D', 'ž'],
-                true
-            )?
+                true,
+                &charmaps::ORTHOGRAPHY_SERBIAN,
+                &Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default())
+            )
             .unwrap()
             .value,
             &['D', 'ž']
@@ -459,9 +2529,12 @@ mod tests {
         assert_eq!(
             Transliterator::digraph_exception(
                 &['d', 'a', 'N', 'J', 'o', 'n', 'i'],
+                2,
                 &['N', 'J'],
-                false
-            )?
+                false,
+                &charmaps::ORTHOGRAPHY_SERBIAN,
+                &Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default())
+            )
             .unwrap()
             .value,
             &['Н', 'Ј']
@@ -469,35 +2542,34 @@ mod tests {
         assert_eq!(
             Transliterator::digraph_exception(
                 &['d', 'a', 'N', 'J', 'o', 'n', 'i'],
+                2,
                 &['N', 'J'],
-                true
-            )?
+                true,
+                &charmaps::ORTHOGRAPHY_SERBIAN,
+                &Exceptions::new(&charmaps::ORTHOGRAPHY_SERBIAN, &Default::default())
+            )
             .unwrap()
             .value,
             &['N', 'J']
         );
-        Ok(())
     }
 
     #[test]
     fn test_transliterate_lat_cyr() -> Result<(), Error> {
-        for (lat, cyr, _) in EXAMPLES {
+        for case in EXAMPLES {
             let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
-            let res = t.process(lat)?;
-            assert_eq!(&&res, cyr);
+            let res = t.process(case.latin)?;
+            assert_eq!(res, case.cyrillic);
         }
         Ok(())
     }
 
     #[test]
     fn test_transliterate_cyr_lat() -> Result<(), Error> {
-        for (lat, cyr, clean) in EXAMPLES {
-            if !clean {
-                continue;
-            }
+        for case in EXAMPLES.iter().filter(|case| case.reversible) {
             let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
-            let res = t.process(cyr)?;
-            assert_eq!(&&res, lat);
+            let res = t.process(case.cyrillic)?;
+            assert_eq!(res, case.latin);
         }
         Ok(())
     }
@@ -505,11 +2577,11 @@ mod tests {
     #[test]
     fn test_skip_foreign() -> Result<(), Error> {
         let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
-        for text in vec!["example", "例子", "مثال", "példa"] {
+        for text in ["example", "例子", "مثال", "példa"] {
             assert_eq!(text, t.process_word(text)?);
         }
         let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, true, false);
-        for (text, expected) in vec![
+        for (text, expected) in [
             ("example", "еxампле"),
             ("例子", "例子"),
             ("مثال", "مثال"),
@@ -520,6 +2592,260 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_process_into() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let mut output = String::from("> ");
+        for case in EXAMPLES {
+            output.truncate(2);
+            t.process_into(case.latin, &mut output)?;
+            assert_eq!(output[2..], *case.cyrillic);
+        }
+        // Foreign words are appended unchanged after a partial transliteration
+        output.clear();
+        t.process_into("Njiva példa  džem", &mut output)?;
+        assert_eq!(output, "Њива példa  џем");
+        t.process_word_into(" márka", &mut output)?;
+        assert_eq!(output, "Њива példa  џем márka");
+        Ok(())
+    }
+
+    #[test]
+    fn test_exception_memo() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let mut context = Context::new(true);
+        let mut output = String::new();
+        for word in ["Konjugacija", "dan", "Konjugacija"] {
+            t.process_word_charmap(word, &mut context, &mut output)?;
+        }
+        assert_eq!(output, "КонјугацијаданКонјугација");
+        // Only words which needed an exception search are memoized
+        assert_eq!(
+            context.memo.get("Konjugacija"),
+            Some(&("Конјугација".to_owned(), Decision::Exception))
+        );
+        assert_eq!(context.memo.len(), 1);
+
+        let document = EXAMPLES
+            .iter()
+            .map(|case| case.latin)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let memoized = t.process(&document)?;
+        let t = t.with_exception_memo(false);
+        assert_eq!(memoized, t.process(&document)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transliterate_word() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        for (word, exceptions, plain) in [
+            ("Konjugacija", "Конјугација", "Коњугација"),
+            ("nadživeti", "надживети", "наџивети"),
+            ("Ljubičica", "Љубичица", "Љубичица"),
+            ("example.com", "example.com", "example.com"),
+        ] {
+            assert_eq!(t.transliterate_word(word, true)?, exceptions);
+            assert_eq!(t.transliterate_word(word, false)?, plain);
+            assert_eq!(t.process_word(word)?, exceptions);
+        }
+        // Transliterators which skip exceptions never search for them
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, true, false, false);
+        assert_eq!(t.transliterate_word("Konjugacija", true)?, "Коњугација");
+        Ok(())
+    }
+
+    #[test]
+    fn test_titlecase_digraphs() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false)
+            .with_titlecase_digraphs(true);
+        for (cyrillic, latin) in [
+            ("Џон", "ǅon"),
+            ("Љубљана", "ǈubljana"),
+            ("Његош", "ǋegoš"),
+            ("ЊЕГОШ", "NJEGOŠ"),
+            ("џем", "džem"),
+            ("Шума", "Šuma"),
+        ] {
+            assert_eq!(t.process(cyrillic)?, latin);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_accents() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        for (cyrillic, latin) in [
+            ("ре́ка и ку̏ћа", "re\u{301}ka i ku\u{30f}ća"),
+            ("сѐ и ѝ", "se\u{300} i i\u{300}"),
+            ("Ње́гош и Џ\u{301}еп", "Nje\u{301}goš i Dž\u{301}ep"),
+            ("конју́гација", "konju\u{301}gacija"),
+        ] {
+            assert_eq!(t.process(cyrillic)?, latin);
+        }
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        for (latin, cyrillic) in [
+            ("konju\u{301}gacija", "конју\u{301}гација"),
+            ("se\u{300} i Lju\u{301}ba", "се\u{300} и Љу\u{301}ба"),
+            // Digraphs don't join letters of separate grapheme clusters
+            ("l\u{327}jubav", "л\u{327}јубав"),
+            ("n\u{20e3}j", "н\u{20e3}ј"),
+            ("dž\u{301}", "џ\u{301}"),
+            ("ljubav👍🏽 🇷🇸nj👨\u{200d}👩\u{200d}👧", "љубав👍🏽 🇷🇸њ👨\u{200d}👩\u{200d}👧"),
+        ] {
+            assert_eq!(t.process(latin)?, cyrillic);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_case_pattern() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        for (cyrillic, latin) in [
+            ("Џеп и ЏЕП", "Džep i DŽEP"),
+            ("Љ. и Њ", "Lj. i Nj"),
+            ("ЏЕЈМС Џ. БОНД", "DŽEJMS DŽ. BOND"),
+            ("ЏЕП-Џ и Њ-Његош", "DŽEP-DŽ i Nj-Njegoš"),
+            ("ЏеП и ЉУБАВн", "DžeP i LJUBAVn"),
+            ("ЊЕГОШ. Љ. Њ. Џ.", "NJEGOŠ. LJ. NJ. DŽ."),
+            ("М. Њ. Џ. ЛИК", "M. NJ. DŽ. LIK"),
+            ("ЉУБАВ Љ Њ Џ", "LJUBAV LJ NJ DŽ"),
+            ("Марко Љ. Њ. и", "Marko Lj. Nj. i"),
+        ] {
+            assert_eq!(t.process(cyrillic)?, latin);
+        }
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        assert_eq!(t.process("Dž. i Lj,")?, "Џ. и Љ,");
+        Ok(())
+    }
+
+    #[cfg(feature = "ngram")]
+    #[test]
+    fn test_ngram() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_ngram(true);
+        for (latin, cyrillic) in [
+            ("predžetveni", "преджетвени"),
+            ("Vanjugoslovenski", "Ванјугословенски"),
+            ("ODŽALIO", "ОДЖАЛИО"),
+            ("odžaci", "оџаци"),
+            ("Njegoš", "Његош"),
+            ("knjigom", "књигом"),
+            ("džemper", "џемпер"),
+            ("nadjen", "нађен"),
+            ("nadjačan", "надјачан"),
+        ] {
+            assert_eq!(t.process(latin)?, cyrillic);
+        }
+        // Skipping digraph exceptions skips the model too
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, true, false, false)
+            .with_ngram(true);
+        assert_eq!(t.process("predžetveni")?, "преџетвени");
+        Ok(())
+    }
+
+    #[test]
+    fn test_letters() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let keep = t.clone().with_letters(Letters::Keep);
+        let serbian = t.clone().with_letters(Letters::Serbian);
+        for (text, converted, kept, letters) in [
+            ("tačka a)", "тачка а)", "тачка a)", "тачка а)"),
+            ("vitamin D i C", "витамин Д и Ц", "витамин D i C", "витамин Д и Ц"),
+            ("plan B.", "план Б.", "план B.", "план Б."),
+            ("faza Dž", "фаза Џ", "фаза Dž", "фаза Џ"),
+            ("æ i ǋ", "ае и Њ", "æ i ǋ", "æ и ǋ"),
+        ] {
+            assert_eq!(t.process(text)?, converted);
+            assert_eq!(keep.process(text)?, kept);
+            assert_eq!(serbian.process(text)?, letters);
+        }
+        assert_eq!(keep.trace("a)")?[0].decision, Decision::Letter);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compounds() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let hyphens = t.clone().with_compounds(Compounds::Hyphens);
+        let apostrophes = t.clone().with_compounds(Compounds::Apostrophes);
+        for (word, whole, hyphenated, elided) in [
+            ("njemu-njoj", "њему-њој", "њему-њој", "њему-њој"),
+            ("Wolfgang-Njegoš", "Wolfgang-Njegoš", "Wolfgang-Његош", "Wolfgang-Njegoš"),
+            ("XIV-veka", "XIV-veka", "XIV-века", "XIV-veka"),
+            ("Wolfgang’ov", "Wolfgang’ov", "Wolfgang’ov", "Wolfgang’ов"),
+            ("moj-sajt.rs", "moj-sajt.rs", "moj-sajt.rs", "moj-sajt.rs"),
+            ("-njoj", "-њој", "-њој", "-њој"),
+        ] {
+            assert_eq!(t.process(word)?, whole);
+            assert_eq!(hyphens.process(word)?, hyphenated);
+            assert_eq!(apostrophes.process(word)?, elided);
+        }
+
+        // Compounds are transliterated if any of their components is
+        let decision = |word| hyphens.trace(word).map(|trace| trace[0].decision.clone());
+        assert_eq!(decision("Wolfgang-Njegoš")?, Decision::Transliterated);
+        assert_eq!(decision("Wolfgang-Wien")?, Decision::Foreign);
+        assert_eq!(decision("Wien-konjugacija")?, Decision::Exception);
+
+        #[cfg(feature = "uzbek")]
+        {
+            let t = t
+                .with_language(Language::Uzbek)
+                .with_compounds(Compounds::All);
+            assert_eq!(t.process("o'zbek-tojik")?, "ўзбек-тожик");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_alphanumeric() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let all = t.clone().with_alphanumeric(Alphanumeric::Transliterate);
+        let keep = t.clone().with_alphanumeric(Alphanumeric::Keep);
+        for (text, suffixes, transliterated, kept) in [
+            ("19-og veka", "19-ог века", "19-ог века", "19-og века"),
+            ("3. mesto", "3. место", "3. место", "3. место"),
+            ("A4 papir", "A4 папир", "А4 папир", "A4 папир"),
+            ("iPhone 12 Pro", "iPhone 12 Pro", "иПхоне 12 Про", "iPhone 12 Pro"),
+            ("MP3 i Pro", "MP3 и Про", "МП3 и Про", "MP3 и Про"),
+            ("eBay, Pro", "eBay, Про", "eBay, Про", "eBay, Про"),
+            ("12 Pro", "12 Про", "12 Про", "12 Про"),
+        ] {
+            assert_eq!(t.process(text)?, suffixes);
+            assert_eq!(all.process(text)?, transliterated);
+            assert_eq!(keep.process(text)?, kept);
+        }
+        assert_eq!(t.process_word("MP3")?, "MP3");
+
+        // Units are numbers with suffixes, which are links unless forced
+        assert_eq!(t.process("100km/h 20m/s")?, "100km/h 20m/s");
+        let forced = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, true);
+        assert_eq!(forced.process("100km/h 20m/s")?, "100км/х 20м/с");
+
+        // Model names are kept only when transliterating from Latin
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        assert_eq!(t.process("МП3 у 19-ом веку")?, "MP3 u 19-om veku");
+        let t = t.with_alphanumeric(Alphanumeric::Keep);
+        assert_eq!(t.process("МП3 у 19-ом веку")?, "МП3 u 19-ом veku");
+        Ok(())
+    }
+
+    #[test]
+    fn test_long_words() -> Result<(), Error> {
+        // Words longer than stack buffers are transliterated the same way
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        for word in ["nadživeti", "Ljubičica", "DŽEM", "lj"] {
+            let short = t.process_word(word)?;
+            let long = word.repeat(STACK_CHARS / word.len() + 1);
+            assert!(long.len() > STACK_CHARS);
+            assert_eq!(t.process_word(&long)?, short.repeat(STACK_CHARS / word.len() + 1));
+        }
+        assert_eq!(t.process_word("nadživeti")?, "надживети");
+        Ok(())
+    }
+
     #[test]
     fn test_digraph_capitalization() -> Result<(), Error> {
         let t_lat_cyr = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
@@ -568,4 +2894,305 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_roman_numerals() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        for (text, expected) in [
+            ("XIX vek", "XIX век"),
+            ("Petar II Petrović", "Петар II Петровић"),
+            ("Luj XIV, kralj", "Луј XIV, краљ"),
+            ("Luj X.", "Луј X."),
+            ("Poglavlje IV.", "Поглавље IV."),
+            ("I tada je rekao", "И тада је рекао"),
+            ("Kraj. I tada", "Крај. И тада"),
+            ("MI SMO DA LI", "МИ СМО ДА ЛИ"),
+            ("DA LI VI ZNATE", "ДА ЛИ ВИ ЗНАТЕ"),
+            ("ZNATE LI VI DA JE CIV DIV", "ЗНАТЕ ЛИ ВИ ДА ЈЕ ЦИВ ДИВ"),
+            ("Luj VI i Pije VI.", "Луј VI и Пије VI."),
+            ("Luj X i Marko", "Луј X и Марко"),
+            ("Ana I Marko", "Ана И Марко"),
+            ("Rat I Mir", "Рат И Мир"),
+            ("Zoran V Petrović", "Зоран В Петровић"),
+            ("Marko\nI tada je otišao.", "Марко\nИ тада је отишао."),
+            ("Petar\r\nI tada", "Петар\r\nИ тада"),
+            ("IIII", "ИИИИ"),
+        ] {
+            assert_eq!(expected, t.process(text)?);
+        }
+        let t = t.with_force_roman(true);
+        assert_eq!("Петар ИИ Петровић", t.process("Petar II Petrović")?);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "detect-language")]
+    fn test_detect_language() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_detect_language(true);
+        let text = "Sutra ćemo ići na more sa prijateljima iz škole. \
+                    He said that he would never come back to this town.";
+        let trace = t.trace(text)?;
+        assert_eq!(trace[0].language, Some("hrv"));
+        assert_eq!(trace[0].decision, Decision::Transliterated);
+        assert_eq!(trace[9].language, Some("eng"));
+        assert_eq!(trace[9].decision, Decision::Language("eng"));
+        assert_eq!(
+            t.process(text)?,
+            "Сутра ћемо ићи на море са пријатељима из школе. \
+             He said that he would never come back to this town."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let text = "Injekcija  iz  example.com, Petar II i João";
+        let decisions = t
+            .trace(text)?
+            .into_iter()
+            .map(|t| (&text[t.span], t.output, t.decision))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decisions,
+            vec![
+                ("Injekcija", "Инјекција".to_owned(), Decision::Exception),
+                ("iz", "из".to_owned(), Decision::Transliterated),
+                ("example.com,", "example.com,".to_owned(), Decision::Link),
+                ("Petar", "Петар".to_owned(), Decision::Transliterated),
+                ("II", "II".to_owned(), Decision::Roman),
+                ("i", "и".to_owned(), Decision::Transliterated),
+                ("João", "João".to_owned(), Decision::Foreign),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ansi_escapes() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        // Escape sequences are passed through only if enabled
+        assert_eq!(t.process("\x1b[31mCrveno\x1b[0m i")?, "\x1b[31mCrveno\x1b[0m и");
+        let t = t.with_ansi(true);
+        for (text, expected) in [
+            ("\x1b[31mCrveno\x1b[0m i", "\x1b[31mЦрвено\x1b[0m и"),
+            ("\x1b[1;32mreč\x1b[m\x1b[2K", "\x1b[1;32mреч\x1b[m\x1b[2K"),
+            ("\x1b]0;naslov\x07kraj", "\x1b]0;naslov\x07крај"),
+            (
+                "\x1b]8;;https://a.rs\x1b\\veza\x1b]8;;\x1b\\",
+                "\x1b]8;;https://a.rs\x1b\\веза\x1b]8;;\x1b\\",
+            ),
+            ("\x1b(Bslovo\x1bMdalje", "\x1b(Bслово\x1bMдаље"),
+        ] {
+            assert_eq!(t.process(text)?, expected);
+        }
+        let trace = t.trace("\x1b[1mNjiva\x1b[0m")?;
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].span, 4..9);
+        let t = t.with_skip_converted(Some(CONVERTED_TOLERANCE));
+        assert_eq!(t.process("\x1b[33mreč\x1b[0m\n")?, "\x1b[33mреч\x1b[0m\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_converted() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_skip_converted(Some(CONVERTED_TOLERANCE));
+        for (text, expected) in [
+            ("", ""),
+            ("12, 13", "12, 13"),
+            ("Конјугација на GitHub-у", "Конјугација на GitHub-у"),
+            ("Конјугација глагола и nj", "Конјугација глагола и nj"),
+            ("Konjugacija i њ", "Конјугација и њ"),
+        ] {
+            assert_eq!(t.process(text)?, expected);
+        }
+        assert!(t.trace("Конјугација глагола и nj")?.is_empty());
+        assert_eq!(
+            t.process("Konjugacija\nКонјугација глагола и nj\n\nnj")?,
+            "Конјугација\nКонјугација глагола и nj\n\nњ"
+        );
+        let t = t.with_skip_converted(Some(0.0));
+        assert_eq!(t.process("Конјугација глагола и nj")?, "Конјугација глагола и њ");
+        // Conversions within a script are never skipped
+        let t = Transliterator::new(Charset::Latin, Charset::LatinUnicode, false, false, false)
+            .with_skip_converted(Some(1.0));
+        assert_eq!(t.process("Njegoš")?, "ǋegoš");
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_max_input(Some(20))
+            .with_max_word(Some(8));
+        assert_eq!(t.process("Dobar dan, Đorđe")?, "Добар дан, Ђорђе");
+        assert!(matches!(t.process("Dobar dan i laku noć"), Err(Error::InputTooLong(21, 20))));
+        assert!(matches!(t.process("Ljubičica"), Err(Error::WordTooLong(10, 8))));
+        assert!(matches!(t.process_word("Ljubičica"), Err(Error::WordTooLong(10, 8))));
+        assert!(matches!(t.trace("a Ljubičica"), Err(Error::WordTooLong(10, 8))));
+        // Limits are checked in bytes
+        assert!(t.process("Ђорђе").is_err());
+        let t = t.with_max_input(None).with_max_word(None);
+        assert_eq!(t.process("Dobar dan i laku noć")?, "Добар дан и лаку ноћ");
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict() -> Result<(), Error> {
+        let text = "Cena: 10 € – „popust” 5%";
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        assert_eq!(t.process(text)?, "Цена: 10 € – „попуст” 5%");
+        let t = t.with_strict(true);
+        assert!(matches!(t.process(text), Err(Error::Unmapped('€', 9))));
+        assert!(matches!(t.process("Ćao ☺"), Err(Error::Unmapped('☺', 5))));
+        assert_eq!(t.process("Ćao, „svete”!\n\tŠta ima?")?, "Ћао, „свете”!\n\tШта има?");
+        let t = t.with_passthrough(&["digits", "punctuation", "currency"])?;
+        assert_eq!(t.process(text)?, "Цена: 10 € – „попуст” 5%");
+        assert!(matches!(t.process("Ćao ☺"), Err(Error::Unmapped('☺', 5))));
+        assert!(matches!(t.with_passthrough(&["emojis"]), Err(Error::UnknownClass(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_orthography() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let t_hr = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_language(Language::Croatian);
+        let t_bs = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_language(Language::Bosnian);
+        for (text, sr, hr_bs) in [
+            ("Đorđe", "Ђорђе", "Ђорђе"),
+            ("Djordje", "Ђорђе", "Дјордје"),
+            ("DJORDJE", "ЂОРЂЕ", "ДЈОРДЈЕ"),
+            ("djevojka", "дјевојка", "дјевојка"),
+            ("izvanjezični", "извањезични", "изванјезични"),
+            ("podžupnik", "поџупник", "поджупник"),
+            ("injekcija", "инјекција", "инјекција"),
+            ("njegov", "његов", "његов"),
+        ] {
+            assert_eq!(sr, t.process(text)?);
+            assert_eq!(hr_bs, t_hr.process(text)?);
+            assert_eq!(hr_bs, t_bs.process(text)?);
+        }
+        assert_eq!("панјугословенски", t_bs.process("panjugoslovenski")?);
+        // Written out `dj` is split even if digraph exceptions are skipped
+        for language in [Language::Croatian, Language::Bosnian] {
+            let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, true, false, false)
+                .with_language(language);
+            assert_eq!("Дјордје, ДЈОРДЈЕ и Ђорђе", t.process("Djordje, DJORDJE i Đorđe")?);
+            assert_eq!("ињекција", t.process("injekcija")?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "kazakh")]
+    fn test_kazakh() -> Result<(), Error> {
+        let t_cyr_lat = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false)
+            .with_language(Language::Kazakh);
+        let t_lat_cyr = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_language(Language::Kazakh);
+        for (cyrillic, latin) in [
+            ("Қазақстан Республикасы", "Qazaqstan Respublikasy"),
+            ("ҚАЗАҚСТАН", "QAZAQSTAN"),
+            ("Әділет және бірлік", "Ädılet jäne bırlık"),
+            ("Өскемен, Үржар, Ұлытау", "Öskemen, Ürjar, Ūlytau"),
+            ("ағаш, теңіз, шаш", "ağaş, teñız, şaş"),
+            ("Іле", "Ile"),
+        ] {
+            assert_eq!(latin, t_cyr_lat.process(cyrillic)?);
+            assert_eq!(cyrillic, t_lat_cyr.process(latin)?);
+        }
+        // Letters of Russian loanwords are not reversible
+        for (cyrillic, latin) in [
+            ("Цирк", "Tsirk"),
+            ("ЦИРК", "TSİRK"),
+            ("Юрий", "İurii"),
+            ("щётка", "şşiotka"),
+            ("съезд", "sezd"),
+            ("Һ", "H"),
+        ] {
+            assert_eq!(latin, t_cyr_lat.process(cyrillic)?);
+        }
+        // Serbian letters are foreign
+        assert_eq!("Ćevapi", t_lat_cyr.process("Ćevapi")?);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "uzbek")]
+    fn test_uzbek() -> Result<(), Error> {
+        let uzbek = |from, into| {
+            Transliterator::new(from, into, false, false, false).with_language(Language::Uzbek)
+        };
+        let t_lat_cyr = uzbek(Charset::Latin, Charset::Cyrillic);
+        let t_cyr_lat = uzbek(Charset::Cyrillic, Charset::Latin);
+        let t_cyr_lat8 = uzbek(Charset::Cyrillic, Charset::LatinUnicode);
+        let t_lat_lat8 = uzbek(Charset::Latin, Charset::LatinUnicode);
+        for (latin, latin8, cyrillic) in [
+            ("O'zbekiston", "Oʻzbekiston", "Ўзбекистон"),
+            ("O'ZBEKISTON", "OʻZBEKISTON", "ЎЗБЕКИСТОН"),
+            ("g'alaba", "gʻalaba", "ғалаба"),
+            ("Shahar, choy, SHAHAR", "Shahar, choy, SHAHAR", "Шаҳар, чой, ШАҲАР"),
+            ("ma'no", "maʼno", "маъно"),
+            ("Is'hoq", "Isʼhoq", "Исъҳоқ"),
+            ("yer, Yer", "yer, Yer", "ер, Ер"),
+            ("ekran", "ekran", "экран"),
+            ("Yoshlik, yulduz, yaxshi", "Yoshlik, yulduz, yaxshi", "Ёшлик, юлдуз, яхши"),
+        ] {
+            assert_eq!(cyrillic, t_lat_cyr.process(latin)?);
+            assert_eq!(cyrillic, t_lat_cyr.process(latin8)?);
+            assert_eq!(latin, t_cyr_lat.process(cyrillic)?);
+            assert_eq!(latin8, t_cyr_lat8.process(cyrillic)?);
+            assert_eq!(latin8, t_lat_lat8.process(latin)?);
+        }
+        for apostrophe in ['\'', '`', '‘', '’', 'ʻ', 'ʼ'] {
+            let word = format!("o{}g{}il", apostrophe, apostrophe);
+            assert_eq!("ўғил", t_lat_cyr.process(word)?);
+        }
+        assert_eq!("'салом'", t_lat_cyr.process("'salom'")?);
+        assert_eq!("'кино' ‘радио’ тоғ", t_lat_cyr.process("'kino' ‘radio’ tog'")?);
+        // Foreign words are left intact
+        assert_eq!("Tsex, Еxample", t_cyr_lat.process("Цех, Еxample")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_archaic() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let text = "Писмо Славеносербскому народу ѿ Вука: вѣра и законъ";
+        assert_eq!("Pismo Slavenoserbskomu narodu ѿ Vuka: вѣра i законъ", t.process(text)?);
+        for (archaic, expected) in [
+            (Archaic::Modernize, "Pismo Slavenoserbskomu narodu ot Vuka: vera i zakon"),
+            (Archaic::Preserve, "Pismo Slavenoserbskomu narodu ѿ Vuka: vѣra i zakonъ"),
+            (
+                Archaic::Annotate,
+                "Pismo Slavenoserbskomu narodu ot[ѿ] Vuka: ve[ѣ]ra i zakon[ъ]",
+            ),
+        ] {
+            let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false)
+                .with_archaic(archaic);
+            assert_eq!(expected, t.process(text)?);
+        }
+        let t = Transliterator::new(Charset::Cyrillic, Charset::LatinUnicode, false, false, false)
+            .with_archaic(Archaic::Modernize);
+        assert_eq!("ǉubav i koǌ", t.process("любав и конь")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_direction() {
+        for (text, from, into) in [
+            ("cyr2lat", Charset::Cyrillic, Charset::Latin),
+            ("l2c", Charset::Latin, Charset::Cyrillic),
+            ("latin2latin8", Charset::Latin, Charset::LatinUnicode),
+        ] {
+            assert_eq!(text.parse::<Direction>().unwrap(), Direction { from, into });
+        }
+        for text in ["", "cyr", "cyr2", "cyr2grk", "2lat"] {
+            assert!(matches!(text.parse::<Direction>(), Err(Error::UnknownDirection(_))));
+        }
+    }
 }
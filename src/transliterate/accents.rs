@@ -0,0 +1,139 @@
+use unicode_segmentation::{Graphemes, UnicodeSegmentation};
+
+const GRAVE: char = '\u{300}';
+const MACRON: char = '\u{304}';
+
+/// Precomposed accented Cyrillic vowels of dictionaries and linguistic
+/// texts, with their letters and accents. Accented Latin vowels are not
+/// split, since they are letters of foreign words (ie. `café`).
+const PRECOMPOSED: &[(char, char, char)] = &[
+    ('ѐ', 'е', GRAVE),
+    ('Ѐ', 'Е', GRAVE),
+    ('ѝ', 'и', GRAVE),
+    ('Ѝ', 'И', GRAVE),
+    ('ӣ', 'и', MACRON),
+    ('Ӣ', 'И', MACRON),
+    ('ӯ', 'у', MACRON),
+    ('Ӯ', 'У', MACRON),
+];
+
+/// Returns whether `c` is a combining mark (ie. accent of `е́`)
+pub fn combining(c: char) -> bool {
+    matches!(
+        c,
+        '\u{300}'..='\u{36f}'
+            | '\u{483}'..='\u{489}'
+            | '\u{1dc0}'..='\u{1dff}'
+            | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe20}'..='\u{fe2f}'
+    )
+}
+
+/// First characters of grapheme clusters of a word. Other characters of
+/// clusters (ie. combining marks, emoji modifiers and joined emoji) are
+/// collected as marks with numbers of letters before them, so they can be
+/// reattached to letters they follow after the letters are transliterated,
+/// and clusters are never split or reordered. Precomposed accented vowels
+/// are split into letters and marks, unless they are `mapped`.
+pub struct Letters<'a, F> {
+    graphemes: Graphemes<'a>,
+    mapped: F,
+    letters: usize,
+    pub marks: Vec<(usize, char)>,
+}
+
+impl<'a, F: Fn(char) -> bool> Letters<'a, F> {
+    pub fn new(word: &'a str, mapped: F) -> Self {
+        Self {
+            graphemes: word.graphemes(true),
+            mapped,
+            letters: 0,
+            marks: Vec::new(),
+        }
+    }
+}
+
+impl<'a, F: Fn(char) -> bool> Iterator for Letters<'a, F> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            let mut chars = self.graphemes.next()?.chars();
+            let c = chars.next()?;
+            // Only clusters at the start of a word begin with a mark
+            if combining(c) {
+                let letters = self.letters;
+                self.marks.push((letters, c));
+                self.marks.extend(chars.map(|mark| (letters, mark)));
+                continue;
+            }
+            self.letters += 1;
+            let letters = self.letters;
+            let mut letter = c;
+            if !(self.mapped)(c) {
+                if let Some((_, l, mark)) = PRECOMPOSED.iter().find(|(p, _, _)| *p == c) {
+                    self.marks.push((letters, *mark));
+                    letter = *l;
+                }
+            }
+            self.marks.extend(chars.map(|mark| (letters, mark)));
+            return Some(letter);
+        }
+    }
+}
+
+/// Returns whether a pattern of `length` letters starting at letter
+/// `position` would split grapheme clusters, because marks follow some of
+/// its letters other than the last one (ie. `l̦j` is not the digraph `lj`)
+pub fn splits(marks: &[(usize, char)], position: usize, length: usize) -> bool {
+    length > 1
+        && marks
+            .iter()
+            .any(|(letters, _)| *letters > position && *letters < position + length)
+}
+
+/// Append `marks` following letters before `position` to `output`,
+/// starting from mark `attached`
+pub fn reattach(
+    marks: &[(usize, char)],
+    attached: &mut usize,
+    position: usize,
+    output: &mut String,
+) {
+    while let Some((letters, mark)) = marks.get(*attached) {
+        if *letters > position {
+            break;
+        }
+        output.push(*mark);
+        *attached += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letters() {
+        let mut letters = Letters::new("\u{301}ре́ка̀ѐ", |c| c == 'а');
+        assert_eq!(letters.by_ref().collect::<String>(), "рекае");
+        assert_eq!(letters.marks, vec![(0, '\u{301}'), (2, '\u{301}'), (4, GRAVE), (5, GRAVE)]);
+        let mut output = String::new();
+        let mut attached = 0;
+        reattach(&letters.marks, &mut attached, 2, &mut output);
+        assert_eq!(output, "\u{301}\u{301}");
+        reattach(&letters.marks, &mut attached, 5, &mut output);
+        assert_eq!(attached, 4);
+        // Mapped letters are not split
+        let mut letters = Letters::new("ѝ", |c| c == 'ѝ');
+        assert_eq!(letters.next(), Some('ѝ'));
+        assert!(letters.marks.is_empty());
+        // Clusters of emoji and letters with marks stay intact
+        let mut letters = Letters::new("ѐ\u{301}👍🏽🇷🇸", |_| false);
+        assert_eq!(letters.by_ref().collect::<String>(), "е👍🇷");
+        assert_eq!(letters.marks, vec![(1, GRAVE), (1, '\u{301}'), (2, '🏽'), (3, '🇸')]);
+        assert!(splits(&letters.marks, 0, 2));
+        assert!(!splits(&letters.marks, 1, 1));
+        assert!(!splits(&[(2, GRAVE)], 0, 2));
+    }
+}
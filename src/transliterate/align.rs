@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use super::{Error, Transliterator};
+
+/// Word of input text aligned with its transliteration
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlignedToken {
+    /// Original word
+    pub original: String,
+    /// Transliterated word
+    pub converted: String,
+    /// Byte range of the word in input text
+    pub span: Range<usize>,
+    /// Byte range of the word in transliterated text
+    pub converted_span: Range<usize>,
+}
+
+impl Transliterator {
+    /// Try transliterating arbitrary text, returning pairs of its words
+    /// and their transliterations, with their byte ranges in input and in
+    /// output of [`Transliterator::process`]. Annotations of words (ie.
+    /// named entities or parts of speech) can be projected from one script
+    /// onto the other through these ranges without annotating it again.
+    /// Words are separated by whitespace, and include their punctuation.
+    /// Words which are copied unchanged (ie. links, or lines left intact
+    /// when text already in the target script is skipped) are aligned too.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to transliterate
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let tokens = t.process_aligned("Đorđe živi u Beogradu.").unwrap();
+    /// assert_eq!(tokens.len(), 4);
+    /// assert_eq!(tokens[0].converted, "Ђорђе");
+    /// assert_eq!((tokens[3].span.clone(), tokens[3].converted_span.clone()), (16..25, 23..40));
+    /// ```
+    pub fn process_aligned<S: AsRef<str>>(&self, input: S) -> Result<Vec<AlignedToken>, Error> {
+        let input = input.as_ref();
+        let mut output = String::with_capacity(input.len());
+        let mut tokens = Vec::new();
+        self.walk(input, &mut output, &mut |output, span, word| match word {
+            Some(word) => tokens.push(AlignedToken {
+                original: input[span.clone()].to_owned(),
+                converted: output[word.output.clone()].to_owned(),
+                span,
+                converted_span: word.output,
+            }),
+            None => {
+                let offset = output.len();
+                let text = &input[span.clone()];
+                output.push_str(text);
+                // Words of text copied unchanged are their own transliterations
+                let mut start = None;
+                for (n, c) in text.char_indices().chain(Some((text.len(), ' '))) {
+                    match (start, c.is_whitespace()) {
+                        (None, false) => start = Some(n),
+                        (Some(left), true) => {
+                            tokens.push(AlignedToken {
+                                original: text[left..n].to_owned(),
+                                converted: text[left..n].to_owned(),
+                                span: span.start + left..span.start + n,
+                                converted_span: offset + left..offset + n,
+                            });
+                            start = None;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        })?;
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_process_aligned() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false)
+            .with_skip_converted(Some(0.9));
+        let input = "Ђорђе  Балашевић\nvisit example.com\nЊЕГОШ, пише\n";
+        let output = t.process(input)?;
+        let tokens = t.process_aligned(input)?;
+        let words = tokens
+            .iter()
+            .map(|t| (t.original.as_str(), t.converted.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            [
+                ("Ђорђе", "Đorđe"),
+                ("Балашевић", "Balašević"),
+                ("visit", "visit"),
+                ("example.com", "example.com"),
+                ("ЊЕГОШ,", "NJEGOŠ,"),
+                ("пише", "piše"),
+            ]
+        );
+        for token in tokens {
+            assert_eq!(input[token.span], token.original);
+            assert_eq!(output[token.converted_span], token.converted);
+        }
+        Ok(())
+    }
+}
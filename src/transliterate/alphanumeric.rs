@@ -0,0 +1,114 @@
+/// Treatment of words which mix digits and letters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alphanumeric {
+    /// Transliterate all words
+    Transliterate,
+    /// Transliterate suffixes of numbers (ie. `19-og`) and keep product and
+    /// model names (ie. `A4`, `iPhone 12 Pro`) intact
+    Suffixes,
+    /// Keep all words mixing digits and letters intact
+    Keep,
+}
+
+/// Class of a word by its digits and letters, ignoring punctuation around it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WordClass {
+    /// Word without digits
+    Plain,
+    /// Digits without letters (ie. `3.`, `1.000,5`)
+    Number,
+    /// Number followed by a lowercase suffix, optionally after a hyphen
+    /// (ie. `19-og`, `90ih`, `5kg`), or by a unit (ie. `100km/h`)
+    Suffixed,
+    /// Other mix of digits and letters (ie. `A4`, `MP3`, `COVID-19`) or a
+    /// word starting with a lowercase letter followed by a capitalized
+    /// one (ie. `iPhone`, `eBay`)
+    Model,
+}
+
+impl WordClass {
+    /// Returns class of `word`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::WordClass;
+    ///
+    /// assert_eq!(WordClass::of("(19-og"), WordClass::Suffixed);
+    /// assert_eq!(WordClass::of("A4,"), WordClass::Model);
+    /// assert_eq!(WordClass::of("iPhone"), WordClass::Model);
+    /// assert_eq!(WordClass::of("3."), WordClass::Number);
+    /// ```
+    pub fn of(word: &str) -> Self {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let letter = match word.find(char::is_alphabetic) {
+            Some(letter) => letter,
+            None if word.is_empty() => return WordClass::Plain,
+            None => return WordClass::Number,
+        };
+        if !word.contains(|c: char| c.is_ascii_digit()) {
+            let mut chars = word.chars();
+            return match (chars.next(), chars.next(), chars.next()) {
+                (Some(a), Some(b), Some(c))
+                    if a.is_lowercase() && b.is_uppercase() && c.is_lowercase() =>
+                {
+                    WordClass::Model
+                }
+                _ => WordClass::Plain,
+            };
+        }
+        let (number, suffix) = word.split_at(letter);
+        let number = number.strip_suffix('-').unwrap_or(number);
+        let separators = |c: char| c == '.' || c == ',';
+        if number.starts_with(|c: char| c.is_ascii_digit())
+            && number.chars().all(|c| c.is_ascii_digit() || separators(c))
+            && !number.ends_with(separators)
+            && suffix
+                .split('/')
+                .all(|unit| !unit.is_empty() && unit.chars().all(char::is_lowercase))
+        {
+            WordClass::Suffixed
+        } else {
+            WordClass::Model
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_class() {
+        for (word, class) in [
+            ("", WordClass::Plain),
+            ("—", WordClass::Plain),
+            ("reč", WordClass::Plain),
+            ("NJEGOŠ", WordClass::Plain),
+            ("Beograd-Novi", WordClass::Plain),
+            ("eBay", WordClass::Model),
+            ("YouTube", WordClass::Plain),
+            ("PredŽivot", WordClass::Plain),
+            ("tANJUG", WordClass::Plain),
+            ("3.", WordClass::Number),
+            ("1.000,5", WordClass::Number),
+            ("19-og", WordClass::Suffixed),
+            ("1990-ih", WordClass::Suffixed),
+            ("3-ći", WordClass::Suffixed),
+            ("90ih", WordClass::Suffixed),
+            ("5kg", WordClass::Suffixed),
+            ("100km/h", WordClass::Suffixed),
+            ("20m/s", WordClass::Suffixed),
+            ("5km//h", WordClass::Model),
+            ("4K", WordClass::Model),
+            ("3D", WordClass::Model),
+            ("MP3", WordClass::Model),
+            ("COVID-19", WordClass::Model),
+            ("19.-og", WordClass::Model),
+            ("a1b", WordClass::Model),
+            ("1-2-og", WordClass::Model),
+        ] {
+            assert_eq!(WordClass::of(word), class, "{}", word);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+/// Pre-reform letters and their modern replacements
+const LETTERS: &[(&str, &str)] = &[
+    // Soft sign after `л` and `н` forms a single letter
+    ("ль", "љ"),
+    ("Ль", "Љ"),
+    ("ЛЬ", "Љ"),
+    ("нь", "њ"),
+    ("Нь", "Њ"),
+    ("НЬ", "Њ"),
+    // So do `я` and `ю` after `л` and `н`
+    ("ля", "ља"),
+    ("Ля", "Ља"),
+    ("ЛЯ", "ЉА"),
+    ("лю", "љу"),
+    ("Лю", "Љу"),
+    ("ЛЮ", "ЉУ"),
+    ("ня", "ња"),
+    ("Ня", "Ња"),
+    ("НЯ", "ЊА"),
+    ("ню", "њу"),
+    ("Ню", "Њу"),
+    ("НЮ", "ЊУ"),
+    ("ѣ", "е"),
+    ("Ѣ", "Е"),
+    ("і", "и"),
+    ("І", "И"),
+    ("ї", "и"),
+    ("Ї", "И"),
+    ("ы", "и"),
+    ("Ы", "И"),
+    ("ѵ", "и"),
+    ("Ѵ", "И"),
+    ("ъ", ""),
+    ("Ъ", ""),
+    ("ь", ""),
+    ("Ь", ""),
+    ("ѳ", "ф"),
+    ("Ѳ", "Ф"),
+    ("ѕ", "з"),
+    ("Ѕ", "З"),
+    ("ѡ", "о"),
+    ("Ѡ", "О"),
+    ("ѿ", "от"),
+    ("Ѿ", "От"),
+    ("ѫ", "у"),
+    ("Ѫ", "У"),
+    ("ѧ", "ја"),
+    ("Ѧ", "Ја"),
+    ("ꙗ", "ја"),
+    ("Ꙗ", "Ја"),
+    ("я", "ја"),
+    ("Я", "Ја"),
+    ("ю", "ју"),
+    ("Ю", "Ју"),
+    ("є", "е"),
+    ("Є", "Е"),
+    ("ѥ", "је"),
+    ("Ѥ", "Је"),
+    ("й", "ј"),
+    ("Й", "Ј"),
+    ("щ", "шт"),
+    ("Щ", "Шт"),
+    ("ѯ", "кс"),
+    ("Ѯ", "Кс"),
+    ("ѱ", "пс"),
+    ("Ѱ", "Пс"),
+];
+
+/// First private use character, used as a placeholder for archaic letters
+const MARKER: u32 = 0xE000;
+
+/// Treatment of pre-reform (Slavonic-Serbian) Cyrillic letters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Archaic {
+    /// Words with archaic letters are foreign
+    Ignore,
+    /// Replace archaic letters with their modern counterparts
+    Modernize,
+    /// Keep archaic letters intact and transliterate the rest of the word
+    Preserve,
+    /// Replace archaic letters and append the original in brackets
+    Annotate,
+}
+
+/// Replace archaic letters of a word according to `mode`. Returns the
+/// modernized word and archaic letters whose placeholders need to be
+/// restored after transliteration.
+pub fn modernize(word: &str, mode: Archaic) -> Option<(String, Vec<&'static str>)> {
+    if mode == Archaic::Ignore
+        || !word
+            .chars()
+            .any(|c| LETTERS.iter().any(|(l, _)| l.starts_with(c)))
+    {
+        return None;
+    }
+    let mut output = String::with_capacity(word.len());
+    let mut originals = Vec::new();
+    let mut rest = word;
+    'outer: while let Some(c) = rest.chars().next() {
+        for (letter, modern) in LETTERS {
+            if rest.starts_with(letter) {
+                if mode != Archaic::Preserve {
+                    output.push_str(modern);
+                }
+                if mode != Archaic::Modernize {
+                    output.push(char::from_u32(MARKER + originals.len() as u32)?);
+                    originals.push(*letter);
+                }
+                rest = &rest[letter.len()..];
+                continue 'outer;
+            }
+        }
+        output.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    Some((output, originals))
+}
+
+/// Replace placeholders in transliterated word with archaic letters
+pub fn restore(word: &str, originals: &[&str], mode: Archaic) -> String {
+    let mut output = String::with_capacity(word.len());
+    for c in word.chars() {
+        match (c as u32)
+            .checked_sub(MARKER)
+            .and_then(|i| originals.get(i as usize))
+        {
+            Some(original) if mode == Archaic::Annotate => {
+                output.push('[');
+                output.push_str(original);
+                output.push(']');
+            }
+            Some(original) => output.push_str(original),
+            None => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modernize() {
+        assert_eq!(modernize("вера", Archaic::Modernize), None);
+        assert_eq!(modernize("вѣра", Archaic::Ignore), None);
+        for (word, mode, expected) in [
+            ("вѣра", Archaic::Modernize, "вера"),
+            ("СЛАВЕНОСЕРБСКІЙ", Archaic::Modernize, "СЛАВЕНОСЕРБСКИЈ"),
+            ("конь", Archaic::Modernize, "коњ"),
+            ("градъ", Archaic::Modernize, "град"),
+            ("любовь", Archaic::Modernize, "љубов"),
+            ("ЗЕМЛЯ", Archaic::Modernize, "ЗЕМЉА"),
+            ("вѣра", Archaic::Preserve, "в\u{e000}ра"),
+            ("вѣра", Archaic::Annotate, "ве\u{e000}ра"),
+        ] {
+            assert_eq!(modernize(word, mode).unwrap().0, expected);
+        }
+    }
+
+    #[test]
+    fn test_restore() {
+        let originals = ["ѣ", "ъ"];
+        assert_eq!(restore("v\u{e000}ra", &originals, Archaic::Preserve), "vѣra");
+        assert_eq!(restore("ve\u{e000}ra", &originals, Archaic::Annotate), "ve[ѣ]ra");
+        assert_eq!(restore("grad\u{e001}", &originals, Archaic::Annotate), "grad[ъ]");
+    }
+}
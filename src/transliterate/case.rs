@@ -0,0 +1,72 @@
+/// Case pattern of a word or a run of letters, which decides case of
+/// letters written with several characters (ie. `Џ` is `Dž` in `Џеп`,
+/// but `DŽ` in `ЏЕП`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    /// All letters are lowercase
+    Lower,
+    /// At least two letters, all uppercase
+    Upper,
+    /// First letter is uppercase or titlecase and the rest are lowercase,
+    /// including single uppercase letters
+    Title,
+    /// Any other pattern (ie. `iPhone`, `McDonald`)
+    Mixed,
+}
+
+impl Pattern {
+    /// Returns case pattern of cased letters of `chars`, or `None` if
+    /// there are no cased letters
+    pub fn of(chars: impl IntoIterator<Item = char>) -> Option<Self> {
+        let mut pattern = None;
+        // Uppercase words don't start with a titlecase letter (ie. `ǅ`)
+        let mut titlecase = false;
+        let mut letters = 0;
+        for c in chars {
+            let upper = c.is_uppercase();
+            let lower = c.is_lowercase();
+            let title = !upper && !lower && c.to_lowercase().ne(Some(c));
+            if !upper && !lower && !title {
+                continue;
+            }
+            pattern = Some(match (pattern, lower) {
+                (None, true) => Pattern::Lower,
+                (None, false) => {
+                    titlecase = title;
+                    Pattern::Title
+                }
+                (Some(Pattern::Lower), true) => Pattern::Lower,
+                (Some(Pattern::Title), true) => Pattern::Title,
+                (Some(Pattern::Title), false) if upper && !titlecase && letters == 1 => {
+                    Pattern::Upper
+                }
+                (Some(Pattern::Upper), false) if upper => Pattern::Upper,
+                _ => Pattern::Mixed,
+            });
+            letters += 1;
+        }
+        pattern
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern() {
+        for (word, pattern) in [
+            ("џеп", Some(Pattern::Lower)),
+            ("ЏЕП,", Some(Pattern::Upper)),
+            ("Џеп", Some(Pattern::Title)),
+            ("Џ.", Some(Pattern::Title)),
+            ("ǅep", Some(Pattern::Title)),
+            ("ǅEP", Some(Pattern::Mixed)),
+            ("iPhone", Some(Pattern::Mixed)),
+            ("ЏеП", Some(Pattern::Mixed)),
+            ("12.", None),
+        ] {
+            assert_eq!(Pattern::of(word.chars()), pattern, "{}", word);
+        }
+    }
+}
@@ -41,494 +41,20 @@ macro_rules! char {
 
 pub const EMPTY: &[Character] = &[];
 
-// Borrowed from Jovan Turanjanin's Ćirilizator
-// https://github.com/turanjanin/cirilizator
+/// Pattern indices of a map by their first character, sorted by
+/// character. Indices are in the order patterns are matched.
+pub type Index = &'static [(char, &'static [usize])];
 
-pub const LATIN_CLEAN: &[Character] = &[
-    char!['A', upper],
-    char!['B', upper],
-    char!['V', upper],
-    char!['G', upper],
-    char!['D', upper],
-    char!['Đ', upper],
-    char!['E', upper],
-    char!['Ž', upper],
-    char!['Z', upper],
-    char!['I', upper],
-    char!['J', upper],
-    char!['K', upper],
-    char!['L', upper],
-    char!['L', 'J', upper], //
-    char!['L', 'j', mixed], //
-    char!['M', upper],
-    char!['N', upper],
-    char!['N', 'J', upper], //
-    char!['N', 'j', mixed], //
-    char!['O', upper],
-    char!['P', upper],
-    char!['R', upper],
-    char!['S', upper],
-    char!['T', upper],
-    char!['Ć', upper],
-    char!['U', upper],
-    char!['F', upper],
-    char!['H', upper],
-    char!['C', upper],
-    char!['Č', upper],
-    char!['D', 'Ž', upper], //
-    char!['D', 'ž', mixed], //
-    char!['Š', upper],
-    char!['a', lower],
-    char!['b', lower],
-    char!['v', lower],
-    char!['g', lower],
-    char!['d', lower],
-    char!['đ', lower],
-    char!['e', lower],
-    char!['ž', lower],
-    char!['z', lower],
-    char!['i', lower],
-    char!['j', lower],
-    char!['k', lower],
-    char!['l', lower],
-    char!['l', 'j', lower],
-    char!['m', lower],
-    char!['n', lower],
-    char!['n', 'j', lower],
-    char!['o', lower],
-    char!['p', lower],
-    char!['r', lower],
-    char!['s', lower],
-    char!['t', lower],
-    char!['ć', lower],
-    char!['u', lower],
-    char!['f', lower],
-    char!['h', lower],
-    char!['c', lower],
-    char!['č', lower],
-    char!['d', 'ž', lower],
-    char!['š', lower],
-];
-
-pub const LATIN_CLEAN_UNICODE: &[Character] = &[
-    char!['A', upper],
-    char!['B', upper],
-    char!['V', upper],
-    char!['G', upper],
-    char!['D', upper],
-    char!['Đ', upper],
-    char!['E', upper],
-    char!['Ž', upper],
-    char!['Z', upper],
-    char!['I', upper],
-    char!['J', upper],
-    char!['K', upper],
-    char!['L', upper],
-    char!['Ǉ', upper], //
-    char!['ǈ', mixed], //
-    char!['M', upper],
-    char!['N', upper],
-    char!['Ǌ', upper], //
-    char!['ǋ', mixed], //
-    char!['O', upper],
-    char!['P', upper],
-    char!['R', upper],
-    char!['S', upper],
-    char!['T', upper],
-    char!['Ć', upper],
-    char!['U', upper],
-    char!['F', upper],
-    char!['H', upper],
-    char!['C', upper],
-    char!['Č', upper],
-    char!['Ǆ', upper], //
-    char!['ǅ', mixed], //
-    char!['Š', upper],
-    char!['a', lower],
-    char!['b', lower],
-    char!['v', lower],
-    char!['g', lower],
-    char!['d', lower],
-    char!['đ', lower],
-    char!['e', lower],
-    char!['ž', lower],
-    char!['z', lower],
-    char!['i', lower],
-    char!['j', lower],
-    char!['k', lower],
-    char!['l', lower],
-    char!['ǉ', lower],
-    char!['m', lower],
-    char!['n', lower],
-    char!['ǌ', lower],
-    char!['o', lower],
-    char!['p', lower],
-    char!['r', lower],
-    char!['s', lower],
-    char!['t', lower],
-    char!['ć', lower],
-    char!['u', lower],
-    char!['f', lower],
-    char!['h', lower],
-    char!['c', lower],
-    char!['č', lower],
-    char!['ǆ', lower],
-    char!['š', lower],
-];
-
-pub const CYRILLIC_CLEAN: &[Character] = &[
-    char!['А', upper],
-    char!['Б', upper],
-    char!['В', upper],
-    char!['Г', upper],
-    char!['Д', upper],
-    char!['Ђ', upper],
-    char!['Е', upper],
-    char!['Ж', upper],
-    char!['З', upper],
-    char!['И', upper],
-    char!['Ј', upper],
-    char!['К', upper],
-    char!['Л', upper],
-    char!['Љ', upper], //
-    char!['Љ', mixed], //
-    char!['М', upper],
-    char!['Н', upper],
-    char!['Њ', upper], //
-    char!['Њ', mixed], //
-    char!['О', upper],
-    char!['П', upper],
-    char!['Р', upper],
-    char!['С', upper],
-    char!['Т', upper],
-    char!['Ћ', upper],
-    char!['У', upper],
-    char!['Ф', upper],
-    char!['Х', upper],
-    char!['Ц', upper],
-    char!['Ч', upper],
-    char!['Џ', upper], //
-    char!['Џ', mixed], //
-    char!['Ш', upper],
-    char!['а', lower],
-    char!['б', lower],
-    char!['в', lower],
-    char!['г', lower],
-    char!['д', lower],
-    char!['ђ', lower],
-    char!['е', lower],
-    char!['ж', lower],
-    char!['з', lower],
-    char!['и', lower],
-    char!['ј', lower],
-    char!['к', lower],
-    char!['л', lower],
-    char!['љ', lower],
-    char!['м', lower],
-    char!['н', lower],
-    char!['њ', lower],
-    char!['о', lower],
-    char!['п', lower],
-    char!['р', lower],
-    char!['с', lower],
-    char!['т', lower],
-    char!['ћ', lower],
-    char!['у', lower],
-    char!['ф', lower],
-    char!['х', lower],
-    char!['ц', lower],
-    char!['ч', lower],
-    char!['џ', lower],
-    char!['ш', lower],
-];
+// Character maps are compiled from `data/charmaps` by build script
+include!(concat!(env!("OUT_DIR"), "/charmaps.rs"));
 
-pub const LATIN_DIRTY: &[Character] = &[
-    char!['A', upper],
-    char!['B', upper],
-    char!['V', upper],
-    char!['G', upper],
-    char!['D', upper],
-    char!['Đ', upper],
-    char!['Ð', upper],
-    char!['D', 'J', upper],
-    char!['D', 'j', mixed],
-    char!['E', upper],
-    char!['Z', upper],
-    char!['Ž', upper],
-    char!['\u{17d}', upper],
-    char!['I', upper],
-    char!['J', upper],
-    char!['K', upper],
-    char!['L', upper],
-    char!['L', 'J', upper],
-    char!['Ǉ', upper],
-    char!['L', 'j', mixed],
-    char!['ǈ', mixed],
-    char!['M', upper],
-    char!['N', upper],
-    char!['N', 'J', upper],
-    char!['Ǌ', upper],
-    char!['N', 'j', mixed],
-    char!['ǋ', mixed],
-    char!['O', upper],
-    char!['P', upper],
-    char!['R', upper],
-    char!['S', upper],
-    char!['T', upper],
-    char!['Ć', upper],
-    char!['\u{106}', upper],
-    char!['U', upper],
-    char!['F', upper],
-    char!['H', upper],
-    char!['C', upper],
-    char!['Č', upper],
-    char!['\u{10c}', upper],
-    char!['D', 'Ž', upper],
-    char!['Ǆ', upper],
-    char!['D', '\u{17d}', upper],
-    char!['D', 'ž', mixed],
-    char!['ǅ', mixed],
-    char!['D', '\u{17e}', mixed],
-    char!['Š', upper],
-    char!['\u{160}', upper],
-    char!['a', lower],
-    char!['æ', lower],
-    char!['b', lower],
-    char!['v', lower],
-    char!['g', lower],
-    char!['d', lower],
-    char!['đ', lower],
-    char!['d', 'j', lower],
-    char!['e', lower],
-    char!['z', lower],
-    char!['ž', lower],
-    char!['\u{17e}', lower],
-    char!['i', lower],
-    char!['ĳ', lower],
-    char!['j', lower],
-    char!['k', lower],
-    char!['l', lower],
-    char!['l', 'j', lower],
-    char!['ǉ', lower],
-    char!['m', lower],
-    char!['n', lower],
-    char!['n', 'j', lower],
-    char!['ǌ', lower],
-    char!['o', lower],
-    char!['œ', lower],
-    char!['p', lower],
-    char!['r', lower],
-    char!['s', lower],
-    char!['ﬆ', lower],
-    char!['t', lower],
-    char!['ć', lower],
-    char!['\u{107}', lower],
-    char!['u', lower],
-    char!['f', lower],
-    char!['ﬁ', lower],
-    char!['ﬂ', lower],
-    char!['h', lower],
-    char!['c', lower],
-    char!['č', lower],
-    char!['\u{10d}', lower],
-    char!['d', 'ž', lower],
-    char!['ǆ', lower],
-    char!['d', '\u{17e}', lower],
-    char!['š', lower],
-    char!['\u{161}', lower],
-];
-
-pub const LATIN_DIRTY_UNICODE: &[Character] = &[
-    char!['A', upper],
-    char!['B', upper],
-    char!['V', upper],
-    char!['G', upper],
-    char!['D', upper],
-    char!['Đ', upper],
-    char!['Đ', upper],
-    char!['Đ', upper],
-    char!['Đ', upper],
-    char!['E', upper],
-    char!['Z', upper],
-    char!['Ž', upper],
-    char!['Ž', upper],
-    char!['I', upper],
-    char!['J', upper],
-    char!['K', upper],
-    char!['L', upper],
-    char!['Ǉ', upper],
-    char!['Ǉ', upper],
-    char!['ǈ', mixed],
-    char!['ǈ', mixed],
-    char!['M', upper],
-    char!['N', upper],
-    char!['Ǌ', upper],
-    char!['Ǌ', upper],
-    char!['ǋ', mixed],
-    char!['ǋ', mixed],
-    char!['O', upper],
-    char!['P', upper],
-    char!['R', upper],
-    char!['S', upper],
-    char!['T', upper],
-    char!['Ć', upper],
-    char!['Ć', upper],
-    char!['U', upper],
-    char!['F', upper],
-    char!['H', upper],
-    char!['C', upper],
-    char!['Č', upper],
-    char!['Č', upper],
-    char!['Ǆ', upper],
-    char!['Ǆ', upper],
-    char!['Ǆ', upper],
-    char!['ǅ', mixed],
-    char!['ǅ', mixed],
-    char!['ǅ', mixed],
-    char!['Š', upper],
-    char!['Š', upper],
-    char!['a', lower],
-    char!['a', 'e', lower],
-    char!['b', lower],
-    char!['v', lower],
-    char!['g', lower],
-    char!['d', lower],
-    char!['đ', lower],
-    char!['đ', lower],
-    char!['e', lower],
-    char!['z', lower],
-    char!['ž', lower],
-    char!['ž', lower],
-    char!['i', lower],
-    char!['i', 'j', lower],
-    char!['j', lower],
-    char!['k', lower],
-    char!['l', lower],
-    char!['ǉ', lower],
-    char!['ǉ', lower],
-    char!['m', lower],
-    char!['n', lower],
-    char!['ǌ', lower],
-    char!['ǌ', lower],
-    char!['o', lower],
-    char!['o', 'e', lower],
-    char!['p', lower],
-    char!['r', lower],
-    char!['s', lower],
-    char!['š', 't', lower],
-    char!['t', lower],
-    char!['ć', lower],
-    char!['ć', lower],
-    char!['u', lower],
-    char!['f', lower],
-    char!['f', 'i', lower],
-    char!['f', 'l', lower],
-    char!['h', lower],
-    char!['c', lower],
-    char!['č', lower],
-    char!['č', lower],
-    char!['ǆ', lower],
-    char!['ǆ', lower],
-    char!['ǆ', lower],
-    char!['š', lower],
-    char!['š', lower],
-];
-
-pub const CYRILLIC_DIRTY: &[Character] = &[
-    char!['А', upper],
-    char!['Б', upper],
-    char!['В', upper],
-    char!['Г', upper],
-    char!['Д', upper],
-    char!['Ђ', upper],
-    char!['Ђ', upper],
-    char!['Ђ', upper],
-    char!['Ђ', upper],
-    char!['Е', upper],
-    char!['З', upper],
-    char!['Ж', upper],
-    char!['Ж', upper],
-    char!['И', upper],
-    char!['Ј', upper],
-    char!['К', upper],
-    char!['Л', upper],
-    char!['Љ', upper],
-    char!['Љ', upper],
-    char!['Љ', mixed],
-    char!['Љ', mixed],
-    char!['М', upper],
-    char!['Н', upper],
-    char!['Њ', upper],
-    char!['Њ', upper],
-    char!['Њ', mixed],
-    char!['Њ', mixed],
-    char!['О', upper],
-    char!['П', upper],
-    char!['Р', upper],
-    char!['С', upper],
-    char!['Т', upper],
-    char!['Ћ', upper],
-    char!['Ћ', upper],
-    char!['У', upper],
-    char!['Ф', upper],
-    char!['Х', upper],
-    char!['Ц', upper],
-    char!['Ч', upper],
-    char!['Ч', upper],
-    char!['Џ', upper],
-    char!['Џ', upper],
-    char!['Џ', upper],
-    char!['Џ', mixed],
-    char!['Џ', mixed],
-    char!['Џ', mixed],
-    char!['Ш', upper],
-    char!['Ш', upper],
-    char!['а', lower],
-    char!['а', 'е', lower],
-    char!['б', lower],
-    char!['в', lower],
-    char!['г', lower],
-    char!['д', lower],
-    char!['ђ', lower],
-    char!['ђ', lower],
-    char!['е', lower],
-    char!['з', lower],
-    char!['ж', lower],
-    char!['ж', lower],
-    char!['и', lower],
-    char!['и', 'ј', lower],
-    char!['ј', lower],
-    char!['к', lower],
-    char!['л', lower],
-    char!['љ', lower],
-    char!['љ', lower],
-    char!['м', lower],
-    char!['н', lower],
-    char!['њ', lower],
-    char!['њ', lower],
-    char!['о', lower],
-    char!['о', 'е', lower],
-    char!['п', lower],
-    char!['р', lower],
-    char!['с', lower],
-    char!['с', 'т', lower],
-    char!['т', lower],
-    char!['ћ', lower],
-    char!['ћ', lower],
-    char!['у', lower],
-    char!['ф', lower],
-    char!['ф', 'и', lower],
-    char!['ф', 'л', lower],
-    char!['х', lower],
-    char!['ц', lower],
-    char!['ч', lower],
-    char!['ч', lower],
-    char!['џ', lower],
-    char!['џ', lower],
-    char!['џ', lower],
-    char!['ш', lower],
-    char!['ш', lower],
-];
+/// Returns index of a character map
+pub fn index(map: &[Character]) -> Index {
+    INDICES
+        .iter()
+        .find(|(m, _)| *m == map)
+        .map_or(&[], |(_, index)| index)
+}
 
 pub struct DigraphException<'a> {
     pub latin: &'a [Character<'a>],
@@ -896,3 +422,185 @@ pub const DIGRAPH_EXCEPTIONS_NJ: &[&str] = &[
     "tanjug",
     "vanjezičk",
 ];
+
+pub struct Orthography<'a> {
+    /// `Đ` is always written out, so `dj` is never a digraph
+    pub split_dj: bool,
+    /// Additional exceptions for each entry of `DIGRAPH_EXCEPTIONS`
+    pub exceptions: [&'a [&'a str]; 3],
+}
+
+pub const ORTHOGRAPHY_SERBIAN: Orthography<'static> = Orthography {
+    split_dj: false,
+    exceptions: [&[], &[], &[]],
+};
+
+pub const ORTHOGRAPHY_CROATIAN: Orthography<'static> = Orthography {
+    split_dj: true,
+    exceptions: [
+        &[],
+        DIGRAPH_EXCEPTIONS_DZ_IJEKAVIAN,
+        DIGRAPH_EXCEPTIONS_NJ_CROATIAN,
+    ],
+};
+
+pub const ORTHOGRAPHY_BOSNIAN: Orthography<'static> = Orthography {
+    split_dj: true,
+    exceptions: [
+        &[],
+        DIGRAPH_EXCEPTIONS_DZ_IJEKAVIAN,
+        DIGRAPH_EXCEPTIONS_NJ_BOSNIAN,
+    ],
+};
+
+pub const DIGRAPH_EXCEPTIONS_DZ_IJEKAVIAN: &[&str] = &["podžupnik"];
+
+pub const DIGRAPH_EXCEPTIONS_NJ_CROATIAN: &[&str] = &["izvanjezičn", "panjugoslaven", "vanjezičn"];
+
+pub const DIGRAPH_EXCEPTIONS_NJ_BOSNIAN: &[&str] =
+    &["izvanjezičn", "panjugoslaven", "panjugosloven", "vanjezičn"];
+
+/// Pairs of maps used together, as (name, from, into)
+#[allow(dead_code)]
+const MAPS: &[(&str, &[Character], &[Character])] = &[
+    ("LATIN_DIRTY", LATIN_DIRTY, LATIN_DIRTY_UNICODE),
+    ("LATIN_CLEAN_UNICODE", LATIN_CLEAN_UNICODE, LATIN_CLEAN),
+    ("LATIN_DIRTY", LATIN_DIRTY, CYRILLIC_DIRTY),
+    ("LATIN_CLEAN_UNICODE", LATIN_CLEAN_UNICODE, CYRILLIC_CLEAN),
+    ("CYRILLIC_CLEAN", CYRILLIC_CLEAN, LATIN_CLEAN),
+    ("CYRILLIC_CLEAN", CYRILLIC_CLEAN, LATIN_CLEAN_UNICODE),
+    #[cfg(feature = "kazakh")]
+    ("KAZAKH_CYRILLIC", KAZAKH_CYRILLIC, KAZAKH_LATIN_FROM_CYRILLIC),
+    #[cfg(feature = "kazakh")]
+    ("KAZAKH_LATIN", KAZAKH_LATIN, KAZAKH_CYRILLIC_FROM_LATIN),
+    #[cfg(feature = "uzbek")]
+    ("UZBEK_CYRILLIC", UZBEK_CYRILLIC, UZBEK_LATIN_FROM_CYRILLIC),
+    #[cfg(feature = "uzbek")]
+    ("UZBEK_CYRILLIC", UZBEK_CYRILLIC, UZBEK_LATIN_ASCII_FROM_CYRILLIC),
+    #[cfg(feature = "uzbek")]
+    ("UZBEK_LATIN", UZBEK_LATIN, UZBEK_CYRILLIC_FROM_LATIN),
+    #[cfg(feature = "uzbek")]
+    ("UZBEK_LATIN", UZBEK_LATIN, UZBEK_LATIN_ASCII),
+];
+
+/// Clean maps which must convert text back and forth without changes
+#[allow(dead_code)]
+const INVERSE: &[(&str, &[Character], &[Character])] = &[
+    ("LATIN_CLEAN", LATIN_CLEAN, CYRILLIC_CLEAN),
+    ("LATIN_CLEAN_UNICODE", LATIN_CLEAN_UNICODE, CYRILLIC_CLEAN),
+    ("LATIN_CLEAN", LATIN_CLEAN, LATIN_CLEAN_UNICODE),
+];
+
+#[allow(dead_code)]
+fn pattern(c: &Character) -> String {
+    c.value.iter().collect()
+}
+
+/// Check integrity of character maps. Returns a description of every
+/// problem found:
+/// * maps used together have different lengths
+/// * a map contains an empty or duplicate pattern
+/// * a pattern comes after a longer pattern which starts with it, so the
+///   longer one is never matched (maps are matched in reverse order)
+/// * clean maps are not inverse of each other
+#[allow(dead_code)]
+pub fn validate() -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+    for (name, from, into) in MAPS {
+        if from.len() != into.len() {
+            problems.push(format!(
+                "{}: {} patterns, but {} replacements",
+                name,
+                from.len(),
+                into.len()
+            ));
+        }
+    }
+    let mut checked: Vec<&str> = Vec::new();
+    for (name, map, _) in MAPS.iter().chain(INVERSE) {
+        if checked.contains(name) {
+            continue;
+        }
+        checked.push(name);
+        for (i, c) in map.iter().enumerate() {
+            if c.value.is_empty() {
+                problems.push(format!("{}[{}]: empty pattern", name, i));
+            }
+            // Mixed case patterns are skipped when followed by uppercase
+            // letter, so earlier patterns are still reachable
+            for (j, other) in map.iter().enumerate().skip(i + 1) {
+                if other.case == Case::Mixed || !c.value.starts_with(other.value) {
+                    continue;
+                }
+                if other.value.len() == c.value.len() {
+                    problems.push(format!(
+                        "{}[{}]: duplicate pattern {:?} of [{}]",
+                        name,
+                        j,
+                        pattern(c),
+                        i
+                    ));
+                } else if !other.value.is_empty() {
+                    problems.push(format!(
+                        "{}[{}]: pattern {:?} hides longer pattern {:?} of [{}]",
+                        name,
+                        j,
+                        pattern(other),
+                        pattern(c),
+                        i
+                    ));
+                }
+            }
+        }
+    }
+    for (name, from, into) in INVERSE {
+        for (i, (f, t)) in from.iter().zip(into.iter()).enumerate() {
+            // Inverse map finds the first matching pattern in reverse order
+            let back = into
+                .iter()
+                .rposition(|c| c.value == t.value && c.case == t.case);
+            if back.map(|j| from[j].value) != Some(f.value) {
+                problems.push(format!(
+                    "{}[{}]: {:?} is not converted back from {:?}",
+                    name,
+                    i,
+                    pattern(f),
+                    pattern(t)
+                ));
+            }
+        }
+    }
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(problems),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_index() {
+        for (map, index) in INDICES {
+            let expected = map
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, c)| !c.value.is_empty())
+                .count();
+            assert_eq!(index.iter().map(|(_, i)| i.len()).sum::<usize>(), expected);
+            for (first, indices) in index.iter() {
+                assert!(indices.windows(2).all(|w| w[0] > w[1]));
+                assert!(indices.iter().all(|&i| map[i].value[0] == *first));
+            }
+        }
+        assert_eq!(index(LATIN_DIRTY), LATIN_DIRTY_INDEX);
+        assert!(index(EMPTY).is_empty());
+    }
+}
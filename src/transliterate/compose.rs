@@ -0,0 +1,226 @@
+use super::{Alphanumeric, Error, Letters, Transliterator};
+
+/// Text produced by typing a character into [`Composer`]
+#[derive(Debug, Default, PartialEq)]
+pub struct Update {
+    /// Converted text which will not change anymore
+    pub commit: String,
+    /// Preview of the rest of the current word, which may still change
+    pub pending: String,
+}
+
+/// Incremental transliterator for input methods and text editors.
+///
+/// Characters are converted as they are typed. Converted text is committed
+/// as soon as no following character can change it, so only the tail
+/// which may still become a digraph (ie. `n` before `j`) is pending.
+///
+/// Word rules which need the whole word (digraph exceptions, foreign
+/// words, links, Roman numerals, product and model names, standalone
+/// letters, protected words, the gazetteer and dialects) are not applied,
+/// because they could change already committed text.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{Charset, Composer, Transliterator, Update};
+///
+/// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+/// let mut c = Composer::new(t);
+/// assert_eq!(c.push('k').unwrap().commit, "к");
+/// assert_eq!(
+///     c.push('n').unwrap(),
+///     Update { commit: "".to_owned(), pending: "н".to_owned() }
+/// );
+/// assert_eq!(c.push('j').unwrap().commit, "њ");
+/// assert_eq!(c.push('i').unwrap().commit, "и");
+/// assert_eq!(c.finish().unwrap(), "");
+/// ```
+pub struct Composer {
+    transliterator: Transliterator,
+    /// Letters which may follow the current word
+    probes: Vec<char>,
+    /// Typed letters of the current word
+    word: String,
+    /// Number of converted characters of the current word which are committed
+    committed: usize,
+}
+
+fn word_char(c: char) -> bool {
+    // Uzbek Latin letters `oʻ` and `gʻ` contain apostrophes
+    c.is_alphabetic() || matches!(c, '\'' | 'ʻ' | 'ʼ' | '‘' | '’')
+}
+
+impl Composer {
+    /// Convert typed text with settings of `transliterator`
+    pub fn new(mut transliterator: Transliterator) -> Self {
+        transliterator.skip_digraph = true;
+        transliterator.force_foreign = true;
+        transliterator.force_links = true;
+        transliterator.force_roman = true;
+        transliterator.alphanumeric = Alphanumeric::Transliterate;
+        transliterator.letters = Letters::Convert;
+        #[cfg(feature = "gazetteer")]
+        {
+            transliterator.gazetteer = false;
+        }
+        #[cfg(feature = "dialect")]
+        {
+            transliterator.dialect = None;
+        }
+        transliterator.protected.clear();
+        transliterator.skip_patterns.clear();
+        let mut probes = transliterator
+            .charset_from
+            .iter()
+            .flat_map(|c| c.value.iter().copied())
+            .chain(['\'', 'ʻ', 'ʼ'])
+            .collect::<Vec<char>>();
+        probes.sort_unstable();
+        probes.dedup();
+        Self {
+            transliterator,
+            probes,
+            word: String::new(),
+            committed: 0,
+        }
+    }
+
+    /// Returns the part of converted current word which no following
+    /// letter can change, and the whole converted word
+    fn stable(&self) -> Result<(String, String), Error> {
+        let output = self.transliterator.process_word(&self.word)?;
+        let mut stable = output.chars().collect::<Vec<char>>();
+        let mut word = self.word.clone();
+        for &probe in &self.probes {
+            word.push(probe);
+            let next = self.transliterator.process_word(&word)?;
+            let common = stable
+                .iter()
+                .zip(next.chars())
+                .take_while(|(a, b)| *a == b)
+                .count();
+            stable.truncate(common);
+            word.pop();
+        }
+        Ok((stable.into_iter().collect(), output))
+    }
+
+    /// Type a character. Returns newly committed text and pending preview.
+    pub fn push(&mut self, c: char) -> Result<Update, Error> {
+        if !word_char(c) {
+            let mut commit = self.finish()?;
+            commit.push(c);
+            return Ok(Update {
+                commit,
+                pending: String::new(),
+            });
+        }
+        self.word.push(c);
+        let (stable, output) = self.stable()?;
+        let stable = stable.chars().count();
+        let mut update = Update::default();
+        if stable > self.committed {
+            update.commit = output
+                .chars()
+                .skip(self.committed)
+                .take(stable - self.committed)
+                .collect();
+            self.committed = stable;
+        }
+        update.pending = output.chars().skip(self.committed).collect();
+        Ok(update)
+    }
+
+    /// Type a string. Returns all newly committed text and pending preview.
+    pub fn push_str(&mut self, text: &str) -> Result<Update, Error> {
+        let mut update = Update::default();
+        for c in text.chars() {
+            let next = self.push(c)?;
+            update.commit.push_str(&next.commit);
+            update.pending = next.pending;
+        }
+        Ok(update)
+    }
+
+    /// End the current word (ie. when input loses focus).
+    /// Returns its pending text, which is now committed.
+    pub fn finish(&mut self) -> Result<String, Error> {
+        let output = self.transliterator.process_word(&self.word)?;
+        let pending = output.chars().skip(self.committed).collect();
+        self.word.clear();
+        self.committed = 0;
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    fn type_text(t: Transliterator, text: &str) -> (String, Vec<String>) {
+        let mut c = Composer::new(t);
+        let mut output = String::new();
+        let mut pending = Vec::new();
+        for ch in text.chars() {
+            let update = c.push(ch).unwrap();
+            output.push_str(&update.commit);
+            pending.push(update.pending);
+        }
+        output.push_str(&c.finish().unwrap());
+        (output, pending)
+    }
+
+    #[test]
+    fn test_composer() {
+        let t = || Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let (output, pending) = type_text(t(), "Ljubav i DŽEP, nju.");
+        assert_eq!(output, "Љубав и ЏЕП, њу.");
+        // Only letters which may start a digraph are pending
+        assert!(pending.iter().all(|p| p.chars().count() <= 1));
+        assert_eq!(pending[0], "Л");
+        assert_eq!(pending[1], "");
+
+        let (output, _) = type_text(
+            Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false),
+            "ЉУБАВ Љубав Џ",
+        );
+        assert_eq!(output, "LJUBAV Ljubav Dž");
+
+        #[cfg(feature = "uzbek")]
+        {
+            use crate::transliterate::Language;
+            let (output, _) = type_text(
+                Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+                    .with_language(Language::Uzbek),
+                "O'zbekiston, g'isht",
+            );
+            assert_eq!(output, "Ўзбекистон, ғишт");
+        }
+    }
+
+    #[test]
+    fn test_composer_process() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_letters(Letters::Keep);
+        #[cfg(feature = "gazetteer")]
+        let t = t.with_gazetteer(true);
+        for text in [
+            "iPhone 12",
+            "MP3 ",
+            "Google i a) Njutn",
+            "Ljubav i DŽEP, nju.",
+        ] {
+            let (output, _) = type_text(t.clone(), text);
+            // Typed text is converted like text converted at once
+            let processed = Composer::new(t.clone())
+                .transliterator
+                .process(text)
+                .unwrap();
+            assert_eq!(output, processed);
+        }
+        assert_eq!(type_text(t.clone(), "iPhone 12").0, "иПхоне 12");
+        assert_eq!(type_text(t, "MP3 ").0, "МП3 ");
+    }
+}
@@ -0,0 +1,73 @@
+/// Hyphens which join components of compound words
+const HYPHENS: &[char] = &['-', '\u{2010}', '\u{2011}'];
+
+/// Apostrophes which mark elisions (ie. `rek'o`)
+const APOSTROPHES: &[char] = &['\'', '\u{2019}'];
+
+/// Splitting of compound words into components, each of which is
+/// transliterated on its own (ie. foreign components are left intact)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compounds {
+    /// Transliterate whole words
+    Whole,
+    /// Split words on internal hyphens (ie. `аудио-визуелни`)
+    Hyphens,
+    /// Split words on internal apostrophes (ie. `rek'o`)
+    Apostrophes,
+    /// Split words on internal hyphens and apostrophes
+    All,
+}
+
+impl Compounds {
+    fn separator(&self, c: char) -> bool {
+        match self {
+            Compounds::Whole => false,
+            Compounds::Hyphens => HYPHENS.contains(&c),
+            Compounds::Apostrophes => APOSTROPHES.contains(&c),
+            Compounds::All => HYPHENS.contains(&c) || APOSTROPHES.contains(&c),
+        }
+    }
+
+    /// Returns components of `word` and the separator following each of
+    /// them. Only separators between two letters split a word.
+    pub fn split<'a>(&self, word: &'a str) -> Vec<(&'a str, &'a str)> {
+        let mut components = Vec::new();
+        let mut start = 0;
+        let mut previous = None;
+        let mut chars = word.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let next = chars.peek().map(|(_, n)| *n);
+            if self.separator(c)
+                && matches!(previous, Some(p) if char::is_alphabetic(p))
+                && matches!(next, Some(n) if n.is_alphabetic())
+            {
+                components.push((&word[start..i], &word[i..i + c.len_utf8()]));
+                start = i + c.len_utf8();
+            }
+            previous = Some(c);
+        }
+        components.push((&word[start..], ""));
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(Compounds::Hyphens.split("аудио-визуелни"), [("аудио", "-"), ("визуелни", "")]);
+        assert_eq!(
+            Compounds::All.split("njemu-njoj’s"),
+            [("njemu", "-"), ("njoj", "’"), ("s", "")]
+        );
+        assert_eq!(Compounds::Hyphens.split("rek'o"), [("rek'o", "")]);
+        assert_eq!(Compounds::Apostrophes.split("rek'o"), [("rek", "'"), ("o", "")]);
+        assert_eq!(Compounds::Whole.split("a-b"), [("a-b", "")]);
+        // Separators at edges or next to other separators don't split words
+        assert_eq!(Compounds::All.split("-ne-'"), [("-ne-'", "")]);
+        assert_eq!(Compounds::All.split("1-2 a--b"), [("1-2 a--b", "")]);
+        assert_eq!(Compounds::All.split(""), [("", "")]);
+    }
+}
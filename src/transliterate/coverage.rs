@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+
+use super::charmaps::{self, Case as LetterCase, Character};
+use super::exceptions::DIGRAPHS;
+use super::{Charset, Context, Decision, Error, Transliterator};
+
+/// Character map entry with its number of uses
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryUsage {
+    /// Letters of the source script
+    pub pattern: String,
+    /// Replacement in the target script
+    pub replacement: String,
+    pub count: usize,
+}
+
+/// Digraph exception with its number of uses
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExceptionUsage {
+    pub exception: String,
+    pub count: usize,
+}
+
+/// Collects which character map entries and digraph exceptions of a
+/// transliterator are used by a corpus, and which letters of the source
+/// script have no mapping, to validate character maps and exceptions of
+/// a language against real text. Only words which are transliterated are
+/// counted, except for unmapped letters, which are counted in skipped
+/// foreign words too.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{Charset, CoverageReport, Transliterator};
+///
+/// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+/// let mut report = CoverageReport::new(t);
+/// report.add("Konjugacija konja, quiz").unwrap();
+/// let nj = report.entries().into_iter().find(|e| e.pattern == "nj").unwrap();
+/// assert_eq!((nj.replacement.as_str(), nj.count), ("њ", 1));
+/// let applied = report.exceptions().into_iter().filter(|e| e.count > 0).count();
+/// assert_eq!(applied, 1);
+/// assert_eq!(report.unmapped(), [('q', 1)]);
+/// ```
+#[derive(Clone)]
+pub struct CoverageReport {
+    transliterator: Transliterator,
+    /// Uses of each entry of the source character map
+    entries: Vec<usize>,
+    /// Digraph, exception and its uses
+    exceptions: Vec<(usize, String, usize)>,
+    unmapped: BTreeMap<char, usize>,
+}
+
+/// Count exceptions which contain the digraph at `position` of `word`
+fn count_exceptions(
+    exceptions: &mut [(usize, String, usize)],
+    word: &[char],
+    position: usize,
+    length: usize,
+) {
+    let lowercase = word
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect::<Vec<char>>();
+    let digraph = lowercase[position..position + length]
+        .iter()
+        .collect::<String>();
+    for (n, exception, count) in exceptions {
+        if DIGRAPHS[*n] != digraph {
+            continue;
+        }
+        let exception = exception.chars().collect::<Vec<char>>();
+        let first = (position + length).saturating_sub(exception.len());
+        if (first..=position).any(|start| lowercase[start..].starts_with(&exception)) {
+            *count += 1;
+        }
+    }
+}
+
+impl CoverageReport {
+    /// Collect coverage of character maps and exceptions of `transliterator`,
+    /// including exceptions configured with [`Transliterator::with_exceptions`]
+    pub fn new(transliterator: Transliterator) -> Self {
+        let mut exceptions: Vec<(usize, String, usize)> = Vec::new();
+        if transliterator.exceptions && !transliterator.skip_digraph {
+            let orthography = transliterator.language.orthography();
+            for n in 0..DIGRAPHS.len() {
+                for exception in charmaps::DIGRAPH_EXCEPTIONS[n]
+                    .exceptions
+                    .iter()
+                    .chain(orthography.exceptions[n])
+                    .map(|e| e.to_string())
+                    .chain(transliterator.digraph_exceptions[n].iter().cloned())
+                {
+                    if !exceptions
+                        .iter()
+                        .any(|(m, e, _)| *m == n && *e == exception)
+                    {
+                        exceptions.push((n, exception, 0));
+                    }
+                }
+            }
+        }
+        Self {
+            entries: vec![0; transliterator.charset_from.len()],
+            exceptions,
+            unmapped: BTreeMap::new(),
+            transliterator,
+        }
+    }
+
+    /// Collect coverage of words of `text`.
+    /// Fails if the text can't be transliterated (ie. it is too long).
+    pub fn add(&mut self, text: &str) -> Result<(), Error> {
+        for trace in self.transliterator.trace(text)? {
+            let word = &text[trace.span];
+            match trace.decision {
+                Decision::Transliterated => self.add_word(word, false),
+                Decision::Exception => self.add_word(word, true),
+                Decision::Foreign => {}
+                _ => continue,
+            }
+            self.add_unmapped(word);
+        }
+        Ok(())
+    }
+
+    /// Count entries used by `word`, following the order in which
+    /// patterns are matched, and exceptions applied to it if `exception`
+    fn add_word(&mut self, word: &str, exception: bool) {
+        let t = &self.transliterator;
+        let chars = word.chars().collect::<Vec<char>>();
+        let context = Context::new(false);
+        let mut cursor = 0;
+        'outer: while cursor < chars.len() {
+            let candidates = match t
+                .index
+                .binary_search_by_key(&chars[cursor], |(first, _)| *first)
+            {
+                Ok(n) => t.index[n].1,
+                Err(_) => &[],
+            };
+            for &i in candidates {
+                let Character { value: c, case: lc } = &t.charset_from[i];
+                if !chars[cursor..].starts_with(c) {
+                    continue;
+                }
+                if exception
+                    && Transliterator::digraph_exception(
+                        &chars,
+                        cursor,
+                        c,
+                        false,
+                        t.language.orthography(),
+                        &t.exception_tries,
+                    )
+                    .is_some()
+                {
+                    count_exceptions(&mut self.exceptions, &chars, cursor, c.len());
+                    cursor += c.len();
+                    continue 'outer;
+                }
+                if lc == &LetterCase::Mixed
+                    && Transliterator::uppercase_letter(&chars, cursor, &context)
+                {
+                    continue;
+                }
+                self.entries[i] += 1;
+                cursor += c.len();
+                continue 'outer;
+            }
+            cursor += 1;
+        }
+    }
+
+    /// Count letters of `word` in the source script which have no mapping
+    fn add_unmapped(&mut self, word: &str) {
+        let t = &self.transliterator;
+        let from = t.from.script();
+        if from == t.into.script() {
+            return;
+        }
+        for c in word.chars() {
+            if Charset::of(c) == Some(from)
+                && t.index
+                    .binary_search_by_key(&c, |(first, _)| *first)
+                    .is_err()
+            {
+                *self.unmapped.entry(c).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Returns every entry of the source character map, in the order in
+    /// which patterns are matched
+    pub fn entries(&self) -> Vec<EntryUsage> {
+        let t = &self.transliterator;
+        t.charset_from
+            .iter()
+            .zip(t.charset_into)
+            .zip(&self.entries)
+            .map(|((from, into), count)| EntryUsage {
+                pattern: from.value.iter().collect(),
+                replacement: into.value.iter().collect(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    /// Returns every digraph exception of the transliterator, or none if
+    /// it skips digraph exceptions
+    pub fn exceptions(&self) -> Vec<ExceptionUsage> {
+        self.exceptions
+            .iter()
+            .map(|(_, exception, count)| ExceptionUsage {
+                exception: exception.clone(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    /// Returns letters of the source script which have no mapping, with
+    /// their number of occurrences, most common first
+    pub fn unmapped(&self) -> Vec<(char, usize)> {
+        let mut unmapped = self
+            .unmapped
+            .iter()
+            .map(|(c, count)| (*c, *count))
+            .collect::<Vec<(char, usize)>>();
+        unmapped.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        unmapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_exceptions(["injunkc"]);
+        let mut report = CoverageReport::new(t);
+        report.add("NJIVA Njiva njiva injunkcija Konjugacija, quiz 12 XIV")?;
+        let count = |pattern: &str| {
+            report
+                .entries()
+                .into_iter()
+                .filter(|e| e.pattern == pattern)
+                .map(|e| e.count)
+                .sum::<usize>()
+        };
+        assert_eq!(count("NJ"), 1);
+        assert_eq!(count("Nj"), 1);
+        assert_eq!(count("nj"), 1);
+        // Letters of skipped words and split digraphs are not counted
+        assert_eq!(count("q"), 0);
+        assert_eq!(count("n"), 1);
+        let applied = report
+            .exceptions()
+            .into_iter()
+            .filter(|e| e.count > 0)
+            .map(|e| e.exception)
+            .collect::<Vec<String>>();
+        assert!(applied.contains(&"injunkc".to_owned()));
+        assert!(applied
+            .iter()
+            .all(|e| e.starts_with("konj") || e == "injunkc"));
+        assert_eq!(report.unmapped(), [('q', 1)]);
+
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let mut report = CoverageReport::new(t);
+        report.add("ЊИВА Њива ёлка")?;
+        let used = report
+            .entries()
+            .into_iter()
+            .filter(|e| e.pattern == "Њ" && e.count > 0)
+            .map(|e| e.replacement)
+            .collect::<Vec<String>>();
+        assert_eq!(used, ["NJ", "Nj"]);
+        assert!(report.exceptions().is_empty());
+        assert_eq!(report.unmapped(), [('ё', 1)]);
+        Ok(())
+    }
+}
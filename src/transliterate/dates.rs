@@ -0,0 +1,215 @@
+use super::{Charset, Error, Transliterator};
+
+/// Month and day names and their abbreviations, in forms used in dates
+/// (ie. `15. januar`, `15. januara`, `u ponedeljak`)
+const VOCABULARY: &[&str] = &[
+    // Months
+    "januar",
+    "januara",
+    "januaru",
+    "februar",
+    "februara",
+    "februaru",
+    "mart",
+    "marta",
+    "martu",
+    "april",
+    "aprila",
+    "aprilu",
+    "maj",
+    "maja",
+    "maju",
+    "jun",
+    "juni",
+    "juna",
+    "junu",
+    "jul",
+    "juli",
+    "jula",
+    "julu",
+    "avgust",
+    "avgusta",
+    "avgustu",
+    "august",
+    "augusta",
+    "augustu",
+    "septembar",
+    "septembra",
+    "septembru",
+    "oktobar",
+    "oktobra",
+    "oktobru",
+    "novembar",
+    "novembra",
+    "novembru",
+    "decembar",
+    "decembra",
+    "decembru",
+    // Croatian months
+    "siječanj",
+    "siječnja",
+    "veljača",
+    "veljače",
+    "ožujak",
+    "ožujka",
+    "travanj",
+    "travnja",
+    "svibanj",
+    "svibnja",
+    "lipanj",
+    "lipnja",
+    "srpanj",
+    "srpnja",
+    "kolovoz",
+    "kolovoza",
+    "rujan",
+    "rujna",
+    "listopad",
+    "listopada",
+    "studeni",
+    "studenog",
+    "studenoga",
+    "prosinac",
+    "prosinca",
+    // Abbreviations of months
+    "jan",
+    "feb",
+    "mar",
+    "apr",
+    "jul",
+    "avg",
+    "aug",
+    "sep",
+    "sept",
+    "okt",
+    "nov",
+    "dec",
+    // Days
+    "ponedeljak",
+    "ponedeljka",
+    "ponedjeljak",
+    "ponedjeljka",
+    "utorak",
+    "utorka",
+    "sreda",
+    "srede",
+    "sredu",
+    "srijeda",
+    "srijede",
+    "srijedu",
+    "četvrtak",
+    "četvrtka",
+    "petak",
+    "petka",
+    "subota",
+    "subote",
+    "subotu",
+    "nedelja",
+    "nedelje",
+    "nedelju",
+    "nedjelja",
+    "nedjelje",
+    "nedjelju",
+    // Abbreviations of days
+    "pon",
+    "uto",
+    "sre",
+    "sri",
+    "čet",
+    "pet",
+    "sub",
+    "ned",
+];
+
+/// Suffixes of ordinal numbers written with digits (ie. `1-vi`, `15-og`)
+const ORDINAL_SUFFIXES: &[&str] = &[
+    "i", "a", "e", "og", "oga", "om", "oj", "ih", "vi", "va", "vo", "ve", "vog", "gi", "ga", "go",
+    "ge", "gog", "ći", "ća", "će", "ćeg", "ti", "ta", "to", "te", "tog", "mi", "ma", "me", "mog",
+];
+
+/// Returns whether lowercase `word` is in `vocabulary`, with Latin
+/// digraph ligatures written as two letters
+fn known(vocabulary: &[&str], word: &str) -> bool {
+    let word = word
+        .to_lowercase()
+        .replace('ǉ', "lj")
+        .replace('ǌ', "nj")
+        .replace('ǆ', "dž");
+    vocabulary.contains(&word.as_str())
+}
+
+impl Transliterator {
+    /// Transliterate `word` into `output` if it is a date or an ordinal
+    /// number written without spaces (ie. `15.januar`, `1.jan.`, `1-vi`),
+    /// whose letters are only month and day names or ordinal suffixes.
+    /// Returns whether the word was transliterated.
+    pub(super) fn date_into(&self, word: &str, output: &mut String) -> Result<bool, Error> {
+        if !word.contains(|c: char| c.is_ascii_digit()) {
+            return Ok(false);
+        }
+        let mut date = String::with_capacity(word.len());
+        let mut rest = word;
+        // Text before the current run of letters
+        let mut previous = "";
+        while !rest.is_empty() {
+            let letters = rest.starts_with(char::is_alphabetic);
+            let end = rest
+                .find(|c: char| c.is_alphabetic() != letters)
+                .unwrap_or(rest.len());
+            let (run, next) = rest.split_at(end);
+            if letters {
+                let converted = self.transliterate_word(run, false)?;
+                let latin = match self.from.script() {
+                    Charset::Cyrillic => &converted,
+                    _ => run,
+                };
+                let ordinal = previous.len() >= 2
+                    && previous.ends_with('-')
+                    && previous[..previous.len() - 1].ends_with(|c: char| c.is_ascii_digit());
+                let suffix = ordinal && known(ORDINAL_SUFFIXES, latin);
+                if !known(VOCABULARY, latin) && !suffix {
+                    return Ok(false);
+                }
+                date.push_str(&converted);
+            } else {
+                date.push_str(run);
+            }
+            previous = run;
+            rest = next;
+        }
+        output.push_str(&date);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Alphanumeric;
+
+    #[test]
+    fn test_dates() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_dates(true);
+        for (text, expected) in [
+            ("15.januar", "15.јануар"),
+            ("ponedeljak, 1.jan.", "понедељак, 1.јан."),
+            ("PON 15.JAN", "ПОН 15.ЈАН"),
+            ("1.siječnja", "1.сијечња"),
+            ("1-vi maj", "1-ви мај"),
+            // Other words with digits are not dates
+            ("15.jan.B92", "15.jan.B92"),
+            ("iPhone 12", "iPhone 12"),
+            ("mp3", "mp3"),
+        ] {
+            assert_eq!(t.process(text)?, expected);
+        }
+        let t = t.with_dates(false);
+        assert_eq!(t.process("15.januar")?, "15.januar");
+        let t = Transliterator::new(Charset::Cyrillic, Charset::LatinUnicode, false, false, false)
+            .with_dates(true)
+            .with_alphanumeric(Alphanumeric::Keep);
+        assert_eq!(t.process("15.НЕДЕЉА, 2-ги")?, "15.NEDEǇA, 2-gi");
+        Ok(())
+    }
+}
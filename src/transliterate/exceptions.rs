@@ -0,0 +1,164 @@
+use super::charmaps::{Orthography, DIGRAPH_EXCEPTIONS};
+
+/// Lowercase digraphs of each entry of `DIGRAPH_EXCEPTIONS`
+pub const DIGRAPHS: [&str; 3] = ["dj", "dž", "nj"];
+
+#[derive(Clone, Default)]
+struct Node {
+    children: Vec<(char, usize)>,
+    /// Node where suffixes of exceptions with this reversed prefix start
+    suffix: Option<usize>,
+    /// An exception ends at this node
+    end: bool,
+}
+
+/// Digraph exceptions as lowercase tries, one for each digraph. Every
+/// exception is split at its digraph into a reversed prefix, which is
+/// matched backwards from the digraph, and a suffix, which is matched
+/// forwards after it, so matching needs no lowercase copy of the word.
+#[derive(Clone)]
+pub struct Exceptions {
+    nodes: Vec<Node>,
+}
+
+impl Exceptions {
+    /// Build tries of built-in exceptions, exceptions of an orthography
+    /// and `extra` exceptions for each digraph
+    pub fn new(orthography: &Orthography, extra: &[Vec<String>; 3]) -> Self {
+        let mut exceptions = Self {
+            nodes: vec![Node::default(); DIGRAPHS.len()],
+        };
+        for (n, digraph) in DIGRAPHS.iter().enumerate() {
+            for exception in DIGRAPH_EXCEPTIONS[n]
+                .exceptions
+                .iter()
+                .chain(orthography.exceptions[n])
+                .copied()
+                .chain(extra[n].iter().map(String::as_str))
+            {
+                for (i, _) in exception.match_indices(digraph) {
+                    exceptions.insert(n, &exception[..i], &exception[i + digraph.len()..]);
+                }
+            }
+        }
+        exceptions
+    }
+
+    fn child(&self, node: usize, c: char) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .iter()
+            .find(|(key, _)| *key == c)
+            .map(|(_, child)| *child)
+    }
+
+    fn child_or_insert(&mut self, node: usize, c: char) -> usize {
+        match self.child(node, c) {
+            Some(child) => child,
+            None => {
+                self.nodes.push(Node::default());
+                let child = self.nodes.len() - 1;
+                self.nodes[node].children.push((c, child));
+                child
+            }
+        }
+    }
+
+    fn insert(&mut self, n: usize, prefix: &str, suffix: &str) {
+        let mut node = n;
+        for c in prefix.chars().rev() {
+            node = self.child_or_insert(node, c);
+        }
+        node = match self.nodes[node].suffix {
+            Some(suffix) => suffix,
+            None => {
+                self.nodes.push(Node::default());
+                let suffix = self.nodes.len() - 1;
+                self.nodes[node].suffix = Some(suffix);
+                suffix
+            }
+        };
+        for c in suffix.chars() {
+            node = self.child_or_insert(node, c);
+        }
+        self.nodes[node].end = true;
+    }
+
+    /// Returns whether any exception of digraph `n` contains the digraph
+    /// `word[position..position + length]`, ignoring case
+    pub fn matches(&self, n: usize, word: &[char], position: usize, length: usize) -> bool {
+        let digraph = &word[position..position + length];
+        if !DIGRAPHS[n]
+            .chars()
+            .eq(digraph.iter().flat_map(|c| c.to_lowercase()))
+        {
+            return false;
+        }
+        let mut before = word[..position]
+            .iter()
+            .rev()
+            .flat_map(|c| c.to_lowercase().rev());
+        let mut node = n;
+        loop {
+            if let Some(suffix) = self.nodes[node].suffix {
+                if self.matches_suffix(suffix, &word[position + length..]) {
+                    return true;
+                }
+            }
+            match before.next().and_then(|c| self.child(node, c)) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+    }
+
+    fn matches_suffix(&self, mut node: usize, after: &[char]) -> bool {
+        for c in after.iter().flat_map(|c| c.to_lowercase()) {
+            if self.nodes[node].end {
+                return true;
+            }
+            match self.child(node, c) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        self.nodes[node].end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::charmaps::{ORTHOGRAPHY_CROATIAN, ORTHOGRAPHY_SERBIAN};
+    use super::*;
+
+    fn matches(exceptions: &Exceptions, n: usize, word: &str, position: usize) -> bool {
+        let word = word.chars().collect::<Vec<char>>();
+        exceptions.matches(n, &word, position, 2)
+    }
+
+    #[test]
+    fn test_matches() {
+        let e = Exceptions::new(&ORTHOGRAPHY_SERBIAN, &Default::default());
+        assert!(matches(&e, 2, "konjugacija", 2));
+        assert!(matches(&e, 2, "KONJUGACIJA", 2));
+        assert!(matches(&e, 2, "Nekonjugovan", 4));
+        assert!(!matches(&e, 2, "konj", 2));
+        assert!(!matches(&e, 2, "njiva", 0));
+        assert!(matches(&e, 1, "nadživeti", 2));
+        assert!(!matches(&e, 1, "nadživeti", 3));
+        assert!(matches(&e, 0, "Gdje", 1));
+        // Only exceptions of the digraph itself apply
+        assert!(!matches(&e, 0, "konjugacija", 2));
+        assert!(!matches(&e, 1, "podžupnik", 2));
+
+        let e = Exceptions::new(&ORTHOGRAPHY_CROATIAN, &Default::default());
+        assert!(matches(&e, 2, "izvanjezični", 4));
+        assert!(matches(&e, 1, "podžupnik", 2));
+
+        // Letters with multiple characters in lowercase (İ is i̇)
+        let extra = [Vec::new(), Vec::new(), vec!["i\u{307}nje".to_owned()]];
+        let e = Exceptions::new(&ORTHOGRAPHY_SERBIAN, &extra);
+        assert!(matches(&e, 2, "İNJE", 1));
+        assert!(!matches(&e, 2, "INJE", 1));
+    }
+}
@@ -0,0 +1,226 @@
+use std::borrow::Cow;
+
+/// Superscript letters, digits and signs, and their plain characters
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('⁰', '0'),
+    ('¹', '1'),
+    ('²', '2'),
+    ('³', '3'),
+    ('⁴', '4'),
+    ('⁵', '5'),
+    ('⁶', '6'),
+    ('⁷', '7'),
+    ('⁸', '8'),
+    ('⁹', '9'),
+    ('⁺', '+'),
+    ('⁻', '-'),
+    ('⁼', '='),
+    ('⁽', '('),
+    ('⁾', ')'),
+    ('ᵃ', 'a'),
+    ('ᵇ', 'b'),
+    ('ᶜ', 'c'),
+    ('ᵈ', 'd'),
+    ('ᵉ', 'e'),
+    ('ᶠ', 'f'),
+    ('ᵍ', 'g'),
+    ('ʰ', 'h'),
+    ('ⁱ', 'i'),
+    ('ʲ', 'j'),
+    ('ᵏ', 'k'),
+    ('ˡ', 'l'),
+    ('ᵐ', 'm'),
+    ('ⁿ', 'n'),
+    ('ᵒ', 'o'),
+    ('ᵖ', 'p'),
+    ('ʳ', 'r'),
+    ('ˢ', 's'),
+    ('ᵗ', 't'),
+    ('ᵘ', 'u'),
+    ('ᵛ', 'v'),
+    ('ʷ', 'w'),
+    ('ˣ', 'x'),
+    ('ʸ', 'y'),
+    ('ᶻ', 'z'),
+    ('ᴬ', 'A'),
+    ('ᴮ', 'B'),
+    ('ᴰ', 'D'),
+    ('ᴱ', 'E'),
+    ('ᴳ', 'G'),
+    ('ᴴ', 'H'),
+    ('ᴵ', 'I'),
+    ('ᴶ', 'J'),
+    ('ᴷ', 'K'),
+    ('ᴸ', 'L'),
+    ('ᴹ', 'M'),
+    ('ᴺ', 'N'),
+    ('ᴼ', 'O'),
+    ('ᴾ', 'P'),
+    ('ᴿ', 'R'),
+    ('ᵀ', 'T'),
+    ('ᵁ', 'U'),
+    ('ⱽ', 'V'),
+    ('ᵂ', 'W'),
+    ('ᵸ', 'н'),
+];
+
+/// Subscript letters, digits and signs, and their plain characters
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('₀', '0'),
+    ('₁', '1'),
+    ('₂', '2'),
+    ('₃', '3'),
+    ('₄', '4'),
+    ('₅', '5'),
+    ('₆', '6'),
+    ('₇', '7'),
+    ('₈', '8'),
+    ('₉', '9'),
+    ('₊', '+'),
+    ('₋', '-'),
+    ('₌', '='),
+    ('₍', '('),
+    ('₎', ')'),
+    ('ₐ', 'a'),
+    ('ₑ', 'e'),
+    ('ₕ', 'h'),
+    ('ᵢ', 'i'),
+    ('ⱼ', 'j'),
+    ('ₖ', 'k'),
+    ('ₗ', 'l'),
+    ('ₘ', 'm'),
+    ('ₙ', 'n'),
+    ('ₒ', 'o'),
+    ('ₚ', 'p'),
+    ('ᵣ', 'r'),
+    ('ₛ', 's'),
+    ('ₜ', 't'),
+    ('ᵤ', 'u'),
+    ('ᵥ', 'v'),
+    ('ₓ', 'x'),
+];
+
+/// Classes of compatibility characters which are folded into plain
+/// letters and digits before transliterating, so they are converted
+/// instead of making words foreign (ie. text of East Asian documents and
+/// OCR). None are folded by default, since folding loses meaning of some
+/// characters (ie. `m²`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fold {
+    /// Fullwidth Latin letters, digits and punctuation (ie. `Ａ`, `ａ`, `１`)
+    pub fullwidth: bool,
+    /// Superscript letters and digits (ie. `ᵃ`, `ᴬ`, `²`)
+    pub superscripts: bool,
+    /// Subscript letters and digits (ie. `ₐ`, `₂`)
+    pub subscripts: bool,
+    /// Circled letters and numbers (ie. `Ⓐ`, `ⓐ`, `⑫`)
+    pub circled: bool,
+}
+
+impl Fold {
+    /// Fold all classes of compatibility characters
+    pub const ALL: Fold = Fold {
+        fullwidth: true,
+        superscripts: true,
+        subscripts: true,
+        circled: true,
+    };
+
+    /// Returns whether no characters are folded
+    pub fn is_empty(&self) -> bool {
+        *self == Fold::default()
+    }
+
+    /// Append plain characters of `c` to `output`, if it is folded
+    fn fold_char(&self, c: char, output: &mut String) -> bool {
+        let table = |table: &[(char, char)]| {
+            table
+                .iter()
+                .find(|(from, _)| *from == c)
+                .map(|(_, into)| *into)
+        };
+        let offset =
+            |c: char, from: char, into: char| char::from_u32(c as u32 - from as u32 + into as u32);
+        let folded = match c {
+            '\u{ff01}'..='\u{ff5e}' if self.fullwidth => offset(c, '\u{ff01}', '!'),
+            _ if self.superscripts && table(SUPERSCRIPTS).is_some() => table(SUPERSCRIPTS),
+            _ if self.subscripts && table(SUBSCRIPTS).is_some() => table(SUBSCRIPTS),
+            'Ⓐ'..='Ⓩ' if self.circled => offset(c, 'Ⓐ', 'A'),
+            'ⓐ'..='ⓩ' if self.circled => offset(c, 'ⓐ', 'a'),
+            '⓪' if self.circled => Some('0'),
+            // Circled numbers go up to 20
+            '①'..='⑳' if self.circled => {
+                output.push_str(&(c as u32 - '①' as u32 + 1).to_string());
+                return true;
+            }
+            _ => None,
+        };
+        match folded {
+            Some(folded) => {
+                output.push(folded);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `word` with compatibility characters of these classes
+    /// replaced by plain characters
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::Fold;
+    /// let fold = Fold { fullwidth: true, ..Default::default() };
+    /// assert_eq!(fold.apply("Ｐｒｉｍｅｒ²"), "Primer²");
+    /// ```
+    pub fn apply<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        if self.is_empty() {
+            return Cow::Borrowed(word);
+        }
+        let mut output = String::new();
+        for (i, c) in word.char_indices() {
+            if output.is_empty() {
+                let mut folded = String::new();
+                if self.fold_char(c, &mut folded) {
+                    output.reserve(word.len());
+                    output.push_str(&word[..i]);
+                    output.push_str(&folded);
+                }
+            } else if !self.fold_char(c, &mut output) {
+                output.push(c);
+            }
+        }
+        match output.is_empty() {
+            true => Cow::Borrowed(word),
+            false => Cow::Owned(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert!(matches!(Fold::default().apply("Ｌｊｕｂａｖ"), Cow::Borrowed(_)));
+        assert!(matches!(Fold::ALL.apply("Ljubav"), Cow::Borrowed(_)));
+        for (word, folded) in [
+            ("Ｌｊｕｂａｖ！", "Ljubav!"),
+            ("H₂O", "H2O"),
+            ("mᵃx²", "max2"),
+            ("ᴰᵃ", "Da"),
+            ("Ⓛⓙⓤⓑⓐⓥ", "Ljubav"),
+            ("⑫⓪①", "1201"),
+            ("ĐaᵃЂ", "ĐaaЂ"),
+        ] {
+            assert_eq!(Fold::ALL.apply(word), folded);
+        }
+        let fullwidth = Fold {
+            fullwidth: true,
+            ..Default::default()
+        };
+        assert_eq!(fullwidth.apply("ｍ²"), "m²");
+    }
+}
@@ -0,0 +1,37 @@
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+
+/// Bundled names of brands, software and people, one per line
+const GAZETTEER: &str = include_str!("../../data/gazetteer.txt");
+
+lazy_static! {
+    static ref NAMES: HashSet<String> = GAZETTEER
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect();
+}
+
+/// Returns whether lowercase `word` is a name in the bundled gazetteer
+pub fn contains(word: &str) -> bool {
+    NAMES.contains(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gazetteer() {
+        assert!(contains("google"));
+        assert!(contains("mcdonald's"));
+        assert!(!contains("Google"));
+        assert!(!contains("tesla"));
+        // Names are single words with letters which would be transliterated
+        for name in NAMES.iter() {
+            assert!(!name.contains(char::is_whitespace), "{}", name);
+            assert!(!name.contains(['w', 'q', 'x', 'y']), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+/// Letters of the Serbian Latin and Cyrillic alphabets, in lowercase
+const SERBIAN: &[&str] = &[
+    "a", "b", "c", "č", "ć", "d", "dž", "đ", "e", "f", "g", "h", "i", "j", "k", "l", "lj", "m",
+    "n", "nj", "o", "p", "r", "s", "š", "t", "u", "v", "z", "ž", "а", "б", "в", "г", "д", "ђ", "е",
+    "ж", "з", "и", "ј", "к", "л", "љ", "м", "н", "њ", "о", "п", "р", "с", "т", "ћ", "у", "ф", "х",
+    "ц", "ч", "џ", "ш",
+];
+
+/// Digraphs which are single letters of the Serbian Latin alphabet
+const DIGRAPHS: &[&str] = &["dž", "lj", "nj"];
+
+/// Treatment of standalone letters, which are often labels (ie. `tačka a)`,
+/// `vitamin D`, `plan B`) rather than words
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Letters {
+    /// Transliterate standalone letters like other words
+    Convert,
+    /// Keep standalone letters intact
+    Keep,
+    /// Transliterate standalone letters only if they are letters of the
+    /// Serbian alphabet, keeping ligatures, lookalikes and letters of
+    /// other alphabets (ie. `æ`, `ѣ`) intact
+    Serbian,
+}
+
+impl Letters {
+    /// Returns whether `word` is a standalone letter which is kept intact
+    pub fn keep(&self, word: &str) -> bool {
+        match (self, letter(word)) {
+            (Letters::Convert, _) | (_, None) => false,
+            (Letters::Keep, Some(_)) => true,
+            (Letters::Serbian, Some(letter)) => !SERBIAN.contains(&letter.to_lowercase().as_str()),
+        }
+    }
+}
+
+/// Returns letter of `word` if it is a single letter or a digraph of the
+/// Serbian Latin alphabet, ignoring punctuation around it (ie. `a)`, `B,`)
+fn letter(word: &str) -> Option<&str> {
+    let letters = word.trim_matches(|c: char| !c.is_alphanumeric());
+    let mut chars = letters.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_alphabetic() => Some(letters),
+        (Some(_), Some(_)) if DIGRAPHS.contains(&letters.to_lowercase().as_str()) => Some(letters),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letters() {
+        for (word, letter_of) in [
+            ("a)", Some("a")),
+            ("(B,", Some("B")),
+            ("Nj.", Some("Nj")),
+            ("ǋ", Some("ǋ")),
+            ("ab", None),
+            ("5)", None),
+            ("—", None),
+            ("", None),
+        ] {
+            assert_eq!(letter(word), letter_of, "{}", word);
+        }
+        assert!(!Letters::Convert.keep("a)"));
+        assert!(Letters::Keep.keep("D"));
+        assert!(!Letters::Keep.keep("Da"));
+        assert!(!Letters::Serbian.keep("Č"));
+        assert!(!Letters::Serbian.keep("ж)"));
+        assert!(!Letters::Serbian.keep("LJ"));
+        for word in ["æ", "ǋ", "ѣ", "я", "x)"] {
+            assert!(Letters::Serbian.keep(word), "{}", word);
+        }
+    }
+}
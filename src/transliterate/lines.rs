@@ -0,0 +1,215 @@
+use std::io::{self, BufRead};
+use std::{error, fmt, str};
+
+use super::{Error, Transliterator};
+
+/// Length in bytes of the longest line converted by default
+pub const MAX_LINE_LENGTH: usize = 1 << 20;
+
+/// Reason why a single line was not converted
+#[derive(Debug)]
+pub enum LineErrorKind {
+    /// Line is not valid UTF-8
+    Utf8(str::Utf8Error),
+    /// Line is longer than the maximum length, with its length in bytes
+    TooLong(usize),
+    /// Line could not be transliterated
+    Transliterate(Error),
+    /// Reading failed, so no lines follow
+    Io(io::Error),
+}
+
+/// Line which was not converted by [`Transliterator::process_lines`]
+#[derive(Debug)]
+pub struct LineError {
+    /// Number of the line, starting from 1
+    pub line: usize,
+    /// Original bytes of the line without its line break, so it can be
+    /// kept as it is. Bytes of lines which are too long are not kept.
+    pub bytes: Vec<u8>,
+    pub kind: LineErrorKind,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {} - ", self.line)?;
+        match &self.kind {
+            LineErrorKind::Utf8(e) => writeln!(f, "{}", e),
+            LineErrorKind::TooLong(length) => writeln!(f, "Line is too long ({} bytes)", length),
+            LineErrorKind::Transliterate(e) => write!(f, "{}", e),
+            LineErrorKind::Io(e) => writeln!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for LineError {}
+
+/// Iterator over converted lines of a reader, returned by
+/// [`Transliterator::process_lines`]
+pub struct Lines<'t, R> {
+    transliterator: &'t Transliterator,
+    reader: R,
+    line: usize,
+    max_length: usize,
+    done: bool,
+}
+
+impl<'t, R: BufRead> Lines<'t, R> {
+    /// Lines longer than `max_length` bytes are skipped without being
+    /// kept in memory
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Read the next line into `bytes`, keeping at most `max_length`
+    /// bytes. Returns length of the line, or `None` at the end of input.
+    fn read_line(&mut self, bytes: &mut Vec<u8>) -> io::Result<Option<usize>> {
+        let mut length = 0;
+        loop {
+            let (consumed, found) = {
+                let buffer = match self.reader.fill_buf() {
+                    Ok(buffer) => buffer,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                };
+                if buffer.is_empty() {
+                    return Ok(if length > 0 { Some(length) } else { None });
+                }
+                let (chunk, consumed, found) = match buffer.iter().position(|b| *b == b'\n') {
+                    Some(i) => (&buffer[..i], i + 1, true),
+                    None => (buffer, buffer.len(), false),
+                };
+                length += chunk.len();
+                if length <= self.max_length {
+                    bytes.extend_from_slice(chunk);
+                }
+                (consumed, found)
+            };
+            self.reader.consume(consumed);
+            if found {
+                return Ok(Some(length));
+            }
+        }
+    }
+}
+
+impl<'t, R: BufRead> Iterator for Lines<'t, R> {
+    type Item = Result<String, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut bytes = Vec::new();
+        let length = match self.read_line(&mut bytes) {
+            Ok(Some(length)) => length,
+            Ok(None) => return None,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(LineError {
+                    line: self.line + 1,
+                    bytes,
+                    kind: LineErrorKind::Io(e),
+                }));
+            }
+        };
+        self.line += 1;
+        let error = |bytes, kind| LineError {
+            line: self.line,
+            bytes,
+            kind,
+        };
+        if length > self.max_length {
+            return Some(Err(error(Vec::new(), LineErrorKind::TooLong(length))));
+        }
+        if bytes.last() == Some(&b'\r') {
+            bytes.pop();
+        }
+        Some(match String::from_utf8(bytes) {
+            Ok(text) => self
+                .transliterator
+                .process(&text)
+                .map_err(|e| error(text.into_bytes(), LineErrorKind::Transliterate(e))),
+            Err(e) => {
+                let kind = LineErrorKind::Utf8(e.utf8_error());
+                Err(error(e.into_bytes(), kind))
+            }
+        })
+    }
+}
+
+impl Transliterator {
+    /// Returns iterator over transliterated lines of `reader`, without
+    /// their line breaks. Lines which are not valid UTF-8 or are too long
+    /// (see [`MAX_LINE_LENGTH`]) are errors of their own, while the rest
+    /// of the input is still converted, so a few damaged lines don't
+    /// stop conversion of large datasets.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Input to read lines from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset, LineErrorKind};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let input: &[u8] = b"Dobar dan\nMa\xc5ina\r\nLaku no\xc4\x87\n";
+    /// let lines = t.process_lines(input).collect::<Vec<_>>();
+    /// assert_eq!(lines[0].as_ref().unwrap(), "Добар дан");
+    /// let error = lines[1].as_ref().unwrap_err();
+    /// assert_eq!((error.line, &error.bytes[..]), (2, &b"Ma\xc5ina"[..]));
+    /// assert!(matches!(error.kind, LineErrorKind::Utf8(_)));
+    /// assert_eq!(lines[2].as_ref().unwrap(), "Лаку ноћ");
+    /// ```
+    pub fn process_lines<R: BufRead>(&self, reader: R) -> Lines<'_, R> {
+        Lines {
+            transliterator: self,
+            reader,
+            line: 0,
+            max_length: MAX_LINE_LENGTH,
+            done: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_process_lines() {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let input = [
+            "Један\n\nдва и три\r\nчетири пет шест\n".as_bytes(),
+            b"\xff\n",
+            "седам".as_bytes(),
+        ]
+        .concat();
+        // Small buffer splits lines between reads
+        let reader = io::BufReader::with_capacity(4, &input[..]);
+        let lines = t
+            .process_lines(reader)
+            .with_max_length(20)
+            .map(|line| line.map_err(|e| (e.line, e.bytes, e.kind)))
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0].as_ref().unwrap(), "Jedan");
+        assert_eq!(lines[1].as_ref().unwrap(), "");
+        assert_eq!(lines[2].as_ref().unwrap(), "dva i tri");
+        assert!(matches!(
+            &lines[3],
+            Err((4, bytes, LineErrorKind::TooLong(28))) if bytes.is_empty()
+        ));
+        assert!(matches!(
+            &lines[4],
+            Err((5, bytes, LineErrorKind::Utf8(_))) if bytes == &[0xff]
+        ));
+        assert_eq!(lines[5].as_ref().unwrap(), "sedam");
+        assert_eq!(t.process_lines(&b""[..]).count(), 0);
+        assert_eq!(t.process_lines(&b"\n"[..]).count(), 1);
+    }
+}
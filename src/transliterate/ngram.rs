@@ -0,0 +1,159 @@
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+use super::exceptions::DIGRAPHS;
+
+/// Annotated words the bundled model is trained on
+const CORPUS: &str = include_str!("../../data/ngram/serbian.txt");
+
+/// Longest context on each side of a digraph, in characters
+const ORDER: usize = 4;
+
+/// Weight of shorter contexts when smoothing counts of longer ones
+const SMOOTHING: f64 = 2.0;
+
+lazy_static! {
+    /// Model trained on the bundled Serbian corpus
+    pub static ref SERBIAN: Model = Model::train(CORPUS);
+}
+
+/// Character n-gram model of ambiguous digraphs. Counts how often each
+/// digraph is a single letter or two letters in contexts of up to `ORDER`
+/// characters on each side, and scores both candidates of a digraph by
+/// backing off from longer contexts to shorter ones.
+#[derive(Clone, Debug, Default)]
+pub struct Model {
+    /// Counts of single letter and split digraphs by context
+    counts: HashMap<String, [u32; 2]>,
+}
+
+/// Returns index of the digraph at `position` of `word` in `DIGRAPHS`
+fn digraph(word: &[char], position: usize) -> Option<usize> {
+    let pair = word.get(position..position + 2)?;
+    DIGRAPHS.iter().position(|digraph| {
+        digraph
+            .chars()
+            .zip(pair)
+            .all(|(d, c)| c.to_lowercase().eq(Some(d)))
+    })
+}
+
+/// Returns lowercase characters before and after the digraph at
+/// `position`, closest first, ending with `^` or `$` at word boundaries
+fn context(word: &[char], position: usize) -> (Vec<char>, Vec<char>) {
+    let side = |chars: &mut dyn Iterator<Item = &char>, boundary: char| {
+        let mut side = Vec::new();
+        for c in chars {
+            if side.len() == ORDER {
+                return side;
+            }
+            if !c.is_alphabetic() {
+                break;
+            }
+            side.extend(c.to_lowercase());
+        }
+        if side.len() < ORDER {
+            side.push(boundary);
+        }
+        side
+    };
+    (
+        side(&mut word[..position].iter().rev(), '^'),
+        side(&mut word[position + 2..].iter(), '$'),
+    )
+}
+
+impl Model {
+    /// Train model on `corpus` of lowercase Latin words, one per line,
+    /// where digraphs written as two letters are split with `|`. Empty
+    /// lines and lines starting with `#` are skipped.
+    pub fn train(corpus: &str) -> Self {
+        let mut model = Self::default();
+        for line in corpus.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut word = Vec::new();
+            let mut splits = Vec::new();
+            for c in line.chars() {
+                match c {
+                    '|' => splits.push(word.len()),
+                    c => word.push(c),
+                }
+            }
+            for position in 0..word.len() {
+                if let Some(n) = digraph(&word, position) {
+                    let split = splits.contains(&(position + 1)) as usize;
+                    for (_, key) in Self::contexts(n, &word, position) {
+                        model.counts.entry(key).or_default()[split] += 1;
+                    }
+                }
+            }
+        }
+        model
+    }
+
+    /// Returns contexts of digraph `n` at `position` with their lengths
+    fn contexts(n: usize, word: &[char], position: usize) -> Vec<(usize, String)> {
+        let (left, right) = context(word, position);
+        let mut contexts = Vec::new();
+        for l in 0..=left.len() {
+            for r in 0..=right.len() {
+                let before = left[..l].iter().rev().collect::<String>();
+                let after = right[..r].iter().collect::<String>();
+                contexts.push((l + r, format!("{}:{}:{}", n, before, after)));
+            }
+        }
+        contexts
+    }
+
+    /// Returns probability that the digraph at `position` of `word` is
+    /// written as two letters, or `None` if it is not a digraph
+    pub fn split_probability(&self, word: &[char], position: usize) -> Option<f64> {
+        let n = digraph(word, position)?;
+        let contexts = Self::contexts(n, word, position);
+        let mut probability = 0.5;
+        for length in 0..=2 * ORDER {
+            let seen = contexts
+                .iter()
+                .filter(|(l, _)| *l == length)
+                .filter_map(|(_, key)| self.counts.get(key))
+                .map(|[joined, split]| {
+                    (*split as f64 + SMOOTHING * probability)
+                        / ((joined + split) as f64 + SMOOTHING)
+                })
+                .collect::<Vec<f64>>();
+            // Longer contexts contain shorter ones, so they aren't seen either
+            if seen.is_empty() {
+                break;
+            }
+            probability = seen.iter().sum::<f64>() / seen.len() as f64;
+        }
+        Some(probability)
+    }
+
+    /// Returns whether the digraph at `position` of `word` is likelier to
+    /// be written as two letters than as a single one
+    pub fn split(&self, word: &[char], position: usize) -> bool {
+        matches!(self.split_probability(word, position), Some(p) if p > 0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model() {
+        let chars = |word: &str| word.chars().collect::<Vec<char>>();
+        let model = Model::train("# comment\n\nkonj\nkonji\nkon|jugacija\nnjiva\nan|jon\n");
+        assert!(model.split(&chars("konjugovati"), 2));
+        assert!(!model.split(&chars("Konjima"), 2));
+        assert!(!model.split(&chars("NJIVE"), 0));
+        assert!(model.split(&chars("(ANJONI)"), 2));
+        assert_eq!(model.split_probability(&chars("konj"), 1), None);
+
+        assert_eq!(context(&chars("„konjugacija"), 3), (chars("ok^"), chars("ugac")));
+        assert_eq!(context(&chars("NJ"), 0), (chars("^"), chars("$")));
+    }
+}
@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+
+/// Latin and Cyrillic letters which look the same
+const HOMOGLYPHS: &[(char, char)] = &[
+    ('A', 'А'),
+    ('B', 'В'),
+    ('C', 'С'),
+    ('E', 'Е'),
+    ('H', 'Н'),
+    ('J', 'Ј'),
+    ('K', 'К'),
+    ('M', 'М'),
+    ('O', 'О'),
+    ('P', 'Р'),
+    ('T', 'Т'),
+    ('X', 'Х'),
+    ('a', 'а'),
+    ('c', 'с'),
+    ('e', 'е'),
+    ('j', 'ј'),
+    ('o', 'о'),
+    ('p', 'р'),
+    ('x', 'х'),
+    ('y', 'у'),
+];
+
+/// Digits which are recognized instead of similar Latin letters,
+/// as (digit, lowercase, uppercase)
+const LATIN_DIGITS: &[(char, char, char)] = &[('0', 'o', 'O'), ('1', 'l', 'I')];
+
+/// Digits which are recognized instead of similar Cyrillic letters
+const CYRILLIC_DIGITS: &[(char, char, char)] = &[('0', 'о', 'О'), ('3', 'з', 'З'), ('6', 'б', 'Б')];
+
+fn is_cyrillic(c: char) -> bool {
+    matches!(c, '\u{400}'..='\u{52f}')
+}
+
+fn is_latin(c: char) -> bool {
+    c.is_alphabetic() && !is_cyrillic(c) && (c.is_ascii() || matches!(c, '\u{c0}'..='\u{24f}'))
+}
+
+/// Fix common OCR errors in a word made of letters and digits
+fn fix_word(word: &[char]) -> Vec<char> {
+    // Script of the word is decided by letters which are not homoglyphs
+    let distinct = |c: &&char| !HOMOGLYPHS.iter().any(|&(l, k)| l == **c || k == **c);
+    let latin = word
+        .iter()
+        .filter(distinct)
+        .filter(|&&c| is_latin(c))
+        .count();
+    let cyrillic = word
+        .iter()
+        .filter(distinct)
+        .filter(|&&c| is_cyrillic(c))
+        .count();
+    if latin == cyrillic {
+        return word.to_vec();
+    }
+    let cyrillic = cyrillic > latin;
+    let mut fixed = word.to_vec();
+    for (i, &c) in word.iter().enumerate() {
+        // Letters of the other script inside a word
+        if let Some(&(l, k)) = HOMOGLYPHS
+            .iter()
+            .find(|&&(l, k)| if cyrillic { l == c } else { k == c })
+        {
+            fixed[i] = if cyrillic { k } else { l };
+            continue;
+        }
+        let (before, after) = match (i.checked_sub(1).map(|i| word[i]), word.get(i + 1)) {
+            (Some(before), Some(&after)) if before.is_alphabetic() && after.is_alphabetic() => {
+                (before, after)
+            }
+            _ => continue,
+        };
+        // Digits between letters (ie. `ko1ač`)
+        let digits = if cyrillic {
+            CYRILLIC_DIGITS
+        } else {
+            LATIN_DIGITS
+        };
+        if let Some(&(_, lower, upper)) = digits.iter().find(|&&(d, _, _)| d == c) {
+            fixed[i] = match before.is_uppercase() && after.is_uppercase() {
+                true => upper,
+                false => lower,
+            };
+        }
+        // Uppercase `I` between lowercase Latin letters (ie. `kIjuč`)
+        if !cyrillic && c == 'I' && before.is_lowercase() && after.is_lowercase() {
+            fixed[i] = 'l';
+        }
+    }
+    // No Serbian word starts with `rn` (ie. `rnesto`)
+    if !cyrillic
+        && fixed.len() > 2
+        && fixed[0] == 'r'
+        && fixed[1] == 'n'
+        && fixed[2].is_alphabetic()
+    {
+        fixed.splice(0..2, ['m']);
+    }
+    // Conjunction `и` is recognized as `м`, which is not a word
+    if fixed == ['м'] {
+        fixed[0] = 'и';
+    }
+    fixed
+}
+
+/// Fix common OCR errors in Serbian text, ie. Latin letters in Cyrillic
+/// words and digits recognized instead of letters. Each word is fixed
+/// only from its own context, so correct words are left unchanged.
+///
+/// # Arguments
+///
+/// * `text` - Word or other text without spaces
+pub fn fix(text: &str) -> Cow<'_, str> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut output = Vec::with_capacity(chars.len());
+    let mut start = 0;
+    while start < chars.len() {
+        let alphanumeric = chars[start].is_alphanumeric();
+        let length = chars[start..]
+            .iter()
+            .take_while(|c| c.is_alphanumeric() == alphanumeric)
+            .count();
+        match alphanumeric {
+            true => output.extend(fix_word(&chars[start..start + length])),
+            false => output.extend_from_slice(&chars[start..start + length]),
+        }
+        start += length;
+    }
+    match output == chars {
+        true => Cow::Borrowed(text),
+        false => Cow::Owned(output.into_iter().collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix() {
+        for (text, expected) in [
+            ("Ko1ač", "Kolač"),
+            ("kIjuč,", "ključ,"),
+            ("rnesto", "mesto"),
+            ("PR0ZOR", "PROZOR"),
+            ("до6ар", "добар"),
+            ("кyћa", "кућа"),
+            ("Beograd", "Beograd"),
+            ("ВЕОГРАД", "ВЕОГРАД"),
+            ("м", "и"),
+            ("(м)", "(и)"),
+            ("crno", "crno"),
+            ("B92", "B92"),
+            ("COVID-19", "COVID-19"),
+            ("Pаris", "Paris"),
+        ] {
+            assert_eq!(fix(text), expected);
+        }
+    }
+}
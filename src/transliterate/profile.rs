@@ -0,0 +1,282 @@
+use std::{fmt, str};
+
+use super::charmaps::{self, Case, Character, Orthography};
+use super::{
+    Alphanumeric, Archaic, Charset, Compounds, Error, Fold, Language, Letters, Transliterator,
+};
+
+/// Behavior of transliteration frozen at a version of the rules, so
+/// archives converted once can be converted again with byte-identical
+/// output after upgrading the crate. Profiles fix values of all options
+/// instead of using defaults, which may change, and are supported only
+/// while built-in character maps and digraph exceptions are the same as
+/// when the profile was released, which is verified with their checksum.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{Charset, Profile};
+/// let t = Profile::v1().transliterator(Charset::Cyrillic, Charset::Latin).unwrap();
+/// assert_eq!(t.process("Ђурђевдан").unwrap(), "Đurđevdan");
+/// assert_eq!(Profile::v1().to_string(), "v1");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Profile {
+    V1,
+}
+
+impl Profile {
+    /// Profile of the rules of this version of the crate
+    pub const LATEST: Profile = Profile::V1;
+
+    /// Profile of the first versioned rules
+    pub fn v1() -> Self {
+        Profile::V1
+    }
+
+    /// Returns checksum of the rules of this profile
+    pub fn checksum(&self) -> u64 {
+        match self {
+            Profile::V1 => 0x11d7_1078_1004_2007,
+        }
+    }
+
+    /// Returns identifier of the rules of this profile, which can be
+    /// stored with converted texts (ie. `translit-v1-0123456789abcdef`)
+    pub fn id(&self) -> String {
+        format!("translit-{}-{:016x}", self, self.checksum())
+    }
+
+    /// Returns whether this version of the crate converts text with the
+    /// rules of this profile
+    pub fn is_supported(&self) -> bool {
+        rules_checksum() == self.checksum()
+    }
+
+    /// Returns transliterator between character sets with options of
+    /// this profile. Fails if rules of the profile are not supported.
+    pub fn transliterator(&self, from: Charset, into: Charset) -> Result<Transliterator, Error> {
+        if !self.is_supported() {
+            return Err(Error::UnsupportedProfile(self.to_string()));
+        }
+        let transliterator = match self {
+            Profile::V1 => Transliterator::new(from, into, false, false, false)
+                .with_force_roman(false)
+                .with_fix_ocr(false)
+                .with_fold(Fold::default())
+                .with_titlecase_digraphs(false)
+                .with_language(Language::Serbian)
+                .with_archaic(Archaic::Ignore)
+                .with_compounds(Compounds::Whole)
+                .with_alphanumeric(Alphanumeric::Suffixes)
+                .with_letters(Letters::Convert)
+                .with_ansi(false)
+                .with_strict(false),
+        };
+        #[cfg(feature = "dates")]
+        let transliterator = transliterator.with_dates(false);
+        #[cfg(feature = "dialect")]
+        let transliterator = transliterator.with_dialect(None);
+        #[cfg(feature = "gazetteer")]
+        let transliterator = transliterator.with_gazetteer(false);
+        #[cfg(feature = "ngram")]
+        let transliterator = transliterator.with_ngram(false);
+        #[cfg(feature = "detect-language")]
+        let transliterator = transliterator.with_detect_language(false);
+        Ok(transliterator)
+    }
+}
+
+impl str::FromStr for Profile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "v1" => Ok(Profile::V1),
+            _ => Err(Error::UnsupportedProfile(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Profile::V1 => "v1",
+        })
+    }
+}
+
+/// FNV-1a hash, which is the same on every platform and version of Rust
+struct Checksum(u64);
+
+impl Checksum {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn chars(&mut self, chars: impl IntoIterator<Item = char>) {
+        for c in chars {
+            self.write(&u32::from(c).to_le_bytes());
+        }
+        // Separates lists, so moving a character between them changes
+        // the checksum
+        self.write(&[0xff]);
+    }
+
+    fn map(&mut self, map: &[Character]) {
+        for character in map {
+            self.write(&[match character.case {
+                Case::Upper => 0,
+                Case::Mixed => 1,
+                Case::Lower => 2,
+            }]);
+            self.chars(character.value.iter().copied());
+        }
+        self.chars(None);
+    }
+
+    fn words(&mut self, words: &[&str]) {
+        for word in words {
+            self.chars(word.chars());
+        }
+        self.chars(None);
+    }
+
+    fn orthography(&mut self, orthography: &Orthography) {
+        self.write(&[u8::from(orthography.split_dj)]);
+        for exceptions in &orthography.exceptions {
+            self.words(exceptions);
+        }
+    }
+}
+
+/// Returns checksum of built-in rules of Serbian, Croatian and Bosnian in
+/// this version of the crate: character maps, digraph exceptions and
+/// titlecase digraphs
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{rules_checksum, Profile};
+/// assert_eq!(rules_checksum(), Profile::LATEST.checksum());
+/// ```
+pub fn rules_checksum() -> u64 {
+    let mut checksum = Checksum(0xcbf2_9ce4_8422_2325);
+    for map in [
+        charmaps::LATIN_DIRTY,
+        charmaps::LATIN_DIRTY_UNICODE,
+        charmaps::LATIN_CLEAN,
+        charmaps::LATIN_CLEAN_UNICODE,
+        charmaps::CYRILLIC_DIRTY,
+        charmaps::CYRILLIC_CLEAN,
+    ] {
+        checksum.map(map);
+    }
+    for exception in charmaps::DIGRAPH_EXCEPTIONS {
+        checksum.map(exception.latin);
+        checksum.map(exception.latinized);
+        checksum.map(exception.cyrillic);
+        checksum.words(exception.exceptions);
+    }
+    for orthography in [
+        &charmaps::ORTHOGRAPHY_SERBIAN,
+        &charmaps::ORTHOGRAPHY_CROATIAN,
+        &charmaps::ORTHOGRAPHY_BOSNIAN,
+    ] {
+        checksum.orthography(orthography);
+    }
+    for (digraph, letter) in super::TITLECASE_DIGRAPHS {
+        checksum.chars(digraph.iter().copied());
+        checksum.chars(Some(*letter));
+    }
+    checksum.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        // Changes of rules need a new profile, while old ones become
+        // unsupported
+        assert_eq!(rules_checksum(), Profile::LATEST.checksum());
+        assert!(Profile::v1().is_supported());
+        assert_eq!(Profile::v1().id(), format!("translit-v1-{:016x}", rules_checksum()));
+        assert_eq!("v1".parse::<Profile>().unwrap(), Profile::V1);
+        assert!("v0".parse::<Profile>().is_err());
+        let t = Profile::v1()
+            .transliterator(Charset::Latin, Charset::Cyrillic)
+            .unwrap();
+        assert_eq!(t.process("Injekcija i NJUTN").unwrap(), "Инјекција и ЊУТН");
+        // Options added later keep output of the profile unchanged
+        assert_eq!(t.process("\x1b[31mCrveno\x1b[0m i").unwrap(), "\x1b[31mCrveno\x1b[0m и");
+    }
+
+    #[test]
+    fn test_profile_v1_corpus() {
+        // Output of the profile is frozen, so it must never change
+        for (from, into, text, expected) in [
+            (
+                Charset::Latin,
+                Charset::Cyrillic,
+                "Dobar dan, Ljubo! NJIVA i Njiva, džep i DŽEP.",
+                "Добар дан, Љубо! ЊИВА и Њива, џеп и ЏЕП.",
+            ),
+            (
+                Charset::Latin,
+                Charset::Cyrillic,
+                "Injekcija, konjunkcija, nadživeti, odjednom i Djordje.",
+                "Инјекција, конјункција, надживети, одједном и Ђорђе.",
+            ),
+            (
+                Charset::Latin,
+                Charset::Cyrillic,
+                "\x1b[31mCrveno\x1b[0m i \\n novi\\tred",
+                "\x1b[31mCrveno\x1b[0m и \\н нови\\тред",
+            ),
+            (
+                Charset::Latin,
+                Charset::Cyrillic,
+                "*Podebljano* i _kurziv_, <b>reč</b> i &amp; znak",
+                "*Подебљано* и _курзив_, <b>reč</b> и &амп; знак",
+            ),
+            (
+                Charset::Latin,
+                Charset::Cyrillic,
+                "Vidi https://primer.rs/strana i pisi na pera@primer.rs",
+                "Види https://primer.rs/strana и писи на pera@primer.rs",
+            ),
+            (
+                Charset::Latin,
+                Charset::Cyrillic,
+                "Luj XIV, iPhone 12, MP3 i 19-og veka, 5 kg",
+                "Луј XIV, iPhone 12, MP3 и 19-ог века, 5 кг",
+            ),
+            (
+                Charset::Cyrillic,
+                Charset::Latin,
+                "Ђорђе, ЉУБАВ и Љубав, Џеп и ЏЕП.",
+                "Đorđe, LJUBAV i Ljubav, Džep i DŽEP.",
+            ),
+            (
+                Charset::Cyrillic,
+                Charset::Latin,
+                "\x1b[1mШта\x1b[0m \\n <i>њива</i>, www.primer.rs",
+                "\x1b[1mШта\x1b[0m \\n <i>њива</i>, www.primer.rs",
+            ),
+            (
+                Charset::Cyrillic,
+                Charset::LatinUnicode,
+                "Љубав, ЊИВА и Џеп",
+                "ǈubav, ǊIVA i ǅep",
+            ),
+        ] {
+            let t = Profile::v1().transliterator(from, into).unwrap();
+            assert_eq!(t.process(text).unwrap(), expected);
+        }
+    }
+}
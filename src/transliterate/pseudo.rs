@@ -0,0 +1,90 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{Error, Transliterator};
+
+lazy_static! {
+    /// Placeholder (ie. `{{name}}`, `{count}`, `%1$s`, `%(name)s`,
+    /// `$name`), HTML tag or character reference, or escape sequence
+    static ref PLACEHOLDER: Regex = Regex::new(concat!(
+        r"\{\{[^{}]*\}\}|\$?\{[^{}]*\}|%(?:\d+\$)?(?:\(\w+\))?[-+0#]*\d*(?:\.\d+)?[sdifuxXeEgGc@%]",
+        r"|\$\w+|<[^<>]*>|&(?:#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);|\\[nrt]"
+    ))
+    .unwrap();
+}
+
+/// Letters of padding, which are the widest letters of Serbian Cyrillic
+/// and ones with parts above or below the line
+const PADDING: [char; 6] = ['Љ', 'Њ', 'Ж', 'Ш', 'Ђ', 'Ћ'];
+
+impl Transliterator {
+    /// Try pseudolocalizing a string of a user interface: it is
+    /// transliterated, wrapped with `[` and `]`, and padded with letters
+    /// by a percentage of its length. Strings which are cut off, strings
+    /// which are not translatable, and layouts which don't fit longer
+    /// text can be found by testing an application with real Serbian text
+    /// in the target script. Placeholders (ie. `{name}`, `%s`), HTML tags
+    /// and escape sequences are left unchanged, and the same string is
+    /// always pseudolocalized the same way.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - String to pseudolocalize
+    /// * `padding` - Length of padding, in percent of the length of input
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// assert_eq!(
+    ///     t.pseudolocalize("Zdravo, {name}!", 30).unwrap(),
+    ///     "[Здраво, {name}! ЉЊЖШЂ]"
+    /// );
+    /// assert_eq!(t.pseudolocalize("", 30).unwrap(), "[]");
+    /// ```
+    pub fn pseudolocalize<S: AsRef<str>>(&self, input: S, padding: usize) -> Result<String, Error> {
+        let input = input.as_ref();
+        let mut output = String::with_capacity(input.len() * 2 + 2);
+        output.push('[');
+        let mut last = 0;
+        for placeholder in PLACEHOLDER.find_iter(input) {
+            self.process_into(&input[last..placeholder.start()], &mut output)?;
+            output.push_str(placeholder.as_str());
+            last = placeholder.end();
+        }
+        self.process_into(&input[last..], &mut output)?;
+        // Padding is rounded up, so short strings are padded too
+        let padding = (input.chars().count() * padding + 99) / 100;
+        if padding > 0 {
+            output.push(' ');
+            output.extend(PADDING.iter().cycle().take(padding));
+        }
+        output.push(']');
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_pseudolocalize() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        assert_eq!(t.pseudolocalize("Sačuvaj", 0)?, "[Сачувај]");
+        assert_eq!(t.pseudolocalize("OK", 30)?, "[ОК Љ]");
+        assert_eq!(t.pseudolocalize("50% sniženja", 0)?, "[50% снижења]");
+        assert_eq!(
+            t.pseudolocalize("Obrisano je %1$d od %2$s fajlova\\n", 50)?,
+            "[Обрисано је %1$d од %2$s фајлова\\n ЉЊЖШЂЋЉЊЖШЂЋЉЊЖШЂ]"
+        );
+        assert_eq!(
+            t.pseudolocalize("<b>{{user}}</b> ima ${count} poruka &amp; %(n)s", 10)?,
+            "[<b>{{user}}</b> има ${count} порука &amp; %(n)s ЉЊЖШЂ]"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+use std::{fmt, str};
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use super::{Charset, Error, Transliterator};
+
+lazy_static! {
+    /// Integer, with optional thousands separators and decimal part
+    /// (ie. `1.250.000`, `3,05`)
+    static ref NUMBER: Regex = Regex::new(r"-?(?:\d{1,3}(?:\.\d{3})+|\d+)(?:,\d+)?").unwrap();
+}
+
+/// Abbreviations and symbols, and words they are read as
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("npr.", "na primer"),
+    ("itd.", "i tako dalje"),
+    ("tj.", "to jest"),
+    ("tzv.", "takozvani"),
+    ("i sl.", "i slično"),
+    ("br.", "broj"),
+    ("str.", "strana"),
+    ("ul.", "ulica"),
+    ("god.", "godine"),
+    ("prof.", "profesor"),
+    ("dr.", "doktor"),
+    ("dr", "doktor"),
+    ("mr.", "magistar"),
+    ("mr", "magistar"),
+    ("gđa", "gospođa"),
+    ("%", "odsto"),
+];
+
+const UNITS: [&str; 10] = [
+    "nula", "jedan", "dva", "tri", "četiri", "pet", "šest", "sedam", "osam", "devet",
+];
+const TEENS: [&str; 10] = [
+    "deset",
+    "jedanaest",
+    "dvanaest",
+    "trinaest",
+    "četrnaest",
+    "petnaest",
+    "šesnaest",
+    "sedamnaest",
+    "osamnaest",
+    "devetnaest",
+];
+const TENS: [&str; 10] = [
+    "",
+    "",
+    "dvadeset",
+    "trideset",
+    "četrdeset",
+    "pedeset",
+    "šezdeset",
+    "sedamdeset",
+    "osamdeset",
+    "devedeset",
+];
+const HUNDREDS: [&str; 10] = [
+    "",
+    "sto",
+    "dvesta",
+    "trista",
+    "četiristo",
+    "petsto",
+    "šeststo",
+    "sedamsto",
+    "osamsto",
+    "devetsto",
+];
+
+/// Powers of thousand, whether they are feminine, and their forms on
+/// their own, after one, two to four and other numbers
+const SCALES: &[(u64, bool, [&str; 4])] = &[
+    (1_000_000_000, true, ["milijarda", "milijarda", "milijarde", "milijardi"]),
+    (1_000_000, false, ["milion", "milion", "miliona", "miliona"]),
+    (1_000, true, ["hiljadu", "hiljada", "hiljade", "hiljada"]),
+];
+
+/// Largest number which is read as a whole, while longer numbers are
+/// read digit by digit
+const MAX_NUMBER: u64 = 999_999_999_999;
+
+/// Push words of a number below thousand, with feminine forms of one and
+/// two (ie. `jedna`, `dve`)
+fn hundreds(number: u64, feminine: bool, words: &mut Vec<&'static str>) {
+    let (hundreds, tens, units) = (number / 100, number / 10 % 10, number % 10);
+    if hundreds > 0 {
+        words.push(HUNDREDS[hundreds as usize]);
+    }
+    match tens {
+        1 => words.push(TEENS[units as usize]),
+        _ => {
+            if tens > 1 {
+                words.push(TENS[tens as usize]);
+            }
+            match units {
+                0 => {}
+                1 if feminine => words.push("jedna"),
+                2 if feminine => words.push("dve"),
+                _ => words.push(UNITS[units as usize]),
+            }
+        }
+    }
+}
+
+/// Returns Serbian (ekavian Latin) cardinal number of at most
+/// [`MAX_NUMBER`]
+fn cardinal(mut number: u64) -> String {
+    if number == 0 {
+        return UNITS[0].to_owned();
+    }
+    let mut words = Vec::new();
+    for (scale, feminine, forms) in SCALES {
+        let count = number / scale;
+        number %= scale;
+        if count == 0 {
+            continue;
+        }
+        // Thousand and million are read without one
+        if count > 1 {
+            hundreds(count, *feminine, &mut words);
+        }
+        words.push(match (count, count % 100, count % 10) {
+            (1, _, _) => forms[0],
+            (_, 11..=14, _) => forms[3],
+            (_, _, 1) => forms[1],
+            (_, _, 2..=4) => forms[2],
+            _ => forms[3],
+        });
+    }
+    hundreds(number, false, &mut words);
+    words.join(" ")
+}
+
+/// Returns words of digits of `digits`, read one by one
+fn digits(digits: &str) -> String {
+    digits
+        .bytes()
+        .map(|d| UNITS[(d - b'0') as usize])
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Returns words of a number matched by [`NUMBER`]
+fn number(text: &str) -> String {
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(text) => ("minus ", text),
+        None => ("", text),
+    };
+    let (integer, fraction) = match text.split_once(',') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text, None),
+    };
+    let integer = integer.replace('.', "");
+    let mut words = match integer.parse::<u64>() {
+        Ok(n) if n <= MAX_NUMBER && (n == 0 || !integer.starts_with('0')) => cardinal(n),
+        _ => digits(&integer),
+    };
+    if let Some(fraction) = fraction {
+        words.push_str(" zapeta ");
+        words.push_str(&match fraction.parse::<u64>() {
+            Ok(n) if n <= MAX_NUMBER && !fraction.starts_with('0') => cardinal(n),
+            _ => digits(fraction),
+        });
+    }
+    format!("{}{}", sign, words)
+}
+
+/// Optional steps of speech normalization, which expand text into words
+/// as it is read
+///
+/// # Example
+///
+/// ```
+/// use translitrs::Expand;
+/// let expand: Expand = "numbers".parse().unwrap();
+/// assert!(expand.numbers && !expand.abbreviations);
+/// assert_eq!(Expand::ALL.to_string(), "numbers,abbreviations");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Expand {
+    /// Numbers are read as words (ie. `21` as `dvadeset jedan`)
+    pub numbers: bool,
+    /// Common abbreviations and symbols are read as words (ie. `npr.` as
+    /// `na primer`, `%` as `odsto`)
+    pub abbreviations: bool,
+}
+
+impl Expand {
+    /// Expand both numbers and abbreviations
+    pub const ALL: Expand = Expand {
+        numbers: true,
+        abbreviations: true,
+    };
+}
+
+impl str::FromStr for Expand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut expand = Expand::default();
+        for step in s.split(',').map(str::trim) {
+            match step {
+                "all" => expand = Expand::ALL,
+                "none" => {}
+                "numbers" => expand.numbers = true,
+                "abbreviations" => expand.abbreviations = true,
+                _ => return Err(Error::UnknownExpansion(step.to_owned())),
+            }
+        }
+        Ok(expand)
+    }
+}
+
+impl fmt::Display for Expand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match (self.numbers, self.abbreviations) {
+            (true, true) => "numbers,abbreviations",
+            (true, false) => "numbers",
+            (false, true) => "abbreviations",
+            (false, false) => "none",
+        })
+    }
+}
+
+impl Transliterator {
+    /// Try normalizing arbitrary text for speech synthesis and recognition
+    /// datasets. Text is transliterated into the target script, numbers
+    /// and abbreviations are optionally expanded into words in the target
+    /// script (and dialect), other symbols and punctuation are removed,
+    /// and whitespace is collapsed into single spaces. Each line is
+    /// normalized separately, so lines of transcripts stay aligned with
+    /// their recordings. The same text is always normalized the same way.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to normalize
+    /// * `expand` - Optional steps which expand text into words
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Charset, Expand, Transliterator};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let text = "Dr Petrović je  kupio 21 knjigu, tj. 3,5% više!";
+    /// assert_eq!(
+    ///     t.normalize_speech(text, Expand::default()).unwrap(),
+    ///     "Др Петровић је купио 21 књигу тј 3,5 више"
+    /// );
+    /// assert_eq!(
+    ///     t.normalize_speech(text, Expand::ALL).unwrap(),
+    ///     "Доктор Петровић је купио двадесет један књигу то јест три запета пет одсто више"
+    /// );
+    /// ```
+    pub fn normalize_speech<S: AsRef<str>>(
+        &self,
+        input: S,
+        expand: Expand,
+    ) -> Result<String, Error> {
+        let text = self.process(input)?;
+        // Expanded words are written in Latin, and converted into the
+        // target script and dialect
+        let cyrillic = match self.into {
+            Charset::Cyrillic => {
+                Some(Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false))
+            }
+            _ => None,
+        };
+        let script = |words: &str| -> Result<String, Error> {
+            #[cfg(feature = "dialect")]
+            let words = match &self.dialect {
+                Some(dialect) => dialect.process(words),
+                None => words.to_owned(),
+            };
+            #[cfg(not(feature = "dialect"))]
+            let words = words.to_owned();
+            match &cyrillic {
+                Some(cyrillic) => cyrillic.process(words),
+                None => Ok(words),
+            }
+        };
+        let mut text = text;
+        if expand.abbreviations {
+            let mut abbreviations = Vec::with_capacity(ABBREVIATIONS.len());
+            for (abbreviation, words) in ABBREVIATIONS {
+                abbreviations.push((script(abbreviation)?.to_lowercase(), script(words)?));
+            }
+            let pattern = abbreviations
+                .iter()
+                .map(|(abbreviation, _)| {
+                    let mut pattern = regex::escape(abbreviation);
+                    if abbreviation.starts_with(char::is_alphabetic) {
+                        pattern.insert_str(0, r"\b");
+                    }
+                    if abbreviation.ends_with(char::is_alphabetic) {
+                        pattern.push_str(r"\b");
+                    }
+                    pattern
+                })
+                .collect::<Vec<String>>()
+                .join("|");
+            let pattern = Regex::new(&format!("(?i){}", pattern)).map_err(Error::InvalidPattern)?;
+            text = pattern
+                .replace_all(&text, |abbreviation: &Captures| {
+                    let matched = &abbreviation[0];
+                    let lowercase = matched.to_lowercase();
+                    let words = abbreviations
+                        .iter()
+                        .find(|(a, _)| *a == lowercase)
+                        .map_or(matched, |(_, words)| words.as_str());
+                    // Symbols are separate words
+                    let mut words = match matched.starts_with(char::is_alphabetic) {
+                        true => words.to_owned(),
+                        false => format!(" {} ", words),
+                    };
+                    if matched.starts_with(char::is_uppercase) {
+                        let first = words.chars().next().unwrap_or_default();
+                        words.replace_range(..first.len_utf8(), &first.to_uppercase().to_string());
+                    }
+                    words
+                })
+                .into_owned();
+        }
+        if expand.numbers {
+            let mut output = String::with_capacity(text.len());
+            let mut last = 0;
+            for found in NUMBER.find_iter(&text) {
+                let mut start = found.start();
+                // Hyphens between words are not signs
+                let before = text[..start].chars().next_back();
+                if text[start..].starts_with('-') && before.map_or(false, char::is_alphanumeric) {
+                    start += 1;
+                }
+                output.push_str(&text[last..start]);
+                output.push(' ');
+                output.push_str(&script(&number(&text[start..found.end()]))?);
+                output.push(' ');
+                last = found.end();
+            }
+            output.push_str(&text[last..]);
+            text = output;
+        }
+        let mut output = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let chars = content.chars().collect::<Vec<char>>();
+            let mut words = String::with_capacity(content.len());
+            for (i, &c) in chars.iter().enumerate() {
+                // Separators of numbers which are not expanded are kept
+                let separator = matches!(c, '.' | ',')
+                    && i > 0
+                    && chars[i - 1].is_ascii_digit()
+                    && chars.get(i + 1).map_or(false, char::is_ascii_digit);
+                match c.is_alphanumeric() || c.is_whitespace() || separator {
+                    true => words.push(c),
+                    false => words.push(' '),
+                }
+            }
+            output.push_str(&words.split_whitespace().collect::<Vec<&str>>().join(" "));
+            output.push_str(&line[content.len()..]);
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal() {
+        for (n, expected) in [
+            (0, "nula"),
+            (11, "jedanaest"),
+            (21, "dvadeset jedan"),
+            (102, "sto dva"),
+            (1_000, "hiljadu"),
+            (2_000, "dve hiljade"),
+            (12_345, "dvanaest hiljada trista četrdeset pet"),
+            (21_000, "dvadeset jedna hiljada"),
+            (1_000_001, "milion jedan"),
+            (3_000_000, "tri miliona"),
+            (2_500_000_000, "dve milijarde petsto miliona"),
+        ] {
+            assert_eq!(cardinal(n), expected);
+        }
+        assert_eq!(number("-1.250,05"), "minus hiljadu dvesta pedeset zapeta nula pet");
+        assert_eq!(number("0042"), "nula nula četiri dva");
+    }
+
+    #[test]
+    fn test_normalize_speech() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let text = "Улица бр. 12-14, стан 3\r\n\n„Здраво,  свете!“ — рекао је 2.000 пута.\n";
+        assert_eq!(
+            t.normalize_speech(text, Expand::default())?,
+            "Ulica br 12 14 stan 3\r\n\nZdravo svete rekao je 2.000 puta\n"
+        );
+        assert_eq!(
+            t.normalize_speech(text, Expand::ALL)?,
+            "Ulica broj dvanaest četrnaest stan tri\r\n\nZdravo svete rekao je dve hiljade puta\n"
+        );
+        assert_eq!("all".parse::<Expand>()?, Expand::ALL);
+        assert!("words".parse::<Expand>().is_err());
+        Ok(())
+    }
+}
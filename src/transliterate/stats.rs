@@ -0,0 +1,91 @@
+use super::{Decision, Error, Transliterator};
+
+/// Counts of what happened to words of a transliterated text
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Number of words
+    pub words: usize,
+    /// Words which are transliterated, including ones with a digraph
+    /// exception applied
+    pub transliterated: usize,
+    /// Words which are transliterated with a digraph exception applied
+    pub exceptions: usize,
+    /// Words which are left intact (ie. foreign words, links, model names)
+    pub skipped: usize,
+    /// Letters of transliterated words
+    pub characters: usize,
+    /// Non-fatal issues, as returned by
+    /// [`Transliterator::process_with_warnings`]
+    pub warnings: usize,
+}
+
+impl Transliterator {
+    /// Try transliterating arbitrary text, returning counts of transliterated
+    /// and skipped words, letters, applied exceptions and warnings instead
+    /// of the transliterated text. Text which is already converted is not
+    /// counted. Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to transliterate
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let stats = t.stats("Konjugacija quiz, reč").unwrap();
+    /// assert_eq!((stats.words, stats.transliterated, stats.exceptions), (3, 2, 1));
+    /// assert_eq!((stats.skipped, stats.characters, stats.warnings), (1, 14, 1));
+    /// ```
+    pub fn stats<S: AsRef<str>>(&self, input: S) -> Result<Stats, Error> {
+        let input = input.as_ref();
+        let mut stats = Stats::default();
+        let mut warnings = Vec::new();
+        self.walk(input, &mut String::new(), &mut |output, span, word| {
+            if let Some(word) = word {
+                let original = &input[span.clone()];
+                stats.words += 1;
+                match word.decision {
+                    Decision::Transliterated | Decision::Exception => {
+                        stats.transliterated += 1;
+                        stats.characters += original.chars().filter(|c| c.is_alphabetic()).count();
+                    }
+                    _ => stats.skipped += 1,
+                }
+                if word.decision == Decision::Exception {
+                    stats.exceptions += 1;
+                }
+                self.check_word(original, span, &output[word.output], word.decision, &mut warnings);
+                output.clear();
+            }
+        })?;
+        stats.warnings = warnings.len();
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_stats() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false)
+            .with_skip_converted(Some(0.0));
+        assert_eq!(t.stats("")?, Stats::default());
+        assert_eq!(
+            t.stats("Zdravo {ime}, example.com XIV Mоскva\nЋирилица\n")?,
+            Stats {
+                words: 5,
+                transliterated: 2,
+                exceptions: 0,
+                skipped: 3,
+                characters: 9,
+                warnings: 2,
+            }
+        );
+        Ok(())
+    }
+}
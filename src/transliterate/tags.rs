@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+
+use super::{Archaic, Charset, Direction, Error, Language};
+#[cfg(feature = "dialect")]
+use crate::dialect::Dialect;
+
+/// Language, script and standard resolved from a BCP 47 language tag
+#[derive(Debug, PartialEq)]
+pub struct Tag {
+    pub language: Language,
+    pub charset: Charset,
+    pub archaic: Archaic,
+    #[cfg(feature = "dialect")]
+    pub dialect: Option<Dialect>,
+}
+
+/// Script used when language tag has no script subtag (CLDR likely subtags)
+fn default_charset(language: Language) -> Charset {
+    match language {
+        Language::Serbian => Charset::Cyrillic,
+        Language::Croatian | Language::Bosnian => Charset::Latin,
+        #[cfg(feature = "kazakh")]
+        Language::Kazakh => Charset::Cyrillic,
+        #[cfg(feature = "uzbek")]
+        Language::Uzbek => Charset::Latin,
+    }
+}
+
+/// Parse a BCP 47 language tag (ie. `sr-Latn`, `sr-Cyrl-RS-ijekavsk`).
+/// Subtags are case insensitive. Region subtags are accepted and ignored.
+///
+/// Supported variant and private use subtags:
+/// * `ekavsk`, `ijekavsk` - dialect of Serbian, Croatian and Bosnian (with
+///   the `dialect` feature)
+/// * `x-unicode` - Latin script with single character digraphs
+pub fn parse(tag: &str) -> Result<Tag, Error> {
+    let unsupported = |reason| Error::UnsupportedTag(tag.to_owned(), reason);
+    let lowercase = tag.to_ascii_lowercase();
+    let mut subtags = lowercase.split(['-', '_']);
+    let language = match subtags.next() {
+        Some("sr" | "srp") => Language::Serbian,
+        Some("hr" | "hrv") => Language::Croatian,
+        Some("bs" | "bos") => Language::Bosnian,
+        #[cfg(feature = "kazakh")]
+        Some("kk" | "kaz") => Language::Kazakh,
+        #[cfg(feature = "uzbek")]
+        Some("uz" | "uzb") => Language::Uzbek,
+        _ => return Err(unsupported("unsupported language")),
+    };
+    let mut charset: Option<Charset> = None;
+    let mut archaic = Archaic::Ignore;
+    #[cfg(feature = "dialect")]
+    let mut dialect: Option<Dialect> = None;
+    let mut unicode = false;
+    while let Some(subtag) = subtags.next() {
+        match subtag {
+            "latn" if charset.is_none() => charset = Some(Charset::Latin),
+            "cyrl" if charset.is_none() => charset = Some(Charset::Cyrillic),
+            // Old Church Slavonic variant of Cyrillic
+            "cyrs" if charset.is_none() => {
+                charset = Some(Charset::Cyrillic);
+                archaic = Archaic::Modernize;
+            }
+            #[cfg(feature = "dialect")]
+            "ekavsk" => dialect = Some(Dialect::Ekavian),
+            #[cfg(feature = "dialect")]
+            "ijekavsk" => dialect = Some(Dialect::Ijekavian),
+            "x" => match subtags.next() {
+                Some("unicode") => unicode = true,
+                _ => return Err(unsupported("unsupported private use subtag")),
+            },
+            // Region subtag
+            s if s.len() == 2 && s.chars().all(|c| c.is_ascii_alphabetic()) => {}
+            s if s.len() == 3 && s.chars().all(|c| c.is_ascii_digit()) => {}
+            s if s.len() == 4 && s.chars().all(|c| c.is_ascii_alphabetic()) => {
+                return Err(unsupported("unsupported script"))
+            }
+            _ => return Err(unsupported("unsupported subtag")),
+        }
+    }
+    #[cfg(feature = "dialect")]
+    if dialect.is_some()
+        && !matches!(language, Language::Serbian | Language::Croatian | Language::Bosnian)
+    {
+        return Err(unsupported("dialect is not used by this language"));
+    }
+    let charset = match (charset.unwrap_or_else(|| default_charset(language)), unicode) {
+        (Charset::Latin, true) => Charset::LatinUnicode,
+        (_, true) => return Err(unsupported("unicode standard requires Latin script")),
+        (charset, false) => charset,
+    };
+    Ok(Tag {
+        language,
+        charset,
+        archaic,
+        #[cfg(feature = "dialect")]
+        dialect,
+    })
+}
+
+/// Returns direction in which stored content should be transliterated for
+/// a client, from its `Accept-Language` HTTP header (ie.
+/// `sr-Latn, en;q=0.8`), so content is served in the script it prefers.
+/// Language ranges are tried in order of their weights, and the first one
+/// with a supported language and script is used, so `sr` and `sr-RS`
+/// prefer Cyrillic and `sr-Latn` and `hr` prefer Latin. Returns `None` if
+/// no range has a supported language, ie. if a client accepts only other
+/// languages or any language (`*`).
+///
+/// # Example
+///
+/// ```
+/// use translitrs::{negotiate_script, Charset, Direction};
+/// let cyr2lat = Direction { from: Charset::Cyrillic, into: Charset::Latin };
+/// assert_eq!(negotiate_script("en-US, sr-Latn-RS;q=0.8, sr;q=0.5"), Some(cyr2lat));
+/// assert_eq!(negotiate_script("de, *;q=0.1"), None);
+/// ```
+pub fn negotiate_script(accept_language: &str) -> Option<Direction> {
+    let mut ranges = Vec::new();
+    for range in accept_language.split(',') {
+        let mut parameters = range.split(';').map(str::trim);
+        let tag = parameters.next().unwrap_or_default();
+        let weight =
+            match parameters.find_map(|p| p.strip_prefix("q=").or_else(|| p.strip_prefix("Q="))) {
+                Some(weight) => match weight.parse::<f32>() {
+                    Ok(weight) if (0.0..=1.0).contains(&weight) => weight,
+                    _ => continue,
+                },
+                None => 1.0,
+            };
+        if weight > 0.0 && !tag.is_empty() {
+            ranges.push((tag, weight));
+        }
+    }
+    // Ranges with the same weight keep their order
+    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+    ranges.into_iter().find_map(|(tag, _)| {
+        let into = parse(tag).ok()?.charset;
+        let from = match into {
+            Charset::Cyrillic => Charset::Latin,
+            _ => Charset::Cyrillic,
+        };
+        Some(Direction { from, into })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let tag = |language, charset, archaic| Tag {
+            language,
+            charset,
+            archaic,
+            #[cfg(feature = "dialect")]
+            dialect: None,
+        };
+        for (text, expected) in [
+            ("sr", tag(Language::Serbian, Charset::Cyrillic, Archaic::Ignore)),
+            ("sr-Latn", tag(Language::Serbian, Charset::Latin, Archaic::Ignore)),
+            ("sr_latn_RS", tag(Language::Serbian, Charset::Latin, Archaic::Ignore)),
+            (
+                "sr-Latn-x-unicode",
+                tag(Language::Serbian, Charset::LatinUnicode, Archaic::Ignore),
+            ),
+            ("sr-Cyrs", tag(Language::Serbian, Charset::Cyrillic, Archaic::Modernize)),
+            #[cfg(feature = "dialect")]
+            (
+                "sr-Cyrl-ME-ijekavsk",
+                Tag {
+                    dialect: Some(Dialect::Ijekavian),
+                    ..tag(Language::Serbian, Charset::Cyrillic, Archaic::Ignore)
+                },
+            ),
+            ("hr", tag(Language::Croatian, Charset::Latin, Archaic::Ignore)),
+            #[cfg(feature = "kazakh")]
+            ("kk-KZ", tag(Language::Kazakh, Charset::Cyrillic, Archaic::Ignore)),
+            #[cfg(feature = "uzbek")]
+            ("uz-Cyrl-UZ", tag(Language::Uzbek, Charset::Cyrillic, Archaic::Ignore)),
+            #[cfg(feature = "uzbek")]
+            ("uz-419", tag(Language::Uzbek, Charset::Latin, Archaic::Ignore)),
+        ] {
+            assert_eq!(parse(text).unwrap(), expected);
+        }
+        for text in [
+            "",
+            "en-Latn",
+            "sr-Grek",
+            "sr-Latn-Cyrl",
+            "kk-ekavsk",
+            "sr-Cyrl-x-unicode",
+            "sr-x-foo",
+        ] {
+            assert!(matches!(parse(text), Err(Error::UnsupportedTag(_, _))));
+        }
+    }
+
+    #[test]
+    fn test_negotiate_script() {
+        let lat2cyr = Some(Direction {
+            from: Charset::Latin,
+            into: Charset::Cyrillic,
+        });
+        let cyr2lat = Some(Direction {
+            from: Charset::Cyrillic,
+            into: Charset::Latin,
+        });
+        for (header, expected) in [
+            ("sr", lat2cyr),
+            ("sr-RS", lat2cyr),
+            ("sr-Latn", cyr2lat),
+            ("SR-latn-rs", cyr2lat),
+            ("sr-ME;q=0.5, hr;q=0.9", cyr2lat),
+            ("sr-Latn;q=0.5, sr-Cyrl;q=0.5", cyr2lat),
+            ("en-US,en;q=0.9,sr;q=0.8", lat2cyr),
+            ("sr-Latn;q=0, sr;q=0.1", lat2cyr),
+            ("sr-Latn;q=2, bs", cyr2lat),
+            ("fr, *", None),
+            ("", None),
+        ] {
+            assert_eq!(negotiate_script(header), expected, "{}", header);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::cmp;
+
+use super::{Error, Transliterator};
+
+/// Latin digraphs, which are not split when a word is cut
+const DIGRAPHS: &[&str] = &["lj", "nj", "dž", "dj"];
+
+/// Returns the longest prefix of `text` with at most `length` bytes
+fn floor_boundary(text: &str, length: usize) -> usize {
+    let mut end = cmp::min(length, text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+impl Transliterator {
+    /// Try transliterating the longest beginning of arbitrary text whose
+    /// output fits in `max_output_bytes` (ie. a message limit of a chat
+    /// platform), returning the output and the number of bytes of input
+    /// which were consumed. Text is cut between words, so the output is
+    /// the beginning of the output of [`Transliterator::process`], and the
+    /// rest of the text can be transliterated starting at the returned
+    /// position. A first word which doesn't fit on its own is cut between
+    /// letters, without splitting digraphs or characters. Nothing is
+    /// consumed only if the first letter doesn't fit.
+    /// Fails if provided string is not valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Text to transliterate
+    /// * `max_output_bytes` - Longest output in bytes
+    /// # Examples
+    ///
+    /// ```
+    /// use translitrs::{Transliterator, Charset};
+    ///
+    /// let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+    /// let input = "Dobar dan, svete";
+    /// let (output, consumed) = t.process_truncated(input, 12).unwrap();
+    /// assert_eq!((output.as_str(), consumed), ("Добар ", 6));
+    /// let (output, consumed) = t.process_truncated(&input[consumed..], 12).unwrap();
+    /// assert_eq!((output.as_str(), consumed), ("дан, ", 5));
+    /// ```
+    pub fn process_truncated<S: AsRef<str>>(
+        &self,
+        input: S,
+        max_output_bytes: usize,
+    ) -> Result<(String, usize), Error> {
+        let input = input.as_ref();
+        let mut output = String::with_capacity(cmp::min(input.len(), max_output_bytes));
+        // Longest output which fits and input it was transliterated from
+        let (mut fits, mut consumed) = (0, 0);
+        let mut full = false;
+        self.walk(input, &mut output, &mut |output, span, word| {
+            if word.is_none() && !full {
+                output.push_str(&input[span.clone()]);
+            }
+            if !full && output.len() <= max_output_bytes {
+                fits = output.len();
+                consumed = span.end;
+            } else {
+                full = true;
+                output.truncate(fits);
+            }
+        })?;
+        output.truncate(fits);
+        if consumed > 0 || input.is_empty() {
+            return Ok((output, consumed));
+        }
+        // Spaces are copied as they are
+        let word = input.split(char::is_whitespace).next().unwrap_or_default();
+        if word.is_empty() {
+            let end = floor_boundary(input, max_output_bytes);
+            return Ok((input[..end].to_owned(), end));
+        }
+        let mut longest = (String::new(), 0);
+        for end in word.char_indices().map(|(n, _)| n).skip(1) {
+            let pair = word[..end]
+                .chars()
+                .last()
+                .into_iter()
+                .chain(word[end..].chars().next())
+                .collect::<String>()
+                .to_lowercase();
+            if DIGRAPHS.contains(&pair.as_str()) {
+                continue;
+            }
+            let converted = self.process(&word[..end])?;
+            if converted.len() > max_output_bytes {
+                break;
+            }
+            longest = (converted, end);
+        }
+        Ok(longest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transliterate::Charset;
+
+    #[test]
+    fn test_process_truncated() -> Result<(), Error> {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let input = "Njegoš je pisao  pesme";
+        let full = t.process(input)?;
+        for max in [0, 1, 3, 4, 12, 13, 14, 20, 40, 100] {
+            let mut chunks = Vec::new();
+            let mut rest = input;
+            while !rest.is_empty() {
+                let (output, consumed) = t.process_truncated(rest, max)?;
+                assert!(output.len() <= max);
+                if consumed == 0 {
+                    break;
+                }
+                chunks.push(output);
+                rest = &rest[consumed..];
+            }
+            if max >= 2 {
+                assert!(rest.is_empty());
+            }
+            if max >= 12 {
+                assert_eq!(chunks.concat(), full);
+            }
+        }
+        assert_eq!(t.process_truncated(input, 13)?, ("Његош ".to_owned(), 8));
+        // Long words are cut between letters, but not inside digraphs
+        assert_eq!(t.process_truncated(input, 3)?, ("Њ".to_owned(), 2));
+        assert_eq!(t.process_truncated(input, 7)?, ("Њег".to_owned(), 4));
+        assert_eq!(t.process_truncated("ljiljan", 5)?, ("љи".to_owned(), 3));
+        assert_eq!(t.process_truncated(input, 1)?, (String::new(), 0));
+        assert_eq!(t.process_truncated("     a", 2)?, ("  ".to_owned(), 2));
+        assert_eq!(t.process_truncated("", 0)?, (String::new(), 0));
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use std::borrow::Cow;
+
+/// Characters commonly typed in place of `ʻ` and `ʼ`
+const APOSTROPHES: &[char] = &[
+    '\'', '`', '\u{b4}', '\u{2bb}', '\u{2bc}', '\u{2018}', '\u{2019}',
+];
+
+/// Replace apostrophe-like characters with `ʻ` in letters `oʻ` and `gʻ`,
+/// and with `ʼ` (tutuq belgisi) between other letters. Apostrophes used as
+/// quotation marks are left intact, so an apostrophe after a final `o` or
+/// `g` is `ʻ` only if no quotation mark before it opens a quote (ie.
+/// `togʻ`, but `'kino'`).
+pub fn normalize_apostrophes(word: &str) -> Cow<'_, str> {
+    if !word.contains(APOSTROPHES) {
+        return Cow::Borrowed(word);
+    }
+    let chars = word.chars().collect::<Vec<char>>();
+    let mut output = String::with_capacity(word.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if !APOSTROPHES.contains(&c) || i == 0 {
+            output.push(c);
+            continue;
+        }
+        let previous = chars[i - 1];
+        let next = chars.get(i + 1).copied();
+        let letter = matches!(next, Some(n) if n.is_alphabetic());
+        // Quotation mark which is not after a letter opens a quote
+        let quoted = chars[..i]
+            .iter()
+            .enumerate()
+            .any(|(j, q)| APOSTROPHES.contains(q) && (j == 0 || !chars[j - 1].is_alphabetic()));
+        if matches!(previous, 'o' | 'O' | 'g' | 'G') && (letter || !quoted) {
+            output.push('\u{2bb}');
+        } else if previous.is_alphabetic() && letter {
+            output.push('\u{2bc}');
+        } else {
+            output.push(c);
+        }
+    }
+    Cow::Owned(output)
+}
+
+/// Cyrillic `е` is written `ye` and Latin `e` is written `э` at the
+/// beginning of a word
+pub fn fix_initial(source: &str, output: &mut String, from_cyrillic: bool) {
+    let skip = |s: &str| s.len() - s.trim_start_matches(|c: char| !c.is_alphabetic()).len();
+    let (source, start) = (&source[skip(source)..], skip(output));
+    let mut letters = source.chars();
+    let (first, second) = (letters.next(), letters.next());
+    let upper = matches!(second, Some(c) if c.is_uppercase());
+    // Number of output characters to replace and their replacement
+    let replacement = match (from_cyrillic, first, second) {
+        (true, Some('е'), _) => Some((1, "ye")),
+        (true, Some('Е'), _) if upper => Some((1, "YE")),
+        (true, Some('Е'), _) => Some((1, "Ye")),
+        (false, Some('y'), Some('e' | 'E')) => Some((2, "е")),
+        (false, Some('Y'), Some('e' | 'E')) => Some((2, "Е")),
+        (false, Some('e'), _) => Some((1, "э")),
+        (false, Some('E'), _) => Some((1, "Э")),
+        _ => None,
+    };
+    if let Some((count, replacement)) = replacement {
+        let length = output[start..]
+            .chars()
+            .take(count)
+            .map(char::len_utf8)
+            .sum::<usize>();
+        output.replace_range(start..start + length, replacement);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_apostrophes() {
+        for (text, expected) in [
+            ("o'zbek", "oʻzbek"),
+            ("O‘zbekiston", "Oʻzbekiston"),
+            ("g`alaba", "gʻalaba"),
+            ("ma'no", "maʼno"),
+            ("ma’no", "maʼno"),
+            ("'salom'", "'salom'"),
+            ("'kino'", "'kino'"),
+            ("‘radio’", "‘radio’"),
+            ("tog'", "togʻ"),
+            ("'tog'ga'", "'togʻga'"),
+            ("\"bog'\"", "\"bogʻ\""),
+            ("salom", "salom"),
+        ] {
+            assert_eq!(normalize_apostrophes(text), expected);
+        }
+    }
+
+    #[test]
+    fn test_fix_initial() {
+        for (source, output, from_cyrillic, expected) in [
+            ("ер", "er", true, "yer"),
+            ("Ер", "Er", true, "Yer"),
+            ("ЕР", "ER", true, "YER"),
+            ("«ер»", "«er»", true, "«yer»"),
+            ("yer", "йер", false, "ер"),
+            ("YER", "ЙЕР", false, "ЕР"),
+            ("Yer", "Йер", false, "Ер"),
+            ("ekran", "екран", false, "экран"),
+            ("Ekran", "Екран", false, "Экран"),
+            ("bet", "бет", false, "бет"),
+        ] {
+            let mut output = output.to_owned();
+            fix_initial(source, &mut output, from_cyrillic);
+            assert_eq!(output, expected);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::ops::Range;
+
+use super::{Charset, Decision, Transliterator};
+
+/// Number of characters of words which are unusually long (ie. hashes or
+/// text without spaces)
+const LONG_WORD: usize = 64;
+
+/// Kind of a non-fatal issue found while transliterating
+#[derive(Clone, Debug, PartialEq)]
+pub enum WarningKind {
+    /// Letter of the source script which has no replacement, so its word
+    /// is left intact
+    Unmapped(char),
+    /// Word mixes Latin and Cyrillic letters (ie. `Mоскva`)
+    MixedScript,
+    /// Word is unusually long
+    LongWord,
+    /// Word looks like a template placeholder (ie. `{name}`, `%s`), but
+    /// was transliterated anyway
+    Placeholder,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unmapped(c) => write!(f, "unmapped character {}", c),
+            Self::MixedScript => write!(f, "word mixes Latin and Cyrillic letters"),
+            Self::LongWord => write!(f, "unusually long word"),
+            Self::Placeholder => write!(f, "placeholder was transliterated"),
+        }
+    }
+}
+
+/// Non-fatal issue found in a word of transliterated text
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    /// Byte range of the word in input text
+    pub span: Range<usize>,
+    pub kind: WarningKind,
+}
+
+impl Transliterator {
+    /// Append warnings about `word` at `span`, which was transliterated
+    /// into `output` because of `decision`
+    pub(super) fn check_word(
+        &self,
+        word: &str,
+        span: Range<usize>,
+        output: &str,
+        decision: Decision,
+        warnings: &mut Vec<Warning>,
+    ) {
+        lazy_static! {
+            static ref PLACEHOLDER: Regex =
+                Regex::new(r"\{\{[^{}]*\}\}|\$?\{\w+\}|%(\(\w+\))?[sdif]|\$\w+").unwrap();
+        }
+        let mut warn = |kind| {
+            warnings.push(Warning {
+                span: span.clone(),
+                kind,
+            })
+        };
+        let from = self.from.script();
+        if decision == Decision::Foreign && from != self.into.script() {
+            let mut unmapped: Vec<char> = Vec::new();
+            for c in word.chars() {
+                if Charset::of(c) == Some(from)
+                    && !unmapped.contains(&c)
+                    && self
+                        .index
+                        .binary_search_by_key(&c, |(first, _)| *first)
+                        .is_err()
+                {
+                    unmapped.push(c);
+                }
+            }
+            unmapped
+                .into_iter()
+                .for_each(|c| warn(WarningKind::Unmapped(c)));
+        }
+        if word.chars().any(|c| Charset::of(c) == Some(Charset::Latin))
+            && word
+                .chars()
+                .any(|c| Charset::of(c) == Some(Charset::Cyrillic))
+        {
+            warn(WarningKind::MixedScript);
+        }
+        if word.chars().count() > LONG_WORD {
+            warn(WarningKind::LongWord);
+        }
+        if matches!(decision, Decision::Transliterated | Decision::Exception)
+            && word != output
+            && PLACEHOLDER.is_match(word)
+        {
+            warn(WarningKind::Placeholder);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warnings() {
+        let t = Transliterator::new(Charset::Latin, Charset::Cyrillic, false, false, false);
+        let text = format!("Zdravo {{ime}}, %s quiz Mоскva 123 {}", "a".repeat(65));
+        let (output, warnings) = t.process_with_warnings(&text).unwrap();
+        assert_eq!(output, t.process(&text).unwrap());
+        assert_eq!(
+            warnings,
+            vec![
+                Warning {
+                    span: 7..13,
+                    kind: WarningKind::Placeholder
+                },
+                Warning {
+                    span: 14..16,
+                    kind: WarningKind::Placeholder
+                },
+                Warning {
+                    span: 17..21,
+                    kind: WarningKind::Unmapped('q')
+                },
+                Warning {
+                    span: 22..31,
+                    kind: WarningKind::MixedScript
+                },
+                Warning {
+                    span: 36..101,
+                    kind: WarningKind::LongWord
+                },
+            ]
+        );
+        // Letters of the target script are not unmapped
+        let t = Transliterator::new(Charset::Cyrillic, Charset::Latin, false, false, false);
+        let (_, warnings) = t.process_with_warnings("Ћао quiz ёлка").unwrap();
+        assert_eq!(
+            warnings,
+            vec![Warning {
+                span: 12..20,
+                kind: WarningKind::Unmapped('ё')
+            }]
+        );
+        // Characters are checked only for conversion between scripts
+        let t = Transliterator::new(Charset::Latin, Charset::LatinUnicode, false, false, false);
+        assert!(t.process_with_warnings("quiz").unwrap().1.is_empty());
+    }
+}
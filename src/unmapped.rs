@@ -0,0 +1,258 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+use crate::transliterate::Error;
+
+/// Number of sample contexts kept for each character
+const SAMPLES: usize = 3;
+
+/// Number of characters kept on each side of a sample context
+const CONTEXT: usize = 12;
+
+/// Characters which always pass through: letters of both scripts,
+/// printable ASCII, whitespace, combining accents, apostrophes used as
+/// letters and byte order marks
+const ALWAYS: &str = r"\p{Latin}\p{Cyrillic}\x20-\x7E\s\u{0300}-\u{036F}\u{02BB}\u{02BC}\u{FEFF}";
+
+/// Character classes which pass through unless others are configured
+pub const DEFAULT_PASSTHROUGH: &[&str] = &["digits", "punctuation"];
+
+/// Named character classes and their regular expression classes
+const CLASSES: &[(&str, &str)] = &[
+    ("digits", r"\p{Nd}"),
+    ("punctuation", r"\pP"),
+    ("emoji", r"\p{Extended_Pictographic}\u{FE0F}\u{200D}\u{1F3FB}-\u{1F3FF}"),
+    ("currency", r"\p{Sc}"),
+    ("math", r"\p{Sm}"),
+    ("symbols", r"\pS"),
+];
+
+lazy_static! {
+    /// Characters outside of the default passthrough classes
+    pub(crate) static ref RE_UNMAPPED: Regex = unmapped(DEFAULT_PASSTHROUGH).unwrap();
+}
+
+/// Place where an unmapped character was found
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    /// Name of the input (ie. file path)
+    pub source: String,
+    /// Line number, starting from 1
+    pub line: usize,
+    /// Text around the character
+    pub context: String,
+}
+
+/// Unmapped character with its number of occurrences
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnmappedChar {
+    pub character: char,
+    pub count: usize,
+    /// First few places where the character was found
+    pub samples: Vec<Sample>,
+}
+
+/// Collects characters which are neither Latin nor Cyrillic letters nor
+/// pass through transliteration unchanged (ASCII, whitespace, digits,
+/// punctuation and combining accents). These are usually signs of
+/// encoding damage (ie. `�`) or unexpected symbols, and should be checked
+/// before converting many files at once.
+///
+/// # Example
+///
+/// ```
+/// use translitrs::UnmappedReport;
+///
+/// let mut report = UnmappedReport::new();
+/// report.add("notes.txt", "Ćao, „svete”!\nKo�ulja ☺");
+/// report.add("other.txt", "Ma�ina");
+/// let chars = report.chars();
+/// assert_eq!(chars.len(), 2);
+/// assert_eq!(chars[0].character, '�');
+/// assert_eq!(chars[0].count, 2);
+/// assert_eq!(chars[0].samples[0].line, 2);
+/// assert_eq!(chars[0].samples[0].context, "Ko�ulja ☺");
+/// ```
+#[derive(Clone, Debug)]
+pub struct UnmappedReport {
+    chars: BTreeMap<char, UnmappedChar>,
+    unmapped: Regex,
+}
+
+impl Default for UnmappedReport {
+    fn default() -> Self {
+        Self {
+            chars: BTreeMap::new(),
+            unmapped: RE_UNMAPPED.clone(),
+        }
+    }
+}
+
+/// Build regular expression matching characters outside of `classes`
+pub(crate) fn unmapped<S: AsRef<str>>(classes: &[S]) -> Result<Regex, Error> {
+    let mut passthrough = ALWAYS.to_owned();
+    for class in classes {
+        let class = class.as_ref();
+        if let Some((_, pattern)) = CLASSES.iter().find(|(name, _)| *name == class) {
+            passthrough.push_str(pattern);
+        } else if let Some(chars) = class.strip_prefix('[').and_then(|c| c.strip_suffix(']')) {
+            passthrough.extend(chars.chars().map(|c| regex::escape(&c.to_string())));
+        } else if class.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && Regex::new(&format!(r"\p{{{}}}", class)).is_ok()
+        {
+            passthrough.push_str(&format!(r"\p{{{}}}", class));
+        } else {
+            return Err(Error::UnknownClass(class.to_owned()));
+        }
+    }
+    Regex::new(&format!("[^{}]", passthrough)).map_err(Error::InvalidPattern)
+}
+
+impl UnmappedReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report characters outside of `classes` instead of the default
+    /// [`DEFAULT_PASSTHROUGH`] classes, for pipelines with more or less
+    /// tolerance for stray symbols. Letters, ASCII, whitespace and
+    /// combining accents always pass through. Fails if a class is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `classes` - Named classes (`digits`, `punctuation`, `emoji`,
+    ///   `currency`, `math` and `symbols`), Unicode general categories or
+    ///   scripts (ie. `So`, `Greek`) and sets of characters (ie. `[№°]`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use translitrs::UnmappedReport;
+    ///
+    /// let mut report = UnmappedReport::with_passthrough(&["currency", "So", "[№]"]).unwrap();
+    /// report.add("notes.txt", "№ 5: 10 € ☺ — 3 ≤ 4");
+    /// let chars = report.chars();
+    /// assert_eq!(chars.len(), 2);
+    /// assert_eq!(chars[0].character, '—');
+    /// assert_eq!(chars[1].character, '≤');
+    /// assert!(UnmappedReport::with_passthrough(&["emojis"]).is_err());
+    /// ```
+    pub fn with_passthrough<S: AsRef<str>>(classes: &[S]) -> Result<Self, Error> {
+        Ok(Self {
+            chars: BTreeMap::new(),
+            unmapped: unmapped(classes)?,
+        })
+    }
+
+    /// Collect unmapped characters from `text`
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Name of the input, used in samples
+    /// * `text` - Text to check
+    pub fn add(&mut self, source: &str, text: &str) {
+        for (number, line) in text.lines().enumerate() {
+            for m in self.unmapped.find_iter(line) {
+                let character = m.as_str().chars().next().unwrap_or_default();
+                let entry = self.chars.entry(character).or_insert(UnmappedChar {
+                    character,
+                    count: 0,
+                    samples: Vec::new(),
+                });
+                entry.count += 1;
+                if entry.samples.len() < SAMPLES {
+                    let start = line[..m.start()]
+                        .char_indices()
+                        .rev()
+                        .nth(CONTEXT - 1)
+                        .map_or(0, |(i, _)| i);
+                    let end = line[m.end()..]
+                        .char_indices()
+                        .nth(CONTEXT)
+                        .map_or(line.len(), |(i, _)| m.end() + i);
+                    entry.samples.push(Sample {
+                        source: source.to_owned(),
+                        line: number + 1,
+                        context: line[start..end].to_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns unmapped characters, most common first
+    pub fn chars(&self) -> Vec<&UnmappedChar> {
+        let mut chars = self.chars.values().collect::<Vec<&UnmappedChar>>();
+        chars.sort_by(|a, b| b.count.cmp(&a.count).then(a.character.cmp(&b.character)));
+        chars
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut report = UnmappedReport::new();
+        report.add(
+            "a.txt",
+            "\u{feff}Ћирилица, latinica, 1–2 “navodnici” Ṽ o‘zbek oʻzbek Ȁ\u{0300}\tkraj\n",
+        );
+        assert!(report.is_empty());
+
+        report.add("b.txt", "€ 10, € 20\nсадржај 日本 €€\nx€");
+        let chars = report.chars();
+        assert_eq!(
+            chars
+                .iter()
+                .map(|c| (c.character, c.count))
+                .collect::<Vec<_>>(),
+            vec![('€', 5), ('日', 1), ('本', 1)]
+        );
+        assert_eq!(chars[0].samples.len(), 3);
+        assert_eq!(chars[0].samples[0].context, "€ 10, € 20");
+        assert_eq!(chars[0].samples[2].line, 2);
+        assert_eq!(chars[1].samples[0].context, "садржај 日本 €€");
+
+        let mut long = UnmappedReport::new();
+        long.add("c.txt", &format!("{}☺{}", "а".repeat(20), "b".repeat(20)));
+        assert_eq!(
+            long.chars()[0].samples[0].context,
+            format!("{}☺{}", "а".repeat(12), "b".repeat(12))
+        );
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let text = "1 € ½ ☺ 👍🏽 ≤ – [] № ٣";
+        let found = |classes: &[&str]| {
+            let mut report = UnmappedReport::with_passthrough(classes).unwrap();
+            report.add("a.txt", text);
+            let mut chars = report
+                .chars()
+                .iter()
+                .map(|c| c.character)
+                .collect::<Vec<_>>();
+            chars.sort_unstable();
+            chars.into_iter().collect::<String>()
+        };
+        assert_eq!(found(DEFAULT_PASSTHROUGH), "½€№≤☺🏽👍");
+        assert_eq!(found(&[]), "½٣–€№≤☺🏽👍");
+        assert_eq!(found(&["emoji", "currency", "math"]), "½٣–№");
+        assert_eq!(found(&["symbols", "N", "[–№]"]), "");
+        assert_eq!(found(&["Sc", "Arabic", "[]"]), "½–№≤☺🏽👍");
+
+        for class in ["emojis", "Xx", "p}", "[-"] {
+            assert!(matches!(
+                UnmappedReport::with_passthrough(&[class]),
+                Err(Error::UnknownClass(c)) if c == class
+            ));
+        }
+    }
+}